@@ -1,4 +1,5 @@
 use alula::framework::render::Render;
+use alula::Color;
 use std::sync::Arc;
 use winit::{
     event::{Event, WindowEvent},
@@ -43,12 +44,7 @@ async fn run() -> Result<(), Box<dyn std::error::Error>> {
             window_id,
             event: WindowEvent::RedrawRequested,
         } if window_id == window.id() => {
-            let red = wgpu::Color {
-                r: 1.0,
-                g: 0.0,
-                b: 0.0,
-                a: 1.0,
-            };
+            let red = Color::rgb(1.0, 0.0, 0.0);
 
             match render.render(red) {
                 Ok(_) => {}
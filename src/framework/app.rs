@@ -1,34 +1,128 @@
+use std::fmt;
+
 use super::widgets::Widget;
+use super::{layout_child, try_layout, BoxConstraints, Event, LayoutError, Overlay, Size, State};
+use winit::keyboard::{Key, NamedKey};
 
-#[derive(Debug)]
+/// Application is the root of the widget tree.
+///
+/// Each frame, the application is built if [`State::needs_build`] returns
+/// true, then laid out and painted. Building lets widgets resolve the values
+/// they inherit, such as the theme.
+///
+/// Animations are driven by [`State::frame_time`], which the event loop
+/// advances with [`State::advance_frame_time`] before every frame. Widgets
+/// that animate request the next frame while painting; the event loop
+/// schedules another redraw if [`State::needs_frame`] returns true after
+/// painting.
 pub struct Application {
     title: String,
     root: Box<dyn Widget>,
+    window: Size,
 }
 
 impl Application {
+    /// Create a new Application instance with the given window title and root widget.
     pub fn new(title: impl Into<String>, root: impl Widget + 'static) -> Self {
         Self {
             title: title.into(),
             root: Box::new(root),
+            window: Size::ZERO,
         }
     }
 
+    /// The window title of the application.
     pub fn title(&self) -> &str {
         &self.title
     }
+
+    /// Layout the widget tree, failing if any widget overflows its constraints.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LayoutError::Overflow`] if any widget reported a size that
+    /// does not satisfy its constraints; the overflows are available from
+    /// [`take_overflow_reports`](crate::framework::take_overflow_reports).
+    /// Use [`Widget::layout`] to only log overflows instead.
+    pub fn try_layout(&mut self, constraints: BoxConstraints) -> Result<Size, LayoutError> {
+        try_layout(self, constraints)
+    }
 }
 
 impl Widget for Application {
-    fn build(&self, state: &mut State<'_>) {
+    fn build(&mut self, state: &mut State<'_>) {
         self.root.build(state);
+        state.overlay_mut().mark_needs_build();
+        state.finish_build();
     }
 
     fn layout(&mut self, constraints: BoxConstraints) -> Size {
-        self.root.layout(constraints)
+        let size = layout_child(self.root.as_mut(), constraints);
+        // The overlay covers the window, which the root may not fill.
+        self.window = if constraints.biggest().is_finite() {
+            constraints.biggest()
+        } else {
+            size
+        };
+        size
     }
 
     fn paint(&self, state: &mut State<'_>) {
+        state.begin_frame();
+        state.focus_mut().begin_frame();
+        state.begin_ime_frame();
+        state.begin_cursor_frame();
         self.root.paint(state);
+        Overlay::paint(state, self.window);
+        state.end_cursor_frame();
+        state.end_ime_frame();
+    }
+
+    fn event(&mut self, event: &Event, state: &mut State<'_>) -> bool {
+        if let Event::ModifiersChanged(modifiers) = event {
+            state.set_modifiers(*modifiers);
+        }
+        if Overlay::event(event, state) {
+            return true;
+        }
+        // Widgets below a modal entry of the overlay get no events.
+        if !state.overlay().has_modal() && self.root.event(event, state) {
+            return true;
+        }
+        match event {
+            Event::KeyDown {
+                key: Key::Named(NamedKey::Tab),
+                modifiers,
+                ..
+            } => {
+                if modifiers.shift_key() {
+                    state.focus_mut().focus_previous();
+                } else {
+                    state.focus_mut().focus_next();
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.root.needs_layout()
+    }
+
+    fn mark_needs_layout(&mut self) {
+        self.root.mark_needs_layout();
+    }
+
+    fn is_relayout_boundary(&self) -> bool {
+        self.root.is_relayout_boundary()
+    }
+}
+
+impl fmt::Debug for Application {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Application")
+            .field("title", &self.title)
+            .finish_non_exhaustive()
     }
 }
@@ -0,0 +1,60 @@
+use std::fmt;
+
+/// ClipboardProvider connects the clipboard to the clipboard of the platform.
+pub trait ClipboardProvider {
+    /// The text on the clipboard, if any.
+    fn get_text(&mut self) -> Option<String>;
+
+    /// Put the text on the clipboard.
+    fn set_text(&mut self, text: String);
+}
+
+/// Clipboard holds text copied or cut by widgets.
+///
+/// Without a provider the text is only shared within the application.
+#[derive(Default)]
+pub struct Clipboard {
+    text: Option<String>,
+    provider: Option<Box<dyn ClipboardProvider>>,
+}
+
+impl Clipboard {
+    /// Create a new empty Clipboard instance local to the application.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new Clipboard instance backed by the provider.
+    pub fn with_provider(provider: impl ClipboardProvider + 'static) -> Self {
+        Clipboard {
+            text: None,
+            provider: Some(Box::new(provider)),
+        }
+    }
+
+    /// The text on the clipboard, if any.
+    pub fn get_text(&mut self) -> Option<String> {
+        match &mut self.provider {
+            Some(provider) => provider.get_text(),
+            None => self.text.clone(),
+        }
+    }
+
+    /// Put the text on the clipboard.
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        let text = text.into();
+        match &mut self.provider {
+            Some(provider) => provider.set_text(text),
+            None => self.text = Some(text),
+        }
+    }
+}
+
+impl fmt::Debug for Clipboard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Clipboard")
+            .field("text", &self.text)
+            .field("has_provider", &self.provider.is_some())
+            .finish()
+    }
+}
@@ -1,20 +1,124 @@
+use crate::framework::ColorError;
+use std::fmt;
+use std::str::FromStr;
+
+/// ColorSpace is the color space the components of a [`Color`] are in.
+///
+/// Each space has primaries that bound its gamut, the colors it can show
+/// with components in [0.0, 1.0], and a transfer function between the stored
+/// components and linear light. All spaces use the D65 white point.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ColorSpace {
+    /// sRGB, the color space of hex and CSS colors and most displays.
+    #[default]
+    Srgb,
+    /// Display P3, the wider gamut of recent phones, laptops and monitors,
+    /// with the sRGB transfer function.
+    DisplayP3,
+    /// Rec. 2020, the very wide gamut of HDR video.
+    Rec2020,
+    /// sRGB primaries with linear components that may exceed [0.0, 1.0] to
+    /// reach colors outside sRGB and brighter than white, as HDR surfaces
+    /// with floating point formats expect.
+    LinearExtendedSrgb,
+}
+
+impl ColorSpace {
+    /// The name of the space in the CSS `color()` function.
+    fn css_name(self) -> &'static str {
+        match self {
+            ColorSpace::Srgb => "srgb",
+            ColorSpace::DisplayP3 => "display-p3",
+            ColorSpace::Rec2020 => "rec2020",
+            ColorSpace::LinearExtendedSrgb => "srgb-linear",
+        }
+    }
+
+    fn from_css_name(name: &str) -> Option<Self> {
+        [
+            ColorSpace::Srgb,
+            ColorSpace::DisplayP3,
+            ColorSpace::Rec2020,
+            ColorSpace::LinearExtendedSrgb,
+        ]
+        .into_iter()
+        .find(|space| space.css_name().eq_ignore_ascii_case(name))
+    }
+
+    /// Returns true if every color is in the gamut of the space.
+    fn is_unbounded(self) -> bool {
+        self == ColorSpace::LinearExtendedSrgb
+    }
+
+    /// Converts a stored component to linear light.
+    fn decode(self, c: f64) -> f64 {
+        match self {
+            ColorSpace::Srgb | ColorSpace::DisplayP3 => srgb_to_linear(c),
+            ColorSpace::Rec2020 => rec2020_to_linear(c),
+            ColorSpace::LinearExtendedSrgb => c,
+        }
+    }
+
+    /// Converts a linear light component to its stored form.
+    fn encode(self, c: f64) -> f64 {
+        match self {
+            ColorSpace::Srgb | ColorSpace::DisplayP3 => linear_to_srgb(c),
+            ColorSpace::Rec2020 => linear_to_rec2020(c),
+            ColorSpace::LinearExtendedSrgb => c,
+        }
+    }
+
+    /// The matrix from linear components to CIE XYZ.
+    fn linear_to_xyz(self) -> &'static [[f64; 3]; 3] {
+        match self {
+            ColorSpace::Srgb | ColorSpace::LinearExtendedSrgb => &LINEAR_SRGB_TO_XYZ,
+            ColorSpace::DisplayP3 => &LINEAR_P3_TO_XYZ,
+            ColorSpace::Rec2020 => &LINEAR_REC2020_TO_XYZ,
+        }
+    }
+
+    /// The matrix from CIE XYZ to linear components.
+    fn xyz_to_linear(self) -> &'static [[f64; 3]; 3] {
+        match self {
+            ColorSpace::Srgb | ColorSpace::LinearExtendedSrgb => &XYZ_TO_LINEAR_SRGB,
+            ColorSpace::DisplayP3 => &XYZ_TO_LINEAR_P3,
+            ColorSpace::Rec2020 => &XYZ_TO_LINEAR_REC2020,
+        }
+    }
+}
+
 /// A struct representing a color with red, green, blue, and alpha components.
 /// Each component is a floating-point value between 0.0 and 1.0.
 #[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd)]
 /// Represents a color in RGBA color space.
 ///
-/// Each component (red, green, blue, alpha) is stored as a 32-bit floating point number
+/// Each component (red, green, blue, alpha) is stored as a 64-bit floating point number
 /// in the range [0.0, 1.0].
 ///
+/// The red, green and blue components are in the [`ColorSpace`] of the
+/// color, which is sRGB unless the color was created in or converted to
+/// another space. sRGB components are encoded like hex and CSS colors, so
+/// 0.5 is perceived about halfway between black and white but emits about a
+/// fifth of the light of white. Blending and interpolation on the GPU need
+/// linear light, see [`Color::to_linear`]. Alpha is always linear.
+///
+/// Components outside [0.0, 1.0] are colors outside the gamut of the space,
+/// which [`Color::map_to_gamut`] brings into a space a display can show.
+///
 /// # Examples
 ///
 /// ```
+/// use alula::{Color, ColorSpace};
+///
 /// let white = Color {
 ///     r: 1.0,
 ///     g: 1.0,
 ///     b: 1.0,
 ///     a: 1.0,
+///     space: ColorSpace::Srgb,
 /// };
+/// assert_eq!(white, "#ffffff".parse().unwrap());
+/// assert_eq!(white.to_hex(), "#FFFFFF");
 /// ```
 ///
 /// # Fields
@@ -28,6 +132,8 @@ pub struct Color {
     pub b: f64,
     /// * `a` - Alpha (opacity) component
     pub a: f64,
+    /// * `space` - The color space of the red, green and blue components
+    pub space: ColorSpace,
 }
 
 impl Color {
@@ -44,7 +150,24 @@ impl Color {
     ///
     /// A new `Color` instance with the specified color components.
     pub fn new(r: f64, g: f64, b: f64, a: f64) -> Self {
-        Color { r, g, b, a }
+        Color::new_in(ColorSpace::Srgb, r, g, b, a)
+    }
+
+    /// Creates a new `Color` instance with components in the given color space.
+    ///
+    /// # Arguments
+    ///
+    /// * `space` - The color space of the components.
+    /// * `r` - The red component of the color.
+    /// * `g` - The green component of the color.
+    /// * `b` - The blue component of the color.
+    /// * `a` - The alpha (opacity) component of the color.
+    ///
+    /// # Returns
+    ///
+    /// A new `Color` instance with the specified color components.
+    pub fn new_in(space: ColorSpace, r: f64, g: f64, b: f64, a: f64) -> Self {
+        Color { r, g, b, a, space }
     }
 
     /// Creates a new `Color` instance with the specified red, green, and blue components.
@@ -60,7 +183,7 @@ impl Color {
     ///
     /// A new `Color` instance with the specified color components.
     pub fn rgb(r: f64, g: f64, b: f64) -> Self {
-        Color { r, g, b, a: 1.0 }
+        Color::new(r, g, b, 1.0)
     }
 
     /// Creates a new `Color` instance with the specified red, green, blue, and alpha components.
@@ -77,26 +200,851 @@ impl Color {
     ///
     /// A new `Color` instance with the specified color components.
     pub fn from_rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Color::new(
+            r as f64 / 255.0,
+            g as f64 / 255.0,
+            b as f64 / 255.0,
+            a as f64 / 255.0,
+        )
+    }
+
+    /// Creates a new `Color` instance from a hex string.
+    ///
+    /// The leading `#` is optional. The string has 3 (`RGB`), 4 (`RGBA`),
+    /// 6 (`RRGGBB`) or 8 (`RRGGBBAA`) hex digits; the short forms repeat
+    /// each digit, so `#f80` equals `#ff8800`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ColorError::InvalidHex`] if the string has another length
+    /// or contains a character that is not a hex digit.
+    pub fn from_hex(hex: &str) -> Result<Self, ColorError> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        let invalid = || ColorError::InvalidHex(hex.to_string());
+        if !digits.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        let value = u32::from_str_radix(digits, 16).map_err(|_| invalid())?;
+        // Short forms are widened by repeating each digit.
+        let widen = |value: u32, count: u32| {
+            (0..count).rev().fold(0, |wide, index| {
+                let digit = (value >> (index * 4)) & 0xF;
+                (wide << 8) | (digit << 4) | digit
+            })
+        };
+        let rgba = match digits.len() {
+            3 => (widen(value, 3) << 8) | 0xFF,
+            4 => widen(value, 4),
+            6 => (value << 8) | 0xFF,
+            8 => value,
+            _ => return Err(invalid()),
+        };
+        let [r, g, b, a] = rgba.to_be_bytes();
+        Ok(Color::from_rgba(r, g, b, a))
+    }
+
+    /// Formats the color as an uppercase hex string.
+    ///
+    /// Opaque colors are formatted as `#RRGGBB` and other colors as
+    /// `#RRGGBBAA`. Components are clamped to [0.0, 1.0] and rounded to the
+    /// nearest 8-bit value. Colors in other color spaces are mapped into the
+    /// sRGB gamut first.
+    pub fn to_hex(&self) -> String {
+        let [r, g, b, a] = self.to_rgba8();
+        if a == u8::MAX {
+            format!("#{r:02X}{g:02X}{b:02X}")
+        } else {
+            format!("#{r:02X}{g:02X}{b:02X}{a:02X}")
+        }
+    }
+
+    /// Returns the sRGB components as 8-bit integers in the range [0, 255],
+    /// clamping and rounding them.
+    pub fn to_rgba8(&self) -> [u8; 4] {
+        let color = match self.space {
+            ColorSpace::Srgb => *self,
+            _ => self.map_to_gamut(ColorSpace::Srgb),
+        };
+        [color.r, color.g, color.b, color.a].map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8)
+    }
+
+    /// Looks up a CSS named color, such as `rebeccapurple` or `transparent`.
+    ///
+    /// The name is matched case-insensitively. Returns `None` for names
+    /// that are not CSS color keywords.
+    pub fn named(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+        if name == "transparent" {
+            return Some(Color::new(0.0, 0.0, 0.0, 0.0));
+        }
+        let index = NAMED_COLORS
+            .binary_search_by(|(named, _)| (*named).cmp(name.as_str()))
+            .ok()?;
+        Color::from_hex(NAMED_COLORS[index].1).ok()
+    }
+
+    /// Creates a new opaque `Color` instance from hue, saturation and lightness.
+    ///
+    /// # Arguments
+    ///
+    /// * `h` - The hue in degrees; any value is wrapped into [0, 360).
+    /// * `s` - The saturation in the range [0.0, 1.0].
+    /// * `l` - The lightness in the range [0.0, 1.0].
+    pub fn from_hsl(h: f64, s: f64, l: f64) -> Self {
+        let (saturation, lightness) = (s.clamp(0.0, 1.0), l.clamp(0.0, 1.0));
+        let hue = h.rem_euclid(360.0);
+        let chroma = saturation * lightness.min(1.0 - lightness);
+        let channel = |offset: f64| {
+            let k = (offset + hue / 30.0).rem_euclid(12.0);
+            lightness - chroma * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0)
+        };
+        Color::rgb(channel(0.0), channel(8.0), channel(4.0))
+    }
+
+    /// Returns the hue in degrees, the saturation and the lightness of the
+    /// color, ignoring alpha.
+    ///
+    /// Gray colors have a hue and saturation of 0.
+    pub fn to_hsl(&self) -> (f64, f64, f64) {
+        let (max, min) = self.extremes();
+        let lightness = (max + min) / 2.0;
+        let saturation = if max == min {
+            0.0
+        } else {
+            (max - min) / (1.0 - (2.0 * lightness - 1.0).abs())
+        };
+        (self.hue(), saturation, lightness)
+    }
+
+    /// Creates a new opaque `Color` instance from hue, saturation and value.
+    ///
+    /// # Arguments
+    ///
+    /// * `h` - The hue in degrees; any value is wrapped into [0, 360).
+    /// * `s` - The saturation in the range [0.0, 1.0].
+    /// * `v` - The value (brightness) in the range [0.0, 1.0].
+    pub fn from_hsv(h: f64, s: f64, v: f64) -> Self {
+        let (saturation, value) = (s.clamp(0.0, 1.0), v.clamp(0.0, 1.0));
+        let hue = h.rem_euclid(360.0);
+        let channel = |offset: f64| {
+            let k = (offset + hue / 60.0).rem_euclid(6.0);
+            value - value * saturation * k.min(4.0 - k).clamp(0.0, 1.0)
+        };
+        Color::rgb(channel(5.0), channel(3.0), channel(1.0))
+    }
+
+    /// Returns the hue in degrees, the saturation and the value of the
+    /// color, ignoring alpha.
+    pub fn to_hsv(&self) -> (f64, f64, f64) {
+        let (max, min) = self.extremes();
+        let s = if max == 0.0 { 0.0 } else { (max - min) / max };
+        (self.hue(), s, max)
+    }
+
+    /// Creates a new opaque `Color` instance from OKLCH coordinates.
+    ///
+    /// OKLCH is a polar form of the perceptually uniform Oklab color space,
+    /// so colors with the same lightness look equally light whatever their
+    /// hue. Colors outside the sRGB gamut are mapped into it, see
+    /// [`Color::map_to_gamut`].
+    ///
+    /// # Arguments
+    ///
+    /// * `l` - The perceived lightness in the range [0.0, 1.0].
+    /// * `c` - The chroma, from 0.0 for gray to about 0.37 for the most
+    ///   saturated sRGB colors.
+    /// * `h` - The hue in degrees.
+    pub fn from_oklch(l: f64, c: f64, h: f64) -> Self {
+        Color::from_oklch_unmapped(l, c, h).map_to_gamut(ColorSpace::Srgb)
+    }
+
+    /// Returns the OKLCH lightness, chroma and hue in degrees of the color,
+    /// ignoring alpha.
+    pub fn to_oklch(&self) -> (f64, f64, f64) {
+        let [lightness, a, b] = self.oklab();
+        let chroma = a.hypot(b);
+        // The hue of grays is meaningless and only noise, so it is reported as 0.
+        let hue = if chroma < 1e-6 {
+            0.0
+        } else {
+            b.atan2(a).to_degrees().rem_euclid(360.0)
+        };
+        (lightness, chroma, hue)
+    }
+
+    /// Returns the color with the red, green and blue components converted
+    /// from the encoding of its color space to linear light, as shaders and
+    /// blending expect.
+    ///
+    /// Alpha and the color space are unchanged. Components outside [0.0, 1.0]
+    /// are converted symmetrically around zero, as in extended sRGB.
+    pub fn to_linear(&self) -> Self {
+        let decode = |c: f64| self.space.decode(c);
+        Color {
+            r: decode(self.r),
+            g: decode(self.g),
+            b: decode(self.b),
+            ..*self
+        }
+    }
+
+    /// Creates a new `Color` instance from linear light components, encoding
+    /// them for their color space. This is the inverse of [`Color::to_linear`].
+    ///
+    /// # Arguments
+    ///
+    /// * `linear` - The color with red, green and blue in linear light.
+    pub fn from_linear(linear: Color) -> Self {
+        let encode = |c: f64| linear.space.encode(c);
+        Color {
+            r: encode(linear.r),
+            g: encode(linear.g),
+            b: encode(linear.b),
+            ..linear
+        }
+    }
+
+    /// Converts the color to another color space.
+    ///
+    /// The result shows the same color, but its components may fall outside
+    /// [0.0, 1.0] if the color is outside the gamut of the space.
+    pub fn to_space(&self, space: ColorSpace) -> Self {
+        if self.space == space {
+            return *self;
+        }
+        let linear = self.to_linear();
+        let xyz = multiply(self.space.linear_to_xyz(), [linear.r, linear.g, linear.b]);
+        let [r, g, b] = multiply(space.xyz_to_linear(), xyz);
+        Color::from_linear(Color::new_in(space, r, g, b, self.a))
+    }
+
+    /// Returns true if the components are within [0.0, 1.0], i.e. the color
+    /// space can show the color. Linear extended sRGB can show every color.
+    pub fn is_in_gamut(&self) -> bool {
+        self.space.is_unbounded()
+            || [self.r, self.g, self.b]
+                .iter()
+                .all(|c| (0.0..=1.0).contains(c))
+    }
+
+    /// Returns the color with the components clamped to [0.0, 1.0].
+    ///
+    /// Clamping changes the hue and lightness of saturated colors, so
+    /// [`Color::map_to_gamut`] is usually the better choice.
+    pub fn clamp_to_gamut(&self) -> Self {
+        if self.space.is_unbounded() {
+            return *self;
+        }
+        Color {
+            r: self.r.clamp(0.0, 1.0),
+            g: self.g.clamp(0.0, 1.0),
+            b: self.b.clamp(0.0, 1.0),
+            ..*self
+        }
+    }
+
+    /// Converts the color to another color space and brings it into the
+    /// gamut of that space.
+    ///
+    /// Colors outside the gamut keep their OKLCH lightness and hue and lose
+    /// chroma until they are in the gamut or only imperceptibly outside it,
+    /// as in the CSS Color 4 gamut mapping algorithm.
+    pub fn map_to_gamut(&self, space: ColorSpace) -> Self {
+        /// The distance in Oklab below which colors look the same.
+        const JUST_NOTICEABLE: f64 = 0.02;
+        /// The precision of the chroma search.
+        const EPSILON: f64 = 0.0001;
+
+        let converted = self.to_space(space);
+        if converted.is_in_gamut() {
+            return converted;
+        }
+        let (lightness, chroma, hue) = self.to_oklch();
+        if lightness >= 1.0 || lightness <= 0.0 {
+            let extreme = lightness.clamp(0.0, 1.0);
+            return Color::rgb(extreme, extreme, extreme)
+                .to_space(space)
+                .with_alpha(self.a);
+        }
+        let at_chroma = |chroma: f64| {
+            Color::from_oklch_unmapped(lightness, chroma, hue)
+                .to_space(space)
+                .with_alpha(self.a)
+        };
+        let difference = |a: Color, b: Color| {
+            let ([l1, a1, b1], [l2, a2, b2]) = (a.oklab(), b.oklab());
+            (l1 - l2).hypot(a1 - a2).hypot(b1 - b2)
+        };
+
+        let mut clipped = converted.clamp_to_gamut();
+        if difference(clipped, converted) < JUST_NOTICEABLE {
+            return clipped;
+        }
+        let (mut low, mut high) = (0.0, chroma);
+        let mut low_in_gamut = true;
+        while high - low > EPSILON {
+            let middle = (low + high) / 2.0;
+            let current = at_chroma(middle);
+            if low_in_gamut && current.is_in_gamut() {
+                low = middle;
+                continue;
+            }
+            clipped = current.clamp_to_gamut();
+            let error = difference(clipped, current);
+            if error >= JUST_NOTICEABLE {
+                high = middle;
+            } else if JUST_NOTICEABLE - error < EPSILON {
+                break;
+            } else {
+                low_in_gamut = false;
+                low = middle;
+            }
+        }
+        clipped
+    }
+
+    /// Returns the color with the alpha component replaced.
+    pub fn with_alpha(&self, a: f64) -> Self {
+        Color { a, ..*self }
+    }
+
+    /// Returns the color with its HSL lightness increased by the amount,
+    /// e.g. 0.1 for ten percentage points.
+    pub fn lighten(&self, amount: f64) -> Self {
+        let (h, s, l) = self.to_hsl();
+        Color {
+            a: self.a,
+            space: self.space,
+            ..Color::from_hsl(h, s, l + amount)
+        }
+    }
+
+    /// Returns the color with its HSL lightness decreased by the amount.
+    pub fn darken(&self, amount: f64) -> Self {
+        self.lighten(-amount)
+    }
+
+    /// Returns the color with its HSL saturation increased by the amount.
+    pub fn saturate(&self, amount: f64) -> Self {
+        let (h, s, l) = self.to_hsl();
+        Color {
+            a: self.a,
+            space: self.space,
+            ..Color::from_hsl(h, s + amount, l)
+        }
+    }
+
+    /// Returns the color with its HSL saturation decreased by the amount.
+    pub fn desaturate(&self, amount: f64) -> Self {
+        self.saturate(-amount)
+    }
+
+    /// Interpolates between two colors, e.g. for animations.
+    ///
+    /// The components are interpolated with premultiplied alpha, so fading
+    /// from a transparent color keeps the hue of the other color instead of
+    /// passing through darker shades. The other color is converted to the
+    /// color space of this color first.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The color reached at `t` = 1.0.
+    /// * `t` - The position between the colors, usually in [0.0, 1.0].
+    pub fn lerp(&self, other: Color, t: f64) -> Self {
+        let mix = |start: f64, end: f64| start + (end - start) * t;
+        let (from, to) = (
+            self.premultiplied(),
+            other.to_space(self.space).premultiplied(),
+        );
+        Color::new_in(
+            self.space,
+            mix(from.r, to.r),
+            mix(from.g, to.g),
+            mix(from.b, to.b),
+            mix(from.a, to.a),
+        )
+        .unpremultiplied()
+    }
+
+    /// Returns the color with the red, green and blue components multiplied
+    /// by alpha, as blending with premultiplied alpha expects.
+    pub fn premultiplied(&self) -> Self {
         Color {
-            r: r as f64 / 255.0,
-            g: g as f64 / 255.0,
-            b: b as f64 / 255.0,
-            a: a as f64 / 255.0,
+            r: self.r * self.a,
+            g: self.g * self.a,
+            b: self.b * self.a,
+            ..*self
+        }
+    }
+
+    /// Returns the color with the red, green and blue components divided by
+    /// alpha, undoing [`Color::premultiplied`].
+    ///
+    /// Fully transparent colors have no recoverable components and become
+    /// transparent black.
+    pub fn unpremultiplied(&self) -> Self {
+        if self.a == 0.0 {
+            return Color::new_in(self.space, 0.0, 0.0, 0.0, 0.0);
+        }
+        Color {
+            r: self.r / self.a,
+            g: self.g / self.a,
+            b: self.b / self.a,
+            ..*self
+        }
+    }
+
+    /// An opaque linear extended sRGB color from OKLCH coordinates, which may
+    /// be outside every gamut.
+    fn from_oklch_unmapped(l: f64, c: f64, h: f64) -> Self {
+        let (sin, cos) = h.to_radians().sin_cos();
+        let cone = multiply(&OKLAB_TO_LMS, [l, c * cos, c * sin]).map(|x| x.powi(3));
+        let [red, green, blue] = multiply(&LMS_TO_LINEAR_SRGB, cone);
+        Color::new_in(ColorSpace::LinearExtendedSrgb, red, green, blue, 1.0)
+    }
+
+    /// The Oklab lightness and a and b axes of the color.
+    fn oklab(&self) -> [f64; 3] {
+        let linear = self.to_space(ColorSpace::LinearExtendedSrgb);
+        let cone = multiply(&LINEAR_SRGB_TO_LMS, [linear.r, linear.g, linear.b]).map(f64::cbrt);
+        multiply(&LMS_TO_OKLAB, cone)
+    }
+
+    /// The largest and smallest of the red, green and blue components.
+    fn extremes(&self) -> (f64, f64) {
+        (
+            self.r.max(self.g).max(self.b),
+            self.r.min(self.g).min(self.b),
+        )
+    }
+
+    /// The hue in degrees shared by HSL and HSV.
+    fn hue(&self) -> f64 {
+        let (max, min) = self.extremes();
+        let delta = max - min;
+        let sector = if delta == 0.0 {
+            return 0.0;
+        } else if max == self.r {
+            (self.g - self.b) / delta
+        } else if max == self.g {
+            (self.b - self.r) / delta + 2.0
+        } else {
+            (self.r - self.g) / delta + 4.0
+        };
+        (sector * 60.0).rem_euclid(360.0)
+    }
+}
+
+/// Converts linear sRGB to CIE XYZ.
+static LINEAR_SRGB_TO_XYZ: [[f64; 3]; 3] = [
+    [
+        0.412_390_799_265_959_34,
+        0.357_584_339_383_878,
+        0.180_480_788_401_834_3,
+    ],
+    [
+        0.212_639_005_871_510_27,
+        0.715_168_678_767_756,
+        0.072_192_315_360_733_71,
+    ],
+    [
+        0.019_330_818_715_591_82,
+        0.119_194_779_794_625_98,
+        0.950_532_152_249_660_7,
+    ],
+];
+
+/// Converts CIE XYZ to linear sRGB.
+static XYZ_TO_LINEAR_SRGB: [[f64; 3]; 3] = [
+    [
+        3.240_969_941_904_522_6,
+        -1.537_383_177_570_094,
+        -0.498_610_760_293_003_4,
+    ],
+    [
+        -0.969_243_636_280_879_6,
+        1.875_967_501_507_720_2,
+        0.041_555_057_407_175_59,
+    ],
+    [
+        0.055_630_079_696_993_66,
+        -0.203_976_958_888_976_52,
+        1.056_971_514_242_878_6,
+    ],
+];
+
+/// Converts linear Display P3 to CIE XYZ.
+static LINEAR_P3_TO_XYZ: [[f64; 3]; 3] = [
+    [
+        0.486_570_948_648_216_2,
+        0.265_667_693_169_093_06,
+        0.198_217_285_234_362_5,
+    ],
+    [
+        0.228_974_564_069_748_8,
+        0.691_738_521_836_506_4,
+        0.079_286_914_093_745,
+    ],
+    [0.0, 0.045_113_381_858_902_64, 1.043_944_368_900_976],
+];
+
+/// Converts CIE XYZ to linear Display P3.
+static XYZ_TO_LINEAR_P3: [[f64; 3]; 3] = [
+    [
+        2.493_496_911_941_425,
+        -0.931_383_617_919_123_9,
+        -0.402_710_784_450_716_84,
+    ],
+    [
+        -0.829_488_969_561_574_7,
+        1.762_664_060_318_346_3,
+        0.023_624_685_841_943_577,
+    ],
+    [
+        0.035_845_830_243_784_47,
+        -0.076_172_389_268_041_82,
+        0.956_884_524_007_687_2,
+    ],
+];
+
+/// Converts linear Rec. 2020 to CIE XYZ.
+static LINEAR_REC2020_TO_XYZ: [[f64; 3]; 3] = [
+    [
+        0.636_958_048_301_291_4,
+        0.144_616_903_586_208_32,
+        0.168_880_975_164_172_1,
+    ],
+    [
+        0.262_700_212_011_267_1,
+        0.677_998_071_518_870_8,
+        0.059_301_716_469_861_96,
+    ],
+    [0.0, 0.028_072_693_049_087_428, 1.060_985_057_710_791],
+];
+
+/// Converts CIE XYZ to linear Rec. 2020.
+static XYZ_TO_LINEAR_REC2020: [[f64; 3]; 3] = [
+    [
+        1.716_651_187_971_268,
+        -0.355_670_783_776_392,
+        -0.253_366_281_373_66,
+    ],
+    [
+        -0.666_684_351_832_489,
+        1.616_481_236_634_939,
+        0.015_768_545_813_911_1,
+    ],
+    [
+        0.017_639_857_445_311,
+        -0.042_770_613_257_809,
+        0.942_103_121_235_474,
+    ],
+];
+
+/// Converts linear sRGB to the cone responses of Oklab.
+static LINEAR_SRGB_TO_LMS: [[f64; 3]; 3] = [
+    [0.412_221_470_8, 0.536_332_536_3, 0.051_445_992_9],
+    [0.211_903_498_2, 0.680_699_545_1, 0.107_396_956_6],
+    [0.088_302_461_9, 0.281_718_837_6, 0.629_978_700_5],
+];
+
+/// Converts the cube roots of the cone responses to Oklab.
+static LMS_TO_OKLAB: [[f64; 3]; 3] = [
+    [0.210_454_255_3, 0.793_617_785, -0.004_072_046_8],
+    [1.977_998_495_1, -2.428_592_205, 0.450_593_709_9],
+    [0.025_904_037_1, 0.782_771_766_2, -0.808_675_766],
+];
+
+/// Converts Oklab to the cube roots of the cone responses.
+static OKLAB_TO_LMS: [[f64; 3]; 3] = [
+    [1.0, 0.396_337_777_4, 0.215_803_757_3],
+    [1.0, -0.105_561_345_8, -0.063_854_172_8],
+    [1.0, -0.089_484_177_5, -1.291_485_548],
+];
+
+/// Converts the cone responses of Oklab to linear sRGB.
+static LMS_TO_LINEAR_SRGB: [[f64; 3]; 3] = [
+    [4.076_741_662_1, -3.307_711_591_3, 0.230_969_929_2],
+    [-1.268_438_004_6, 2.609_757_401_1, -0.341_319_396_5],
+    [-0.004_196_086_3, -0.703_418_614_7, 1.707_614_701],
+];
+
+/// Multiplies a 3x3 matrix with a column vector.
+fn multiply(matrix: &[[f64; 3]; 3], vector: [f64; 3]) -> [f64; 3] {
+    matrix.map(|row| row[0] * vector[0] + row[1] * vector[1] + row[2] * vector[2])
+}
+
+/// Converts an sRGB encoded component to linear light.
+fn srgb_to_linear(c: f64) -> f64 {
+    if c.abs() <= 0.04045 {
+        c / 12.92
+    } else {
+        c.signum() * ((c.abs() + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Converts a linear light component to sRGB encoding.
+fn linear_to_srgb(c: f64) -> f64 {
+    if c.abs() <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        c.signum() * (1.055 * c.abs().powf(1.0 / 2.4) - 0.055)
+    }
+}
+
+/// The Rec. 2020 transfer function constants.
+const REC2020_ALPHA: f64 = 1.099_296_826_809_44;
+const REC2020_BETA: f64 = 0.018_053_968_510_807;
+
+/// Converts a Rec. 2020 encoded component to linear light.
+fn rec2020_to_linear(c: f64) -> f64 {
+    if c.abs() < REC2020_BETA * 4.5 {
+        c / 4.5
+    } else {
+        c.signum() * ((c.abs() + REC2020_ALPHA - 1.0) / REC2020_ALPHA).powf(1.0 / 0.45)
+    }
+}
+
+/// Converts a linear light component to Rec. 2020 encoding.
+fn linear_to_rec2020(c: f64) -> f64 {
+    if c.abs() < REC2020_BETA {
+        c * 4.5
+    } else {
+        c.signum() * (REC2020_ALPHA * c.abs().powf(0.45) - (REC2020_ALPHA - 1.0))
+    }
+}
+
+/// Parses the CSS `color()` function, e.g. `color(display-p3 1 0.5 0 / 0.8)`.
+fn parse_color_function(s: &str) -> Option<Color> {
+    let arguments = s.strip_prefix("color(")?.strip_suffix(')')?;
+    let (components, opacity) = match arguments.split_once('/') {
+        Some((components, opacity)) => (components, Some(opacity)),
+        None => (arguments, None),
+    };
+    let mut words = components.split_whitespace();
+    let space = ColorSpace::from_css_name(words.next()?)?;
+    let parse = |word: &str| match word.strip_suffix('%') {
+        Some(percent) => percent.parse::<f64>().ok().map(|value| value / 100.0),
+        None => word.parse::<f64>().ok(),
+    };
+    let values = words.map(parse).collect::<Option<Vec<_>>>()?;
+    let [red, green, blue] = values[..] else {
+        return None;
+    };
+    let alpha = match opacity {
+        Some(opacity) => parse(opacity.trim())?,
+        None => 1.0,
+    };
+    Some(Color::new_in(space, red, green, blue, alpha))
+}
+
+/// Parses a hex color starting with `#`, a CSS color name or the CSS
+/// `color()` function with one of the supported color spaces.
+impl FromStr for Color {
+    type Err = ColorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.starts_with('#') {
+            Color::from_hex(s)
+        } else if s.starts_with("color(") {
+            parse_color_function(s).ok_or_else(|| ColorError::UnknownColor(s.to_string()))
+        } else {
+            Color::named(s).ok_or_else(|| ColorError::UnknownColor(s.to_string()))
         }
     }
 }
 
+/// Formats sRGB colors as a hex string, see [`Color::to_hex`], and colors in
+/// other color spaces with the CSS `color()` function.
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.space == ColorSpace::Srgb {
+            return f.write_str(&self.to_hex());
+        }
+        write!(
+            f,
+            "color({} {} {} {}",
+            self.space.css_name(),
+            self.r,
+            self.g,
+            self.b
+        )?;
+        if self.a != 1.0 {
+            write!(f, " / {}", self.a)?;
+        }
+        f.write_str(")")
+    }
+}
+
+/// The CSS named colors, sorted by name for binary search.
+static NAMED_COLORS: [(&str, &str); 148] = [
+    ("aliceblue", "#F0F8FF"),
+    ("antiquewhite", "#FAEBD7"),
+    ("aqua", "#00FFFF"),
+    ("aquamarine", "#7FFFD4"),
+    ("azure", "#F0FFFF"),
+    ("beige", "#F5F5DC"),
+    ("bisque", "#FFE4C4"),
+    ("black", "#000000"),
+    ("blanchedalmond", "#FFEBCD"),
+    ("blue", "#0000FF"),
+    ("blueviolet", "#8A2BE2"),
+    ("brown", "#A52A2A"),
+    ("burlywood", "#DEB887"),
+    ("cadetblue", "#5F9EA0"),
+    ("chartreuse", "#7FFF00"),
+    ("chocolate", "#D2691E"),
+    ("coral", "#FF7F50"),
+    ("cornflowerblue", "#6495ED"),
+    ("cornsilk", "#FFF8DC"),
+    ("crimson", "#DC143C"),
+    ("cyan", "#00FFFF"),
+    ("darkblue", "#00008B"),
+    ("darkcyan", "#008B8B"),
+    ("darkgoldenrod", "#B8860B"),
+    ("darkgray", "#A9A9A9"),
+    ("darkgreen", "#006400"),
+    ("darkgrey", "#A9A9A9"),
+    ("darkkhaki", "#BDB76B"),
+    ("darkmagenta", "#8B008B"),
+    ("darkolivegreen", "#556B2F"),
+    ("darkorange", "#FF8C00"),
+    ("darkorchid", "#9932CC"),
+    ("darkred", "#8B0000"),
+    ("darksalmon", "#E9967A"),
+    ("darkseagreen", "#8FBC8F"),
+    ("darkslateblue", "#483D8B"),
+    ("darkslategray", "#2F4F4F"),
+    ("darkslategrey", "#2F4F4F"),
+    ("darkturquoise", "#00CED1"),
+    ("darkviolet", "#9400D3"),
+    ("deeppink", "#FF1493"),
+    ("deepskyblue", "#00BFFF"),
+    ("dimgray", "#696969"),
+    ("dimgrey", "#696969"),
+    ("dodgerblue", "#1E90FF"),
+    ("firebrick", "#B22222"),
+    ("floralwhite", "#FFFAF0"),
+    ("forestgreen", "#228B22"),
+    ("fuchsia", "#FF00FF"),
+    ("gainsboro", "#DCDCDC"),
+    ("ghostwhite", "#F8F8FF"),
+    ("gold", "#FFD700"),
+    ("goldenrod", "#DAA520"),
+    ("gray", "#808080"),
+    ("green", "#008000"),
+    ("greenyellow", "#ADFF2F"),
+    ("grey", "#808080"),
+    ("honeydew", "#F0FFF0"),
+    ("hotpink", "#FF69B4"),
+    ("indianred", "#CD5C5C"),
+    ("indigo", "#4B0082"),
+    ("ivory", "#FFFFF0"),
+    ("khaki", "#F0E68C"),
+    ("lavender", "#E6E6FA"),
+    ("lavenderblush", "#FFF0F5"),
+    ("lawngreen", "#7CFC00"),
+    ("lemonchiffon", "#FFFACD"),
+    ("lightblue", "#ADD8E6"),
+    ("lightcoral", "#F08080"),
+    ("lightcyan", "#E0FFFF"),
+    ("lightgoldenrodyellow", "#FAFAD2"),
+    ("lightgray", "#D3D3D3"),
+    ("lightgreen", "#90EE90"),
+    ("lightgrey", "#D3D3D3"),
+    ("lightpink", "#FFB6C1"),
+    ("lightsalmon", "#FFA07A"),
+    ("lightseagreen", "#20B2AA"),
+    ("lightskyblue", "#87CEFA"),
+    ("lightslategray", "#778899"),
+    ("lightslategrey", "#778899"),
+    ("lightsteelblue", "#B0C4DE"),
+    ("lightyellow", "#FFFFE0"),
+    ("lime", "#00FF00"),
+    ("limegreen", "#32CD32"),
+    ("linen", "#FAF0E6"),
+    ("magenta", "#FF00FF"),
+    ("maroon", "#800000"),
+    ("mediumaquamarine", "#66CDAA"),
+    ("mediumblue", "#0000CD"),
+    ("mediumorchid", "#BA55D3"),
+    ("mediumpurple", "#9370DB"),
+    ("mediumseagreen", "#3CB371"),
+    ("mediumslateblue", "#7B68EE"),
+    ("mediumspringgreen", "#00FA9A"),
+    ("mediumturquoise", "#48D1CC"),
+    ("mediumvioletred", "#C71585"),
+    ("midnightblue", "#191970"),
+    ("mintcream", "#F5FFFA"),
+    ("mistyrose", "#FFE4E1"),
+    ("moccasin", "#FFE4B5"),
+    ("navajowhite", "#FFDEAD"),
+    ("navy", "#000080"),
+    ("oldlace", "#FDF5E6"),
+    ("olive", "#808000"),
+    ("olivedrab", "#6B8E23"),
+    ("orange", "#FFA500"),
+    ("orangered", "#FF4500"),
+    ("orchid", "#DA70D6"),
+    ("palegoldenrod", "#EEE8AA"),
+    ("palegreen", "#98FB98"),
+    ("paleturquoise", "#AFEEEE"),
+    ("palevioletred", "#DB7093"),
+    ("papayawhip", "#FFEFD5"),
+    ("peachpuff", "#FFDAB9"),
+    ("peru", "#CD853F"),
+    ("pink", "#FFC0CB"),
+    ("plum", "#DDA0DD"),
+    ("powderblue", "#B0E0E6"),
+    ("purple", "#800080"),
+    ("rebeccapurple", "#663399"),
+    ("red", "#FF0000"),
+    ("rosybrown", "#BC8F8F"),
+    ("royalblue", "#4169E1"),
+    ("saddlebrown", "#8B4513"),
+    ("salmon", "#FA8072"),
+    ("sandybrown", "#F4A460"),
+    ("seagreen", "#2E8B57"),
+    ("seashell", "#FFF5EE"),
+    ("sienna", "#A0522D"),
+    ("silver", "#C0C0C0"),
+    ("skyblue", "#87CEEB"),
+    ("slateblue", "#6A5ACD"),
+    ("slategray", "#708090"),
+    ("slategrey", "#708090"),
+    ("snow", "#FFFAFA"),
+    ("springgreen", "#00FF7F"),
+    ("steelblue", "#4682B4"),
+    ("tan", "#D2B48C"),
+    ("teal", "#008080"),
+    ("thistle", "#D8BFD8"),
+    ("tomato", "#FF6347"),
+    ("turquoise", "#40E0D0"),
+    ("violet", "#EE82EE"),
+    ("wheat", "#F5DEB3"),
+    ("white", "#FFFFFF"),
+    ("whitesmoke", "#F5F5F5"),
+    ("yellow", "#FFFF00"),
+    ("yellowgreen", "#9ACD32"),
+];
+
 /// Converts a `Color` into a `wgpu::Color`.
 ///
+/// wgpu expects linear colors, e.g. for clearing an sRGB render target, so
+/// the color is mapped into the sRGB gamut and the components are converted
+/// with [`Color::to_linear`].
+///
 /// # Arguments
 ///
 /// * `color` - The `Color` instance to convert.
 ///
 /// # Returns
 ///
-/// A `wgpu::Color` instance with the same color components as the input `Color`.
+/// A `wgpu::Color` instance with the linear color components of the input `Color`.
 impl From<Color> for wgpu::Color {
     fn from(color: Color) -> Self {
+        let color = color.map_to_gamut(ColorSpace::Srgb).to_linear();
         wgpu::Color {
             r: color.r,
             g: color.g,
@@ -105,3 +1053,210 @@ impl From<Color> for wgpu::Color {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: Color, b: Color) {
+        let close = |x: f64, y: f64| (x - y).abs() < 1e-3;
+        assert!(
+            a.space == b.space
+                && close(a.r, b.r)
+                && close(a.g, b.g)
+                && close(a.b, b.b)
+                && close(a.a, b.a),
+            "{a:?} != {b:?}"
+        );
+    }
+
+    #[test]
+    fn test_hex() {
+        let orange = Color::from_rgba(0xFF, 0x88, 0x00, 0xFF);
+        assert_eq!(Color::from_hex("#ff8800"), Ok(orange));
+        assert_eq!(Color::from_hex("F80"), Ok(orange));
+        assert_eq!(
+            Color::from_hex("#f808"),
+            Ok(orange.with_alpha(136.0 / 255.0))
+        );
+        assert_eq!(
+            Color::from_hex("#FF880080"),
+            Ok(Color::from_rgba(0xFF, 0x88, 0x00, 0x80))
+        );
+        assert_eq!(orange.to_hex(), "#FF8800");
+        assert_eq!(Color::new(2.0, -1.0, 0.0, 0.5).to_hex(), "#FF000080");
+
+        for invalid in ["#12345", "#ggg", "", "#+fff", "#ffffffffff"] {
+            assert_eq!(
+                Color::from_hex(invalid),
+                Err(ColorError::InvalidHex(invalid.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn test_named_and_parse() {
+        assert!(NAMED_COLORS.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert_eq!(
+            Color::named("RebeccaPurple"),
+            Color::from_hex("#663399").ok()
+        );
+        assert_eq!(Color::named("transparent").map(|c| c.a), Some(0.0));
+        assert_eq!(Color::named("blurple"), None);
+
+        assert_eq!(" teal ".parse(), Ok(Color::from_rgba(0, 128, 128, 255)));
+        assert_eq!(
+            "#008080".parse::<Color>().map(|c| c.to_string()),
+            Ok("#008080".into())
+        );
+        assert_eq!(
+            "blurple".parse::<Color>(),
+            Err(ColorError::UnknownColor("blurple".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_hsl_hsv() {
+        assert_close(Color::from_hsl(0.0, 1.0, 0.5), Color::rgb(1.0, 0.0, 0.0));
+        assert_close(Color::from_hsl(480.0, 1.0, 0.25), Color::rgb(0.0, 0.5, 0.0));
+        assert_close(Color::from_hsv(240.0, 1.0, 1.0), Color::rgb(0.0, 0.0, 1.0));
+
+        let color = Color::from_hex("#3a7bd5").unwrap();
+        let (h, s, l) = color.to_hsl();
+        assert_close(Color::from_hsl(h, s, l), color);
+        let (h, s, v) = color.to_hsv();
+        assert_close(Color::from_hsv(h, s, v), color);
+        assert_eq!(Color::rgb(0.5, 0.5, 0.5).to_hsl(), (0.0, 0.0, 0.5));
+
+        let gray = Color::rgb(0.5, 0.5, 0.5).with_alpha(0.5);
+        assert_close(gray.lighten(0.25), Color::new(0.75, 0.75, 0.75, 0.5));
+        assert_close(gray.darken(1.0), Color::new(0.0, 0.0, 0.0, 0.5));
+        assert_close(
+            Color::from_hsl(120.0, 0.5, 0.5).saturate(0.5),
+            Color::rgb(0.0, 1.0, 0.0),
+        );
+        assert_close(
+            Color::from_hsl(120.0, 0.5, 0.5).desaturate(0.5),
+            Color::rgb(0.5, 0.5, 0.5),
+        );
+    }
+
+    #[test]
+    fn test_oklch() {
+        let (l, c, _) = Color::rgb(1.0, 1.0, 1.0).to_oklch();
+        assert!((l - 1.0).abs() < 1e-4 && c < 1e-4);
+        // Reference values from the Oklab definition.
+        let (l, c, h) = Color::rgb(1.0, 0.0, 0.0).to_oklch();
+        assert!((l - 0.628).abs() < 1e-3 && (c - 0.2577).abs() < 1e-3 && (h - 29.23).abs() < 0.1);
+
+        let color = Color::from_hex("#3a7bd5").unwrap();
+        let (l, c, h) = color.to_oklch();
+        assert_close(Color::from_oklch(l, c, h), color);
+    }
+
+    #[test]
+    fn test_linear() {
+        let gray = Color::rgb(0.5, 0.5, 0.5).with_alpha(0.5);
+        let linear = gray.to_linear();
+        assert!((linear.r - 0.214).abs() < 1e-3);
+        assert_eq!(linear.a, 0.5);
+        assert_close(Color::from_linear(linear), gray);
+        assert_eq!(
+            Color::rgb(1.0, 0.0, 0.0).to_linear(),
+            Color::rgb(1.0, 0.0, 0.0)
+        );
+        // Extended components keep their sign.
+        assert_close(
+            Color::from_linear(Color::rgb(-0.5, 1.5, 0.0).to_linear()),
+            Color::rgb(-0.5, 1.5, 0.0),
+        );
+
+        let clear = wgpu::Color::from(gray);
+        assert!((clear.r - 0.214).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_color_spaces() {
+        let red = Color::rgb(1.0, 0.0, 0.0);
+        // Reference values from CSS Color 4.
+        let p3 = red.to_space(ColorSpace::DisplayP3);
+        assert_close(
+            p3,
+            Color::new_in(ColorSpace::DisplayP3, 0.9175, 0.2003, 0.1386, 1.0),
+        );
+        assert!(p3.is_in_gamut());
+        assert_close(p3.to_space(ColorSpace::Srgb), red);
+
+        let rec2020 = Color::new_in(ColorSpace::Rec2020, 0.3, 0.6, 0.01, 0.5);
+        assert_close(Color::from_linear(rec2020.to_linear()), rec2020);
+        let round_trip = rec2020
+            .to_space(ColorSpace::DisplayP3)
+            .to_space(ColorSpace::LinearExtendedSrgb)
+            .to_space(ColorSpace::Rec2020);
+        assert_close(round_trip, rec2020);
+
+        // Wide gamut colors fall outside sRGB, which the extended space keeps.
+        let p3_red = Color::new_in(ColorSpace::DisplayP3, 1.0, 0.0, 0.0, 1.0);
+        let srgb = p3_red.to_space(ColorSpace::Srgb);
+        assert!(!srgb.is_in_gamut() && srgb.r > 1.0 && srgb.g < 0.0);
+        assert!(p3_red
+            .to_space(ColorSpace::LinearExtendedSrgb)
+            .is_in_gamut());
+    }
+
+    #[test]
+    fn test_map_to_gamut() {
+        let p3_red = Color::new_in(ColorSpace::DisplayP3, 1.0, 0.0, 0.0, 0.8);
+        let mapped = p3_red.map_to_gamut(ColorSpace::Srgb);
+        assert!(mapped.is_in_gamut());
+        assert_eq!((mapped.space, mapped.a), (ColorSpace::Srgb, 0.8));
+        // Lightness and hue are kept, unlike with clamping.
+        let (l, _, h) = p3_red.to_oklch();
+        let (mapped_l, _, mapped_h) = mapped.to_oklch();
+        assert!((l - mapped_l).abs() < 0.02 && (h - mapped_h).abs() < 2.0);
+
+        let in_gamut = Color::rgb(0.2, 0.4, 0.6);
+        assert_eq!(in_gamut.map_to_gamut(ColorSpace::Srgb), in_gamut);
+        let bright = Color::new_in(ColorSpace::LinearExtendedSrgb, 2.0, 2.0, 2.0, 1.0);
+        assert_close(
+            bright.map_to_gamut(ColorSpace::Srgb),
+            Color::rgb(1.0, 1.0, 1.0),
+        );
+        assert_eq!(p3_red.to_hex(), mapped.to_hex());
+    }
+
+    #[test]
+    fn test_color_function() {
+        let color: Color = "color(display-p3 1 50% 0 / 0.5)".parse().unwrap();
+        assert_eq!(
+            color,
+            Color::new_in(ColorSpace::DisplayP3, 1.0, 0.5, 0.0, 0.5)
+        );
+        assert_eq!(color.to_string(), "color(display-p3 1 0.5 0 / 0.5)");
+        assert_eq!(color.to_string().parse::<Color>(), Ok(color));
+        assert_eq!(
+            "color(rec2020 0 1 0)"
+                .parse::<Color>()
+                .map(|color| color.space),
+            Ok(ColorSpace::Rec2020)
+        );
+        assert!("color(xyz 0 1 0)".parse::<Color>().is_err());
+        assert!("color(srgb 0 1)".parse::<Color>().is_err());
+    }
+
+    #[test]
+    fn test_lerp_and_premultiplied() {
+        let red = Color::rgb(1.0, 0.0, 0.0);
+        let blue = Color::rgb(0.0, 0.0, 1.0);
+        assert_close(red.lerp(blue, 0.5), Color::rgb(0.5, 0.0, 0.5));
+        assert_eq!(red.lerp(blue, 0.0), red);
+        // Fading in from transparent keeps the color.
+        let clear = Color::new(0.0, 0.0, 0.0, 0.0);
+        assert_close(clear.lerp(red, 0.5), red.with_alpha(0.5));
+
+        let color = Color::new(0.8, 0.4, 0.2, 0.5);
+        assert_close(color.premultiplied(), Color::new(0.4, 0.2, 0.1, 0.5));
+        assert_close(color.premultiplied().unpremultiplied(), color);
+        assert_eq!(clear.unpremultiplied(), clear);
+    }
+}
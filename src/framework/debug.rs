@@ -0,0 +1,297 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+
+use super::layout::track_child_layout;
+use super::widgets::Widget;
+use super::{BoxConstraints, Canvas, Color, EdgeInsets, LayoutError, Rect, Size};
+
+/// The thickness of the overflow indicator in logical pixels.
+const INDICATOR_THICKNESS: f32 = 8.0;
+
+/// The length of a single stripe of the overflow indicator in logical pixels.
+const STRIPE_LENGTH: f32 = 6.0;
+
+/// Sizes may exceed their constraints by this much before being reported,
+/// to absorb floating point rounding.
+const OVERFLOW_TOLERANCE: f32 = 0.01;
+
+thread_local! {
+    static LAYOUT_PATH: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+    /// The overflows reported since the current or last layout pass started.
+    static OVERFLOW_REPORTS: RefCell<Vec<OverflowReport>> = const { RefCell::new(Vec::new()) };
+    /// The paths of the widgets whose overflow was logged and that still
+    /// overflowed in the last layout pass, so each overflow is logged once.
+    static LOGGED_OVERFLOWS: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
+}
+
+/// OverflowReport describes a widget whose size did not satisfy its constraints.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OverflowReport {
+    /// The path of the widget from the root, e.g. `Application > Container > Text`.
+    pub path: String,
+    /// The constraints the widget was given.
+    pub constraints: BoxConstraints,
+    /// The size the widget reported.
+    pub size: Size,
+}
+
+impl OverflowReport {
+    /// By how much the reported size exceeds the maximum width.
+    pub fn horizontal_overflow(&self) -> f32 {
+        (self.size.width - self.constraints.max_width).max(0.0)
+    }
+
+    /// By how much the reported size exceeds the maximum height.
+    pub fn vertical_overflow(&self) -> f32 {
+        (self.size.height - self.constraints.max_height).max(0.0)
+    }
+
+    /// By how much the reported size falls short of the minimum width.
+    pub fn horizontal_shortfall(&self) -> f32 {
+        (self.constraints.min_width - self.size.width).max(0.0)
+    }
+
+    /// By how much the reported size falls short of the minimum height.
+    pub fn vertical_shortfall(&self) -> f32 {
+        (self.constraints.min_height - self.size.height).max(0.0)
+    }
+}
+
+/// Lay out a child widget, reporting an overflow if its size does not
+/// satisfy the constraints.
+///
+/// Parents should lay out their children through this function so overflowing
+/// widgets can be named by their path in the tree. The reported size is
+/// returned unchanged; it is up to the parent to clip or constrain it.
+///
+/// Laying out a root widget starts a new layout pass, which drops the reports
+/// of the previous pass. An overflow is logged when a widget starts
+/// overflowing, not again for every pass in which it keeps overflowing.
+pub fn layout_child<W: Widget + ?Sized>(child: &mut W, constraints: BoxConstraints) -> Size {
+    let is_root = LAYOUT_PATH.with(|path| {
+        let mut path = path.borrow_mut();
+        path.push(child.debug_name());
+        path.len() == 1
+    });
+    if is_root {
+        take_overflow_reports();
+    }
+    let size = track_child_layout(|| child.layout(constraints));
+    let path = LAYOUT_PATH.with(|path| {
+        let mut path = path.borrow_mut();
+        let joined = path.join(" > ");
+        path.pop();
+        joined
+    });
+
+    let tolerance = OVERFLOW_TOLERANCE;
+    if size.width > constraints.max_width + tolerance
+        || size.height > constraints.max_height + tolerance
+        || size.width < constraints.min_width - tolerance
+        || size.height < constraints.min_height - tolerance
+    {
+        report_overflow(OverflowReport {
+            path,
+            constraints,
+            size,
+        });
+    }
+    if is_root {
+        // Forget the overflows that went away, so they are logged again if
+        // they come back.
+        OVERFLOW_REPORTS.with(|reports| {
+            let reports = reports.borrow();
+            LOGGED_OVERFLOWS.with(|logged| {
+                logged
+                    .borrow_mut()
+                    .retain(|path| reports.iter().any(|report| report.path == *path));
+            });
+        });
+    }
+
+    size
+}
+
+/// Record an overflow, logging it if its widget did not overflow before.
+fn report_overflow(report: OverflowReport) {
+    let first = LOGGED_OVERFLOWS.with(|logged| logged.borrow_mut().insert(report.path.clone()));
+    if first {
+        log::warn!(
+            target: "alula::layout",
+            "layout overflow: path=\"{}\" width={} height={} min_width={} max_width={} min_height={} max_height={}",
+            report.path,
+            report.size.width,
+            report.size.height,
+            report.constraints.min_width,
+            report.constraints.max_width,
+            report.constraints.min_height,
+            report.constraints.max_height,
+        );
+    }
+    OVERFLOW_REPORTS.with(|reports| reports.borrow_mut().push(report));
+}
+
+/// Lay out a root widget and return an error if any widget in the tree overflowed.
+///
+/// The reports of the pass are kept, so the caller can inspect them with
+/// [`take_overflow_reports`].
+///
+/// # Errors
+///
+/// Returns [`LayoutError::Overflow`] if any descendant laid out through
+/// [`layout_child`] reported a size that does not satisfy its constraints.
+pub fn try_layout<W: Widget + ?Sized>(
+    root: &mut W,
+    constraints: BoxConstraints,
+) -> Result<Size, LayoutError> {
+    let size = layout_child(root, constraints);
+    if OVERFLOW_REPORTS.with(|reports| reports.borrow().is_empty()) {
+        Ok(size)
+    } else {
+        Err(LayoutError::Overflow)
+    }
+}
+
+/// Remove and return the overflows reported in the current or last layout
+/// pass.
+pub fn take_overflow_reports() -> Vec<OverflowReport> {
+    OVERFLOW_REPORTS.with(|reports| std::mem::take(&mut *reports.borrow_mut()))
+}
+
+/// By how much a child placed at `child` overflows each edge of `bounds`.
+pub fn overflow_edges(child: Rect, bounds: Rect) -> EdgeInsets {
+    EdgeInsets {
+        left: (bounds.left() - child.left()).max(0.0),
+        top: (bounds.top() - child.top()).max(0.0),
+        right: (child.right() - bounds.right()).max(0.0),
+        bottom: (child.bottom() - bounds.bottom()).max(0.0),
+    }
+}
+
+/// Paint the yellow-and-black striped overflow indicator inside `bounds`
+/// along every edge with a non-zero overflow.
+///
+/// Does nothing in release builds.
+pub fn paint_overflow_indicator(canvas: &mut Canvas, bounds: Rect, overflow: EdgeInsets) {
+    if !cfg!(debug_assertions) {
+        return;
+    }
+    let thickness_x = INDICATOR_THICKNESS.min(bounds.size.width);
+    let thickness_y = INDICATOR_THICKNESS.min(bounds.size.height);
+    if overflow.left > 0.0 {
+        let strip = Rect::new(bounds.left(), bounds.top(), thickness_x, bounds.size.height);
+        paint_stripes(canvas, strip, false);
+    }
+    if overflow.right > 0.0 {
+        let strip = Rect::new(
+            bounds.right() - thickness_x,
+            bounds.top(),
+            thickness_x,
+            bounds.size.height,
+        );
+        paint_stripes(canvas, strip, false);
+    }
+    if overflow.top > 0.0 {
+        let strip = Rect::new(bounds.left(), bounds.top(), bounds.size.width, thickness_y);
+        paint_stripes(canvas, strip, true);
+    }
+    if overflow.bottom > 0.0 {
+        let strip = Rect::new(
+            bounds.left(),
+            bounds.bottom() - thickness_y,
+            bounds.size.width,
+            thickness_y,
+        );
+        paint_stripes(canvas, strip, true);
+    }
+}
+
+/// Fill the strip with alternating yellow and black stripes along its length.
+fn paint_stripes(canvas: &mut Canvas, strip: Rect, horizontal: bool) {
+    let yellow = Color::from_rgba(255, 235, 59, 255);
+    let black = Color::rgb(0.0, 0.0, 0.0);
+    let length = if horizontal {
+        strip.size.width
+    } else {
+        strip.size.height
+    };
+
+    let count = (length / STRIPE_LENGTH).ceil() as usize;
+    for index in 0..count {
+        let start = index as f32 * STRIPE_LENGTH;
+        let stripe = STRIPE_LENGTH.min(length - start);
+        let rect = if horizontal {
+            Rect::new(strip.left() + start, strip.top(), stripe, strip.size.height)
+        } else {
+            Rect::new(strip.left(), strip.top() + start, strip.size.width, stripe)
+        };
+        canvas.fill_rect(rect, if index % 2 == 0 { yellow } else { black });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::{Container, State};
+
+    #[derive(Debug)]
+    struct Fixed(Size);
+
+    impl Widget for Fixed {
+        fn build(&mut self, _state: &mut State<'_>) {}
+
+        fn layout(&mut self, _constraints: BoxConstraints) -> Size {
+            self.0
+        }
+
+        fn paint(&self, _state: &mut State<'_>) {}
+    }
+
+    #[test]
+    fn test_overflow_is_reported_with_path() {
+        let mut root = Container::default().child(Fixed(Size::new(300.0, 10.0)));
+        let result = try_layout(&mut root, BoxConstraints::loose(Size::new(100.0, 100.0)));
+        assert!(matches!(result, Err(LayoutError::Overflow)));
+        let reports = take_overflow_reports();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].path, "Container > Fixed");
+        assert_eq!(reports[0].horizontal_overflow(), 200.0);
+        assert_eq!(reports[0].vertical_overflow(), 0.0);
+
+        // Every pass starts with no reports, so they do not pile up.
+        for _ in 0..3 {
+            layout_child(&mut root, BoxConstraints::loose(Size::new(100.0, 100.0)));
+        }
+        assert_eq!(take_overflow_reports().len(), 1);
+    }
+
+    #[test]
+    fn test_shortfall_is_reported() {
+        let mut root = Fixed(Size::new(30.0, 10.0));
+        let result = try_layout(&mut root, BoxConstraints::tight(Size::new(50.0, 10.0)));
+        assert!(matches!(result, Err(LayoutError::Overflow)));
+        let reports = take_overflow_reports();
+        assert_eq!(reports[0].horizontal_shortfall(), 20.0);
+        assert_eq!(reports[0].vertical_shortfall(), 0.0);
+    }
+
+    #[test]
+    fn test_no_overflow() {
+        let mut root = Container::default().child(Fixed(Size::new(30.0, 10.0)));
+        let result = try_layout(&mut root, BoxConstraints::loose(Size::new(100.0, 100.0)));
+        assert_eq!(result.ok(), Some(Size::new(30.0, 10.0)));
+    }
+
+    #[test]
+    fn test_overflow_indicator() {
+        let bounds = Rect::new(0.0, 0.0, 100.0, 20.0);
+        let child = Rect::new(0.0, 0.0, 150.0, 20.0);
+        let overflow = overflow_edges(child, bounds);
+        assert_eq!(overflow, EdgeInsets::only(0.0, 0.0, 50.0, 0.0));
+
+        let mut canvas = Canvas::new();
+        paint_overflow_indicator(&mut canvas, bounds, overflow);
+        // A 20px high strip on the right edge holds four 6px stripes.
+        assert_eq!(canvas.commands().len(), 4);
+    }
+}
@@ -18,6 +18,18 @@ pub enum Error {
     /// Errors that occur in state management
     #[error("State error: {0}")]
     State(#[from] StateError),
+
+    /// Errors that occur when navigating between pages
+    #[error("Navigation error: {0}")]
+    Navigation(#[from] NavigationError),
+
+    /// Errors that occur when parsing colors
+    #[error("Color error: {0}")]
+    Color(#[from] ColorError),
+
+    /// Errors that occur when loading fonts
+    #[error("Font error: {0}")]
+    Font(#[from] FontError),
 }
 
 /// Errors that can occur during rendering
@@ -76,6 +88,42 @@ pub enum StateError {
     StateNotFound,
 }
 
+/// Errors that can occur when navigating between pages
+#[derive(Debug, Error)]
+pub enum NavigationError {
+    /// No route is registered under the name
+    #[error("Unknown route: {0}")]
+    UnknownRoute(String),
+
+    /// The arguments do not have the type the route expects
+    #[error("Invalid arguments for route: {0}")]
+    InvalidArguments(String),
+}
+
+/// Errors that can occur when parsing colors
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ColorError {
+    /// The text is not a hex color with 3, 4, 6 or 8 digits
+    #[error("Invalid hex color: {0}")]
+    InvalidHex(String),
+
+    /// The text is neither a hex color nor a CSS color name
+    #[error("Unknown color: {0}")]
+    UnknownColor(String),
+}
+
+/// Errors that can occur when loading fonts
+#[derive(Debug, Error)]
+pub enum FontError {
+    /// The font file could not be read
+    #[error("Failed to read font: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The data is not a TrueType or OpenType font
+    #[error("Invalid font data: {0}")]
+    InvalidData(String),
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -130,6 +178,42 @@ mod tests {
         assert_eq!(not_found_error.to_string(), "State not found");
     }
 
+    #[test]
+    fn test_navigation_errors() {
+        let unknown_error = NavigationError::UnknownRoute("/settings".to_string());
+        assert_eq!(unknown_error.to_string(), "Unknown route: /settings");
+
+        let arguments_error = NavigationError::InvalidArguments("/details".to_string());
+        assert_eq!(
+            arguments_error.to_string(),
+            "Invalid arguments for route: /details"
+        );
+    }
+
+    #[test]
+    fn test_color_errors() {
+        let hex_error = ColorError::InvalidHex("#12345".to_string());
+        assert_eq!(hex_error.to_string(), "Invalid hex color: #12345");
+
+        let name_error = ColorError::UnknownColor("blurple".to_string());
+        assert_eq!(name_error.to_string(), "Unknown color: blurple");
+
+        let error: Error = name_error.into();
+        assert!(matches!(error, Error::Color(_)));
+    }
+
+    #[test]
+    fn test_font_errors() {
+        let io_error = FontError::from(std::io::Error::from(std::io::ErrorKind::NotFound));
+        assert!(matches!(io_error, FontError::Io(_)));
+
+        let data_error = FontError::InvalidData("unknown magic".to_string());
+        assert_eq!(data_error.to_string(), "Invalid font data: unknown magic");
+
+        let error: Error = data_error.into();
+        assert!(matches!(error, Error::Font(_)));
+    }
+
     #[test]
     fn test_error_conversion() {
         // Test conversion from RenderError to Error
@@ -0,0 +1,220 @@
+use winit::event::{ElementState, Ime, MouseButton, MouseScrollDelta, WindowEvent};
+use winit::keyboard::{Key, ModifiersState};
+
+use super::Offset;
+
+/// The distance scrolled by one line of a mouse wheel, in logical pixels.
+const LINE_SCROLL_DISTANCE: f32 = 40.0;
+
+/// Event is an input event delivered to widgets.
+///
+/// Pointer positions are in the local coordinate space of the widget receiving
+/// the event; parents translate them before forwarding to their children.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    /// The pointer moved.
+    PointerMove {
+        /// The pointer position.
+        position: Offset,
+    },
+    /// A pointer button was pressed.
+    PointerDown {
+        /// The pointer position.
+        position: Offset,
+        /// The pressed button.
+        button: MouseButton,
+    },
+    /// A pointer button was released.
+    PointerUp {
+        /// The pointer position.
+        position: Offset,
+        /// The released button.
+        button: MouseButton,
+    },
+    /// The pointer left the window.
+    PointerExit,
+    /// The mouse wheel or touchpad scrolled.
+    Scroll {
+        /// The pointer position.
+        position: Offset,
+        /// The distance to scroll the content by in logical pixels, positive
+        /// towards its end, i.e. right and down.
+        delta: Offset,
+    },
+    /// A key was pressed.
+    KeyDown {
+        /// The logical key, taking the keyboard layout into account.
+        key: Key,
+        /// The modifiers held while the key was pressed.
+        modifiers: ModifiersState,
+        /// The text produced by the key press, if any.
+        text: Option<String>,
+    },
+    /// A key was released.
+    KeyUp {
+        /// The logical key, taking the keyboard layout into account.
+        key: Key,
+        /// The modifiers held while the key was released.
+        modifiers: ModifiersState,
+    },
+    /// The held modifier keys changed.
+    ///
+    /// The application keeps the current modifiers in
+    /// [`State::modifiers`](super::State::modifiers), e.g. for Shift+click.
+    ModifiersChanged(ModifiersState),
+    /// An input method composed or committed text.
+    ///
+    /// Only delivered while a widget has requested the input method with
+    /// [`State::request_ime`](super::State::request_ime).
+    Ime(Ime),
+}
+
+impl Event {
+    /// The pointer position of the event, if it is a positioned pointer event.
+    pub fn position(&self) -> Option<Offset> {
+        match self {
+            Event::PointerMove { position }
+            | Event::PointerDown { position, .. }
+            | Event::PointerUp { position, .. }
+            | Event::Scroll { position, .. } => Some(*position),
+            _ => None,
+        }
+    }
+
+    /// Returns the event as seen by a child placed at the given offset.
+    pub fn to_local(&self, offset: Offset) -> Event {
+        let mut event = self.clone();
+        match &mut event {
+            Event::PointerMove { position }
+            | Event::PointerDown { position, .. }
+            | Event::PointerUp { position, .. }
+            | Event::Scroll { position, .. } => {
+                position.x -= offset.x;
+                position.y -= offset.y;
+            }
+            _ => {}
+        }
+        event
+    }
+
+    /// Returns the event as seen by a child scaled by the given factors.
+    pub fn to_scaled(&self, scale_x: f32, scale_y: f32) -> Event {
+        let mut event = self.clone();
+        match &mut event {
+            Event::PointerMove { position }
+            | Event::PointerDown { position, .. }
+            | Event::PointerUp { position, .. }
+            | Event::Scroll { position, .. } => {
+                if scale_x != 0.0 && scale_y != 0.0 {
+                    position.x /= scale_x;
+                    position.y /= scale_y;
+                }
+            }
+            _ => {}
+        }
+        event
+    }
+}
+
+/// EventTranslator converts winit window events into framework events.
+///
+/// It keeps track of the pointer position and modifiers, which winit reports
+/// separately from the events that need them.
+#[derive(Debug, Clone, Copy)]
+pub struct EventTranslator {
+    scale_factor: f64,
+    position: Offset,
+    modifiers: ModifiersState,
+}
+
+impl Default for EventTranslator {
+    fn default() -> Self {
+        EventTranslator {
+            scale_factor: 1.0,
+            position: Offset::ZERO,
+            modifiers: ModifiersState::empty(),
+        }
+    }
+}
+
+impl EventTranslator {
+    /// Create a new EventTranslator instance for a window with the given scale factor.
+    pub fn new(scale_factor: f64) -> Self {
+        EventTranslator {
+            scale_factor,
+            ..Default::default()
+        }
+    }
+
+    /// The last known pointer position in logical pixels.
+    pub fn pointer_position(&self) -> Offset {
+        self.position
+    }
+
+    /// Translate a window event, returning `None` for events widgets do not receive.
+    pub fn translate(&mut self, event: &WindowEvent) -> Option<Event> {
+        match event {
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                self.scale_factor = *scale_factor;
+                None
+            }
+            WindowEvent::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers.state();
+                Some(Event::ModifiersChanged(self.modifiers))
+            }
+            WindowEvent::CursorMoved { position, .. } => {
+                let position = position.to_logical::<f32>(self.scale_factor);
+                self.position = Offset {
+                    x: position.x,
+                    y: position.y,
+                };
+                Some(Event::PointerMove {
+                    position: self.position,
+                })
+            }
+            WindowEvent::CursorLeft { .. } => Some(Event::PointerExit),
+            WindowEvent::MouseWheel { delta, .. } => {
+                let delta = match delta {
+                    MouseScrollDelta::LineDelta(x, y) => Offset {
+                        x: -x * LINE_SCROLL_DISTANCE,
+                        y: -y * LINE_SCROLL_DISTANCE,
+                    },
+                    MouseScrollDelta::PixelDelta(delta) => {
+                        let delta = delta.to_logical::<f32>(self.scale_factor);
+                        Offset {
+                            x: -delta.x,
+                            y: -delta.y,
+                        }
+                    }
+                };
+                Some(Event::Scroll {
+                    position: self.position,
+                    delta,
+                })
+            }
+            WindowEvent::MouseInput { state, button, .. } => Some(match state {
+                ElementState::Pressed => Event::PointerDown {
+                    position: self.position,
+                    button: *button,
+                },
+                ElementState::Released => Event::PointerUp {
+                    position: self.position,
+                    button: *button,
+                },
+            }),
+            WindowEvent::KeyboardInput { event, .. } => Some(match event.state {
+                ElementState::Pressed => Event::KeyDown {
+                    key: event.logical_key.clone(),
+                    modifiers: self.modifiers,
+                    text: event.text.as_ref().map(ToString::to_string),
+                },
+                ElementState::Released => Event::KeyUp {
+                    key: event.logical_key.clone(),
+                    modifiers: self.modifiers,
+                },
+            }),
+            WindowEvent::Ime(ime) => Some(Event::Ime(ime.clone())),
+            _ => None,
+        }
+    }
+}
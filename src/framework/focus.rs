@@ -0,0 +1,172 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// FocusId identifies a widget that can receive keyboard focus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FocusId(u64);
+
+impl FocusId {
+    /// Create a new unique FocusId instance.
+    pub fn new() -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+        FocusId(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl Default for FocusId {
+    fn default() -> Self {
+        FocusId::new()
+    }
+}
+
+/// FocusManager tracks which widget has keyboard focus.
+///
+/// Focusable widgets register themselves while painting, so the traversal
+/// order used by Tab and Shift+Tab follows the order of the widget tree. A
+/// focus scope, such as a modal dialog, traps traversal inside the widgets
+/// registered after it began.
+#[derive(Debug, Default)]
+pub struct FocusManager {
+    focused: Option<FocusId>,
+    order: Vec<FocusId>,
+    scoped: bool,
+}
+
+impl FocusManager {
+    /// Create a new FocusManager instance with nothing focused.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget the traversal order before the widgets register again.
+    pub fn begin_frame(&mut self) {
+        self.order.clear();
+        self.scoped = false;
+    }
+
+    /// Begin a focus scope, leaving the widgets registered so far out of the
+    /// traversal order.
+    pub fn begin_scope(&mut self) {
+        self.order.clear();
+        self.scoped = true;
+    }
+
+    /// Move focus into the current scope if it is on a widget outside of it.
+    pub fn end_scope(&mut self) {
+        if !self.scoped {
+            return;
+        }
+        let inside = self
+            .focused
+            .is_some_and(|focused| self.order.contains(&focused));
+        if !inside {
+            self.focused = self.order.first().copied();
+        }
+    }
+
+    /// Register a focusable widget in traversal order.
+    pub fn register(&mut self, id: FocusId) {
+        self.order.push(id);
+    }
+
+    /// The focusable widgets registered so far in this frame, in traversal order.
+    pub fn order(&self) -> &[FocusId] {
+        &self.order
+    }
+
+    /// The widget that currently has focus, if any.
+    pub fn focused(&self) -> Option<FocusId> {
+        self.focused
+    }
+
+    /// Returns true if the widget has focus.
+    pub fn is_focused(&self, id: FocusId) -> bool {
+        self.focused == Some(id)
+    }
+
+    /// Give focus to the widget.
+    pub fn request_focus(&mut self, id: FocusId) {
+        self.focused = Some(id);
+    }
+
+    /// Remove focus from whichever widget has it.
+    pub fn unfocus(&mut self) {
+        self.focused = None;
+    }
+
+    /// Move focus to the next focusable widget, wrapping around at the end.
+    pub fn focus_next(&mut self) {
+        self.move_focus(true);
+    }
+
+    /// Move focus to the previous focusable widget, wrapping around at the start.
+    pub fn focus_previous(&mut self) {
+        self.move_focus(false);
+    }
+
+    fn move_focus(&mut self, forward: bool) {
+        if self.order.is_empty() {
+            return;
+        }
+        let len = self.order.len();
+        let current = self
+            .focused
+            .and_then(|focused| self.order.iter().position(|id| *id == focused));
+        let next = match (current, forward) {
+            (Some(index), true) => (index + 1) % len,
+            (Some(index), false) => (index + len - 1) % len,
+            (None, true) => 0,
+            (None, false) => len - 1,
+        };
+        self.focused = Some(self.order[next]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_focus_traversal() {
+        let first = FocusId::new();
+        let second = FocusId::new();
+        let mut focus = FocusManager::new();
+        focus.register(first);
+        focus.register(second);
+
+        focus.focus_next();
+        assert!(focus.is_focused(first));
+        focus.focus_next();
+        assert!(focus.is_focused(second));
+        focus.focus_next();
+        assert!(focus.is_focused(first));
+        focus.focus_previous();
+        assert!(focus.is_focused(second));
+
+        focus.unfocus();
+        focus.focus_previous();
+        assert!(focus.is_focused(second));
+    }
+
+    #[test]
+    fn test_focus_scope() {
+        let outside = FocusId::new();
+        let first = FocusId::new();
+        let second = FocusId::new();
+        let mut focus = FocusManager::new();
+        focus.request_focus(outside);
+
+        focus.begin_frame();
+        focus.register(outside);
+        focus.begin_scope();
+        focus.register(first);
+        focus.register(second);
+        focus.end_scope();
+        assert!(focus.is_focused(first));
+
+        focus.focus_next();
+        focus.focus_next();
+        assert!(focus.is_focused(first));
+        focus.focus_previous();
+        assert!(focus.is_focused(second));
+    }
+}
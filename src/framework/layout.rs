@@ -1,5 +1,9 @@
+use super::error::LayoutError;
+use std::cell::{Cell, RefCell};
+use std::rc::{Rc, Weak};
+
 /// Size is a 2D size.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
 pub struct Size {
     /// The width of the size in logical pixels.
     pub width: f32,
@@ -7,8 +11,32 @@ pub struct Size {
     pub height: f32,
 }
 
+impl Size {
+    /// A size with zero width and height.
+    pub const ZERO: Size = Size {
+        width: 0.0,
+        height: 0.0,
+    };
+
+    /// A size that is infinitely large in both dimensions.
+    pub const INFINITE: Size = Size {
+        width: f32::INFINITY,
+        height: f32::INFINITY,
+    };
+
+    /// Create a new Size instance.
+    pub fn new(width: f32, height: f32) -> Self {
+        Size { width, height }
+    }
+
+    /// Returns true if both dimensions are finite.
+    pub fn is_finite(&self) -> bool {
+        self.width.is_finite() && self.height.is_finite()
+    }
+}
+
 /// Offset is a 2D offset.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
 pub struct Offset {
     /// The horizontal offset in logical pixels.
     pub x: f32,
@@ -16,8 +44,288 @@ pub struct Offset {
     pub y: f32,
 }
 
+impl Offset {
+    /// An offset of zero in both directions.
+    pub const ZERO: Offset = Offset { x: 0.0, y: 0.0 };
+
+    /// Create a new Offset instance.
+    pub fn new(x: f32, y: f32) -> Self {
+        Offset { x, y }
+    }
+}
+
+/// EdgeInsets is a set of offsets for each of the four edges of a box.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct EdgeInsets {
+    /// The offset from the left edge.
+    pub left: f32,
+    /// The offset from the top edge.
+    pub top: f32,
+    /// The offset from the right edge.
+    pub right: f32,
+    /// The offset from the bottom edge.
+    pub bottom: f32,
+}
+
+impl EdgeInsets {
+    /// Insets of zero on every edge.
+    pub const ZERO: EdgeInsets = EdgeInsets {
+        left: 0.0,
+        top: 0.0,
+        right: 0.0,
+        bottom: 0.0,
+    };
+
+    /// Create insets with the same value on every edge.
+    pub fn all(value: f32) -> Self {
+        EdgeInsets {
+            left: value,
+            top: value,
+            right: value,
+            bottom: value,
+        }
+    }
+
+    /// Create insets with the given horizontal (left and right) and
+    /// vertical (top and bottom) values.
+    pub fn symmetric(horizontal: f32, vertical: f32) -> Self {
+        EdgeInsets {
+            left: horizontal,
+            top: vertical,
+            right: horizontal,
+            bottom: vertical,
+        }
+    }
+
+    /// Create insets with only the given values non-zero.
+    pub fn only(left: f32, top: f32, right: f32, bottom: f32) -> Self {
+        EdgeInsets {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+
+    /// The offset of the top-left corner of the inset content.
+    pub fn top_left(&self) -> Offset {
+        Offset {
+            x: self.left,
+            y: self.top,
+        }
+    }
+
+    /// Returns the size with the insets added on every side.
+    pub fn inflate_size(&self, size: Size) -> Size {
+        Size {
+            width: size.width + self.horizontal(),
+            height: size.height + self.vertical(),
+        }
+    }
+
+    /// Returns the size with the insets removed from every side, never below zero.
+    pub fn deflate_size(&self, size: Size) -> Size {
+        Size {
+            width: (size.width - self.horizontal()).max(0.0),
+            height: (size.height - self.vertical()).max(0.0),
+        }
+    }
+
+    /// The total horizontal insets (left + right).
+    pub fn horizontal(&self) -> f32 {
+        self.left + self.right
+    }
+
+    /// The total vertical insets (top + bottom).
+    pub fn vertical(&self) -> f32 {
+        self.top + self.bottom
+    }
+}
+
+impl From<f32> for EdgeInsets {
+    fn from(value: f32) -> Self {
+        EdgeInsets::all(value)
+    }
+}
+
+/// TextDirection is the direction in which text and directional layout flow.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum TextDirection {
+    /// Left-to-right, the start edge is on the left.
+    #[default]
+    Ltr,
+    /// Right-to-left, the start edge is on the right.
+    Rtl,
+}
+
+/// EdgeInsetsDirectional is a set of offsets whose horizontal edges depend on
+/// the text direction.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct EdgeInsetsDirectional {
+    /// The offset from the start edge (left in LTR, right in RTL).
+    pub start: f32,
+    /// The offset from the top edge.
+    pub top: f32,
+    /// The offset from the end edge (right in LTR, left in RTL).
+    pub end: f32,
+    /// The offset from the bottom edge.
+    pub bottom: f32,
+}
+
+impl EdgeInsetsDirectional {
+    /// Create directional insets with only the given values non-zero.
+    pub fn only(start: f32, top: f32, end: f32, bottom: f32) -> Self {
+        EdgeInsetsDirectional {
+            start,
+            top,
+            end,
+            bottom,
+        }
+    }
+
+    /// Convert to absolute insets for the given text direction.
+    pub fn resolve(&self, direction: TextDirection) -> EdgeInsets {
+        let (left, right) = match direction {
+            TextDirection::Ltr => (self.start, self.end),
+            TextDirection::Rtl => (self.end, self.start),
+        };
+        EdgeInsets {
+            left,
+            top: self.top,
+            right,
+            bottom: self.bottom,
+        }
+    }
+}
+
+/// EdgeInsetsGeometry is either absolute or direction-dependent insets.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EdgeInsetsGeometry {
+    /// Insets with fixed left and right edges.
+    Absolute(EdgeInsets),
+    /// Insets whose left and right edges depend on the text direction.
+    Directional(EdgeInsetsDirectional),
+}
+
+impl Default for EdgeInsetsGeometry {
+    fn default() -> Self {
+        EdgeInsetsGeometry::Absolute(EdgeInsets::ZERO)
+    }
+}
+
+impl EdgeInsetsGeometry {
+    /// Convert to absolute insets for the given text direction.
+    pub fn resolve(&self, direction: TextDirection) -> EdgeInsets {
+        match self {
+            EdgeInsetsGeometry::Absolute(insets) => *insets,
+            EdgeInsetsGeometry::Directional(insets) => insets.resolve(direction),
+        }
+    }
+}
+
+impl From<f32> for EdgeInsetsGeometry {
+    fn from(value: f32) -> Self {
+        EdgeInsetsGeometry::Absolute(EdgeInsets::all(value))
+    }
+}
+
+impl From<EdgeInsets> for EdgeInsetsGeometry {
+    fn from(insets: EdgeInsets) -> Self {
+        EdgeInsetsGeometry::Absolute(insets)
+    }
+}
+
+impl From<EdgeInsetsDirectional> for EdgeInsetsGeometry {
+    fn from(insets: EdgeInsetsDirectional) -> Self {
+        EdgeInsetsGeometry::Directional(insets)
+    }
+}
+
+/// Rect is an axis-aligned rectangle.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Rect {
+    /// The position of the top-left corner in logical pixels.
+    pub origin: Offset,
+    /// The size of the rectangle in logical pixels.
+    pub size: Size,
+}
+
+impl Rect {
+    /// Create a new Rect instance from its top-left corner and size.
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Rect {
+            origin: Offset { x, y },
+            size: Size { width, height },
+        }
+    }
+
+    /// Create a new Rect instance at the origin with the given size.
+    pub fn from_size(size: Size) -> Self {
+        Rect {
+            origin: Offset::ZERO,
+            size,
+        }
+    }
+
+    /// The x coordinate of the left edge.
+    pub fn left(&self) -> f32 {
+        self.origin.x
+    }
+
+    /// The y coordinate of the top edge.
+    pub fn top(&self) -> f32 {
+        self.origin.y
+    }
+
+    /// The x coordinate of the right edge.
+    pub fn right(&self) -> f32 {
+        self.origin.x + self.size.width
+    }
+
+    /// The y coordinate of the bottom edge.
+    pub fn bottom(&self) -> f32 {
+        self.origin.y + self.size.height
+    }
+
+    /// Returns true if the rectangle has no area.
+    pub fn is_empty(&self) -> bool {
+        self.size.width <= 0.0 || self.size.height <= 0.0
+    }
+
+    /// Returns true if the point lies inside the rectangle.
+    pub fn contains(&self, point: Offset) -> bool {
+        point.x >= self.left()
+            && point.x < self.right()
+            && point.y >= self.top()
+            && point.y < self.bottom()
+    }
+
+    /// Returns the rectangle moved by the given offset.
+    pub fn translate(&self, offset: Offset) -> Self {
+        Rect {
+            origin: Offset {
+                x: self.origin.x + offset.x,
+                y: self.origin.y + offset.y,
+            },
+            size: self.size,
+        }
+    }
+
+    /// Returns the area covered by both rectangles, which is empty if they
+    /// do not overlap.
+    pub fn intersect(&self, other: Rect) -> Self {
+        let left = self.left().max(other.left());
+        let top = self.top().max(other.top());
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+        Rect::new(left, top, (right - left).max(0.0), (bottom - top).max(0.0))
+    }
+}
+
 /// BoxConstraints is a set of constraints for a widget's size.
-#[derive(Debug, Clone, Copy)]
+///
+/// A maximum of `f32::INFINITY` means the dimension is unbounded.
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct BoxConstraints {
     /// The minimum width that satisfies the constraints.
     pub min_width: f32,
@@ -29,7 +337,32 @@ pub struct BoxConstraints {
     pub max_height: f32,
 }
 
+impl Default for BoxConstraints {
+    /// Unconstrained in both dimensions.
+    fn default() -> Self {
+        BoxConstraints::UNBOUNDED
+    }
+}
+
 impl BoxConstraints {
+    /// Constraints that allow any size, from zero to infinity.
+    pub const UNBOUNDED: BoxConstraints = BoxConstraints {
+        min_width: 0.0,
+        max_width: f32::INFINITY,
+        min_height: 0.0,
+        max_height: f32::INFINITY,
+    };
+
+    /// Create a new BoxConstraints instance.
+    pub fn new(min_width: f32, max_width: f32, min_height: f32, max_height: f32) -> Self {
+        BoxConstraints {
+            min_width,
+            max_width,
+            min_height,
+            max_height,
+        }
+    }
+
     /// Create a new BoxConstraints instance with tight constraints.
     pub fn tight(size: Size) -> Self {
         BoxConstraints {
@@ -40,6 +373,16 @@ impl BoxConstraints {
         }
     }
 
+    /// Create constraints that are tight in the given dimensions and unbounded otherwise.
+    pub fn tight_for(width: Option<f32>, height: Option<f32>) -> Self {
+        BoxConstraints {
+            min_width: width.unwrap_or(0.0),
+            max_width: width.unwrap_or(f32::INFINITY),
+            min_height: height.unwrap_or(0.0),
+            max_height: height.unwrap_or(f32::INFINITY),
+        }
+    }
+
     /// Create a new BoxConstraints instance with loose constraints.
     pub fn loose(size: Size) -> Self {
         BoxConstraints {
@@ -49,10 +392,448 @@ impl BoxConstraints {
             max_height: size.height,
         }
     }
+
+    /// Create constraints that force the given dimensions, or an infinite
+    /// size when a dimension is `None`.
+    ///
+    /// An infinite minimum does not pass [`BoxConstraints::validate`], so
+    /// [`BoxConstraints::enforce`] these against the incoming constraints,
+    /// which turns an infinite dimension into their maximum.
+    pub fn expand(width: Option<f32>, height: Option<f32>) -> Self {
+        let width = width.unwrap_or(f32::INFINITY);
+        let height = height.unwrap_or(f32::INFINITY);
+        BoxConstraints {
+            min_width: width,
+            max_width: width,
+            min_height: height,
+            max_height: height,
+        }
+    }
+
+    /// Returns the width that best satisfies the constraints.
+    pub fn constrain_width(&self, width: f32) -> f32 {
+        width.max(self.min_width).min(self.max_width)
+    }
+
+    /// Returns the height that best satisfies the constraints.
+    pub fn constrain_height(&self, height: f32) -> f32 {
+        height.max(self.min_height).min(self.max_height)
+    }
+
+    /// Returns the size that best satisfies the constraints.
+    pub fn constrain(&self, size: Size) -> Size {
+        Size {
+            width: self.constrain_width(size.width),
+            height: self.constrain_height(size.height),
+        }
+    }
+
+    /// Returns the size that best satisfies the constraints while keeping the
+    /// aspect ratio of the given size where possible.
+    pub fn constrain_preserving_aspect_ratio(&self, size: Size) -> Size {
+        if self.is_tight() || size.width <= 0.0 || size.height <= 0.0 {
+            return self.constrain(size);
+        }
+        let ratio = size.width / size.height;
+        let (mut width, mut height) = (size.width, size.height);
+        if width > self.max_width {
+            width = self.max_width;
+            height = width / ratio;
+        }
+        if height > self.max_height {
+            height = self.max_height;
+            width = height * ratio;
+        }
+        if width < self.min_width {
+            width = self.min_width;
+            height = width / ratio;
+        }
+        if height < self.min_height {
+            height = self.min_height;
+            width = height * ratio;
+        }
+        self.constrain(Size { width, height })
+    }
+
+    /// Returns new constraints shrunk by the given insets.
+    ///
+    /// The resulting constraints never go below zero, and unbounded
+    /// maximums stay unbounded.
+    pub fn deflate(&self, insets: EdgeInsets) -> Self {
+        let horizontal = insets.horizontal();
+        let vertical = insets.vertical();
+        let min_width = (self.min_width - horizontal).max(0.0);
+        let min_height = (self.min_height - vertical).max(0.0);
+        BoxConstraints {
+            min_width,
+            max_width: (self.max_width - horizontal).max(min_width),
+            min_height,
+            max_height: (self.max_height - vertical).max(min_height),
+        }
+    }
+
+    /// Returns new constraints with the same maximums but no minimums.
+    pub fn loosen(&self) -> Self {
+        BoxConstraints {
+            min_width: 0.0,
+            max_width: self.max_width,
+            min_height: 0.0,
+            max_height: self.max_height,
+        }
+    }
+
+    /// Returns new constraints that are tight in the given dimensions,
+    /// as close as possible to the given values while respecting these constraints.
+    pub fn tighten(&self, width: Option<f32>, height: Option<f32>) -> Self {
+        let (min_width, max_width) = match width {
+            Some(width) => {
+                let width = self.constrain_width(width);
+                (width, width)
+            }
+            None => (self.min_width, self.max_width),
+        };
+        let (min_height, max_height) = match height {
+            Some(height) => {
+                let height = self.constrain_height(height);
+                (height, height)
+            }
+            None => (self.min_height, self.max_height),
+        };
+        BoxConstraints {
+            min_width,
+            max_width,
+            min_height,
+            max_height,
+        }
+    }
+
+    /// Returns new constraints that respect these constraints while staying
+    /// as close as possible to the given constraints.
+    pub fn enforce(&self, constraints: BoxConstraints) -> Self {
+        BoxConstraints {
+            min_width: self
+                .min_width
+                .max(constraints.min_width)
+                .min(constraints.max_width),
+            max_width: self
+                .max_width
+                .max(constraints.min_width)
+                .min(constraints.max_width),
+            min_height: self
+                .min_height
+                .max(constraints.min_height)
+                .min(constraints.max_height),
+            max_height: self
+                .max_height
+                .max(constraints.min_height)
+                .min(constraints.max_height),
+        }
+    }
+
+    /// Returns true if exactly one width and one height satisfy the constraints.
+    pub fn is_tight(&self) -> bool {
+        self.min_width >= self.max_width && self.min_height >= self.max_height
+    }
+
+    /// Returns true if the maximum width is finite.
+    pub fn has_bounded_width(&self) -> bool {
+        self.max_width.is_finite()
+    }
+
+    /// Returns true if the maximum height is finite.
+    pub fn has_bounded_height(&self) -> bool {
+        self.max_height.is_finite()
+    }
+
+    /// Returns true if the minimum width is infinite.
+    pub fn has_infinite_width(&self) -> bool {
+        self.min_width.is_infinite()
+    }
+
+    /// Returns true if the minimum height is infinite.
+    pub fn has_infinite_height(&self) -> bool {
+        self.min_height.is_infinite()
+    }
+
+    /// The biggest size that satisfies the constraints.
+    ///
+    /// Unbounded dimensions are reported as `f32::INFINITY`.
+    pub fn biggest(&self) -> Size {
+        self.constrain(Size::INFINITE)
+    }
+
+    /// The smallest size that satisfies the constraints.
+    pub fn smallest(&self) -> Size {
+        self.constrain(Size::ZERO)
+    }
+
+    /// Returns true if the size satisfies the constraints.
+    pub fn is_satisfied_by(&self, size: Size) -> bool {
+        size.width >= self.min_width
+            && size.width <= self.max_width
+            && size.height >= self.min_height
+            && size.height <= self.max_height
+    }
+
+    /// Returns true if the constraints are well formed: non-negative,
+    /// not NaN, finite minimums, and minimums not exceeding maximums.
+    pub fn is_normalized(&self) -> bool {
+        self.validate().is_ok()
+    }
+
+    /// Returns well-formed constraints that are as close as possible to these.
+    ///
+    /// NaN and negative values become zero, and each minimum is capped at its maximum.
+    pub fn normalize(&self) -> Self {
+        let sanitize = |value: f32| if value.is_nan() { 0.0 } else { value.max(0.0) };
+        let max_width = sanitize(self.max_width);
+        let max_height = sanitize(self.max_height);
+        let min_width = sanitize(self.min_width);
+        let min_height = sanitize(self.min_height);
+        BoxConstraints {
+            min_width: if min_width.is_finite() {
+                min_width.min(max_width)
+            } else {
+                0.0
+            },
+            max_width,
+            min_height: if min_height.is_finite() {
+                min_height.min(max_height)
+            } else {
+                0.0
+            },
+            max_height,
+        }
+    }
+
+    /// Check that the constraints are well formed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LayoutError::InvalidConstraints`] if any value is negative or NaN,
+    /// if a minimum is infinite, or if a minimum exceeds its maximum.
+    pub fn validate(&self) -> Result<(), LayoutError> {
+        let values = [
+            ("min_width", self.min_width),
+            ("max_width", self.max_width),
+            ("min_height", self.min_height),
+            ("max_height", self.max_height),
+        ];
+        for (name, value) in values {
+            if value.is_nan() {
+                return Err(LayoutError::InvalidConstraints(format!("{name} is NaN")));
+            }
+            if value < 0.0 {
+                return Err(LayoutError::InvalidConstraints(format!(
+                    "{name} cannot be negative ({value})"
+                )));
+            }
+        }
+        if self.min_width.is_infinite() || self.min_height.is_infinite() {
+            return Err(LayoutError::InvalidConstraints(
+                "minimums must be finite".to_string(),
+            ));
+        }
+        if self.min_width > self.max_width {
+            return Err(LayoutError::InvalidConstraints(format!(
+                "min_width ({}) exceeds max_width ({})",
+                self.min_width, self.max_width
+            )));
+        }
+        if self.min_height > self.max_height {
+            return Err(LayoutError::InvalidConstraints(format!(
+                "min_height ({}) exceeds max_height ({})",
+                self.min_height, self.max_height
+            )));
+        }
+        Ok(())
+    }
+
+    /// Validate the constraints in debug builds only.
+    ///
+    /// Release builds skip the checks and always return `Ok`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`BoxConstraints::validate`] in debug builds.
+    pub fn debug_validate(&self) -> Result<(), LayoutError> {
+        if cfg!(debug_assertions) {
+            self.validate()
+        } else {
+            Ok(())
+        }
+    }
+}
+
+thread_local! {
+    /// The node of the widget that last looked up its layout cache, which is
+    /// the parent of the caches looked up by the children it lays out.
+    static CURRENT_NODE: RefCell<Weak<LayoutNode>> = const { RefCell::new(Weak::new()) };
+}
+
+/// The dirty flags of a widget that caches its layout, linked to the node of
+/// the nearest ancestor that does.
+#[derive(Debug, Default)]
+struct LayoutNode {
+    /// Whether the widget must be laid out again.
+    dirty: Cell<bool>,
+    /// Whether a relayout boundary below must be laid out again, although
+    /// the widget keeps its size.
+    dirty_descendant: Cell<bool>,
+    /// Whether a child without a cache was laid out, which may change at any
+    /// time.
+    uncached_child: Cell<bool>,
+    /// Whether the last layout used tight constraints.
+    boundary: Cell<bool>,
+    parent: RefCell<Weak<LayoutNode>>,
+}
+
+impl LayoutNode {
+    /// Mark the node dirty and tell its ancestors: the ones whose size may
+    /// change must be laid out again, the ones above the first relayout
+    /// boundary only need to visit their children.
+    fn mark_dirty(&self) {
+        if self.dirty.replace(true) {
+            return;
+        }
+        let mut relayout = !self.boundary.get();
+        let mut parent = self.parent.borrow().upgrade();
+        while let Some(node) = parent {
+            if relayout {
+                if node.dirty.replace(true) {
+                    break;
+                }
+                relayout = !node.boundary.get();
+            } else if node.dirty.get() || node.dirty_descendant.replace(true) {
+                break;
+            }
+            parent = node.parent.borrow().upgrade();
+        }
+    }
+}
+
+/// Lay out a child, linking the layout caches it looks up to the cache of
+/// the widget laying it out.
+///
+/// The widget is marked as needing layout every time if the child has no
+/// cache, since nothing would tell it when the child changes.
+pub(crate) fn track_child_layout<R>(layout: impl FnOnce() -> R) -> R {
+    let outer = CURRENT_NODE.with(|current| current.borrow().clone());
+    let result = layout();
+    let inner = CURRENT_NODE.with(|current| current.replace(outer.clone()));
+    if Weak::ptr_eq(&inner, &outer) {
+        if let Some(node) = outer.upgrade() {
+            node.uncached_child.set(true);
+        }
+    }
+    result
+}
+
+/// LayoutCache stores the result of a widget's last layout.
+///
+/// A widget that owns a cache can skip its layout when it is laid out again
+/// with the same constraints and neither it nor a descendant affecting its
+/// size has been marked dirty since. Marking a cache dirty marks the caches
+/// of the ancestors right away, so checking a cache never visits the
+/// descendants.
+#[derive(Debug, Default)]
+pub struct LayoutCache {
+    constraints: Option<BoxConstraints>,
+    size: Size,
+    node: Rc<LayoutNode>,
+}
+
+impl LayoutCache {
+    /// Returns the cached size if the cache is clean and was computed for the
+    /// same constraints.
+    ///
+    /// Widgets look up their cache before laying out their children, which
+    /// links the caches of the children to this one.
+    pub fn get(&self, constraints: BoxConstraints) -> Option<Size> {
+        CURRENT_NODE.with(|current| {
+            let mut current = current.borrow_mut();
+            let node = Rc::downgrade(&self.node);
+            if !Weak::ptr_eq(&current, &node) {
+                *self.node.parent.borrow_mut() = std::mem::replace(&mut *current, node);
+            }
+        });
+        let hit = !self.is_dirty() && self.constraints == Some(constraints);
+        if !hit {
+            // Laying out the children finds out again whether one has no cache.
+            self.node.uncached_child.set(false);
+        }
+        hit.then_some(self.size)
+    }
+
+    /// Store the result of a layout and mark the cache clean.
+    pub fn store(&mut self, constraints: BoxConstraints, size: Size) {
+        self.constraints = Some(constraints);
+        self.size = size;
+        self.node.dirty.set(false);
+        self.node.dirty_descendant.set(false);
+        self.node.boundary.set(constraints.is_tight());
+    }
+
+    /// Mark the cached result as stale.
+    pub fn mark_dirty(&mut self) {
+        self.node.mark_dirty();
+    }
+
+    /// A handle that marks the cache dirty from outside the widget, e.g.
+    /// from a controller shared with the application.
+    pub fn marker(&self) -> LayoutMarker {
+        LayoutMarker(Rc::downgrade(&self.node))
+    }
+
+    /// Returns true if the widget must be laid out again.
+    pub fn is_dirty(&self) -> bool {
+        self.node.dirty.get() || self.node.uncached_child.get() || self.constraints.is_none()
+    }
+
+    /// Returns true if a relayout boundary below must be laid out again,
+    /// and forgets it.
+    ///
+    /// Widgets that skip their layout lay out their children again with the
+    /// same constraints if this returns true, so the boundaries are reached.
+    pub fn take_dirty_descendant(&self) -> bool {
+        self.node.dirty_descendant.replace(false)
+    }
+
+    /// The constraints of the last layout, if any.
+    pub fn constraints(&self) -> Option<BoxConstraints> {
+        self.constraints
+    }
+
+    /// The size of the last layout.
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Returns true if the last layout used tight constraints.
+    ///
+    /// The size of such a widget cannot change when it is laid out again, so
+    /// changes inside it never require its parent to be laid out again.
+    pub fn is_relayout_boundary(&self) -> bool {
+        self.constraints
+            .is_some_and(|constraints| constraints.is_tight())
+    }
+}
+
+/// LayoutMarker marks a [`LayoutCache`] dirty without access to its widget.
+#[derive(Debug, Clone, Default)]
+pub struct LayoutMarker(Weak<LayoutNode>);
+
+impl LayoutMarker {
+    /// Mark the cache as stale, if its widget still exists.
+    pub fn mark_dirty(&self) {
+        if let Some(node) = self.0.upgrade() {
+            node.mark_dirty();
+        }
+    }
 }
 
 /// Alignment is a set of alignments for a widget's position.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub enum Alignment {
     /// Align to the top-left corner.
     TopLeft,
@@ -63,6 +844,7 @@ pub enum Alignment {
     /// Align to the center-left.
     CenterLeft,
     /// Align to the center.
+    #[default]
     Center,
     /// Align to the center-right.
     CenterRight,
@@ -72,4 +854,277 @@ pub enum Alignment {
     BottomCenter,
     /// Align to the bottom-right corner.
     BottomRight,
-}
\ No newline at end of file
+    /// Align to the top corner on the start side.
+    TopStart,
+    /// Align to the top corner on the end side.
+    TopEnd,
+    /// Align to the center of the start side.
+    CenterStart,
+    /// Align to the center of the end side.
+    CenterEnd,
+    /// Align to the bottom corner on the start side.
+    BottomStart,
+    /// Align to the bottom corner on the end side.
+    BottomEnd,
+}
+
+impl Alignment {
+    /// Returns true if the alignment is relative to the start or end side,
+    /// which depends on the text direction.
+    pub fn is_directional(&self) -> bool {
+        matches!(
+            self,
+            Alignment::TopStart
+                | Alignment::TopEnd
+                | Alignment::CenterStart
+                | Alignment::CenterEnd
+                | Alignment::BottomStart
+                | Alignment::BottomEnd
+        )
+    }
+
+    /// Convert to an absolute alignment for the given text direction.
+    pub fn resolve(&self, direction: TextDirection) -> Alignment {
+        let rtl = direction == TextDirection::Rtl;
+        match (self, rtl) {
+            (Alignment::TopStart, false) | (Alignment::TopEnd, true) => Alignment::TopLeft,
+            (Alignment::TopStart, true) | (Alignment::TopEnd, false) => Alignment::TopRight,
+            (Alignment::CenterStart, false) | (Alignment::CenterEnd, true) => Alignment::CenterLeft,
+            (Alignment::CenterStart, true) | (Alignment::CenterEnd, false) => {
+                Alignment::CenterRight
+            }
+            (Alignment::BottomStart, false) | (Alignment::BottomEnd, true) => Alignment::BottomLeft,
+            (Alignment::BottomStart, true) | (Alignment::BottomEnd, false) => {
+                Alignment::BottomRight
+            }
+            (alignment, _) => *alignment,
+        }
+    }
+
+    /// The horizontal and vertical position as fractions of the free space,
+    /// where 0.0 is the left/top edge and 1.0 is the right/bottom edge.
+    ///
+    /// Directional alignments are taken as left-to-right; see
+    /// [`Alignment::resolve`].
+    pub fn factors(&self) -> (f32, f32) {
+        match self {
+            Alignment::TopLeft => (0.0, 0.0),
+            Alignment::TopCenter => (0.5, 0.0),
+            Alignment::TopRight => (1.0, 0.0),
+            Alignment::CenterLeft => (0.0, 0.5),
+            Alignment::Center => (0.5, 0.5),
+            Alignment::CenterRight => (1.0, 0.5),
+            Alignment::BottomLeft => (0.0, 1.0),
+            Alignment::BottomCenter => (0.5, 1.0),
+            Alignment::BottomRight => (1.0, 1.0),
+            Alignment::TopStart => (0.0, 0.0),
+            Alignment::TopEnd => (1.0, 0.0),
+            Alignment::CenterStart => (0.0, 0.5),
+            Alignment::CenterEnd => (1.0, 0.5),
+            Alignment::BottomStart => (0.0, 1.0),
+            Alignment::BottomEnd => (1.0, 1.0),
+        }
+    }
+
+    /// The offset at which to place a child of the given size inside a
+    /// container of the given size.
+    pub fn align(&self, child: Size, container: Size) -> Offset {
+        let (x, y) = self.factors();
+        Offset {
+            x: (container.width - child.width) * x,
+            y: (container.height - child.height) * y,
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constrain() {
+        let constraints = BoxConstraints::new(10.0, 100.0, 20.0, 50.0);
+        assert_eq!(
+            constraints.constrain(Size::new(5.0, 80.0)),
+            Size::new(10.0, 50.0)
+        );
+        assert_eq!(
+            constraints.constrain(Size::new(40.0, 30.0)),
+            Size::new(40.0, 30.0)
+        );
+        assert_eq!(constraints.smallest(), Size::new(10.0, 20.0));
+        assert_eq!(constraints.biggest(), Size::new(100.0, 50.0));
+    }
+
+    #[test]
+    fn test_constrain_preserving_aspect_ratio() {
+        let constraints = BoxConstraints::loose(Size::new(100.0, 100.0));
+        assert_eq!(
+            constraints.constrain_preserving_aspect_ratio(Size::new(200.0, 100.0)),
+            Size::new(100.0, 50.0)
+        );
+        assert_eq!(
+            constraints.constrain_preserving_aspect_ratio(Size::new(50.0, 400.0)),
+            Size::new(12.5, 100.0)
+        );
+    }
+
+    #[test]
+    fn test_deflate() {
+        let constraints = BoxConstraints::new(10.0, 100.0, 0.0, f32::INFINITY);
+        let deflated = constraints.deflate(EdgeInsets::all(8.0));
+        assert_eq!(deflated, BoxConstraints::new(0.0, 84.0, 0.0, f32::INFINITY));
+
+        // Padding larger than the available space must not produce negative constraints.
+        let deflated = BoxConstraints::tight(Size::new(10.0, 10.0)).deflate(EdgeInsets::all(20.0));
+        assert!(deflated.validate().is_ok());
+        assert_eq!(deflated.biggest(), Size::ZERO);
+    }
+
+    #[test]
+    fn test_loosen_tighten_enforce() {
+        let constraints = BoxConstraints::tight(Size::new(30.0, 40.0));
+        assert!(constraints.is_tight());
+        assert_eq!(
+            constraints.loosen(),
+            BoxConstraints::loose(Size::new(30.0, 40.0))
+        );
+
+        let loose = BoxConstraints::loose(Size::new(100.0, 100.0));
+        let tightened = loose.tighten(Some(150.0), None);
+        assert_eq!(tightened, BoxConstraints::new(100.0, 100.0, 0.0, 100.0));
+
+        let enforced = BoxConstraints::UNBOUNDED.enforce(loose);
+        assert_eq!(enforced, loose);
+        let enforced = BoxConstraints::tight(Size::new(200.0, 5.0)).enforce(loose);
+        assert_eq!(enforced, BoxConstraints::tight(Size::new(100.0, 5.0)));
+    }
+
+    #[test]
+    fn test_unbounded() {
+        let constraints = BoxConstraints::tight_for(Some(50.0), None);
+        assert!(constraints.has_bounded_width());
+        assert!(!constraints.has_bounded_height());
+        assert!(!constraints.has_infinite_height());
+        assert_eq!(constraints.biggest().height, f32::INFINITY);
+        let expand = BoxConstraints::expand(Some(50.0), None);
+        assert!(expand.has_infinite_height());
+        assert!(expand.validate().is_err());
+        let enforced = expand.enforce(BoxConstraints::loose(Size::new(100.0, 80.0)));
+        assert_eq!(enforced, BoxConstraints::tight(Size::new(50.0, 80.0)));
+        assert!(enforced.validate().is_ok());
+    }
+
+    #[test]
+    fn test_edge_insets() {
+        let insets = EdgeInsets::symmetric(4.0, 2.0);
+        assert_eq!(insets, EdgeInsets::only(4.0, 2.0, 4.0, 2.0));
+        assert_eq!(insets.horizontal(), 8.0);
+        assert_eq!(insets.vertical(), 4.0);
+        assert_eq!(
+            insets.inflate_size(Size::new(10.0, 10.0)),
+            Size::new(18.0, 14.0)
+        );
+        assert_eq!(
+            insets.deflate_size(Size::new(6.0, 10.0)),
+            Size::new(0.0, 6.0)
+        );
+
+        let directional = EdgeInsetsDirectional::only(1.0, 2.0, 3.0, 4.0);
+        assert_eq!(
+            directional.resolve(TextDirection::Ltr),
+            EdgeInsets::only(1.0, 2.0, 3.0, 4.0)
+        );
+        assert_eq!(
+            EdgeInsetsGeometry::from(directional).resolve(TextDirection::Rtl),
+            EdgeInsets::only(3.0, 2.0, 1.0, 4.0)
+        );
+    }
+
+    #[test]
+    fn test_rect_intersect() {
+        let rect = Rect::new(0.0, 0.0, 100.0, 50.0);
+        assert_eq!(
+            rect.intersect(Rect::new(50.0, 20.0, 100.0, 100.0)),
+            Rect::new(50.0, 20.0, 50.0, 30.0)
+        );
+        assert!(rect.intersect(Rect::new(200.0, 0.0, 10.0, 10.0)).is_empty());
+    }
+
+    #[test]
+    fn test_alignment() {
+        let child = Size::new(10.0, 20.0);
+        let container = Size::new(100.0, 50.0);
+        assert_eq!(Alignment::TopLeft.align(child, container), Offset::ZERO);
+        assert_eq!(
+            Alignment::Center.align(child, container),
+            Offset::new(45.0, 15.0)
+        );
+        assert_eq!(
+            Alignment::BottomRight.align(child, container),
+            Offset::new(90.0, 30.0)
+        );
+
+        assert_eq!(
+            Alignment::CenterStart.resolve(TextDirection::Ltr),
+            Alignment::CenterLeft
+        );
+        assert_eq!(
+            Alignment::CenterStart.resolve(TextDirection::Rtl),
+            Alignment::CenterRight
+        );
+        assert_eq!(
+            Alignment::BottomEnd.resolve(TextDirection::Rtl),
+            Alignment::BottomLeft
+        );
+        assert_eq!(
+            Alignment::TopRight.resolve(TextDirection::Rtl),
+            Alignment::TopRight
+        );
+        assert!(!Alignment::Center.is_directional());
+    }
+
+    #[test]
+    fn test_layout_cache() {
+        let constraints = BoxConstraints::loose(Size::new(10.0, 10.0));
+        let mut cache = LayoutCache::default();
+        assert!(cache.is_dirty());
+        assert_eq!(cache.get(constraints), None);
+
+        cache.store(constraints, Size::new(5.0, 5.0));
+        assert!(!cache.is_dirty());
+        assert!(!cache.is_relayout_boundary());
+        assert_eq!(cache.get(constraints), Some(Size::new(5.0, 5.0)));
+        assert_eq!(cache.get(BoxConstraints::UNBOUNDED), None);
+
+        cache.mark_dirty();
+        assert_eq!(cache.get(constraints), None);
+
+        cache.store(
+            BoxConstraints::tight(Size::new(5.0, 5.0)),
+            Size::new(5.0, 5.0),
+        );
+        assert!(cache.is_relayout_boundary());
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(BoxConstraints::loose(Size::new(10.0, 10.0))
+            .validate()
+            .is_ok());
+        assert!(BoxConstraints::UNBOUNDED.validate().is_ok());
+
+        let negative = BoxConstraints::new(-1.0, 10.0, 0.0, 10.0);
+        assert!(matches!(
+            negative.validate(),
+            Err(LayoutError::InvalidConstraints(_))
+        ));
+
+        let nan = BoxConstraints::new(0.0, f32::NAN, 0.0, 10.0);
+        assert!(nan.validate().is_err());
+        assert!(nan.normalize().validate().is_ok());
+
+        let inverted = BoxConstraints::new(20.0, 10.0, 0.0, 10.0);
+        assert!(inverted.validate().is_err());
+        assert_eq!(inverted.normalize().min_width, 10.0);
+    }
+}
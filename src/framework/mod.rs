@@ -1,13 +1,37 @@
+mod app;
+mod clipboard;
 pub mod color;
+mod debug;
 mod error;
+mod event;
+mod focus;
 mod layout;
+mod overlay;
+mod paint;
 pub mod render;
 pub mod state;
+mod text;
+mod theme;
 pub mod widgets;
 
-pub use color::Color;
-pub use error::Error;
+pub use app::Application;
+pub use clipboard::{Clipboard, ClipboardProvider};
+pub use color::{Color, ColorSpace};
+pub use debug::{
+    layout_child, overflow_edges, paint_overflow_indicator, take_overflow_reports, try_layout,
+    OverflowReport,
+};
+pub use error::{ColorError, Error, FontError, LayoutError, NavigationError};
+pub use event::{Event, EventTranslator};
+pub use focus::{FocusId, FocusManager};
 pub use layout::*;
+pub use overlay::{Barrier, DialogHandle, Overlay, OverlayId, Placement};
+pub use paint::{Canvas, DrawCommand};
 pub use render::*;
 pub use state::State;
+pub use text::{
+    FontCollection, FontFace, FontId, FontRun, FontStyle, FontWeight, InputFilter, LineMetrics,
+    ParagraphStyle, TextAlign, TextEditor, TextLayout, TextOverflow, TextSelection, TextStyle,
+};
+pub use theme::{Brightness, ColorScheme, Elevation, Radii, Spacing, Theme, Typography};
 pub use widgets::*;
@@ -0,0 +1,725 @@
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use super::widgets::Widget;
+use super::{layout_child, BoxConstraints, Color, Event, FocusId, Offset, Rect, Size, State};
+use winit::keyboard::{Key, NamedKey};
+
+/// OverlayId identifies an entry of the [`Overlay`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OverlayId(u64);
+
+impl OverlayId {
+    fn new() -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+        OverlayId(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// Placement is where an overlay entry is shown relative to its anchor.
+///
+/// Entries that would not fit inside the window are flipped to the other side
+/// of the anchor, and then moved inside the window.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Placement {
+    /// Below the anchor, aligned with its left edge, e.g. for dropdowns.
+    #[default]
+    Below,
+    /// Below the anchor, centered on it, e.g. for tooltips.
+    BelowCenter,
+    /// Right of the anchor, aligned with its top edge, e.g. for submenus.
+    Beside,
+    /// Centered in the window, ignoring the anchor, e.g. for dialogs.
+    Center,
+}
+
+impl Placement {
+    /// The position of an entry of the given size, kept inside the window.
+    pub fn position(self, anchor: Rect, size: Size, window: Size) -> Offset {
+        let (x, y) = match self {
+            Placement::Below => (anchor.left(), Self::below(anchor, size, window)),
+            Placement::BelowCenter => (
+                anchor.left() + (anchor.size.width - size.width) / 2.0,
+                Self::below(anchor, size, window),
+            ),
+            Placement::Beside => {
+                let x = if anchor.right() + size.width > window.width
+                    && anchor.left() - size.width >= 0.0
+                {
+                    anchor.left() - size.width
+                } else {
+                    anchor.right()
+                };
+                (x, anchor.top())
+            }
+            Placement::Center => (
+                (window.width - size.width) / 2.0,
+                (window.height - size.height) / 2.0,
+            ),
+        };
+        Offset::new(
+            x.min(window.width - size.width).max(0.0),
+            y.min(window.height - size.height).max(0.0),
+        )
+    }
+
+    /// The top of an entry below the anchor, or above it if there is no room.
+    fn below(anchor: Rect, size: Size, window: Size) -> f32 {
+        if anchor.bottom() + size.height > window.height && anchor.top() - size.height >= 0.0 {
+            anchor.top() - size.height
+        } else {
+            anchor.bottom()
+        }
+    }
+}
+
+/// Barrier is the scrim painted behind a modal entry of the [`Overlay`].
+///
+/// The barrier covers the window and keeps pointer and key events from
+/// reaching the entries and widgets below it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Barrier {
+    color: Color,
+    dismissible: bool,
+}
+
+impl Barrier {
+    /// Create a new Barrier instance painted with the given color.
+    pub fn new(color: Color) -> Self {
+        Barrier {
+            color,
+            dismissible: true,
+        }
+    }
+
+    /// Set whether clicking the barrier dismisses the modal entry.
+    pub fn dismissible(mut self, dismissible: bool) -> Self {
+        self.dismissible = dismissible;
+        self
+    }
+
+    /// The color of the scrim.
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    /// Returns true if clicking the barrier dismisses the modal entry.
+    pub fn is_dismissible(&self) -> bool {
+        self.dismissible
+    }
+}
+
+impl Default for Barrier {
+    fn default() -> Self {
+        Barrier::new(Color::new(0.0, 0.0, 0.0, 0.54))
+    }
+}
+
+/// The result of a dialog, reported once when its entry goes away.
+trait Completion {
+    fn is_closed(&self) -> bool;
+    fn finish(&self);
+}
+
+/// Called once with the value a dialog was closed with, if any.
+type ResultCallback<T> = Box<dyn FnOnce(Option<T>)>;
+
+struct DialogResult<T> {
+    closed: Cell<bool>,
+    value: RefCell<Option<T>>,
+    on_result: RefCell<Option<ResultCallback<T>>>,
+}
+
+impl<T> Completion for DialogResult<T> {
+    fn is_closed(&self) -> bool {
+        self.closed.get()
+    }
+
+    fn finish(&self) {
+        self.closed.set(true);
+        let on_result = self.on_result.borrow_mut().take();
+        if let Some(on_result) = on_result {
+            let value = self.value.borrow_mut().take();
+            on_result(value);
+        }
+    }
+}
+
+/// DialogHandle closes the dialog it was given to with a result.
+///
+/// Widgets inside a dialog keep a clone of the handle, typically in the
+/// callbacks of their buttons. The dialog is removed from the overlay once
+/// the event that closed it has been handled.
+pub struct DialogHandle<T> {
+    result: Rc<DialogResult<T>>,
+}
+
+impl<T> DialogHandle<T> {
+    /// Close the dialog with the given result.
+    pub fn close(&self, value: T) {
+        if !self.result.closed.get() {
+            *self.result.value.borrow_mut() = Some(value);
+            self.result.closed.set(true);
+        }
+    }
+
+    /// Close the dialog without a result, as Escape or the barrier does.
+    pub fn dismiss(&self) {
+        self.result.closed.set(true);
+    }
+
+    /// Returns true if the dialog has been closed.
+    pub fn is_closed(&self) -> bool {
+        self.result.closed.get()
+    }
+}
+
+impl<T> Clone for DialogHandle<T> {
+    fn clone(&self) -> Self {
+        DialogHandle {
+            result: Rc::clone(&self.result),
+        }
+    }
+}
+
+impl<T> fmt::Debug for DialogHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DialogHandle")
+            .field("closed", &self.result.closed.get())
+            .finish_non_exhaustive()
+    }
+}
+
+/// The modal part of an entry.
+struct Modal {
+    barrier: Barrier,
+    completion: Option<Rc<dyn Completion>>,
+    /// Whether the entry has been painted and saved the previous focus.
+    painted: bool,
+    /// The focus before the entry was first painted, restored when it goes away.
+    previous_focus: Option<FocusId>,
+}
+
+impl Modal {
+    fn is_closed(&self) -> bool {
+        self.completion
+            .as_ref()
+            .is_some_and(|completion| completion.is_closed())
+    }
+}
+
+/// A widget shown in the overlay.
+struct OverlayEntry {
+    id: OverlayId,
+    widget: Box<dyn Widget>,
+    anchor: Rect,
+    placement: Placement,
+    position: Offset,
+    size: Size,
+    modal: Option<Modal>,
+    /// Passive entries ignore input and are not hit by the pointer.
+    passive: bool,
+    /// Whether the widget was built since it was shown or the tree was last
+    /// built.
+    built: bool,
+}
+
+impl OverlayEntry {
+    fn contains(&self, position: Offset) -> bool {
+        Rect {
+            origin: self.position,
+            size: self.size,
+        }
+        .contains(position)
+    }
+}
+
+/// Overlay is a stack of widgets painted above the root widget.
+///
+/// Popups such as menus are shown in the overlay so they are not clipped or
+/// covered by their parents. Entries are positioned in window space next to
+/// an anchor rectangle, receive pointer events before the root widget and are
+/// dismissed by clicking outside of them. The topmost entry receives key
+/// events first.
+///
+/// Modal entries, such as dialogs, are painted above a [`Barrier`] that keeps
+/// events from the entries and widgets below, and trap keyboard focus until
+/// they are closed.
+#[derive(Default)]
+pub struct Overlay {
+    entries: Vec<OverlayEntry>,
+    /// The entries taken out while they paint or handle an event.
+    taken: Vec<OverlayId>,
+    /// The taken entries removed while they were taken.
+    removed: Vec<OverlayId>,
+    /// Whether a modal entry went away and the focus should be restored.
+    refocus: bool,
+    /// The focus to restore after a modal entry went away.
+    restore_focus: Option<FocusId>,
+}
+
+impl Overlay {
+    /// Create a new empty Overlay instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Show a widget next to the anchor, given in window space.
+    pub fn show(
+        &mut self,
+        widget: impl Widget + 'static,
+        anchor: Rect,
+        placement: Placement,
+    ) -> OverlayId {
+        let id = OverlayId::new();
+        self.entries.push(OverlayEntry {
+            id,
+            widget: Box::new(widget),
+            anchor,
+            placement,
+            position: anchor.origin,
+            size: Size::ZERO,
+            modal: None,
+            passive: false,
+            built: false,
+        });
+        id
+    }
+
+    /// Show a widget that ignores input, such as a tooltip, next to the anchor.
+    ///
+    /// Passive entries do not catch the pointer or keys, and are dismissed by
+    /// clicking outside the other entries only along with them.
+    pub fn show_passive(
+        &mut self,
+        widget: impl Widget + 'static,
+        anchor: Rect,
+        placement: Placement,
+    ) -> OverlayId {
+        let id = self.show(widget, anchor, placement);
+        if let Some(entry) = self.entries.last_mut() {
+            entry.passive = true;
+        }
+        id
+    }
+
+    /// Show a widget centered in the window above a barrier.
+    pub fn show_modal(&mut self, widget: impl Widget + 'static, barrier: Barrier) -> OverlayId {
+        self.push_modal(Box::new(widget), barrier, None)
+    }
+
+    /// Show a dialog built with a handle that closes it with a result.
+    ///
+    /// The callback is called once with the result when the dialog goes away,
+    /// or with `None` if it was dismissed with Escape, the barrier or
+    /// [`Overlay::remove`].
+    pub fn show_dialog<T: 'static, W: Widget + 'static>(
+        &mut self,
+        builder: impl FnOnce(DialogHandle<T>) -> W,
+        on_result: impl FnOnce(Option<T>) + 'static,
+    ) -> OverlayId {
+        let result = Rc::new(DialogResult {
+            closed: Cell::new(false),
+            value: RefCell::new(None),
+            on_result: RefCell::new(Some(Box::new(on_result))),
+        });
+        let widget = builder(DialogHandle {
+            result: Rc::clone(&result),
+        });
+        self.push_modal(Box::new(widget), Barrier::default(), Some(result))
+    }
+
+    fn push_modal(
+        &mut self,
+        widget: Box<dyn Widget>,
+        barrier: Barrier,
+        completion: Option<Rc<dyn Completion>>,
+    ) -> OverlayId {
+        let id = OverlayId::new();
+        self.entries.push(OverlayEntry {
+            id,
+            widget,
+            anchor: Rect::from_size(Size::ZERO),
+            placement: Placement::Center,
+            position: Offset::ZERO,
+            size: Size::ZERO,
+            modal: Some(Modal {
+                barrier,
+                completion,
+                painted: false,
+                previous_focus: None,
+            }),
+            passive: false,
+            built: false,
+        });
+        id
+    }
+
+    /// Remove the entry, if it is still shown.
+    pub fn remove(&mut self, id: OverlayId) {
+        if let Some(index) = self.entries.iter().position(|entry| entry.id == id) {
+            let entry = self.entries.remove(index);
+            self.dropped(&entry);
+        }
+        if self.taken.contains(&id) {
+            self.removed.push(id);
+        }
+    }
+
+    /// Returns true if a modal entry blocks the widgets below it.
+    pub fn has_modal(&self) -> bool {
+        self.entries.iter().any(|entry| entry.modal.is_some())
+    }
+
+    /// Returns true if the entry is shown.
+    pub fn contains(&self, id: OverlayId) -> bool {
+        self.entries.iter().any(|entry| entry.id == id)
+            || (self.taken.contains(&id) && !self.removed.contains(&id))
+    }
+
+    /// Returns true if nothing is shown.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty() && self.taken.len() == self.removed.len()
+    }
+
+    /// Build the widgets of the entries again before they are next painted.
+    pub(crate) fn mark_needs_build(&mut self) {
+        for entry in &mut self.entries {
+            entry.built = false;
+        }
+    }
+
+    /// Take the entries out so they can be given the state.
+    fn take(&mut self) -> Vec<OverlayEntry> {
+        let entries = std::mem::take(&mut self.entries);
+        self.taken = entries.iter().map(|entry| entry.id).collect();
+        self.removed.clear();
+        entries
+    }
+
+    /// Put the taken entries back below the entries shown in the meantime,
+    /// then remove the dialogs closed in the meantime.
+    fn restore(&mut self, entries: Vec<OverlayEntry>) {
+        let (removed, mut entries): (Vec<_>, Vec<_>) = entries
+            .into_iter()
+            .partition(|entry| self.removed.contains(&entry.id));
+        for entry in &removed {
+            self.dropped(entry);
+        }
+        entries.append(&mut self.entries);
+        self.entries = entries;
+        self.taken.clear();
+        self.removed.clear();
+
+        let closed: Vec<_> = self
+            .entries
+            .iter()
+            .filter(|entry| entry.modal.as_ref().is_some_and(Modal::is_closed))
+            .map(|entry| entry.id)
+            .collect();
+        for id in closed {
+            self.remove(id);
+        }
+    }
+
+    /// Finish the dialog of an entry that went away and restore the focus.
+    fn dropped(&mut self, entry: &OverlayEntry) {
+        if let Some(modal) = &entry.modal {
+            if let Some(completion) = &modal.completion {
+                completion.finish();
+            }
+            if modal.painted {
+                self.refocus = true;
+                self.restore_focus = modal.previous_focus;
+            }
+        }
+    }
+
+    /// Remove the entries from the index up, dismissing their dialogs.
+    fn dismiss_from(&mut self, entries: &[OverlayEntry], index: usize) {
+        for entry in &entries[index..] {
+            self.remove(entry.id);
+        }
+    }
+
+    /// Lay out and paint the entries above everything painted before.
+    pub(crate) fn paint(state: &mut State<'_>, window: Size) {
+        let overlay = state.overlay_mut();
+        if std::mem::take(&mut overlay.refocus) {
+            match overlay.restore_focus.take() {
+                Some(id) => state.focus_mut().request_focus(id),
+                None => state.focus_mut().unfocus(),
+            }
+        }
+        let mut entries = state.overlay_mut().take();
+        for entry in &mut entries {
+            // Entries inherit the application theme, not the one of the
+            // widget that showed them.
+            if !std::mem::replace(&mut entry.built, true) {
+                entry.widget.build(state);
+            }
+            entry.size = layout_child(entry.widget.as_mut(), BoxConstraints::loose(window));
+            entry.position = entry.placement.position(entry.anchor, entry.size, window);
+            if let Some(modal) = &mut entry.modal {
+                if !modal.painted {
+                    modal.painted = true;
+                    modal.previous_focus = state.focus().focused();
+                }
+                state
+                    .canvas_mut()
+                    .fill_rect(Rect::from_size(window), modal.barrier.color);
+                state.focus_mut().begin_scope();
+            }
+            let canvas = state.canvas_mut();
+            canvas.save();
+            canvas.translate(entry.position);
+            entry.widget.paint(state);
+            state.canvas_mut().restore();
+        }
+        state.focus_mut().end_scope();
+        state.overlay_mut().restore(entries);
+    }
+
+    /// Deliver an event to the entries, returning true if it should not reach
+    /// the root widget.
+    pub(crate) fn event(event: &Event, state: &mut State<'_>) -> bool {
+        if state.overlay().is_empty() {
+            return false;
+        }
+        let mut entries = state.overlay_mut().take();
+        if entries.iter().all(|entry| entry.passive) {
+            state.overlay_mut().restore(entries);
+            return false;
+        }
+        // Only the topmost modal entry and the entries above it get events.
+        let modal = entries.iter().rposition(|entry| entry.modal.is_some());
+        let first = modal.unwrap_or(0);
+        let handled = match event {
+            Event::PointerDown { position, .. }
+            | Event::PointerUp { position, .. }
+            | Event::Scroll { position, .. } => {
+                match entries[first..]
+                    .iter_mut()
+                    .rev()
+                    .find(|entry| !entry.passive && entry.contains(*position))
+                {
+                    Some(entry) => {
+                        entry.widget.event(&event.to_local(entry.position), state);
+                        true
+                    }
+                    // Clicking outside dismisses the popups above the modal
+                    // entry, or the modal entry if its barrier allows it.
+                    None if matches!(event, Event::PointerDown { .. }) => {
+                        match modal {
+                            Some(index) if entries.len() > index + 1 => {
+                                state.overlay_mut().dismiss_from(&entries, index + 1);
+                            }
+                            Some(index) => {
+                                if entries[index]
+                                    .modal
+                                    .as_ref()
+                                    .is_some_and(|modal| modal.barrier.dismissible)
+                                {
+                                    state.overlay_mut().dismiss_from(&entries, index);
+                                }
+                            }
+                            None => state.overlay_mut().dismiss_from(&entries, 0),
+                        }
+                        true
+                    }
+                    None => modal.is_some(),
+                }
+            }
+            Event::PointerMove { position } => {
+                for entry in entries[first..].iter_mut().filter(|entry| !entry.passive) {
+                    entry.widget.event(&event.to_local(entry.position), state);
+                }
+                modal.is_some()
+                    || entries
+                        .iter()
+                        .any(|entry| !entry.passive && entry.contains(*position))
+            }
+            Event::PointerExit => {
+                for entry in entries.iter_mut().filter(|entry| !entry.passive) {
+                    entry.widget.event(event, state);
+                }
+                false
+            }
+            Event::KeyDown {
+                key: Key::Named(NamedKey::Escape),
+                ..
+            } => {
+                if let Some(entry) = entries.iter_mut().rev().find(|entry| !entry.passive) {
+                    if !entry.widget.event(event, state) {
+                        state.overlay_mut().remove(entry.id);
+                    }
+                }
+                true
+            }
+            _ => entries
+                .iter_mut()
+                .rev()
+                .find(|entry| !entry.passive)
+                .is_some_and(|entry| entry.widget.event(event, state)),
+        };
+        state.overlay_mut().restore(entries);
+        handled
+    }
+}
+
+impl fmt::Debug for Overlay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Overlay")
+            .field(
+                "entries",
+                &self
+                    .entries
+                    .iter()
+                    .map(|entry| entry.id)
+                    .collect::<Vec<_>>(),
+            )
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::{Application, Button, SizedBox};
+    use winit::event::MouseButton;
+    use winit::keyboard::ModifiersState;
+
+    #[test]
+    fn test_placement() {
+        let window = Size::new(200.0, 100.0);
+        let size = Size::new(50.0, 40.0);
+        let anchor = Rect::new(10.0, 10.0, 30.0, 20.0);
+        assert_eq!(
+            Placement::Below.position(anchor, size, window),
+            Offset::new(10.0, 30.0)
+        );
+        assert_eq!(
+            Placement::Beside.position(anchor, size, window),
+            Offset::new(40.0, 10.0)
+        );
+        assert_eq!(
+            Placement::BelowCenter.position(anchor, size, window),
+            Offset::new(0.0, 30.0)
+        );
+
+        // Near the bottom right corner the entry flips to the other side.
+        let anchor = Rect::new(170.0, 70.0, 20.0, 20.0);
+        assert_eq!(
+            Placement::Below.position(anchor, size, window),
+            Offset::new(150.0, 30.0)
+        );
+        assert_eq!(
+            Placement::Beside.position(anchor, size, window),
+            Offset::new(120.0, 60.0)
+        );
+    }
+
+    #[test]
+    fn test_dismiss_on_outside_click() {
+        let mut state = State::default();
+        let id = state.overlay_mut().show(
+            SizedBox::new(50.0, 50.0),
+            Rect::new(0.0, 0.0, 10.0, 10.0),
+            Placement::Below,
+        );
+        Overlay::paint(&mut state, Size::new(200.0, 200.0));
+        assert!(state.overlay().contains(id));
+
+        let down = |x: f32| Event::PointerDown {
+            position: Offset::new(x, 20.0),
+            button: MouseButton::Left,
+        };
+        assert!(Overlay::event(&down(20.0), &mut state));
+        assert!(state.overlay().contains(id));
+        assert!(Overlay::event(&down(100.0), &mut state));
+        assert!(!state.overlay().contains(id));
+        assert!(state.overlay().is_empty());
+        assert!(!Overlay::event(&down(100.0), &mut state));
+    }
+
+    #[test]
+    fn test_dialog() {
+        let pressed = Rc::new(Cell::new(0));
+        let root = {
+            let pressed = Rc::clone(&pressed);
+            Button::new(SizedBox::new(50.0, 50.0)).on_press(move || pressed.set(pressed.get() + 1))
+        };
+        let root_focus = root.focus_id();
+        let mut app = Application::new("test", root);
+        let mut state = State::default();
+        let frame = |app: &mut Application, state: &mut State<'_>| {
+            app.layout(BoxConstraints::loose(Size::new(400.0, 300.0)));
+            app.paint(state);
+        };
+        let click = |app: &mut Application, state: &mut State<'_>, x: f32, y: f32| {
+            let position = Offset::new(x, y);
+            let button = MouseButton::Left;
+            app.event(&Event::PointerDown { position, button }, state);
+            app.event(&Event::PointerUp { position, button }, state);
+        };
+        frame(&mut app, &mut state);
+        state.focus_mut().request_focus(root_focus);
+
+        let results = Rc::new(RefCell::new(Vec::new()));
+        let dialog_focus = Rc::new(Cell::new(None));
+        let show = |state: &mut State<'_>| {
+            let results = Rc::clone(&results);
+            let dialog_focus = Rc::clone(&dialog_focus);
+            state.overlay_mut().show_dialog(
+                move |handle| {
+                    let button =
+                        Button::new(SizedBox::new(80.0, 40.0)).on_press(move || handle.close(42));
+                    dialog_focus.set(Some(button.focus_id()));
+                    button
+                },
+                move |result| results.borrow_mut().push(result),
+            )
+        };
+
+        // Focus moves into the dialog, and clicking the barrier dismisses it
+        // without reaching the root.
+        show(&mut state);
+        frame(&mut app, &mut state);
+        assert!(state.focus().is_focused(dialog_focus.get().unwrap()));
+        click(&mut app, &mut state, 5.0, 5.0);
+        assert_eq!(pressed.get(), 0);
+        assert_eq!(*results.borrow(), [None]);
+        assert!(state.overlay().is_empty());
+
+        // The dialog closes with a result and focus returns to the root.
+        show(&mut state);
+        frame(&mut app, &mut state);
+        click(&mut app, &mut state, 200.0, 150.0);
+        assert_eq!(*results.borrow(), [None, Some(42)]);
+        assert!(state.overlay().is_empty());
+        frame(&mut app, &mut state);
+        assert!(state.focus().is_focused(root_focus));
+
+        // Escape dismisses a dialog whose barrier is not dismissible.
+        let id = show(&mut state);
+        state.overlay_mut().entries[0]
+            .modal
+            .as_mut()
+            .unwrap()
+            .barrier = Barrier::default().dismissible(false);
+        frame(&mut app, &mut state);
+        click(&mut app, &mut state, 5.0, 5.0);
+        assert!(state.overlay().contains(id));
+        let escape = Event::KeyDown {
+            key: Key::Named(NamedKey::Escape),
+            modifiers: ModifiersState::empty(),
+            text: None,
+        };
+        app.event(&escape, &mut state);
+        assert!(!state.overlay().contains(id));
+        assert_eq!(*results.borrow(), [None, Some(42), None]);
+        assert_eq!(pressed.get(), 0);
+    }
+}
@@ -0,0 +1,316 @@
+use std::rc::Rc;
+
+use super::{Color, FontCollection, FontStyle, FontWeight, Offset, Rect, TextDirection, TextStyle};
+
+/// DrawCommand is a single drawing operation recorded by a [`Canvas`].
+///
+/// All coordinates are in window space, in logical pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DrawCommand {
+    /// Fill a rectangle with a solid color.
+    FillRect {
+        /// The rectangle to fill.
+        rect: Rect,
+        /// The fill color.
+        color: Color,
+    },
+    /// Draw a single line of text.
+    Text {
+        /// The top-left corner of the line.
+        origin: Offset,
+        /// The distance from the top of the line down to its baseline.
+        ascent: f32,
+        /// The text to draw.
+        text: String,
+        /// The font family, or `None` for the default family.
+        font_family: Option<Rc<str>>,
+        /// The font size in logical pixels.
+        font_size: f32,
+        /// The thickness of the glyphs.
+        weight: FontWeight,
+        /// Whether the glyphs are upright or italic.
+        font_style: FontStyle,
+        /// The extra space after every grapheme in logical pixels.
+        letter_spacing: f32,
+        /// The direction of the text, which runs from the right edge of its
+        /// box if it is right-to-left.
+        direction: TextDirection,
+        /// The text color.
+        color: Color,
+        /// The area outside which no part of the glyphs is drawn, or `None`
+        /// if the text is not clipped.
+        clip: Option<Rect>,
+    },
+}
+
+/// Canvas records the drawing operations of a frame.
+///
+/// Widgets paint in their own coordinate space; the canvas keeps track of the
+/// current origin and scale so parents can transform before painting their
+/// children.
+#[derive(Debug, Default)]
+pub struct Canvas {
+    commands: Vec<DrawCommand>,
+    transform: Transform,
+    saved: Vec<Transform>,
+    /// The fonts text is measured and drawn with.
+    fonts: Rc<FontCollection>,
+}
+
+/// The mapping from local coordinates to window space, the opacity applied
+/// to the colors and the clip in window space.
+#[derive(Debug, Clone, Copy)]
+struct Transform {
+    origin: Offset,
+    scale_x: f32,
+    scale_y: f32,
+    opacity: f64,
+    clip: Option<Rect>,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform {
+            origin: Offset::ZERO,
+            scale_x: 1.0,
+            scale_y: 1.0,
+            opacity: 1.0,
+            clip: None,
+        }
+    }
+}
+
+impl Transform {
+    fn apply(&self, rect: Rect) -> Rect {
+        Rect::new(
+            self.origin.x + rect.left() * self.scale_x,
+            self.origin.y + rect.top() * self.scale_y,
+            rect.size.width * self.scale_x,
+            rect.size.height * self.scale_y,
+        )
+    }
+
+    fn apply_opacity(&self, color: Color) -> Color {
+        Color {
+            a: color.a * self.opacity,
+            ..color
+        }
+    }
+}
+
+impl Canvas {
+    /// Create a new empty Canvas instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the fonts text is measured and drawn with.
+    pub(crate) fn set_fonts(&mut self, fonts: Rc<FontCollection>) {
+        self.fonts = fonts;
+    }
+
+    /// Save the current transform, opacity and clip so they can be restored
+    /// later.
+    pub fn save(&mut self) {
+        self.saved.push(self.transform);
+    }
+
+    /// Restore the transform, opacity and clip saved by the matching
+    /// [`Canvas::save`].
+    pub fn restore(&mut self) {
+        if let Some(transform) = self.saved.pop() {
+            self.transform = transform;
+        }
+    }
+
+    /// Move the origin by the given offset, in local coordinates.
+    pub fn translate(&mut self, offset: Offset) {
+        self.transform.origin.x += offset.x * self.transform.scale_x;
+        self.transform.origin.y += offset.y * self.transform.scale_y;
+    }
+
+    /// Scale subsequent drawing by the given horizontal and vertical factors.
+    pub fn scale(&mut self, scale_x: f32, scale_y: f32) {
+        self.transform.scale_x *= scale_x;
+        self.transform.scale_y *= scale_y;
+    }
+
+    /// Multiply the opacity of subsequent drawing by the given factor, from 0
+    /// (transparent) to 1 (unchanged).
+    pub fn apply_opacity(&mut self, opacity: f64) {
+        self.transform.opacity *= opacity.clamp(0.0, 1.0);
+    }
+
+    /// Restrict subsequent drawing to a rectangle, given in local
+    /// coordinates, within the current clip.
+    pub fn clip_rect(&mut self, rect: Rect) {
+        let rect = self.transform.apply(rect);
+        self.transform.clip = Some(match self.transform.clip {
+            Some(clip) => clip.intersect(rect),
+            None => rect,
+        });
+    }
+
+    /// The opacity applied to subsequent drawing.
+    pub fn opacity(&self) -> f64 {
+        self.transform.opacity
+    }
+
+    /// The current origin in window space.
+    pub fn origin(&self) -> Offset {
+        self.transform.origin
+    }
+
+    /// Map a rectangle in local coordinates to window space.
+    pub fn to_window(&self, rect: Rect) -> Rect {
+        self.transform.apply(rect)
+    }
+
+    /// Fill a rectangle, given in local coordinates, with a solid color.
+    pub fn fill_rect(&mut self, rect: Rect, color: Color) {
+        let color = self.transform.apply_opacity(color);
+        if rect.is_empty() || color.a <= 0.0 {
+            return;
+        }
+        let mut rect = self.transform.apply(rect);
+        if let Some(clip) = self.transform.clip {
+            rect = rect.intersect(clip);
+            if rect.is_empty() {
+                return;
+            }
+        }
+        self.commands.push(DrawCommand::FillRect { rect, color });
+    }
+
+    /// Draw the outline of a rectangle, given in local coordinates, with the
+    /// stroke inside the rectangle.
+    pub fn stroke_rect(&mut self, rect: Rect, width: f32, color: Color) {
+        let width = width.min(rect.size.width / 2.0).min(rect.size.height / 2.0);
+        if width <= 0.0 {
+            return;
+        }
+        let inner_height = rect.size.height - width * 2.0;
+        self.fill_rect(
+            Rect::new(rect.left(), rect.top(), rect.size.width, width),
+            color,
+        );
+        self.fill_rect(
+            Rect::new(rect.left(), rect.bottom() - width, rect.size.width, width),
+            color,
+        );
+        self.fill_rect(
+            Rect::new(rect.left(), rect.top() + width, width, inner_height),
+            color,
+        );
+        self.fill_rect(
+            Rect::new(
+                rect.right() - width,
+                rect.top() + width,
+                width,
+                inner_height,
+            ),
+            color,
+        );
+    }
+
+    /// Fill a circle, given in local coordinates, with a solid color.
+    ///
+    /// The circle is approximated with one rectangle per logical pixel row.
+    pub fn fill_circle(&mut self, center: Offset, radius: f32, color: Color) {
+        if radius <= 0.0 {
+            return;
+        }
+        let rows = (radius * 2.0).ceil() as usize;
+        let top = center.y - radius;
+        for row in 0..rows {
+            let y = top + row as f32;
+            let height = (center.y + radius - y).min(1.0);
+            let dy = y + height / 2.0 - center.y;
+            let half_width = (radius * radius - dy * dy).max(0.0).sqrt();
+            self.fill_rect(
+                Rect::new(center.x - half_width, y, half_width * 2.0, height),
+                color,
+            );
+        }
+    }
+
+    /// Draw a single line of text with its top-left corner at the origin,
+    /// given in local coordinates.
+    ///
+    /// The background and the lines of the style are drawn as rectangles
+    /// around the text.
+    pub fn draw_text(&mut self, origin: Offset, text: &str, style: &TextStyle) {
+        self.draw_directed_text(origin, text, style, TextDirection::Ltr);
+    }
+
+    /// Draw a single line of text running in one direction, with the
+    /// top-left corner of its box at the origin.
+    ///
+    /// The graphemes of right-to-left text are given in logical order; the
+    /// first is drawn at the right edge of the box.
+    pub fn draw_directed_text(
+        &mut self,
+        origin: Offset,
+        text: &str,
+        style: &TextStyle,
+        direction: TextDirection,
+    ) {
+        if text.is_empty() {
+            return;
+        }
+        let width = self.fonts.measure(text, style);
+        if let Some(background) = style.background {
+            let rect = Rect::new(origin.x, origin.y, width, style.line_extent());
+            self.fill_rect(rect, background);
+        }
+        let color = self.transform.apply_opacity(style.color);
+        if color.a <= 0.0 {
+            return;
+        }
+        let rect = self
+            .transform
+            .apply(Rect::new(origin.x, origin.y, 0.0, 0.0));
+        self.commands.push(DrawCommand::Text {
+            origin: rect.origin,
+            ascent: self.fonts.ascent(style) * self.transform.scale_y,
+            text: text.to_string(),
+            font_family: style.font_family.clone(),
+            font_size: style.font_size * self.transform.scale_y,
+            weight: style.weight,
+            font_style: style.font_style,
+            letter_spacing: style.letter_spacing * self.transform.scale_x,
+            direction,
+            color,
+            clip: self.transform.clip,
+        });
+
+        let thickness = (style.font_size / 14.0).max(1.0);
+        let baseline = origin.y + self.fonts.ascent(style);
+        if style.underline {
+            let top = baseline + thickness;
+            self.fill_rect(Rect::new(origin.x, top, width, thickness), style.color);
+        }
+        if style.strikethrough {
+            // Through the middle of the lowercase letters.
+            let top = baseline - style.font_size * 0.25 - thickness / 2.0;
+            self.fill_rect(Rect::new(origin.x, top, width, thickness), style.color);
+        }
+    }
+
+    /// The fonts the text of the recorded operations is drawn with.
+    pub(crate) fn fonts(&self) -> &FontCollection {
+        &self.fonts
+    }
+
+    /// The recorded drawing operations, in painting order.
+    pub fn commands(&self) -> &[DrawCommand] {
+        &self.commands
+    }
+
+    /// Remove all recorded operations and reset the origin.
+    pub fn clear(&mut self) {
+        self.commands.clear();
+        self.saved.clear();
+        self.transform = Transform::default();
+    }
+}
@@ -1,20 +1,63 @@
+use std::cell::RefCell;
+use std::ops::Range;
+
 use wgpu;
+use wgpu::util::DeviceExt;
 use winit::window::Window;
 
 use crate::framework::error::RenderError;
+use crate::framework::paint::{Canvas, DrawCommand};
+use crate::framework::{Color, ColorSpace, Rect, TextStyle};
+
+use atlas::GlyphAtlas;
+
+mod atlas;
+
+/// The number of bytes of a single vertex: a 2D position and an RGBA color.
+const VERTEX_SIZE: usize = 6 * std::mem::size_of::<f32>();
+
+/// The number of bytes of a single text vertex: a 2D position, a position
+/// in the glyph atlas and an RGBA color.
+const TEXT_VERTEX_SIZE: usize = 8 * std::mem::size_of::<f32>();
 
 /// Render is the main renderer of the application.
+///
+/// Colors are sRGB encoded (see [`Color`]) and converted to linear light for
+/// the GPU, so vertex colors are interpolated in linear space. An sRGB
+/// surface is preferred, which blends in linear light and encodes the result
+/// in hardware; on other surfaces the shader encodes the output itself.
+///
+/// Such surfaces can only show sRGB, so colors in wider color spaces are
+/// mapped into the sRGB gamut. An HDR render, see [`Render::with_hdr`], uses
+/// a floating point surface in linear extended sRGB instead, which keeps
+/// colors outside sRGB and brighter than white on displays that support them.
+///
+/// Text is drawn with the glyphs of the fonts of the canvas, rasterized at
+/// the physical font size into a glyph atlas the first time they are drawn.
 #[derive(Debug)]
 pub struct Render<'a> {
     surface: wgpu::Surface<'a>,
     device: wgpu::Device,
     queue: wgpu::Queue,
     config: wgpu::SurfaceConfiguration,
+    pipeline: wgpu::RenderPipeline,
+    text_pipeline: wgpu::RenderPipeline,
+    atlas: RefCell<GlyphAtlas>,
+    scale_factor: f64,
 }
 
 impl<'a> Render<'a> {
     /// Create a new Render instance.
     pub async fn new(window: &'a Window) -> Result<Self, RenderError> {
+        Self::with_hdr(window, false).await
+    }
+
+    /// Create a new Render instance, preferring an `Rgba16Float` surface if
+    /// `hdr` is true.
+    ///
+    /// Falls back to the usual sRGB surface if the display does not offer a
+    /// floating point format; [`Render::is_hdr`] tells which one is in use.
+    pub async fn with_hdr(window: &'a Window, hdr: bool) -> Result<Self, RenderError> {
         let size = window.inner_size();
         let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
             backends: wgpu::Backends::all(),
@@ -44,12 +87,17 @@ impl<'a> Render<'a> {
             .await?;
 
         let surface_caps = surface.get_capabilities(&adapter);
-        let surface_format = surface_caps
-            .formats
-            .iter()
-            .copied()
-            .find(|f| f.is_srgb())
-            .unwrap_or(surface_caps.formats[0]);
+        let float_format = wgpu::TextureFormat::Rgba16Float;
+        let surface_format = if hdr && surface_caps.formats.contains(&float_format) {
+            float_format
+        } else {
+            surface_caps
+                .formats
+                .iter()
+                .copied()
+                .find(|f| f.is_srgb())
+                .unwrap_or(surface_caps.formats[0])
+        };
 
         let config = wgpu::SurfaceConfiguration {
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
@@ -63,11 +111,20 @@ impl<'a> Render<'a> {
         };
         surface.configure(&device, &config);
 
+        let pipeline = create_rect_pipeline(&device, config.format);
+        let atlas_layout = GlyphAtlas::bind_group_layout(&device);
+        let text_pipeline = create_text_pipeline(&device, config.format, &atlas_layout);
+        let atlas = RefCell::new(GlyphAtlas::new(&device, &atlas_layout));
+
         Ok(Self {
             surface,
             device,
             queue,
             config,
+            pipeline,
+            text_pipeline,
+            atlas,
+            scale_factor: window.scale_factor(),
         })
     }
 
@@ -81,13 +138,61 @@ impl<'a> Render<'a> {
         Ok(())
     }
 
+    /// Returns true if the surface has a floating point format that shows
+    /// colors outside sRGB.
+    pub fn is_hdr(&self) -> bool {
+        is_hdr_format(self.config.format)
+    }
+
+    /// The color space colors are drawn in.
+    pub fn output_color_space(&self) -> ColorSpace {
+        if self.is_hdr() {
+            ColorSpace::LinearExtendedSrgb
+        } else {
+            ColorSpace::Srgb
+        }
+    }
+
+    /// Set the number of physical pixels per logical pixel.
+    pub fn set_scale_factor(&mut self, scale_factor: f64) {
+        self.scale_factor = scale_factor;
+    }
+
     /// Render a frame.
-    pub fn render(&self, clear_color: wgpu::Color) -> Result<(), wgpu::SurfaceError> {
+    pub fn render(&self, clear_color: Color) -> Result<(), wgpu::SurfaceError> {
+        self.draw(clear_color, &Canvas::new())
+    }
+
+    /// Render a frame with the operations recorded on the canvas.
+    pub fn draw(&self, clear_color: Color, canvas: &Canvas) -> Result<(), wgpu::SurfaceError> {
         let output = self.surface.get_current_texture()?;
         let view = output
             .texture
             .create_view(&wgpu::TextureViewDescriptor::default());
 
+        let mut atlas = self.atlas.borrow_mut();
+        let mut frame = self.tessellate(canvas, &mut atlas);
+        if atlas.is_full() {
+            // Start over with only the glyphs of this frame.
+            atlas.clear();
+            frame = self.tessellate(canvas, &mut atlas);
+            if atlas.is_full() {
+                log::warn!("Some glyphs of the frame do not fit into the glyph atlas");
+            }
+        }
+        let create_buffer = |label, contents: &[u8]| {
+            (!contents.is_empty()).then(|| {
+                self.device
+                    .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                        label: Some(label),
+                        contents,
+                        usage: wgpu::BufferUsages::VERTEX,
+                    })
+            })
+        };
+        let vertex_buffer = create_buffer("Vertex Buffer", &frame.vertices);
+        let text_vertex_buffer = create_buffer("Text Vertex Buffer", &frame.text_vertices);
+
         let mut encoder = self
             .device
             .create_command_encoder(&wgpu::CommandEncoderDescriptor {
@@ -95,13 +200,13 @@ impl<'a> Render<'a> {
             });
 
         {
-            let _render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Render Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
                     view: &view,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(clear_color),
+                        load: wgpu::LoadOp::Clear(self.clear_value(clear_color)),
                         store: wgpu::StoreOp::Store,
                     },
                 })],
@@ -109,6 +214,21 @@ impl<'a> Render<'a> {
                 timestamp_writes: None,
                 occlusion_query_set: None,
             });
+
+            for batch in &frame.batches {
+                let buffer = if batch.text {
+                    render_pass.set_pipeline(&self.text_pipeline);
+                    render_pass.set_bind_group(0, atlas.bind_group(), &[]);
+                    &text_vertex_buffer
+                } else {
+                    render_pass.set_pipeline(&self.pipeline);
+                    &vertex_buffer
+                };
+                if let Some(buffer) = buffer {
+                    render_pass.set_vertex_buffer(0, buffer.slice(..));
+                    render_pass.draw(batch.vertices.clone(), 0..1);
+                }
+            }
         }
 
         self.queue.submit(std::iter::once(encoder.finish()));
@@ -116,4 +236,342 @@ impl<'a> Render<'a> {
 
         Ok(())
     }
+
+    /// The clear value writing the color to the surface.
+    ///
+    /// Clear values bypass the shader, so they are only converted to linear
+    /// light when the surface encodes to sRGB itself or is HDR.
+    fn clear_value(&self, color: Color) -> wgpu::Color {
+        let color = if self.config.format.is_srgb() || self.is_hdr() {
+            self.linear_color(color)
+        } else {
+            color.map_to_gamut(ColorSpace::Srgb)
+        };
+        wgpu::Color {
+            r: color.r,
+            g: color.g,
+            b: color.b,
+            a: color.a,
+        }
+    }
+
+    /// The color in linear light of the output color space, mapped into its
+    /// gamut.
+    fn linear_color(&self, color: Color) -> Color {
+        color.map_to_gamut(self.output_color_space()).to_linear()
+    }
+
+    /// Convert the canvas operations into triangle vertices in clip space,
+    /// adding the glyphs of the text to the atlas.
+    fn tessellate(&self, canvas: &Canvas, atlas: &mut GlyphAtlas) -> Frame {
+        let width = self.config.width as f32;
+        let height = self.config.height as f32;
+        let scale = self.scale_factor as f32;
+        // Positions are in physical pixels.
+        let to_clip = |x: f32, y: f32| [x / width * 2.0 - 1.0, 1.0 - y / height * 2.0];
+        let to_physical = |rect: Rect| {
+            Rect::new(
+                rect.left() * scale,
+                rect.top() * scale,
+                rect.size.width * scale,
+                rect.size.height * scale,
+            )
+        };
+        let to_linear = |color: Color| {
+            let color = self.linear_color(color);
+            [
+                color.r as f32,
+                color.g as f32,
+                color.b as f32,
+                color.a as f32,
+            ]
+        };
+
+        let mut frame = Frame::default();
+        for command in canvas.commands() {
+            match command {
+                DrawCommand::FillRect { rect, color } => {
+                    let color = to_linear(*color);
+                    for (x, y) in quad_corners(to_physical(*rect)) {
+                        frame.push(false, &[&to_clip(x, y), &color]);
+                    }
+                }
+                DrawCommand::Text {
+                    origin,
+                    ascent,
+                    text,
+                    font_family,
+                    font_size,
+                    weight,
+                    font_style,
+                    letter_spacing,
+                    direction,
+                    color,
+                    clip,
+                } => {
+                    let style = TextStyle {
+                        font_family: font_family.clone(),
+                        weight: *weight,
+                        font_style: *font_style,
+                        letter_spacing: *letter_spacing,
+                        ..TextStyle::new(*font_size)
+                    };
+                    let color = to_linear(*color);
+                    let clip = clip.map(to_physical);
+                    let fonts = canvas.fonts();
+                    // Glyphs are drawn at whole pixels, one texel per pixel.
+                    let baseline = ((origin.y + ascent) * scale).round();
+                    let atlas_size = atlas.size() as f32;
+                    for placed in fonts.glyphs(text, &style, *direction) {
+                        let Some(glyph) =
+                            atlas.glyph(&self.queue, fonts, &placed, font_size * scale)
+                        else {
+                            continue;
+                        };
+                        let pen = ((origin.x + placed.x) * scale).round();
+                        let quad = Rect::new(
+                            pen + glyph.left as f32,
+                            baseline + glyph.top as f32,
+                            glyph.width as f32,
+                            glyph.height as f32,
+                        );
+                        let texels = Rect::new(
+                            glyph.x as f32,
+                            glyph.y as f32,
+                            glyph.width as f32,
+                            glyph.height as f32,
+                        );
+                        let Some((quad, texels)) = clip_quad(quad, texels, clip) else {
+                            continue;
+                        };
+                        for ((x, y), (u, v)) in
+                            quad_corners(quad).into_iter().zip(quad_corners(texels))
+                        {
+                            let uv = [u / atlas_size, v / atlas_size];
+                            frame.push(true, &[&to_clip(x, y), &uv, &color]);
+                        }
+                    }
+                }
+            }
+        }
+        frame
+    }
+}
+
+/// Frame holds the vertices of the canvas operations, drawn in batches of
+/// one pipeline in painting order.
+#[derive(Debug, Default)]
+struct Frame {
+    vertices: Vec<u8>,
+    text_vertices: Vec<u8>,
+    batches: Vec<Batch>,
+}
+
+/// A range of consecutive vertices drawn with the same pipeline.
+#[derive(Debug)]
+struct Batch {
+    /// Whether the vertices are glyphs rather than solid triangles.
+    text: bool,
+    vertices: Range<u32>,
+}
+
+impl Frame {
+    /// Add a vertex made of the attributes, continuing the last batch if it
+    /// is drawn with the same pipeline.
+    fn push(&mut self, text: bool, attributes: &[&[f32]]) {
+        let (bytes, size) = if text {
+            (&mut self.text_vertices, TEXT_VERTEX_SIZE)
+        } else {
+            (&mut self.vertices, VERTEX_SIZE)
+        };
+        for value in attributes.iter().copied().flatten() {
+            bytes.extend_from_slice(&value.to_ne_bytes());
+        }
+        let index = (bytes.len() / size) as u32 - 1;
+        match self.batches.last_mut() {
+            Some(batch) if batch.text == text => batch.vertices.end = index + 1,
+            _ => self.batches.push(Batch {
+                text,
+                vertices: index..index + 1,
+            }),
+        }
+    }
+}
+
+/// The corners of the two triangles covering a rectangle.
+fn quad_corners(rect: Rect) -> [(f32, f32); 6] {
+    let top_left = (rect.left(), rect.top());
+    let top_right = (rect.right(), rect.top());
+    let bottom_left = (rect.left(), rect.bottom());
+    let bottom_right = (rect.right(), rect.bottom());
+    [
+        top_left,
+        bottom_left,
+        top_right,
+        top_right,
+        bottom_left,
+        bottom_right,
+    ]
+}
+
+/// Cut a glyph quad and the texels it is drawn with, one per pixel, to the
+/// part inside the clip. Returns `None` if nothing of the glyph is left.
+fn clip_quad(quad: Rect, texels: Rect, clip: Option<Rect>) -> Option<(Rect, Rect)> {
+    let Some(clip) = clip else {
+        return Some((quad, texels));
+    };
+    let clipped = quad.intersect(clip);
+    if clipped.is_empty() {
+        return None;
+    }
+    let texels = Rect::new(
+        texels.left() + clipped.left() - quad.left(),
+        texels.top() + clipped.top() - quad.top(),
+        clipped.size.width,
+        clipped.size.height,
+    );
+    Some((clipped, texels))
+}
+
+/// Create the pipeline used to draw solid-colored triangles.
+///
+/// The vertex colors are linear; for formats that are neither sRGB nor HDR the
+/// fragment shader encodes them before writing.
+fn create_rect_pipeline(
+    device: &wgpu::Device,
+    format: wgpu::TextureFormat,
+) -> wgpu::RenderPipeline {
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Rect Shader"),
+        source: wgpu::ShaderSource::Wgsl(include_str!("shaders/rect.wgsl").into()),
+    });
+
+    let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Rect Pipeline Layout"),
+        bind_group_layouts: &[],
+        push_constant_ranges: &[],
+    });
+
+    let buffer = wgpu::VertexBufferLayout {
+        array_stride: VERTEX_SIZE as wgpu::BufferAddress,
+        step_mode: wgpu::VertexStepMode::Vertex,
+        attributes: &wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x4],
+    };
+    create_pipeline(device, format, &shader, &layout, buffer)
+}
+
+/// Create the pipeline used to draw glyphs from the glyph atlas, in the
+/// linear vertex colors like [`create_rect_pipeline`].
+fn create_text_pipeline(
+    device: &wgpu::Device,
+    format: wgpu::TextureFormat,
+    atlas_layout: &wgpu::BindGroupLayout,
+) -> wgpu::RenderPipeline {
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Text Shader"),
+        source: wgpu::ShaderSource::Wgsl(include_str!("shaders/text.wgsl").into()),
+    });
+
+    let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Text Pipeline Layout"),
+        bind_group_layouts: &[atlas_layout],
+        push_constant_ranges: &[],
+    });
+
+    let buffer = wgpu::VertexBufferLayout {
+        array_stride: TEXT_VERTEX_SIZE as wgpu::BufferAddress,
+        step_mode: wgpu::VertexStepMode::Vertex,
+        attributes: &wgpu::vertex_attr_array![
+            0 => Float32x2,
+            1 => Float32x2,
+            2 => Float32x4
+        ],
+    };
+    create_pipeline(device, format, &shader, &layout, buffer)
+}
+
+/// Create a pipeline blending the output of the shader over the surface,
+/// with the fragment entry point that suits the format.
+fn create_pipeline(
+    device: &wgpu::Device,
+    format: wgpu::TextureFormat,
+    shader: &wgpu::ShaderModule,
+    layout: &wgpu::PipelineLayout,
+    buffer: wgpu::VertexBufferLayout<'_>,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: None,
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some("vs_main"),
+            buffers: &[buffer],
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: Some(if format.is_srgb() || is_hdr_format(format) {
+                "fs_main"
+            } else {
+                "fs_main_encode_srgb"
+            }),
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+        }),
+        primitive: wgpu::PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+        multiview: None,
+        cache: None,
+    })
+}
+
+/// Returns true for the floating point format used by HDR surfaces, whose
+/// values are linear extended sRGB.
+fn is_hdr_format(format: wgpu::TextureFormat) -> bool {
+    format == wgpu::TextureFormat::Rgba16Float
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_batches() {
+        let mut frame = Frame::default();
+        for text in [false, false, true, false] {
+            let uv: &[f32] = if text { &[0.0; 2] } else { &[] };
+            frame.push(text, &[&[0.0; 2], uv, &[1.0; 4]]);
+        }
+        let batches: Vec<_> = frame
+            .batches
+            .iter()
+            .map(|batch| (batch.text, batch.vertices.clone()))
+            .collect();
+        // Solid and text vertices are counted separately.
+        assert_eq!(batches, [(false, 0..2), (true, 0..1), (false, 2..3)]);
+        assert_eq!(frame.vertices.len(), 3 * VERTEX_SIZE);
+    }
+
+    #[test]
+    fn test_clip_quad() {
+        let quad = Rect::new(10.0, 10.0, 4.0, 6.0);
+        let texels = Rect::new(100.0, 0.0, 4.0, 6.0);
+        assert_eq!(clip_quad(quad, texels, None), Some((quad, texels)));
+        assert_eq!(
+            clip_quad(quad, texels, Some(Rect::new(12.0, 0.0, 20.0, 14.0))),
+            Some((
+                Rect::new(12.0, 10.0, 2.0, 4.0),
+                Rect::new(102.0, 0.0, 2.0, 4.0)
+            ))
+        );
+        assert_eq!(
+            clip_quad(quad, texels, Some(Rect::new(0.0, 0.0, 10.0, 10.0))),
+            None
+        );
+    }
 }
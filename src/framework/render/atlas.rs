@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+
+use ttf_parser::GlyphId;
+
+use crate::framework::text::{GlyphBitmap, PlacedGlyph};
+use crate::framework::{FontCollection, FontId};
+
+/// The width and height of the atlas texture in pixels.
+const ATLAS_SIZE: u32 = 1024;
+
+/// The empty pixels kept between glyphs.
+const PADDING: u32 = 1;
+
+/// A glyph rasterized at a size, given as the bits of the size in pixels.
+type GlyphKey = (FontId, GlyphId, u32);
+
+/// AtlasGlyph is a glyph stored in the atlas.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct AtlasGlyph {
+    /// The distance from the pen position to the left edge of the glyph.
+    pub(super) left: i32,
+    /// The distance from the baseline down to the top edge of the glyph.
+    pub(super) top: i32,
+    /// The position and size of the glyph in the texture, in pixels.
+    pub(super) x: u32,
+    pub(super) y: u32,
+    pub(super) width: u32,
+    pub(super) height: u32,
+}
+
+/// GlyphAtlas keeps the glyphs drawn so far in a single-channel texture of
+/// their coverage, so each glyph is only rasterized and uploaded once.
+///
+/// Once the texture is full, [`GlyphAtlas::glyph`] returns `None` and marks
+/// the atlas as full; the renderer then clears it and draws the frame again.
+#[derive(Debug)]
+pub(super) struct GlyphAtlas {
+    texture: wgpu::Texture,
+    bind_group: wgpu::BindGroup,
+    packer: ShelfPacker,
+    /// The glyphs rasterized so far, `None` for glyphs without an outline.
+    glyphs: HashMap<GlyphKey, Option<AtlasGlyph>>,
+    full: bool,
+}
+
+impl GlyphAtlas {
+    /// Create a new GlyphAtlas instance with an empty texture bound to the
+    /// layout.
+    pub(super) fn new(device: &wgpu::Device, layout: &wgpu::BindGroupLayout) -> Self {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Glyph Atlas"),
+            size: wgpu::Extent3d {
+                width: ATLAS_SIZE,
+                height: ATLAS_SIZE,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::R8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        // Glyphs are drawn at whole pixels, so every pixel samples one texel.
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Glyph Atlas Sampler"),
+            ..Default::default()
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Glyph Atlas Bind Group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+        });
+        GlyphAtlas {
+            texture,
+            bind_group,
+            packer: ShelfPacker::new(ATLAS_SIZE),
+            glyphs: HashMap::new(),
+            full: false,
+        }
+    }
+
+    /// The layout of the bind group holding the texture and its sampler.
+    pub(super) fn bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Glyph Atlas Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        })
+    }
+
+    /// The bind group holding the texture and its sampler.
+    pub(super) fn bind_group(&self) -> &wgpu::BindGroup {
+        &self.bind_group
+    }
+
+    /// The size of the texture in pixels.
+    pub(super) fn size(&self) -> u32 {
+        ATLAS_SIZE
+    }
+
+    /// Get a glyph with an em of `size` pixels, rasterizing and uploading
+    /// it if it is not in the atlas yet.
+    ///
+    /// Returns `None` if the glyph has no outline or does not fit.
+    pub(super) fn glyph(
+        &mut self,
+        queue: &wgpu::Queue,
+        fonts: &FontCollection,
+        placed: &PlacedGlyph,
+        size: f32,
+    ) -> Option<AtlasGlyph> {
+        let PlacedGlyph { font, glyph, .. } = *placed;
+        let key = (font, glyph, size.to_bits());
+        if let Some(&glyph) = self.glyphs.get(&key) {
+            return glyph;
+        }
+        let Some(bitmap) = fonts.rasterize(font, glyph, size) else {
+            self.glyphs.insert(key, None);
+            return None;
+        };
+        let Some((x, y)) = self
+            .packer
+            .allocate(bitmap.width + PADDING, bitmap.height + PADDING)
+        else {
+            self.full = true;
+            return None;
+        };
+        self.upload(queue, &bitmap, x, y);
+        let glyph = AtlasGlyph {
+            left: bitmap.left,
+            top: bitmap.top,
+            x,
+            y,
+            width: bitmap.width,
+            height: bitmap.height,
+        };
+        self.glyphs.insert(key, Some(glyph));
+        Some(glyph)
+    }
+
+    /// Returns true if a glyph did not fit since the atlas was last cleared.
+    pub(super) fn is_full(&self) -> bool {
+        self.full
+    }
+
+    /// Forget all glyphs, so the texture can be filled again.
+    pub(super) fn clear(&mut self) {
+        self.packer.clear();
+        self.glyphs.clear();
+        self.full = false;
+    }
+
+    fn upload(&self, queue: &wgpu::Queue, bitmap: &GlyphBitmap, x: u32, y: u32) {
+        queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &self.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d { x, y, z: 0 },
+                aspect: wgpu::TextureAspect::All,
+            },
+            &bitmap.coverage,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(bitmap.width),
+                rows_per_image: Some(bitmap.height),
+            },
+            wgpu::Extent3d {
+                width: bitmap.width,
+                height: bitmap.height,
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+}
+
+/// ShelfPacker places rectangles in rows of the height of the first
+/// rectangle placed in them, left to right and top to bottom.
+#[derive(Debug)]
+struct ShelfPacker {
+    size: u32,
+    shelves: Vec<Shelf>,
+}
+
+/// A row of a [`ShelfPacker`].
+#[derive(Debug)]
+struct Shelf {
+    y: u32,
+    height: u32,
+    /// The left edge of the free space of the row.
+    x: u32,
+}
+
+impl ShelfPacker {
+    fn new(size: u32) -> Self {
+        ShelfPacker {
+            size,
+            shelves: Vec::new(),
+        }
+    }
+
+    /// Find the top-left corner of free space for a rectangle, or `None`
+    /// if there is none.
+    ///
+    /// The rectangle goes into the lowest row it fits in that is at most
+    /// half as high again, so short glyphs do not waste tall rows.
+    fn allocate(&mut self, width: u32, height: u32) -> Option<(u32, u32)> {
+        if width > self.size || height > self.size {
+            return None;
+        }
+        let size = self.size;
+        let fits = |shelf: &&mut Shelf| {
+            height <= shelf.height && shelf.height <= height * 3 / 2 && width <= size - shelf.x
+        };
+        if let Some(shelf) = self.shelves.iter_mut().find(fits) {
+            shelf.x += width;
+            return Some((shelf.x - width, shelf.y));
+        }
+        let y = self
+            .shelves
+            .last()
+            .map_or(0, |shelf| shelf.y + shelf.height);
+        if height > self.size - y {
+            return None;
+        }
+        self.shelves.push(Shelf {
+            y,
+            height,
+            x: width,
+        });
+        Some((0, y))
+    }
+
+    fn clear(&mut self) {
+        self.shelves.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shelf_packer() {
+        let mut packer = ShelfPacker::new(10);
+        assert_eq!(packer.allocate(4, 4), Some((0, 0)));
+        assert_eq!(packer.allocate(4, 3), Some((4, 0)));
+        // Rows are filled before new ones are started.
+        assert_eq!(packer.allocate(4, 4), Some((0, 4)));
+        // Much shorter rectangles start a row of their own.
+        assert_eq!(packer.allocate(2, 2), Some((0, 8)));
+        assert_eq!(packer.allocate(2, 2), Some((2, 8)));
+        assert_eq!(packer.allocate(2, 3), Some((8, 0)));
+        assert_eq!(packer.allocate(3, 3), Some((4, 4)));
+        assert_eq!(packer.allocate(11, 1), None);
+        assert_eq!(packer.allocate(1, 5), None);
+
+        packer.clear();
+        assert_eq!(packer.allocate(10, 10), Some((0, 0)));
+    }
+}
@@ -1,10 +1,94 @@
+use std::any::{Any, TypeId};
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use super::clipboard::Clipboard;
+use super::focus::FocusManager;
+use super::overlay::Overlay;
+use super::paint::Canvas;
 use super::render::Render;
-use winit::window::Window;
+use super::text::FontCollection;
+use super::theme::Theme;
+use super::{Rect, TextDirection};
+use winit::dpi::{LogicalPosition, LogicalSize};
+use winit::keyboard::ModifiersState;
+use winit::window::{CursorIcon, Window};
+
+thread_local! {
+    /// The last version given to an inherited value.
+    static LAST_VERSION: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Return a version no inherited value had before, for a value that changed.
+pub(crate) fn next_version() -> u64 {
+    LAST_VERSION.with(|last| {
+        last.set(last.get() + 1);
+        last.get()
+    })
+}
+
+/// A value inherited by the widgets below the ancestor that provides it.
+#[derive(Debug)]
+struct Inherited {
+    type_id: TypeId,
+    value: Rc<dyn Any>,
+    version: u64,
+    /// The shared state of the provider, for handles looked up by type.
+    source: Option<Rc<dyn Any>>,
+}
 
 /// State is the main state of the application.
 #[derive(Debug)]
 pub struct State<'a> {
+    window: Option<&'a Window>,
     render: Option<Render<'a>>,
+    canvas: Canvas,
+    focus: FocusManager,
+    overlay: Overlay,
+    clipboard: Clipboard,
+    fonts: Rc<FontCollection>,
+    /// When the state was created, which frame times are measured from.
+    epoch: Instant,
+    frame_time: Duration,
+    ime_cursor_area: Option<Rect>,
+    ime_requested: Option<Rect>,
+    cursor: CursorIcon,
+    cursor_requested: CursorIcon,
+    modifiers: ModifiersState,
+    theme: Theme,
+    theme_version: u64,
+    /// The values provided by the ancestors of the widget being visited,
+    /// innermost last.
+    inherited: Vec<Inherited>,
+    needs_build: bool,
+    needs_frame: bool,
+}
+
+impl Default for State<'_> {
+    fn default() -> Self {
+        Self {
+            window: None,
+            render: None,
+            canvas: Canvas::new(),
+            focus: FocusManager::new(),
+            overlay: Overlay::new(),
+            clipboard: Clipboard::new(),
+            fonts: Rc::new(FontCollection::new()),
+            epoch: Instant::now(),
+            frame_time: Duration::ZERO,
+            ime_cursor_area: None,
+            ime_requested: None,
+            cursor: CursorIcon::Default,
+            cursor_requested: CursorIcon::Default,
+            modifiers: ModifiersState::empty(),
+            theme: Theme::default(),
+            theme_version: next_version(),
+            inherited: Vec::new(),
+            needs_build: true,
+            needs_frame: false,
+        }
+    }
 }
 
 impl<'a> State<'a> {
@@ -12,7 +96,9 @@ impl<'a> State<'a> {
     pub async fn new(window: &'a Window) -> Result<Self, crate::framework::error::RenderError> {
         let render = Render::new(window).await?;
         Ok(Self {
+            window: Some(window),
             render: Some(render),
+            ..Default::default()
         })
     }
 
@@ -25,4 +111,284 @@ impl<'a> State<'a> {
     pub fn render_mut(&mut self) -> Option<&mut Render<'a>> {
         self.render.as_mut()
     }
-}
\ No newline at end of file
+
+    /// Get a reference to the canvas of the current frame.
+    pub fn canvas(&self) -> &Canvas {
+        &self.canvas
+    }
+
+    /// Get a mutable reference to the canvas of the current frame.
+    pub fn canvas_mut(&mut self) -> &mut Canvas {
+        &mut self.canvas
+    }
+
+    /// Get a reference to the focus manager.
+    pub fn focus(&self) -> &FocusManager {
+        &self.focus
+    }
+
+    /// Get a mutable reference to the focus manager.
+    pub fn focus_mut(&mut self) -> &mut FocusManager {
+        &mut self.focus
+    }
+
+    /// Get a reference to the overlay shown above the root widget.
+    pub fn overlay(&self) -> &Overlay {
+        &self.overlay
+    }
+
+    /// Get a mutable reference to the overlay shown above the root widget.
+    pub fn overlay_mut(&mut self) -> &mut Overlay {
+        &mut self.overlay
+    }
+
+    /// Get a mutable reference to the clipboard.
+    pub fn clipboard_mut(&mut self) -> &mut Clipboard {
+        &mut self.clipboard
+    }
+
+    /// Replace the clipboard, e.g. with one backed by the platform clipboard.
+    pub fn set_clipboard(&mut self, clipboard: Clipboard) {
+        self.clipboard = clipboard;
+    }
+
+    /// Get a reference to the fonts text is measured and drawn with.
+    ///
+    /// Widgets that lay out text keep a clone while they are built and lay
+    /// out their text with it.
+    pub fn fonts(&self) -> &Rc<FontCollection> {
+        &self.fonts
+    }
+
+    /// Get a mutable reference to the fonts text is measured and drawn with,
+    /// e.g. to register bundled fonts or load the system fonts at startup.
+    ///
+    /// The widget tree is built again before the next layout, so widgets
+    /// measure their text with the new fonts.
+    pub fn fonts_mut(&mut self) -> &mut FontCollection {
+        self.request_build();
+        Rc::make_mut(&mut self.fonts)
+    }
+
+    /// The time of the current frame since the application started.
+    ///
+    /// Time-based effects such as the blinking caret are derived from it.
+    pub fn frame_time(&self) -> Duration {
+        self.frame_time
+    }
+
+    /// Set the time of the current frame since the application started.
+    pub fn set_frame_time(&mut self, frame_time: Duration) {
+        self.frame_time = frame_time;
+    }
+
+    /// Set the time of the current frame to the time elapsed since the state
+    /// was created, before the frame is built.
+    pub fn advance_frame_time(&mut self) {
+        self.frame_time = self.epoch.elapsed();
+    }
+
+    /// The modifier keys currently held.
+    pub fn modifiers(&self) -> ModifiersState {
+        self.modifiers
+    }
+
+    /// Set the modifier keys currently held.
+    pub fn set_modifiers(&mut self, modifiers: ModifiersState) {
+        self.modifiers = modifiers;
+    }
+
+    /// The theme of the widget being visited: the theme of the innermost
+    /// [`ThemeScope`](crate::framework::ThemeScope) around it, or the
+    /// application theme.
+    pub fn theme(&self) -> &Theme {
+        self.read::<Theme>().unwrap_or(&self.theme)
+    }
+
+    /// The text direction of the widget being visited: the direction of the
+    /// innermost [`Directionality`](crate::framework::Directionality) around
+    /// it, or left-to-right.
+    pub fn text_direction(&self) -> TextDirection {
+        self.read::<TextDirection>().copied().unwrap_or_default()
+    }
+
+    /// Set the application theme, e.g. to switch between light and dark.
+    ///
+    /// The widget tree is built again before the next layout if the theme
+    /// changed, so widgets pick up their new defaults.
+    pub fn set_theme(&mut self, theme: Theme) {
+        if theme != self.theme {
+            self.theme = theme;
+            self.theme_version = next_version();
+            self.request_build();
+        }
+    }
+
+    /// Returns true if the widget tree must be built before the next layout.
+    ///
+    /// This is true before the first frame and after something the widgets
+    /// inherit, such as the theme, changed.
+    pub fn needs_build(&self) -> bool {
+        self.needs_build
+    }
+
+    /// Request the widget tree to be built before the next layout, e.g.
+    /// because a widget was added that has not been built yet.
+    pub fn request_build(&mut self) {
+        self.needs_build = true;
+    }
+
+    /// Forget the build request once the tree has been built.
+    pub(crate) fn finish_build(&mut self) {
+        self.needs_build = false;
+    }
+
+    /// Returns true if another frame must be painted after the current one.
+    ///
+    /// This is true while something painted is animating or waiting for a
+    /// delay to pass, even if no event arrives.
+    pub fn needs_frame(&self) -> bool {
+        self.needs_frame
+    }
+
+    /// Request another frame after the current one, e.g. because a widget
+    /// is animating with [`State::frame_time`].
+    pub fn request_frame(&mut self) {
+        self.needs_frame = true;
+    }
+
+    /// Forget the frame request and the commands of the previous frame
+    /// before the widget tree is painted, and measure the text painted on
+    /// the canvas with the current fonts.
+    pub(crate) fn begin_frame(&mut self) {
+        self.needs_frame = false;
+        self.canvas.clear();
+        self.canvas.set_fonts(Rc::clone(&self.fonts));
+    }
+
+    /// The value of type `T` provided by the nearest ancestor of the widget
+    /// being visited, e.g. by a [`Provider`](crate::framework::Provider).
+    ///
+    /// Reading the value does not make the widget depend on it. Widgets that
+    /// resolve something from the value while they are built track it with a
+    /// [`Dependency`](crate::framework::Dependency).
+    pub fn read<T: 'static>(&self) -> Option<&T> {
+        self.find(TypeId::of::<T>())
+            .and_then(|inherited| inherited.value.downcast_ref())
+    }
+
+    /// The version of the value of type `T` the widget being visited
+    /// inherits, which changes whenever the value does.
+    pub(crate) fn version<T: 'static>(&self) -> Option<u64> {
+        let type_id = TypeId::of::<T>();
+        match self.find(type_id) {
+            Some(inherited) => Some(inherited.version),
+            // The application theme is inherited by all widgets.
+            None if type_id == TypeId::of::<Theme>() => Some(self.theme_version),
+            None => None,
+        }
+    }
+
+    /// The shared state of the nearest provider of a value of type `T`.
+    pub(crate) fn source<T: 'static>(&self) -> Option<&Rc<dyn Any>> {
+        self.find(TypeId::of::<T>())
+            .and_then(|inherited| inherited.source.as_ref())
+    }
+
+    /// Make the value inherited by the widgets visited until it is popped.
+    pub(crate) fn push_inherited<T: 'static>(
+        &mut self,
+        value: Rc<T>,
+        version: u64,
+        source: Option<Rc<dyn Any>>,
+    ) {
+        self.inherited.push(Inherited {
+            type_id: TypeId::of::<T>(),
+            value,
+            version,
+            source,
+        });
+    }
+
+    /// Restore the values inherited before the last push.
+    pub(crate) fn pop_inherited(&mut self) {
+        self.inherited.pop();
+    }
+
+    /// The innermost inherited value of the type.
+    fn find(&self, type_id: TypeId) -> Option<&Inherited> {
+        self.inherited
+            .iter()
+            .rev()
+            .find(|inherited| inherited.type_id == type_id)
+    }
+
+    /// Enable the input method for the current frame.
+    ///
+    /// The focused text input calls this while painting, with the caret
+    /// rectangle in window space, so the candidate window of the input method
+    /// is placed next to the caret. The input method is disabled again after
+    /// a frame in which no widget requested it.
+    pub fn request_ime(&mut self, cursor_area: Rect) {
+        self.ime_requested = Some(cursor_area);
+    }
+
+    /// The caret rectangle reported to the input method, or `None` if it is disabled.
+    pub fn ime_cursor_area(&self) -> Option<Rect> {
+        self.ime_cursor_area
+    }
+
+    /// Forget the input method request of the previous frame.
+    pub(crate) fn begin_ime_frame(&mut self) {
+        self.ime_requested = None;
+    }
+
+    /// Apply the input method request of the frame to the window.
+    pub(crate) fn end_ime_frame(&mut self) {
+        if self.ime_requested == self.ime_cursor_area {
+            return;
+        }
+        if let Some(window) = self.window {
+            if self.ime_cursor_area.is_none() != self.ime_requested.is_none() {
+                window.set_ime_allowed(self.ime_requested.is_some());
+            }
+            if let Some(area) = self.ime_requested {
+                window.set_ime_cursor_area(
+                    LogicalPosition::new(area.left(), area.top()),
+                    LogicalSize::new(area.size.width, area.size.height),
+                );
+            }
+        }
+        self.ime_cursor_area = self.ime_requested;
+    }
+
+    /// Show the given pointer cursor for the current frame.
+    ///
+    /// The widget under the pointer calls this while painting, e.g. with a
+    /// resize cursor over a divider. The default arrow is shown again after a
+    /// frame in which no widget requested a cursor.
+    pub fn request_cursor(&mut self, cursor: CursorIcon) {
+        self.cursor_requested = cursor;
+    }
+
+    /// The pointer cursor shown in the window.
+    pub fn cursor(&self) -> CursorIcon {
+        self.cursor
+    }
+
+    /// Forget the cursor request of the previous frame.
+    pub(crate) fn begin_cursor_frame(&mut self) {
+        self.cursor_requested = CursorIcon::Default;
+    }
+
+    /// Apply the cursor request of the frame to the window.
+    pub(crate) fn end_cursor_frame(&mut self) {
+        if self.cursor_requested == self.cursor {
+            return;
+        }
+        if let Some(window) = self.window {
+            window.set_cursor_icon(self.cursor_requested);
+        }
+        self.cursor = self.cursor_requested;
+    }
+}
@@ -0,0 +1,517 @@
+use std::fmt;
+use std::ops::Range;
+use std::rc::Rc;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// TextSelection is a range of text selected by the user.
+///
+/// Indices are byte offsets into the text and always lie on grapheme
+/// boundaries. The base is where the selection started and the extent is where
+/// the caret is; the extent may come before the base.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextSelection {
+    /// The byte offset where the selection started.
+    pub base: usize,
+    /// The byte offset of the caret.
+    pub extent: usize,
+}
+
+impl TextSelection {
+    /// Create a new TextSelection instance from base to extent.
+    pub fn new(base: usize, extent: usize) -> Self {
+        TextSelection { base, extent }
+    }
+
+    /// Create a new empty TextSelection instance with the caret at the index.
+    pub fn collapsed(index: usize) -> Self {
+        TextSelection::new(index, index)
+    }
+
+    /// The smaller of base and extent.
+    pub fn start(&self) -> usize {
+        self.base.min(self.extent)
+    }
+
+    /// The larger of base and extent.
+    pub fn end(&self) -> usize {
+        self.base.max(self.extent)
+    }
+
+    /// The selected byte range.
+    pub fn range(&self) -> Range<usize> {
+        self.start()..self.end()
+    }
+
+    /// Returns true if nothing is selected.
+    pub fn is_collapsed(&self) -> bool {
+        self.base == self.extent
+    }
+}
+
+/// InputFilter decides which characters may be entered into a text field.
+#[derive(Clone)]
+pub struct InputFilter {
+    accepts: Rc<dyn Fn(char) -> bool>,
+}
+
+impl InputFilter {
+    /// Create a new InputFilter instance accepting only characters matching the predicate.
+    pub fn allow(predicate: impl Fn(char) -> bool + 'static) -> Self {
+        InputFilter {
+            accepts: Rc::new(predicate),
+        }
+    }
+
+    /// Create a new InputFilter instance rejecting characters matching the predicate.
+    pub fn deny(predicate: impl Fn(char) -> bool + 'static) -> Self {
+        InputFilter::allow(move |c| !predicate(c))
+    }
+
+    /// Create a new InputFilter instance accepting only ASCII digits.
+    pub fn digits_only() -> Self {
+        InputFilter::allow(|c| c.is_ascii_digit())
+    }
+
+    /// Create a new InputFilter instance rejecting line breaks.
+    pub fn single_line() -> Self {
+        InputFilter::deny(|c| c == '\n' || c == '\r')
+    }
+
+    /// Returns true if the character may be entered.
+    pub fn accepts(&self, c: char) -> bool {
+        (self.accepts)(c)
+    }
+}
+
+impl fmt::Debug for InputFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InputFilter").finish_non_exhaustive()
+    }
+}
+
+/// The kind of the last edit, used to merge consecutive edits into a single
+/// undo step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EditKind {
+    None,
+    Typing,
+    Deleting,
+    Other,
+}
+
+/// A saved state of the editor for undo and redo.
+#[derive(Debug, Clone)]
+struct Snapshot {
+    text: String,
+    selection: TextSelection,
+}
+
+/// TextEditor is the editable text and selection behind a text field.
+///
+/// It implements the editing operations independent of layout and input
+/// handling: inserting and deleting graphemes and words, moving the caret,
+/// and undo and redo history.
+#[derive(Debug, Clone)]
+pub struct TextEditor {
+    text: String,
+    selection: TextSelection,
+    max_length: Option<usize>,
+    filters: Vec<InputFilter>,
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+    last_edit: EditKind,
+}
+
+impl Default for TextEditor {
+    fn default() -> Self {
+        TextEditor::new("")
+    }
+}
+
+impl TextEditor {
+    /// Create a new TextEditor instance with the caret after the given text.
+    pub fn new(text: impl Into<String>) -> Self {
+        let text = text.into();
+        TextEditor {
+            selection: TextSelection::collapsed(text.len()),
+            text,
+            max_length: None,
+            filters: Vec::new(),
+            undo: Vec::new(),
+            redo: Vec::new(),
+            last_edit: EditKind::None,
+        }
+    }
+
+    /// The current text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Replace the text, clearing the undo history and moving the caret to the end.
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        self.text = text.into();
+        self.selection = TextSelection::collapsed(self.text.len());
+        self.undo.clear();
+        self.redo.clear();
+        self.last_edit = EditKind::None;
+    }
+
+    /// The current selection.
+    pub fn selection(&self) -> TextSelection {
+        self.selection
+    }
+
+    /// Set the selection, clamped to the text and snapped to grapheme boundaries.
+    pub fn set_selection(&mut self, selection: TextSelection) {
+        self.selection = TextSelection::new(self.snap(selection.base), self.snap(selection.extent));
+        self.last_edit = EditKind::None;
+    }
+
+    /// The selected text.
+    pub fn selected_text(&self) -> &str {
+        &self.text[self.selection.range()]
+    }
+
+    /// Limit the text to the given number of grapheme clusters.
+    pub fn set_max_length(&mut self, max_length: Option<usize>) {
+        self.max_length = max_length;
+    }
+
+    /// Add a filter every inserted character must pass.
+    pub fn add_filter(&mut self, filter: InputFilter) {
+        self.filters.push(filter);
+    }
+
+    /// Replace the selection with the text, returning true if the text changed.
+    ///
+    /// Characters rejected by a filter are dropped and the text is truncated to
+    /// respect the maximum length.
+    pub fn insert(&mut self, text: &str) -> bool {
+        self.insert_as(text, EditKind::Typing)
+    }
+
+    /// Replace the selection with pasted text, returning true if the text changed.
+    ///
+    /// Unlike typing, every paste is undone on its own.
+    pub fn paste(&mut self, text: &str) -> bool {
+        self.insert_as(text, EditKind::Other)
+    }
+
+    fn insert_as(&mut self, text: &str, kind: EditKind) -> bool {
+        let mut text: String = text
+            .chars()
+            .filter(|c| self.filters.iter().all(|filter| filter.accepts(*c)))
+            .collect();
+        if let Some(max_length) = self.max_length {
+            let kept =
+                self.text.graphemes(true).count() - self.selected_text().graphemes(true).count();
+            let available = max_length.saturating_sub(kept);
+            if let Some((index, _)) = text.grapheme_indices(true).nth(available) {
+                text.truncate(index);
+            }
+        }
+        if text.is_empty() && self.selection.is_collapsed() {
+            return false;
+        }
+        self.replace_selection(&text, kind);
+        true
+    }
+
+    /// Delete the selection or the grapheme (or word) before the caret.
+    pub fn delete_backward(&mut self, word: bool) -> bool {
+        if self.selection.is_collapsed() {
+            let caret = self.selection.extent;
+            let target = if word {
+                self.previous_word(caret)
+            } else {
+                self.previous_grapheme(caret)
+            };
+            self.selection = TextSelection::new(target, caret);
+        }
+        self.delete_selection()
+    }
+
+    /// Delete the selection or the grapheme (or word) after the caret.
+    pub fn delete_forward(&mut self, word: bool) -> bool {
+        if self.selection.is_collapsed() {
+            let caret = self.selection.extent;
+            let target = if word {
+                self.next_word(caret)
+            } else {
+                self.next_grapheme(caret)
+            };
+            self.selection = TextSelection::new(caret, target);
+        }
+        self.delete_selection()
+    }
+
+    /// Delete the selected text, returning true if anything was deleted.
+    pub fn delete_selection(&mut self) -> bool {
+        if self.selection.is_collapsed() {
+            return false;
+        }
+        self.replace_selection("", EditKind::Deleting);
+        true
+    }
+
+    /// Move the caret one grapheme (or word) to the left.
+    ///
+    /// Without `extend` a selection collapses to its start instead.
+    pub fn move_left(&mut self, word: bool, extend: bool) {
+        let caret = self.selection.extent;
+        let target = match (word, extend || self.selection.is_collapsed()) {
+            (_, false) => self.selection.start(),
+            (true, true) => self.previous_word(caret),
+            (false, true) => self.previous_grapheme(caret),
+        };
+        self.move_to(target, extend);
+    }
+
+    /// Move the caret one grapheme (or word) to the right.
+    ///
+    /// Without `extend` a selection collapses to its end instead.
+    pub fn move_right(&mut self, word: bool, extend: bool) {
+        let caret = self.selection.extent;
+        let target = match (word, extend || self.selection.is_collapsed()) {
+            (_, false) => self.selection.end(),
+            (true, true) => self.next_word(caret),
+            (false, true) => self.next_grapheme(caret),
+        };
+        self.move_to(target, extend);
+    }
+
+    /// Move the caret to the start of its line.
+    pub fn move_line_start(&mut self, extend: bool) {
+        let caret = self.selection.extent;
+        let target = self.text[..caret].rfind('\n').map_or(0, |index| index + 1);
+        self.move_to(target, extend);
+    }
+
+    /// Move the caret to the end of its line.
+    pub fn move_line_end(&mut self, extend: bool) {
+        let caret = self.selection.extent;
+        let target = self.text[caret..]
+            .find('\n')
+            .map_or(self.text.len(), |index| caret + index);
+        self.move_to(target, extend);
+    }
+
+    /// Move the caret to the start of the text.
+    pub fn move_document_start(&mut self, extend: bool) {
+        self.move_to(0, extend);
+    }
+
+    /// Move the caret to the end of the text.
+    pub fn move_document_end(&mut self, extend: bool) {
+        self.move_to(self.text.len(), extend);
+    }
+
+    /// Move the caret to the index, extending the selection if requested.
+    pub fn move_to(&mut self, index: usize, extend: bool) {
+        let index = self.snap(index);
+        let base = if extend { self.selection.base } else { index };
+        self.selection = TextSelection::new(base, index);
+        self.last_edit = EditKind::None;
+    }
+
+    /// Select the whole text.
+    pub fn select_all(&mut self) {
+        self.selection = TextSelection::new(0, self.text.len());
+        self.last_edit = EditKind::None;
+    }
+
+    /// The byte range of the word at the index.
+    pub fn word_at(&self, index: usize) -> Range<usize> {
+        self.text
+            .split_word_bound_indices()
+            .map(|(start, word)| start..start + word.len())
+            .find(|range| range.contains(&index))
+            .unwrap_or(index..index)
+    }
+
+    /// Returns true if there is an edit to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// Returns true if there is an undone edit to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Revert the last edit, returning true if there was one.
+    pub fn undo(&mut self) -> bool {
+        let Some(snapshot) = self.undo.pop() else {
+            return false;
+        };
+        self.redo.push(self.snapshot());
+        self.restore(snapshot);
+        true
+    }
+
+    /// Apply the last undone edit again, returning true if there was one.
+    pub fn redo(&mut self) -> bool {
+        let Some(snapshot) = self.redo.pop() else {
+            return false;
+        };
+        self.undo.push(self.snapshot());
+        self.restore(snapshot);
+        true
+    }
+
+    fn replace_selection(&mut self, text: &str, kind: EditKind) {
+        // Consecutive typing or deleting is undone in a single step.
+        if kind != self.last_edit || kind == EditKind::Other {
+            self.undo.push(self.snapshot());
+        }
+        self.redo.clear();
+        let start = self.selection.start();
+        self.text.replace_range(self.selection.range(), text);
+        self.selection = TextSelection::collapsed(start + text.len());
+        self.last_edit = kind;
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            text: self.text.clone(),
+            selection: self.selection,
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.text = snapshot.text;
+        self.selection = snapshot.selection;
+        self.last_edit = EditKind::None;
+    }
+
+    /// Clamp the index to the text and move it back to a grapheme boundary.
+    fn snap(&self, index: usize) -> usize {
+        if index >= self.text.len() {
+            return self.text.len();
+        }
+        self.text
+            .grapheme_indices(true)
+            .map(|(start, _)| start)
+            .take_while(|start| *start <= index)
+            .last()
+            .unwrap_or(0)
+    }
+
+    fn previous_grapheme(&self, index: usize) -> usize {
+        self.text[..index]
+            .grapheme_indices(true)
+            .next_back()
+            .map_or(0, |(start, _)| start)
+    }
+
+    fn next_grapheme(&self, index: usize) -> usize {
+        self.text[index..]
+            .graphemes(true)
+            .next()
+            .map_or(index, |grapheme| index + grapheme.len())
+    }
+
+    /// The start of the word before the index, skipping whitespace.
+    fn previous_word(&self, index: usize) -> usize {
+        self.text
+            .split_word_bound_indices()
+            .filter(|(start, word)| *start < index && !word.trim().is_empty())
+            .last()
+            .map_or(0, |(start, _)| start)
+    }
+
+    /// The end of the word after the index, skipping whitespace.
+    fn next_word(&self, index: usize) -> usize {
+        self.text
+            .split_word_bound_indices()
+            .map(|(start, word)| (start + word.len(), word))
+            .find(|(end, word)| *end > index && !word.trim().is_empty())
+            .map_or(self.text.len(), |(end, _)| end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_and_delete() {
+        let mut editor = TextEditor::new("hello");
+        assert!(editor.insert(" world"));
+        assert_eq!(editor.text(), "hello world");
+        editor.move_left(true, false);
+        assert_eq!(editor.selection(), TextSelection::collapsed(6));
+        assert!(editor.delete_backward(false));
+        assert_eq!(editor.text(), "helloworld");
+        assert!(editor.delete_forward(true));
+        assert_eq!(editor.text(), "hello");
+        assert!(!editor.delete_forward(false));
+    }
+
+    #[test]
+    fn test_graphemes() {
+        let mut editor = TextEditor::new("ae\u{301}");
+        editor.move_left(false, false);
+        assert_eq!(editor.selection().extent, 1);
+        editor.set_selection(TextSelection::collapsed(2));
+        assert_eq!(editor.selection().extent, 1);
+        editor.move_document_end(false);
+        assert!(editor.delete_backward(false));
+        assert_eq!(editor.text(), "a");
+    }
+
+    #[test]
+    fn test_selection_movement() {
+        let mut editor = TextEditor::new("one two\nthree");
+        editor.move_line_start(false);
+        assert_eq!(editor.selection().extent, 8);
+        editor.move_left(true, true);
+        assert_eq!(editor.selection(), TextSelection::new(8, 4));
+        assert_eq!(editor.selected_text(), "two\n");
+        editor.move_right(false, false);
+        assert_eq!(editor.selection(), TextSelection::collapsed(8));
+        editor.move_document_start(false);
+        editor.move_line_end(true);
+        assert_eq!(editor.selected_text(), "one two");
+        assert_eq!(editor.word_at(5), 4..7);
+        editor.select_all();
+        assert!(editor.insert("x"));
+        assert_eq!(editor.text(), "x");
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let mut editor = TextEditor::new("");
+        editor.insert("a");
+        editor.insert("b");
+        editor.move_left(false, false);
+        editor.insert("c");
+        assert_eq!(editor.text(), "acb");
+        assert!(editor.undo());
+        assert_eq!(editor.text(), "ab");
+        assert!(editor.undo());
+        assert_eq!(editor.text(), "");
+        assert!(!editor.undo());
+        assert!(editor.redo());
+        assert_eq!(editor.text(), "ab");
+        editor.insert("d");
+        assert!(!editor.can_redo());
+    }
+
+    #[test]
+    fn test_filters_and_max_length() {
+        let mut editor = TextEditor::new("");
+        editor.add_filter(InputFilter::digits_only());
+        editor.set_max_length(Some(3));
+        assert!(editor.insert("1a2b"));
+        assert_eq!(editor.text(), "12");
+        assert!(editor.insert("345"));
+        assert_eq!(editor.text(), "123");
+        assert!(!editor.insert("6"));
+        editor.select_all();
+        assert!(editor.insert("789"));
+        assert_eq!(editor.text(), "789");
+    }
+}
@@ -0,0 +1,1275 @@
+use std::cell::{Cell, OnceCell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::ffi::OsStr;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use ttf_parser::{name_id, Face, GlyphId, Width};
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::raster::{self, GlyphBitmap};
+use super::style::{FontStyle, FontWeight, TextStyle, TAB_WIDTH};
+use crate::framework::error::FontError;
+use crate::framework::TextDirection;
+
+/// The family used when a text style names none.
+const DEFAULT_FAMILY: &str = "sans-serif";
+
+/// The file extensions of the fonts found in font directories.
+const FONT_EXTENSIONS: &[&str] = &["ttf", "otf", "ttc", "otc"];
+
+/// The tables holding the names, styles and vertical metrics of a face,
+/// which are all that is read of a font file to register it.
+const REGISTRATION_TABLES: &[&[u8; 4]] = &[b"OS/2", b"head", b"hhea", b"maxp", b"name"];
+
+/// FontId identifies a face registered with a [`FontCollection`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FontId(usize);
+
+/// Where the bytes of a face come from.
+#[derive(Debug, Clone)]
+enum FontSource {
+    /// The bytes were registered directly.
+    Memory,
+    /// The bytes are read from the file when first needed.
+    File(PathBuf),
+}
+
+/// FontFace is a single face of a font, e.g. the bold italic face of a
+/// family.
+#[derive(Clone)]
+pub struct FontFace {
+    family: String,
+    weight: FontWeight,
+    style: FontStyle,
+    /// Whether the glyphs are neither condensed nor expanded.
+    normal_width: bool,
+    /// The height of the glyphs above the baseline as a fraction of the em.
+    ascent: f32,
+    /// The depth of the glyphs below the baseline as a fraction of the em.
+    descent: f32,
+    /// The index of the face in a font collection file.
+    index: u32,
+    source: FontSource,
+    data: OnceCell<Arc<[u8]>>,
+    /// Whether reading the file of the face failed before, so the failure is
+    /// only logged once.
+    read_failed: Cell<bool>,
+}
+
+impl FontFace {
+    /// Read the family, weight and style of the face.
+    fn new(face: &Face<'_>, index: u32, source: FontSource) -> Self {
+        let name = |id| {
+            face.names()
+                .into_iter()
+                .filter(|name| name.name_id == id)
+                .find_map(|name| name.to_string())
+        };
+        let weight = match face.weight().to_number() {
+            // Fonts without an OS/2 table report no weight.
+            0 => FontWeight::NORMAL,
+            weight => FontWeight(weight.min(1000)),
+        };
+        let em = f32::from(face.units_per_em().max(1));
+        FontFace {
+            family: name(name_id::TYPOGRAPHIC_FAMILY)
+                .or_else(|| name(name_id::FAMILY))
+                .unwrap_or_default(),
+            weight,
+            style: if face.is_italic() || face.is_oblique() {
+                FontStyle::Italic
+            } else {
+                FontStyle::Normal
+            },
+            normal_width: face.width() == Width::Normal,
+            ascent: f32::from(face.ascender()) / em,
+            descent: -f32::from(face.descender()) / em,
+            index,
+            source,
+            data: OnceCell::new(),
+            read_failed: Cell::new(false),
+        }
+    }
+
+    /// The name of the family the face belongs to.
+    pub fn family(&self) -> &str {
+        &self.family
+    }
+
+    /// The thickness of the glyphs of the face.
+    pub fn weight(&self) -> FontWeight {
+        self.weight
+    }
+
+    /// Whether the glyphs of the face are upright or italic.
+    pub fn style(&self) -> FontStyle {
+        self.style
+    }
+
+    /// The index of the face within its data, which is non-zero for the
+    /// later faces of a font collection (`.ttc`) file.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// The file the face was loaded from, if any.
+    pub fn path(&self) -> Option<&Path> {
+        match &self.source {
+            FontSource::Memory => None,
+            FontSource::File(path) => Some(path),
+        }
+    }
+
+    /// The bytes of the font holding the face.
+    ///
+    /// Faces found on disk are read when first needed, and return `None`
+    /// while the file cannot be read; it is read again the next time.
+    pub fn data(&self) -> Option<Arc<[u8]>> {
+        self.bytes().cloned()
+    }
+
+    /// Return whether the face has a glyph for the character.
+    pub fn has_glyph(&self, c: char) -> bool {
+        self.parse()
+            .is_some_and(|face| face.glyph_index(c).is_some())
+    }
+
+    fn bytes(&self) -> Option<&Arc<[u8]>> {
+        if let Some(data) = self.data.get() {
+            return Some(data);
+        }
+        let FontSource::File(path) = &self.source else {
+            return None;
+        };
+        match fs::read(path) {
+            Ok(data) => Some(self.data.get_or_init(|| data.into())),
+            Err(err) => {
+                if !self.read_failed.replace(true) {
+                    log::warn!("Failed to read font {}: {err}", path.display());
+                }
+                None
+            }
+        }
+    }
+
+    fn parse(&self) -> Option<Face<'_>> {
+        Face::parse(self.bytes()?, self.index).ok()
+    }
+}
+
+impl fmt::Debug for FontFace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FontFace")
+            .field("family", &self.family)
+            .field("weight", &self.weight)
+            .field("style", &self.style)
+            .field("index", &self.index)
+            .field("source", &self.source)
+            .finish_non_exhaustive()
+    }
+}
+
+/// PlacedGlyph is a glyph of a line of text and where it is drawn.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct PlacedGlyph {
+    /// The face the glyph is drawn with.
+    pub font: FontId,
+    /// The glyph in the face.
+    pub glyph: GlyphId,
+    /// The pen position from the left edge of the line, in logical pixels.
+    pub x: f32,
+}
+
+/// FontRun is a range of text drawn with a single face.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FontRun {
+    /// The byte range of the run in the text.
+    pub range: Range<usize>,
+    /// The face the run is drawn with.
+    pub font: FontId,
+}
+
+/// FontCollection holds the fonts text can be drawn with.
+///
+/// Fonts are registered from bytes, e.g. ones bundled with
+/// `include_bytes!`, from files, or from the directories the system
+/// installs fonts in. Text styles select a face by family, weight and
+/// style; characters the selected face has no glyph for are drawn with the
+/// first face of the fallback families that has one, such as an emoji or a
+/// CJK font.
+///
+/// The generic families `sans-serif`, `serif` and `monospace` stand for the
+/// first installed family of a list of common ones, and can be pointed at
+/// other families with [`set_generic_family`](Self::set_generic_family).
+///
+/// Text is measured with the faces it is drawn with, see
+/// [`advances`](Self::advances); without any face the approximate metrics
+/// of [`TextStyle`] are used.
+#[derive(Clone)]
+pub struct FontCollection {
+    faces: Vec<FontFace>,
+    /// The files faces were loaded from, so no file is loaded twice.
+    paths: HashSet<PathBuf>,
+    generic: HashMap<String, Vec<String>>,
+    fallback: Vec<String>,
+    /// The face found for characters none of the fallback families cover.
+    last_resort: RefCell<HashMap<char, Option<FontId>>>,
+    /// The advances of the glyphs of the characters measured so far, as a
+    /// fraction of the em.
+    glyph_advances: RefCell<HashMap<(FontId, char), f32>>,
+}
+
+impl Default for FontCollection {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FontCollection {
+    /// Create a new FontCollection instance without any fonts.
+    pub fn new() -> Self {
+        let families = |names: &[&str]| names.iter().copied().map(String::from).collect();
+        FontCollection {
+            faces: Vec::new(),
+            paths: HashSet::new(),
+            generic: HashMap::from([
+                (
+                    "sans-serif".to_string(),
+                    families(&[
+                        "Noto Sans",
+                        "DejaVu Sans",
+                        "Liberation Sans",
+                        "Cantarell",
+                        "Ubuntu",
+                        "Arial",
+                        "Helvetica",
+                    ]),
+                ),
+                (
+                    "serif".to_string(),
+                    families(&[
+                        "Noto Serif",
+                        "DejaVu Serif",
+                        "Liberation Serif",
+                        "Times New Roman",
+                        "Times",
+                    ]),
+                ),
+                (
+                    "monospace".to_string(),
+                    families(&[
+                        "Noto Sans Mono",
+                        "DejaVu Sans Mono",
+                        "Liberation Mono",
+                        "Ubuntu Mono",
+                        "Courier New",
+                    ]),
+                ),
+            ]),
+            fallback: families(&[
+                "Noto Color Emoji",
+                "Noto Emoji",
+                "Twemoji",
+                "Noto Sans CJK SC",
+                "Noto Sans CJK JP",
+                "Noto Sans CJK KR",
+                "Source Han Sans",
+                "WenQuanYi Micro Hei",
+                "Noto Sans Arabic",
+                "Noto Sans Hebrew",
+                "Noto Sans Devanagari",
+                "Noto Sans Thai",
+                "Noto Sans Symbols",
+                "Noto Sans Symbols2",
+                "DejaVu Sans",
+            ]),
+            last_resort: RefCell::new(HashMap::new()),
+            glyph_advances: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Register the faces of a font, returning their ids.
+    ///
+    /// Font collections (`.ttc` and `.otc` data) register all their faces.
+    pub fn register(&mut self, data: impl Into<Arc<[u8]>>) -> Result<Vec<FontId>, FontError> {
+        let data = data.into();
+        let faces = parse_faces(&data, || FontSource::Memory)?;
+        let ids = self.push(faces);
+        for &id in &ids {
+            let _ = self.faces[id.0].data.set(data.clone());
+        }
+        Ok(ids)
+    }
+
+    /// Register the faces of the font in a file, returning their ids.
+    ///
+    /// Only the tables holding the names, styles and metrics of the faces
+    /// are read; the whole file is read when the glyphs of a face are first
+    /// needed. A file that was registered before registers no faces.
+    pub fn register_file(&mut self, path: impl AsRef<Path>) -> Result<Vec<FontId>, FontError> {
+        let path = path.as_ref();
+        if self.paths.contains(path) {
+            return Ok(Vec::new());
+        }
+        let faces = read_faces(path)?;
+        self.paths.insert(path.to_path_buf());
+        Ok(self.push(faces))
+    }
+
+    /// Register the fonts in a directory and its subdirectories, returning
+    /// the number of faces found.
+    ///
+    /// Files that are not fonts, or cannot be read, are skipped.
+    pub fn add_font_dir(&mut self, dir: impl AsRef<Path>) -> usize {
+        let mut count = 0;
+        for path in font_files(dir.as_ref()) {
+            match self.register_file(&path) {
+                Ok(ids) => count += ids.len(),
+                Err(err) => log::debug!("Skipping font {}: {err}", path.display()),
+            }
+        }
+        count
+    }
+
+    /// Register the fonts installed on the system, returning the number of
+    /// faces found.
+    ///
+    /// On Linux and other Unix systems the directories fontconfig searches
+    /// by default are scanned: the `fonts` directories of the XDG data
+    /// directories, `/usr/share/fonts`, `/usr/local/share/fonts` and
+    /// `~/.fonts`. No fonts are found on other systems.
+    pub fn load_system_fonts(&mut self) -> usize {
+        system_font_dirs()
+            .iter()
+            .map(|dir| self.add_font_dir(dir))
+            .sum()
+    }
+
+    /// The number of registered faces.
+    pub fn len(&self) -> usize {
+        self.faces.len()
+    }
+
+    /// Return whether no face is registered.
+    pub fn is_empty(&self) -> bool {
+        self.faces.is_empty()
+    }
+
+    /// Get the face with the id, if it is registered with this collection.
+    pub fn face(&self, id: FontId) -> Option<&FontFace> {
+        self.faces.get(id.0)
+    }
+
+    /// Iterate over the registered faces in the order they were registered.
+    pub fn faces(&self) -> impl Iterator<Item = (FontId, &FontFace)> {
+        self.faces
+            .iter()
+            .enumerate()
+            .map(|(index, face)| (FontId(index), face))
+    }
+
+    /// Return whether a face of the family is registered, ignoring case.
+    pub fn has_family(&self, family: &str) -> bool {
+        self.faces
+            .iter()
+            .any(|face| face.family.eq_ignore_ascii_case(family))
+    }
+
+    /// Set the families a generic family such as `sans-serif` stands for,
+    /// in order of preference.
+    pub fn set_generic_family(&mut self, generic: &str, families: Vec<String>) {
+        self.generic.insert(generic.to_ascii_lowercase(), families);
+    }
+
+    /// Set the families characters missing from the selected face are drawn
+    /// with, in order of preference.
+    pub fn set_fallback(&mut self, families: Vec<String>) {
+        self.fallback = families;
+        self.last_resort.borrow_mut().clear();
+    }
+
+    /// The families characters missing from the selected face are drawn
+    /// with.
+    pub fn fallback(&self) -> &[String] {
+        &self.fallback
+    }
+
+    /// Select the face closest to the family, weight and style.
+    ///
+    /// `None` selects the `sans-serif` family. Within a family the closest
+    /// face is found the way CSS does: the style is matched first, with
+    /// italic falling back to upright, then the weight, with weights below
+    /// 400 preferring lighter faces, above 500 preferring heavier ones, and
+    /// 400 and 500 trying the weights up to 500 first. If the family is not
+    /// registered the default family is used, then any face. Returns `None`
+    /// only if the collection is empty.
+    pub fn query(
+        &self,
+        family: Option<&str>,
+        weight: FontWeight,
+        style: FontStyle,
+    ) -> Option<FontId> {
+        let family = family.unwrap_or(DEFAULT_FAMILY);
+        self.find_family(family, weight, style)
+            .or_else(|| self.find_family(DEFAULT_FAMILY, weight, style))
+            .or_else(|| best_match(self.faces(), weight, style))
+    }
+
+    /// Split the text into runs drawn with a single face each.
+    ///
+    /// Every grapheme is drawn with the face the style selects if it has
+    /// glyphs for it, otherwise with the first fallback family that does,
+    /// otherwise with any registered face that does. Graphemes no face
+    /// covers are left to the selected face. Spaces and punctuation stay in
+    /// the face of the text before them when it covers them, so they do not
+    /// break a run of fallback text. Returns no runs if the collection is
+    /// empty.
+    pub fn resolve(&self, text: &str, style: &TextStyle) -> Vec<FontRun> {
+        let Some(primary) =
+            self.query(style.font_family.as_deref(), style.weight, style.font_style)
+        else {
+            return Vec::new();
+        };
+        let mut chain = vec![primary];
+        for family in &self.fallback {
+            if let Some(id) = self.find_family(family, style.weight, style.font_style) {
+                if !chain.contains(&id) {
+                    chain.push(id);
+                }
+            }
+        }
+        let parsed: Vec<(FontId, Option<Face<'_>>)> = chain
+            .into_iter()
+            .map(|id| (id, self.faces[id.0].parse()))
+            .collect();
+        let covers =
+            |id: FontId, grapheme: &str| match parsed.iter().find(|(chained, _)| *chained == id) {
+                Some((_, face)) => face.as_ref().is_some_and(|face| covers(face, grapheme)),
+                // Faces found as a last resort are not part of the chain.
+                None => self.faces[id.0]
+                    .parse()
+                    .is_some_and(|face| covers(&face, grapheme)),
+            };
+
+        let mut runs: Vec<FontRun> = Vec::new();
+        for (start, grapheme) in text.grapheme_indices(true) {
+            let previous = runs.last().map(|run| run.font);
+            let neutral = !grapheme.chars().any(char::is_alphanumeric);
+            let font = previous
+                .filter(|&id| neutral && covers(id, grapheme))
+                .or_else(|| {
+                    parsed
+                        .iter()
+                        .map(|(id, _)| *id)
+                        .find(|&id| covers(id, grapheme))
+                })
+                .or_else(|| self.last_resort(grapheme))
+                .unwrap_or(primary);
+            let end = start + grapheme.len();
+            match runs.last_mut() {
+                Some(run) if run.font == font => run.range.end = end,
+                _ => runs.push(FontRun {
+                    range: start..end,
+                    font,
+                }),
+            }
+        }
+        runs
+    }
+
+    /// The horizontal advances of the graphemes of a single line of text in
+    /// the style, in logical pixels.
+    ///
+    /// Every grapheme is measured with the face [`resolve`](Self::resolve)
+    /// selects for it. Text is not shaped, so a grapheme is as wide as the
+    /// glyph of its first character, and a tab as wide as four spaces. The
+    /// letter spacing is added to every grapheme but line breaks. Graphemes
+    /// no face has horizontal metrics for are measured with
+    /// [`TextStyle::advance`], as is all text if the collection is empty.
+    pub fn advances(&self, text: &str, style: &TextStyle) -> Vec<f32> {
+        let runs = self.resolve(text, style);
+        let mut runs = runs.iter().peekable();
+        text.grapheme_indices(true)
+            .map(|(start, grapheme)| {
+                while runs.next_if(|run| run.range.end <= start).is_some() {}
+                runs.peek()
+                    .and_then(|run| self.advance(run.font, grapheme, style))
+                    .unwrap_or_else(|| style.advance(grapheme))
+            })
+            .collect()
+    }
+
+    /// The width of a single line of text in the style.
+    pub fn measure(&self, text: &str, style: &TextStyle) -> f32 {
+        self.advances(text, style).into_iter().sum()
+    }
+
+    /// The glyphs of a single line of text in the style, in the order of
+    /// the text.
+    ///
+    /// The graphemes are placed by their [`advances`](Self::advances), from
+    /// the left edge of the line or, for right-to-left text, from its right
+    /// edge. Text is not shaped: every character of a grapheme is drawn with
+    /// its own glyph at the start of the grapheme. Control characters have
+    /// no glyph, and neither has any text if the collection is empty.
+    pub(crate) fn glyphs(
+        &self,
+        text: &str,
+        style: &TextStyle,
+        direction: TextDirection,
+    ) -> Vec<PlacedGlyph> {
+        let runs = self.resolve(text, style);
+        let advances = self.advances(text, style);
+        let width: f32 = advances.iter().sum();
+        let mut parsed: HashMap<FontId, Option<Face<'_>>> = HashMap::new();
+        let mut pen = 0.0;
+        let mut glyphs = Vec::new();
+        for ((start, grapheme), advance) in text.grapheme_indices(true).zip(advances) {
+            let x = match direction {
+                TextDirection::Ltr => pen,
+                TextDirection::Rtl => width - pen - advance,
+            };
+            pen += advance;
+            let Some(run) = runs.iter().find(|run| run.range.contains(&start)) else {
+                continue;
+            };
+            let face = parsed
+                .entry(run.font)
+                .or_insert_with(|| self.faces[run.font.0].parse());
+            let Some(face) = face else {
+                continue;
+            };
+            for c in grapheme.chars().filter(|c| !c.is_control()) {
+                glyphs.push(PlacedGlyph {
+                    font: run.font,
+                    glyph: face.glyph_index(c).unwrap_or(GlyphId(0)),
+                    x,
+                });
+            }
+        }
+        glyphs
+    }
+
+    /// Rasterize a glyph of a face with an em of `size` pixels, or return
+    /// `None` if the face cannot be read or the glyph has no outline.
+    pub(crate) fn rasterize(&self, font: FontId, glyph: GlyphId, size: f32) -> Option<GlyphBitmap> {
+        let face = self.faces.get(font.0)?.parse()?;
+        raster::rasterize(&face, glyph, size)
+    }
+
+    /// The distance from the top of a line of text in the style to its
+    /// baseline.
+    ///
+    /// Like [`TextStyle::ascent`] it includes half of the space the line
+    /// height adds around the glyphs, but the glyphs are as high as the
+    /// ascender and descender of the face the style selects say. Faces
+    /// without vertical metrics, or no face, use the approximate metrics of
+    /// the style.
+    pub fn ascent(&self, style: &TextStyle) -> f32 {
+        let face = self
+            .query(style.font_family.as_deref(), style.weight, style.font_style)
+            .map(|id| &self.faces[id.0]);
+        match face {
+            Some(face) if face.ascent + face.descent > 0.0 => {
+                let glyphs = (face.ascent + face.descent) * style.font_size;
+                (style.line_extent() - glyphs) / 2.0 + face.ascent * style.font_size
+            }
+            _ => style.ascent(),
+        }
+    }
+
+    /// The advance of a grapheme drawn with the face, or `None` if the face
+    /// cannot be read or has no horizontal metrics.
+    fn advance(&self, font: FontId, grapheme: &str, style: &TextStyle) -> Option<f32> {
+        let (c, count) = match grapheme.chars().next()? {
+            '\n' | '\r' => return Some(0.0),
+            '\t' => (' ', TAB_WIDTH),
+            c => (c, 1.0),
+        };
+        let em = self.glyph_advance(font, c)?;
+        Some(em * count * style.font_size + style.letter_spacing)
+    }
+
+    /// The advance of the glyph of a character as a fraction of the em.
+    fn glyph_advance(&self, font: FontId, c: char) -> Option<f32> {
+        if let Some(&advance) = self.glyph_advances.borrow().get(&(font, c)) {
+            return Some(advance);
+        }
+        let face = self.faces.get(font.0)?.parse()?;
+        // Characters without a glyph are drawn with the missing glyph.
+        let glyph = face.glyph_index(c).unwrap_or(GlyphId(0));
+        let advance =
+            f32::from(face.glyph_hor_advance(glyph)?) / f32::from(face.units_per_em().max(1));
+        self.glyph_advances.borrow_mut().insert((font, c), advance);
+        Some(advance)
+    }
+
+    fn push(&mut self, faces: Vec<FontFace>) -> Vec<FontId> {
+        let start = self.faces.len();
+        self.faces.extend(faces);
+        self.last_resort.borrow_mut().clear();
+        (start..self.faces.len()).map(FontId).collect()
+    }
+
+    /// Select the closest face of a family, or of the families a generic
+    /// family stands for.
+    fn find_family(&self, family: &str, weight: FontWeight, style: FontStyle) -> Option<FontId> {
+        let in_family = |name: &str| {
+            best_match(
+                self.faces()
+                    .filter(|(_, face)| face.family.eq_ignore_ascii_case(name)),
+                weight,
+                style,
+            )
+        };
+        match self.generic.get(&family.to_ascii_lowercase()) {
+            Some(families) => families.iter().find_map(|name| in_family(name)),
+            None => in_family(family),
+        }
+    }
+
+    /// Find any face covering the first character of a grapheme no
+    /// fallback family covers.
+    fn last_resort(&self, grapheme: &str) -> Option<FontId> {
+        let c = grapheme.chars().find(|&c| !is_ignorable(c))?;
+        *self.last_resort.borrow_mut().entry(c).or_insert_with(|| {
+            self.faces()
+                .find(|(_, face)| face.has_glyph(c))
+                .map(|(id, _)| id)
+        })
+    }
+}
+
+impl fmt::Debug for FontCollection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FontCollection")
+            .field("faces", &self.faces)
+            .field("generic", &self.generic)
+            .field("fallback", &self.fallback)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Read the faces of a font or font collection.
+fn parse_faces(data: &[u8], source: impl Fn() -> FontSource) -> Result<Vec<FontFace>, FontError> {
+    let count = ttf_parser::fonts_in_collection(data).unwrap_or(1);
+    collect_faces(count, |index| {
+        let face = Face::parse(data, index).map_err(invalid_data)?;
+        Ok(FontFace::new(&face, index, source()))
+    })
+}
+
+/// Read the faces of a font or font collection file, reading only the
+/// tables needed to register them.
+fn read_faces(path: &Path) -> Result<Vec<FontFace>, FontError> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    let header = read_at(&mut file, 0, 12, len)?;
+    let offsets = if header.starts_with(b"ttcf") {
+        let count = u32::from_be_bytes([header[8], header[9], header[10], header[11]]);
+        read_at(&mut file, 12, u64::from(count) * 4, len)?
+            .chunks_exact(4)
+            .map(|offset| {
+                u64::from(u32::from_be_bytes([
+                    offset[0], offset[1], offset[2], offset[3],
+                ]))
+            })
+            .collect()
+    } else {
+        vec![0]
+    };
+    collect_faces(offsets.len() as u32, |index| {
+        let data = read_face_tables(&mut file, offsets[index as usize], len)?;
+        let face = Face::parse(&data, 0).map_err(invalid_data)?;
+        Ok(FontFace::new(
+            &face,
+            index,
+            FontSource::File(path.to_path_buf()),
+        ))
+    })
+}
+
+/// Read the faces of a font with the given number of faces, skipping broken
+/// faces of collections.
+fn collect_faces(
+    count: u32,
+    mut read_face: impl FnMut(u32) -> Result<FontFace, FontError>,
+) -> Result<Vec<FontFace>, FontError> {
+    let mut faces = Vec::new();
+    for index in 0..count {
+        match read_face(index) {
+            Ok(face) => faces.push(face),
+            // A broken face in a collection does not hide the others.
+            Err(err) if count == 1 => return Err(err),
+            Err(err) => log::debug!("Skipping face {index} of font collection: {err}"),
+        }
+    }
+    if faces.is_empty() {
+        return Err(FontError::InvalidData(
+            "no faces in font collection".to_string(),
+        ));
+    }
+    Ok(faces)
+}
+
+/// Read the registration tables of the face whose table directory starts at
+/// the offset, as a font of its own.
+fn read_face_tables(file: &mut File, offset: u64, len: u64) -> Result<Vec<u8>, FontError> {
+    let header = read_at(file, offset, 12, len)?;
+    let table_count = u16::from_be_bytes([header[4], header[5]]);
+    let records = read_at(file, offset + 12, u64::from(table_count) * 16, len)?;
+    let mut tables = Vec::new();
+    // The records are sorted by tag, and stay so.
+    for record in records.chunks_exact(16) {
+        let tag = [record[0], record[1], record[2], record[3]];
+        if !REGISTRATION_TABLES.contains(&&tag) {
+            continue;
+        }
+        let table_offset = u32::from_be_bytes([record[8], record[9], record[10], record[11]]);
+        let table_len = u32::from_be_bytes([record[12], record[13], record[14], record[15]]);
+        let table = read_at(file, table_offset.into(), table_len.into(), len)?;
+        tables.push((tag, table));
+    }
+    let version = [header[0], header[1], header[2], header[3]];
+    Ok(build_font(version, &tables))
+}
+
+/// Read a range of a file of the given length.
+fn read_at(file: &mut File, offset: u64, count: u64, len: u64) -> Result<Vec<u8>, FontError> {
+    if offset.checked_add(count).map_or(true, |end| end > len) {
+        return Err(FontError::InvalidData(
+            "table outside of the file".to_string(),
+        ));
+    }
+    file.seek(SeekFrom::Start(offset))?;
+    let mut data = vec![0; count as usize];
+    file.read_exact(&mut data)?;
+    Ok(data)
+}
+
+/// Write a font made of the tables, which are sorted by tag.
+fn build_font(version: [u8; 4], tables: &[([u8; 4], Vec<u8>)]) -> Vec<u8> {
+    let mut font = Vec::new();
+    font.extend_from_slice(&version);
+    // The binary search fields of the header are not used when parsing.
+    font.extend_from_slice(&(tables.len() as u16).to_be_bytes());
+    font.extend_from_slice(&[0; 6]);
+    let mut offset = 12 + 16 * tables.len() as u32;
+    for (tag, table) in tables {
+        font.extend_from_slice(tag);
+        font.extend_from_slice(&0u32.to_be_bytes());
+        font.extend_from_slice(&offset.to_be_bytes());
+        font.extend_from_slice(&(table.len() as u32).to_be_bytes());
+        offset += table.len().next_multiple_of(4) as u32;
+    }
+    for (_, table) in tables {
+        font.extend_from_slice(table);
+        font.resize(font.len().next_multiple_of(4), 0);
+    }
+    font
+}
+
+fn invalid_data(err: ttf_parser::FaceParsingError) -> FontError {
+    FontError::InvalidData(err.to_string())
+}
+
+/// Select the face closest to the weight and style among the candidates.
+fn best_match<'a>(
+    faces: impl Iterator<Item = (FontId, &'a FontFace)>,
+    weight: FontWeight,
+    style: FontStyle,
+) -> Option<FontId> {
+    faces
+        .min_by_key(|(id, face)| {
+            (
+                // Condensed and expanded faces are only used if a family
+                // has no others.
+                !face.normal_width,
+                face.style != style,
+                weight_distance(weight.0, face.weight.0),
+                *id,
+            )
+        })
+        .map(|(id, _)| id)
+}
+
+/// Rank how far a face's weight is from the desired one, following the CSS
+/// font matching rules; lower ranks are better.
+fn weight_distance(desired: u16, weight: u16) -> (u8, u16) {
+    let lighter = desired.saturating_sub(weight);
+    let heavier = weight.saturating_sub(desired);
+    if weight == desired {
+        (0, 0)
+    } else if (400..=500).contains(&desired) {
+        if weight > desired && weight <= 500 {
+            (1, heavier)
+        } else if weight < desired {
+            (2, lighter)
+        } else {
+            (3, heavier)
+        }
+    } else if desired < 400 {
+        if weight < desired {
+            (1, lighter)
+        } else {
+            (2, heavier)
+        }
+    } else if weight > desired {
+        (1, heavier)
+    } else {
+        (2, lighter)
+    }
+}
+
+/// Return whether the face has glyphs for the characters of a grapheme.
+fn covers(face: &Face<'_>, grapheme: &str) -> bool {
+    grapheme
+        .chars()
+        .filter(|&c| !is_ignorable(c))
+        .all(|c| face.glyph_index(c).is_some())
+}
+
+/// Return whether a character needs no glyph of its own, such as a joiner
+/// or a variation selector.
+fn is_ignorable(c: char) -> bool {
+    c.is_control() || matches!(c, '\u{200C}' | '\u{200D}' | '\u{FE00}'..='\u{FE0F}')
+}
+
+/// The directories fonts are installed in on this system.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn system_font_dirs() -> Vec<PathBuf> {
+    font_dirs(
+        env::var_os("HOME").as_deref(),
+        env::var_os("XDG_DATA_HOME").as_deref(),
+        env::var_os("XDG_DATA_DIRS").as_deref(),
+    )
+}
+
+/// The directories fonts are installed in on this system.
+#[cfg(not(all(unix, not(target_os = "macos"))))]
+fn system_font_dirs() -> Vec<PathBuf> {
+    Vec::new()
+}
+
+/// The font directories fontconfig searches by default, given the home
+/// directory and the XDG data directory variables.
+fn font_dirs(
+    home: Option<&OsStr>,
+    data_home: Option<&OsStr>,
+    data_dirs: Option<&OsStr>,
+) -> Vec<PathBuf> {
+    let home = home.filter(|home| !home.is_empty()).map(PathBuf::from);
+    let data_home = data_home
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home.as_ref().map(|home| home.join(".local/share")));
+    let data_dirs = data_dirs
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or_else(|| OsStr::new("/usr/local/share:/usr/share"));
+
+    let mut dirs = Vec::new();
+    dirs.extend(data_home);
+    dirs.extend(env::split_paths(data_dirs));
+    let mut dirs: Vec<PathBuf> = dirs.into_iter().map(|dir| dir.join("fonts")).collect();
+    dirs.push(PathBuf::from("/usr/share/fonts"));
+    dirs.push(PathBuf::from("/usr/local/share/fonts"));
+    dirs.extend(home.map(|home| home.join(".fonts")));
+
+    let mut seen = HashSet::new();
+    dirs.retain(|dir| seen.insert(dir.clone()));
+    dirs
+}
+
+/// List the font files in a directory and its subdirectories, sorted within
+/// each directory.
+fn font_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut visited = HashSet::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        // Symbolic links may lead back to a directory already scanned.
+        let Ok(canonical) = dir.canonicalize() else {
+            continue;
+        };
+        if !visited.insert(canonical) {
+            continue;
+        }
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut entries: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).collect();
+        entries.sort();
+        let mut subdirs = Vec::new();
+        for path in entries {
+            if path.is_dir() {
+                subdirs.push(path);
+            } else if path.extension().and_then(OsStr::to_str).is_some_and(|ext| {
+                FONT_EXTENSIONS
+                    .iter()
+                    .any(|known| ext.eq_ignore_ascii_case(known))
+            }) {
+                files.push(path);
+            }
+        }
+        pending.extend(subdirs.into_iter().rev());
+    }
+    files
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Build a font with an empty glyph for each of the characters. Glyphs
+    /// are half an em wide, and the font is 0.9 em high above the baseline
+    /// and 0.3 em below it.
+    pub(crate) fn test_font(family: &str, weight: u16, italic: bool, chars: &[char]) -> Vec<u8> {
+        fn be16(out: &mut Vec<u8>, value: u16) {
+            out.extend_from_slice(&value.to_be_bytes());
+        }
+        fn be32(out: &mut Vec<u8>, value: u32) {
+            out.extend_from_slice(&value.to_be_bytes());
+        }
+
+        let mut head = vec![0; 54];
+        head[18..20].copy_from_slice(&1000u16.to_be_bytes());
+        let mut hhea = vec![0; 36];
+        hhea[4..6].copy_from_slice(&900i16.to_be_bytes());
+        hhea[6..8].copy_from_slice(&(-300i16).to_be_bytes());
+        hhea[34..36].copy_from_slice(&1u16.to_be_bytes());
+        let mut hmtx = Vec::new();
+        be16(&mut hmtx, 500);
+        for _ in 0..=chars.len() {
+            be16(&mut hmtx, 0);
+        }
+        let mut maxp = Vec::new();
+        be32(&mut maxp, 0x5000);
+        be16(&mut maxp, chars.len() as u16 + 1);
+        let mut os2 = vec![0; 78];
+        os2[4..6].copy_from_slice(&weight.to_be_bytes());
+        os2[62..64].copy_from_slice(&u16::from(italic).to_be_bytes());
+
+        let family: Vec<u8> = family.encode_utf16().flat_map(u16::to_be_bytes).collect();
+        let mut name = Vec::new();
+        for value in [0, 1, 18, 3, 1, 0x409, 1, family.len() as u16, 0] {
+            be16(&mut name, value);
+        }
+        name.extend(family);
+
+        let mut cmap = Vec::new();
+        for value in [0, 1, 3, 10] {
+            be16(&mut cmap, value);
+        }
+        be32(&mut cmap, 12);
+        be16(&mut cmap, 12);
+        be16(&mut cmap, 0);
+        be32(&mut cmap, 16 + 12 * chars.len() as u32);
+        be32(&mut cmap, 0);
+        be32(&mut cmap, chars.len() as u32);
+        // The character groups of the format 12 subtable are sorted.
+        let mut chars = chars.to_vec();
+        chars.sort_unstable();
+        for (glyph, &c) in chars.iter().enumerate() {
+            be32(&mut cmap, c as u32);
+            be32(&mut cmap, c as u32);
+            be32(&mut cmap, glyph as u32 + 1);
+        }
+
+        build_font(
+            [0, 1, 0, 0],
+            &[
+                (*b"OS/2", os2),
+                (*b"cmap", cmap),
+                (*b"head", head),
+                (*b"hhea", hhea),
+                (*b"hmtx", hmtx),
+                (*b"maxp", maxp),
+                (*b"name", name),
+            ],
+        )
+    }
+
+    /// Combine fonts into a font collection.
+    fn test_collection(fonts: &[Vec<u8>]) -> Vec<u8> {
+        let mut data = b"ttcf".to_vec();
+        data.extend_from_slice(&0x0001_0000u32.to_be_bytes());
+        data.extend_from_slice(&(fonts.len() as u32).to_be_bytes());
+        let mut offset = 12 + 4 * fonts.len() as u32;
+        for font in fonts {
+            data.extend_from_slice(&offset.to_be_bytes());
+            offset += font.len() as u32;
+        }
+        for font in fonts {
+            // Table offsets are from the start of the collection.
+            let start = data.len() as u32;
+            let mut font = font.clone();
+            let table_count = usize::from(u16::from_be_bytes([font[4], font[5]]));
+            for record in 0..table_count {
+                let at = 12 + record * 16 + 8;
+                let offset = u32::from_be_bytes(font[at..at + 4].try_into().unwrap());
+                font[at..at + 4].copy_from_slice(&(offset + start).to_be_bytes());
+            }
+            data.extend(font);
+        }
+        data
+    }
+
+    fn latin() -> Vec<char> {
+        "abc ,.!".chars().collect()
+    }
+
+    #[test]
+    fn test_register() {
+        let mut fonts = FontCollection::new();
+        let ids = fonts
+            .register(test_font("Test Sans", 700, true, &latin()))
+            .unwrap();
+        assert_eq!(ids.len(), 1);
+
+        let face = fonts.face(ids[0]).unwrap();
+        assert_eq!(face.family(), "Test Sans");
+        assert_eq!(face.weight(), FontWeight::BOLD);
+        assert_eq!(face.style(), FontStyle::Italic);
+        assert!(face.has_glyph('a'));
+        assert!(!face.has_glyph('z'));
+        assert!(face.path().is_none());
+        assert!(fonts.has_family("test sans"));
+        // The family read from the face selects it.
+        let style = TextStyle::default().font_family(face.family());
+        let runs = fonts.resolve("abc", &style);
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].font, ids[0]);
+
+        assert!(matches!(
+            fonts.register(vec![0u8; 16]),
+            Err(FontError::InvalidData(_))
+        ));
+        assert_eq!(fonts.len(), 1);
+    }
+
+    #[test]
+    fn test_query() {
+        let mut fonts = FontCollection::new();
+        assert_eq!(
+            fonts.query(None, FontWeight::NORMAL, FontStyle::Normal),
+            None
+        );
+
+        let mut register = |family, weight, italic| {
+            fonts
+                .register(test_font(family, weight, italic, &latin()))
+                .unwrap()[0]
+        };
+        let light = register("Body", 300, false);
+        let regular = register("Body", 400, false);
+        let bold = register("Body", 700, false);
+        let italic = register("Body", 400, true);
+        let mono = register("Code", 400, false);
+        fonts.set_generic_family("monospace", vec!["Missing".into(), "Code".into()]);
+
+        let query = |family, weight, style| fonts.query(family, FontWeight(weight), style);
+        assert_eq!(query(Some("body"), 400, FontStyle::Normal), Some(regular));
+        assert_eq!(query(Some("Body"), 400, FontStyle::Italic), Some(italic));
+        // Italic falls back to upright faces, upright to italic ones.
+        assert_eq!(query(Some("Body"), 700, FontStyle::Italic), Some(italic));
+        assert_eq!(query(Some("Code"), 400, FontStyle::Italic), Some(mono));
+        // Weights are matched the way CSS does.
+        assert_eq!(query(Some("Body"), 500, FontStyle::Normal), Some(regular));
+        assert_eq!(query(Some("Body"), 600, FontStyle::Normal), Some(bold));
+        assert_eq!(query(Some("Body"), 200, FontStyle::Normal), Some(light));
+        assert_eq!(query(Some("Body"), 350, FontStyle::Normal), Some(light));
+        assert_eq!(query(Some("Body"), 900, FontStyle::Normal), Some(bold));
+        // Generic families stand for their first registered family.
+        assert_eq!(query(Some("monospace"), 400, FontStyle::Normal), Some(mono));
+        // Unknown families fall back to the default family, then any face.
+        assert_eq!(query(Some("Missing"), 700, FontStyle::Normal), Some(bold));
+        fonts.set_generic_family("sans-serif", vec!["Body".into()]);
+        assert_eq!(
+            fonts.query(None, FontWeight::NORMAL, FontStyle::Normal),
+            Some(regular)
+        );
+    }
+
+    #[test]
+    fn test_glyphs() {
+        let style = TextStyle::new(10.0);
+        let mut fonts = FontCollection::new();
+        assert!(fonts.glyphs("ab", &style, TextDirection::Ltr).is_empty());
+
+        let id = fonts
+            .register(test_font("Sans", 400, false, &['a', 'b']))
+            .unwrap()[0];
+        let placed = |direction| -> Vec<(u16, f32)> {
+            fonts
+                .glyphs("ab\tz", &style, direction)
+                .iter()
+                .inspect(|glyph| assert_eq!(glyph.font, id))
+                .map(|glyph| (glyph.glyph.0, glyph.x))
+                .collect()
+        };
+        // Tabs take space but have no glyph; "z" is drawn with the missing
+        // glyph.
+        assert_eq!(placed(TextDirection::Ltr), [(1, 0.0), (2, 5.0), (0, 30.0)]);
+        assert_eq!(placed(TextDirection::Rtl), [(1, 30.0), (2, 25.0), (0, 0.0)]);
+    }
+
+    #[test]
+    fn test_metrics() {
+        let style = TextStyle::new(10.0).line_height(2.0).letter_spacing(1.0);
+        let mut fonts = FontCollection::new();
+        // Without fonts the approximate metrics of the style are used.
+        assert_eq!(
+            fonts.advances("a\t\n", &style),
+            [style.advance("a"), style.advance("\t"), 0.0]
+        );
+        assert_eq!(fonts.ascent(&style), style.ascent());
+
+        fonts
+            .register(test_font("Sans", 400, false, &latin()))
+            .unwrap();
+        // Glyphs are half an em wide, and a tab is four spaces.
+        assert_eq!(fonts.advances("a\t\n", &style), [6.0, 21.0, 0.0]);
+        assert_eq!(fonts.measure("abc", &style), 18.0);
+        // The glyphs are 12 pixels high, and the line adds 4 pixels above.
+        assert_eq!(fonts.ascent(&style), 13.0);
+    }
+
+    #[test]
+    fn test_weight_distance() {
+        let order = |desired| {
+            let mut weights = vec![100, 200, 300, 400, 500, 600, 700, 800, 900];
+            weights.sort_by_key(|&weight| weight_distance(desired, weight));
+            weights
+        };
+        assert_eq!(order(400), [400, 500, 300, 200, 100, 600, 700, 800, 900]);
+        assert_eq!(order(500), [500, 400, 300, 200, 100, 600, 700, 800, 900]);
+        assert_eq!(order(300), [300, 200, 100, 400, 500, 600, 700, 800, 900]);
+        assert_eq!(order(600), [600, 700, 800, 900, 500, 400, 300, 200, 100]);
+    }
+
+    #[test]
+    fn test_fallback() {
+        let mut fonts = FontCollection::new();
+        let sans = fonts
+            .register(test_font("Sans", 400, false, &latin()))
+            .unwrap()[0];
+        let emoji = fonts
+            .register(test_font("Emoji", 400, false, &['\u{1F600}', ' ']))
+            .unwrap()[0];
+        let cjk = fonts
+            .register(test_font("CJK", 400, false, &['漢', '字', ' ', ',']))
+            .unwrap()[0];
+        fonts.set_fallback(vec!["Emoji".into()]);
+
+        let style = TextStyle::default().font_family("Sans");
+        let runs = |text| {
+            fonts
+                .resolve(text, &style)
+                .into_iter()
+                .map(|run| (run.range, run.font))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(runs("abc"), [(0..3, sans)]);
+        // The emoji comes from the fallback family, the CJK characters from
+        // the only face that has them.
+        assert_eq!(
+            runs("a\u{1F600}b"),
+            [(0..1, sans), (1..5, emoji), (5..6, sans)]
+        );
+        assert_eq!(runs("a漢字"), [(0..1, sans), (1..7, cjk)]);
+        // Spaces and punctuation stay in the face before them.
+        assert_eq!(runs("漢 字,a"), [(0..8, cjk), (8..9, sans)]);
+        // Variation selectors need no glyph.
+        assert_eq!(runs("\u{1F600}\u{FE0F}"), [(0..7, emoji)]);
+        // Characters no face has are left to the selected face.
+        assert_eq!(runs("z"), [(0..1, sans)]);
+        assert!(FontCollection::new().resolve("abc", &style).is_empty());
+    }
+
+    #[test]
+    fn test_font_dirs() {
+        let dirs = font_dirs(Some(OsStr::new("/home/user")), None, None);
+        assert_eq!(
+            dirs,
+            [
+                "/home/user/.local/share/fonts",
+                "/usr/local/share/fonts",
+                "/usr/share/fonts",
+                "/home/user/.fonts",
+            ]
+            .map(PathBuf::from)
+        );
+
+        let dirs = font_dirs(
+            None,
+            Some(OsStr::new("/data")),
+            Some(OsStr::new("/opt/share:/usr/share")),
+        );
+        assert_eq!(
+            dirs,
+            [
+                "/data/fonts",
+                "/opt/share/fonts",
+                "/usr/share/fonts",
+                "/usr/local/share/fonts",
+            ]
+            .map(PathBuf::from)
+        );
+    }
+
+    #[test]
+    fn test_add_font_dir() {
+        let dir = env::temp_dir().join(format!("alula-fonts-{}", std::process::id()));
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        fs::write(dir.join("a.ttf"), test_font("A", 400, false, &latin())).unwrap();
+        fs::write(
+            dir.join("nested/b.OTF"),
+            test_font("B", 400, false, &latin()),
+        )
+        .unwrap();
+        fs::write(dir.join("broken.ttf"), b"not a font").unwrap();
+        let collection = test_collection(&[
+            test_font("C1", 400, false, &latin()),
+            test_font("C2", 700, false, &latin()),
+        ]);
+        fs::write(dir.join("c.ttc"), &collection).unwrap();
+        fs::write(dir.join("notes.txt"), b"not a font either").unwrap();
+
+        let mut fonts = FontCollection::new();
+        assert_eq!(fonts.add_font_dir(&dir), 4);
+        // Files are not registered twice.
+        assert_eq!(fonts.add_font_dir(&dir), 0);
+        let families: Vec<&str> = fonts.faces().map(|(_, face)| face.family()).collect();
+        assert_eq!(families, ["A", "C1", "C2", "B"]);
+        let (_, face) = fonts.faces().nth(2).unwrap();
+        assert_eq!((face.index(), face.weight()), (1, FontWeight::BOLD));
+        // Registering the collection from memory finds the same faces.
+        let mut memory = FontCollection::new();
+        assert_eq!(memory.register(collection).unwrap().len(), 2);
+        assert_eq!(memory.face(FontId(1)).unwrap().family(), "C2");
+
+        // The bytes of faces found on disk are read when first needed.
+        let (_, face) = fonts.faces().next().unwrap();
+        assert_eq!(face.path(), Some(dir.join("a.ttf").as_path()));
+        assert!(face.has_glyph('a'));
+        assert!(face.data().is_some());
+        assert_eq!(fonts.ascent(&TextStyle::new(10.0).line_height(2.0)), 13.0);
+
+        // A file that cannot be read is read again when next needed.
+        let (_, face) = fonts.faces().nth(3).unwrap();
+        let path = face.path().unwrap().to_path_buf();
+        let data = fs::read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(face.data().is_none());
+        fs::write(&path, data).unwrap();
+        assert!(face.has_glyph('a'));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
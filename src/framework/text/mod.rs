@@ -0,0 +1,13 @@
+mod editing;
+mod font;
+mod raster;
+mod style;
+mod text_layout;
+
+pub use editing::{InputFilter, TextEditor, TextSelection};
+pub(crate) use font::PlacedGlyph;
+pub use font::{FontCollection, FontFace, FontId, FontRun};
+pub(crate) use raster::GlyphBitmap;
+pub use style::{FontStyle, FontWeight, TextStyle};
+pub use text_layout::{LineMetrics, ParagraphStyle, TextAlign, TextLayout, TextOverflow};
+pub(crate) use text_layout::{RunKind, TextRun, CARET_WIDTH, ELLIPSIS};
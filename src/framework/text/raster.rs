@@ -0,0 +1,310 @@
+use ttf_parser::{Face, GlyphId, OutlineBuilder};
+
+/// The largest distance in pixels a flattened curve strays from the curve.
+const TOLERANCE: f32 = 0.1;
+
+/// GlyphBitmap is the coverage of a glyph rasterized at a font size.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct GlyphBitmap {
+    /// The distance from the pen position to the left edge of the bitmap.
+    pub left: i32,
+    /// The distance from the baseline down to the top edge of the bitmap,
+    /// negative for glyphs above the baseline.
+    pub top: i32,
+    /// The width of the bitmap in pixels.
+    pub width: u32,
+    /// The height of the bitmap in pixels.
+    pub height: u32,
+    /// The coverage of the pixels, row by row from the top, where 255 is a
+    /// pixel the glyph covers completely.
+    pub coverage: Vec<u8>,
+}
+
+/// Rasterize the outline of a glyph with an em of `size` pixels.
+///
+/// Returns `None` for glyphs without an outline, e.g. spaces, and for
+/// bitmap or SVG glyphs.
+pub(crate) fn rasterize(face: &Face<'_>, glyph: GlyphId, size: f32) -> Option<GlyphBitmap> {
+    let mut outline = Outline::new(size / f32::from(face.units_per_em().max(1)));
+    face.outline_glyph(glyph, &mut outline)?;
+    outline.rasterize()
+}
+
+/// Outline flattens the contours of a glyph into lines in pixels, with y
+/// pointing down.
+#[derive(Debug, Default)]
+struct Outline {
+    scale: f32,
+    lines: Vec<[(f32, f32); 2]>,
+    start: (f32, f32),
+    current: (f32, f32),
+}
+
+impl Outline {
+    fn new(scale: f32) -> Self {
+        Outline {
+            scale,
+            ..Default::default()
+        }
+    }
+
+    fn point(&self, x: f32, y: f32) -> (f32, f32) {
+        (x * self.scale, -y * self.scale)
+    }
+
+    fn push_line(&mut self, to: (f32, f32)) {
+        if to != self.current {
+            self.lines.push([self.current, to]);
+        }
+        self.current = to;
+    }
+
+    /// Flatten a curve given by the point at a parameter between 0 and 1,
+    /// with a number of segments that grows with the distance of the control
+    /// points from the chord.
+    fn push_curve(&mut self, deviation: f32, at: impl Fn(f32) -> (f32, f32)) {
+        let segments = (deviation / TOLERANCE).sqrt().ceil().clamp(1.0, 64.0) as usize;
+        for step in 1..=segments {
+            self.push_line(at(step as f32 / segments as f32));
+        }
+    }
+
+    /// Fill the lines with the nonzero rule into a bitmap that just covers
+    /// them.
+    fn rasterize(&self) -> Option<GlyphBitmap> {
+        let points = self.lines.iter().flatten();
+        let min_x = points.clone().map(|p| p.0).fold(f32::INFINITY, f32::min);
+        let max_x = points
+            .clone()
+            .map(|p| p.0)
+            .fold(f32::NEG_INFINITY, f32::max);
+        let min_y = points.clone().map(|p| p.1).fold(f32::INFINITY, f32::min);
+        let max_y = points.map(|p| p.1).fold(f32::NEG_INFINITY, f32::max);
+        let (left, top) = (min_x.floor(), min_y.floor());
+        let width = (max_x.ceil() - left) as usize;
+        let height = (max_y.ceil() - top) as usize;
+        if self.lines.is_empty() || width == 0 || height == 0 {
+            return None;
+        }
+
+        let mut raster = Raster::new(width, height);
+        for [from, to] in &self.lines {
+            raster.line((from.0 - left, from.1 - top), (to.0 - left, to.1 - top));
+        }
+        Some(GlyphBitmap {
+            left: left as i32,
+            top: top as i32,
+            width: width as u32,
+            height: height as u32,
+            coverage: raster.coverage(),
+        })
+    }
+}
+
+impl OutlineBuilder for Outline {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.close();
+        self.start = self.point(x, y);
+        self.current = self.start;
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let to = self.point(x, y);
+        self.push_line(to);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let from = self.current;
+        let control = self.point(x1, y1);
+        let to = self.point(x, y);
+        let deviation = distance(control, midpoint(from, to));
+        self.push_curve(deviation, |t| {
+            let u = 1.0 - t;
+            (
+                u * u * from.0 + 2.0 * u * t * control.0 + t * t * to.0,
+                u * u * from.1 + 2.0 * u * t * control.1 + t * t * to.1,
+            )
+        });
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let from = self.current;
+        let first = self.point(x1, y1);
+        let second = self.point(x2, y2);
+        let to = self.point(x, y);
+        let deviation = distance(first, from).max(distance(second, to));
+        self.push_curve(deviation, |t| {
+            let u = 1.0 - t;
+            let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+            (
+                a * from.0 + b * first.0 + c * second.0 + d * to.0,
+                a * from.1 + b * first.1 + c * second.1 + d * to.1,
+            )
+        });
+    }
+
+    fn close(&mut self) {
+        let start = self.start;
+        self.push_line(start);
+    }
+}
+
+fn midpoint(a: (f32, f32), b: (f32, f32)) -> (f32, f32) {
+    ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0)
+}
+
+fn distance(a: (f32, f32), b: (f32, f32)) -> f32 {
+    (a.0 - b.0).hypot(a.1 - b.1)
+}
+
+/// Raster accumulates the signed area lines cover in every pixel, whose
+/// running sum is the winding of the pixel.
+struct Raster {
+    width: usize,
+    height: usize,
+    area: Vec<f32>,
+}
+
+impl Raster {
+    fn new(width: usize, height: usize) -> Self {
+        Raster {
+            width,
+            height,
+            // The area right of the last pixel of a row spills into the
+            // next row, which the running sum carries over.
+            area: vec![0.0; width * height + 2],
+        }
+    }
+
+    /// Add the area right of a line, whose points are inside the raster.
+    fn line(&mut self, from: (f32, f32), to: (f32, f32)) {
+        if from.1 == to.1 {
+            return;
+        }
+        let (sign, from, to) = if from.1 < to.1 {
+            (1.0, from, to)
+        } else {
+            (-1.0, to, from)
+        };
+        let dxdy = (to.0 - from.0) / (to.1 - from.1);
+        let mut x = from.0;
+        let first_row = from.1.floor() as usize;
+        let end_row = (to.1.ceil() as usize).min(self.height);
+        for row in first_row..end_row {
+            let dy = ((row + 1) as f32).min(to.1) - (row as f32).max(from.1);
+            let next_x = x + dxdy * dy;
+            let area = dy * sign;
+            let start = row * self.width;
+            let (x0, x1) = if x < next_x { (x, next_x) } else { (next_x, x) };
+            let x0_floor = x0.floor();
+            let x1_ceil = x1.ceil();
+            let column = x0_floor as usize;
+            let end_column = x1_ceil as usize;
+            if end_column <= column + 1 {
+                // The line crosses a single pixel of the row.
+                let right = (x + next_x) / 2.0 - x0_floor;
+                self.area[start + column] += area * (1.0 - right);
+                self.area[start + column + 1] += area * right;
+            } else {
+                let slope = (x1 - x0).recip();
+                let x0_fraction = x0 - x0_floor;
+                let first = 0.5 * slope * (1.0 - x0_fraction) * (1.0 - x0_fraction);
+                let x1_fraction = x1 - x1_ceil + 1.0;
+                let last = 0.5 * slope * x1_fraction * x1_fraction;
+                self.area[start + column] += area * first;
+                if end_column == column + 2 {
+                    self.area[start + column + 1] += area * (1.0 - first - last);
+                } else {
+                    let second = slope * (1.5 - x0_fraction);
+                    self.area[start + column + 1] += area * (second - first);
+                    for middle in column + 2..end_column - 1 {
+                        self.area[start + middle] += area * slope;
+                    }
+                    let before_last = second + (end_column - column - 3) as f32 * slope;
+                    self.area[start + end_column - 1] += area * (1.0 - before_last - last);
+                }
+                self.area[start + end_column] += area * last;
+            }
+            x = next_x;
+        }
+    }
+
+    /// The coverage of the pixels with the nonzero rule.
+    fn coverage(&self) -> Vec<u8> {
+        let mut winding = 0.0;
+        self.area[..self.width * self.height]
+            .iter()
+            .map(|area| {
+                winding += area;
+                (winding.abs().min(1.0) * 255.0).round() as u8
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(outline: &mut Outline, min: f32, max: f32) {
+        outline.move_to(min, min);
+        outline.line_to(max, min);
+        outline.line_to(max, max);
+        outline.line_to(min, max);
+        outline.close();
+    }
+
+    #[test]
+    fn test_rasterize_square() {
+        let mut outline = Outline::new(1.0);
+        square(&mut outline, 0.0, 2.0);
+        let bitmap = outline.rasterize().unwrap();
+        // Outlines point up and bitmaps down.
+        assert_eq!((bitmap.left, bitmap.top), (0, -2));
+        assert_eq!((bitmap.width, bitmap.height), (2, 2));
+        assert_eq!(bitmap.coverage, [255; 4]);
+
+        // Edges between pixels cover them partly.
+        let mut outline = Outline::new(1.0);
+        square(&mut outline, 0.5, 2.5);
+        let bitmap = outline.rasterize().unwrap();
+        assert_eq!((bitmap.left, bitmap.top), (0, -3));
+        assert_eq!(bitmap.coverage, [64, 128, 64, 128, 255, 128, 64, 128, 64]);
+    }
+
+    #[test]
+    fn test_rasterize_winding() {
+        // A hole winds the other way; an overlapping square the same way.
+        let mut outline = Outline::new(1.0);
+        square(&mut outline, 0.0, 3.0);
+        outline.move_to(1.0, 1.0);
+        outline.line_to(1.0, 2.0);
+        outline.line_to(2.0, 2.0);
+        outline.line_to(2.0, 1.0);
+        outline.close();
+        let bitmap = outline.rasterize().unwrap();
+        assert_eq!(bitmap.coverage, [255, 255, 255, 255, 0, 255, 255, 255, 255]);
+
+        let mut outline = Outline::new(1.0);
+        square(&mut outline, 0.0, 2.0);
+        square(&mut outline, 0.0, 2.0);
+        assert_eq!(outline.rasterize().unwrap().coverage, [255; 4]);
+    }
+
+    #[test]
+    fn test_rasterize_curve() {
+        // A quarter circle covers about pi / 4 of the square around it.
+        let mut outline = Outline::new(10.0);
+        outline.move_to(0.0, 0.0);
+        outline.line_to(1.0, 0.0);
+        outline.curve_to(1.0, 0.552, 0.552, 1.0, 0.0, 1.0);
+        outline.close();
+        let bitmap = outline.rasterize().unwrap();
+        assert_eq!((bitmap.width, bitmap.height), (10, 10));
+        let covered: f32 = bitmap.coverage.iter().map(|&c| f32::from(c) / 255.0).sum();
+        // The flattened curve cuts a little off the circle.
+        assert!((covered - 25.0 * std::f32::consts::PI).abs() < 1.0);
+        // Empty outlines have no bitmap.
+        assert_eq!(Outline::new(1.0).rasterize(), None);
+    }
+}
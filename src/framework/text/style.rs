@@ -0,0 +1,221 @@
+use std::rc::Rc;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::framework::Color;
+
+/// The advance of a regular character as a fraction of the font size.
+const NARROW_ADVANCE: f32 = 0.6;
+
+/// The advance of a wide (e.g. CJK) character as a fraction of the font size.
+const WIDE_ADVANCE: f32 = 1.0;
+
+/// The number of regular advances a tab takes.
+pub(super) const TAB_WIDTH: f32 = 4.0;
+
+/// The height of the glyphs above the baseline as a fraction of the font size.
+const ASCENT: f32 = 0.8;
+
+/// FontWeight is the thickness of the strokes of a font, from 100 (thin) to
+/// 900 (black).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FontWeight(pub u16);
+
+impl FontWeight {
+    /// The thinnest weight, 100.
+    pub const THIN: FontWeight = FontWeight(100);
+    /// The light weight, 300.
+    pub const LIGHT: FontWeight = FontWeight(300);
+    /// The regular weight, 400.
+    pub const NORMAL: FontWeight = FontWeight(400);
+    /// The medium weight, 500.
+    pub const MEDIUM: FontWeight = FontWeight(500);
+    /// The semi-bold weight, 600.
+    pub const SEMI_BOLD: FontWeight = FontWeight(600);
+    /// The bold weight, 700.
+    pub const BOLD: FontWeight = FontWeight(700);
+    /// The thickest weight, 900.
+    pub const BLACK: FontWeight = FontWeight(900);
+}
+
+impl Default for FontWeight {
+    fn default() -> Self {
+        FontWeight::NORMAL
+    }
+}
+
+/// FontStyle is whether the upright or the slanted faces of a font are used.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum FontStyle {
+    /// Upright glyphs.
+    #[default]
+    Normal,
+    /// Slanted, cursive glyphs.
+    Italic,
+}
+
+/// TextStyle describes how text is drawn.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextStyle {
+    /// The font family, or `None` for the default family.
+    pub font_family: Option<Rc<str>>,
+    /// The font size in logical pixels.
+    pub font_size: f32,
+    /// The thickness of the glyphs.
+    pub weight: FontWeight,
+    /// Whether the glyphs are upright or italic.
+    pub font_style: FontStyle,
+    /// The height of a line as a multiple of the font size.
+    pub line_height: f32,
+    /// The color of the text.
+    pub color: Color,
+    /// Whether a line is drawn below the text.
+    pub underline: bool,
+    /// Whether a line is drawn through the text.
+    pub strikethrough: bool,
+    /// The extra space after every grapheme in logical pixels, which may be
+    /// negative.
+    pub letter_spacing: f32,
+    /// The color painted behind the text, if any.
+    pub background: Option<Color>,
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        TextStyle {
+            font_family: None,
+            font_size: 14.0,
+            weight: FontWeight::NORMAL,
+            font_style: FontStyle::Normal,
+            line_height: 1.2,
+            color: Color::rgb(0.0, 0.0, 0.0),
+            underline: false,
+            strikethrough: false,
+            letter_spacing: 0.0,
+            background: None,
+        }
+    }
+}
+
+impl TextStyle {
+    /// Create a new TextStyle instance with the given font size.
+    pub fn new(font_size: f32) -> Self {
+        TextStyle {
+            font_size,
+            ..Default::default()
+        }
+    }
+
+    /// Set the color of the text.
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Set the height of a line as a multiple of the font size.
+    pub fn line_height(mut self, line_height: f32) -> Self {
+        self.line_height = line_height;
+        self
+    }
+
+    /// Set the font family, e.g. a generic family or the family of a face
+    /// registered with a [`FontCollection`](super::FontCollection).
+    pub fn font_family(mut self, font_family: impl Into<Rc<str>>) -> Self {
+        self.font_family = Some(font_family.into());
+        self
+    }
+
+    /// Set the thickness of the glyphs.
+    pub fn weight(mut self, weight: FontWeight) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// Use the bold weight.
+    pub fn bold(self) -> Self {
+        self.weight(FontWeight::BOLD)
+    }
+
+    /// Use the italic glyphs.
+    pub fn italic(mut self) -> Self {
+        self.font_style = FontStyle::Italic;
+        self
+    }
+
+    /// Draw a line below the text.
+    pub fn underline(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+
+    /// Draw a line through the text.
+    pub fn strikethrough(mut self) -> Self {
+        self.strikethrough = true;
+        self
+    }
+
+    /// Set the extra space after every grapheme.
+    pub fn letter_spacing(mut self, letter_spacing: f32) -> Self {
+        self.letter_spacing = letter_spacing;
+        self
+    }
+
+    /// Set the color painted behind the text.
+    pub fn background(mut self, background: Color) -> Self {
+        self.background = Some(background);
+        self
+    }
+
+    /// The height of a line in logical pixels.
+    pub fn line_extent(&self) -> f32 {
+        self.font_size * self.line_height
+    }
+
+    /// The distance from the top of a line to the baseline, which includes
+    /// half of the space the line height adds around the glyphs.
+    pub fn ascent(&self) -> f32 {
+        (self.line_extent() - self.font_size) / 2.0 + self.font_size * ASCENT
+    }
+
+    /// The width of a single line of text.
+    pub fn measure(&self, text: &str) -> f32 {
+        text.graphemes(true)
+            .map(|grapheme| self.advance(grapheme))
+            .sum()
+    }
+
+    /// The horizontal advance of a grapheme cluster in logical pixels.
+    ///
+    /// These are approximate metrics that do not depend on a font: regular
+    /// characters take a fixed fraction of the font size and wide characters
+    /// take a full em. The letter spacing is added to every grapheme but line
+    /// breaks. Text is measured with the faces it is drawn with by
+    /// [`FontCollection::advances`](super::FontCollection::advances), which
+    /// only falls back to these metrics where no face has any.
+    pub fn advance(&self, grapheme: &str) -> f32 {
+        let factor = match grapheme.chars().next() {
+            Some('\t') => NARROW_ADVANCE * TAB_WIDTH,
+            Some('\n' | '\r') | None => return 0.0,
+            Some(c) if is_wide(c) => WIDE_ADVANCE,
+            Some(_) => NARROW_ADVANCE,
+        };
+        self.font_size * factor + self.letter_spacing
+    }
+}
+
+/// Returns true for characters that are usually displayed double-width.
+fn is_wide(c: char) -> bool {
+    matches!(
+        u32::from(c),
+        0x1100..=0x115F
+            | 0x2E80..=0xA4CF
+            | 0xAC00..=0xD7A3
+            | 0xF900..=0xFAFF
+            | 0xFE30..=0xFE4F
+            | 0xFF00..=0xFF60
+            | 0xFFE0..=0xFFE6
+            | 0x1F300..=0x1F64F
+            | 0x1F900..=0x1F9FF
+            | 0x20000..=0x3FFFD
+    )
+}
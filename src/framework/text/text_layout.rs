@@ -0,0 +1,1199 @@
+use std::ops::Range;
+
+use unicode_bidi::{BidiInfo, Level};
+use unicode_linebreak::{linebreaks, BreakOpportunity};
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::font::FontCollection;
+use super::style::TextStyle;
+use crate::framework::{Offset, Rect, Size, TextDirection};
+
+/// The width of the caret in logical pixels.
+pub(crate) const CARET_WIDTH: f32 = 1.5;
+
+/// The text shown at the end of the last line when lines are cut off.
+pub(crate) const ELLIPSIS: &str = "\u{2026}";
+
+/// TextAlign is the horizontal position of lines narrower than the
+/// paragraph.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum TextAlign {
+    /// Lines start at the start edge: the left edge of left-to-right text
+    /// and the right edge of right-to-left text.
+    #[default]
+    Start,
+    /// Lines end at the end edge.
+    End,
+    /// Lines are centered.
+    Center,
+    /// Lines broken to fit the width are stretched to it by widening their
+    /// white space; other lines start at the start edge.
+    Justify,
+}
+
+/// TextOverflow is how the end of text cut off by the maximum number of
+/// lines is shown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum TextOverflow {
+    /// The last line simply ends.
+    #[default]
+    Clip,
+    /// The last line ends with an ellipsis, dropping graphemes to make room.
+    Ellipsis,
+}
+
+/// ParagraphStyle describes how text is arranged in lines.
+///
+/// The height of the lines comes from the [`TextStyle`] of their text.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ParagraphStyle {
+    /// The horizontal position of the lines.
+    pub align: TextAlign,
+    /// The maximum number of lines, or `None` for any number.
+    pub max_lines: Option<usize>,
+    /// How text cut off by the maximum number of lines ends.
+    pub overflow: TextOverflow,
+    /// The extra space after every line ending with a newline, in logical
+    /// pixels.
+    pub paragraph_spacing: f32,
+    /// The base direction of the text, which decides the order of runs of
+    /// text in both directions and which edge lines start at.
+    pub direction: TextDirection,
+}
+
+impl ParagraphStyle {
+    /// Create a new ParagraphStyle instance with the given alignment.
+    pub fn new(align: TextAlign) -> Self {
+        ParagraphStyle {
+            align,
+            ..Default::default()
+        }
+    }
+
+    /// Set the maximum number of lines and how cut off text ends.
+    pub fn max_lines(mut self, max_lines: usize, overflow: TextOverflow) -> Self {
+        self.max_lines = Some(max_lines);
+        self.overflow = overflow;
+        self
+    }
+
+    /// Set the extra space after every line ending with a newline.
+    pub fn paragraph_spacing(mut self, paragraph_spacing: f32) -> Self {
+        self.paragraph_spacing = paragraph_spacing;
+        self
+    }
+
+    /// Set the base direction of the text.
+    pub fn direction(mut self, direction: TextDirection) -> Self {
+        self.direction = direction;
+        self
+    }
+}
+
+/// LineMetrics is the position and extent of a laid out line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineMetrics {
+    /// The byte range of the line in the text, excluding the line break.
+    pub range: Range<usize>,
+    /// The distance from the top of the text to the top of the line.
+    pub top: f32,
+    /// The height of the line.
+    pub height: f32,
+    /// The distance from the top of the line to its baseline.
+    pub ascent: f32,
+    /// The distance from the baseline to the bottom of the line.
+    pub descent: f32,
+    /// The distance from the left edge of the text to the left edge of the
+    /// line.
+    pub left: f32,
+    /// The width of the line without trailing white space.
+    pub width: f32,
+    /// Whether the line ends with a newline rather than being broken to fit
+    /// the width.
+    pub hard_break: bool,
+}
+
+impl LineMetrics {
+    /// The distance from the top of the text to the baseline of the line.
+    pub fn baseline(&self) -> f32 {
+        self.top + self.ascent
+    }
+}
+
+/// What a run of the text is made of.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum RunKind {
+    /// Text drawn in the style.
+    Text(TextStyle),
+    /// A single object replacement character standing for an inline box of
+    /// the size, which sits on the baseline.
+    Placeholder(Size),
+}
+
+/// TextRun is a range of the text laid out the same way.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TextRun {
+    pub(crate) range: Range<usize>,
+    pub(crate) kind: RunKind,
+}
+
+/// Fragment is the part of a run placed on one line.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Fragment {
+    /// The index of the run.
+    pub(crate) run: usize,
+    /// The byte range of the fragment in the text.
+    pub(crate) range: Range<usize>,
+    /// The box of the fragment; for text, the line box of its style.
+    pub(crate) rect: Rect,
+    /// Whether the fragment is the ellipsis ending a cut off line, drawn
+    /// instead of the text of the range.
+    pub(crate) ellipsis: bool,
+    /// Whether the text of the fragment runs from right to left.
+    pub(crate) rtl: bool,
+}
+
+impl Fragment {
+    /// The direction of the text of the fragment.
+    pub(crate) fn direction(&self) -> TextDirection {
+        if self.rtl {
+            TextDirection::Rtl
+        } else {
+            TextDirection::Ltr
+        }
+    }
+}
+
+/// A grapheme, or a run of placeholders.
+#[derive(Debug, Clone)]
+struct Cluster {
+    run: usize,
+    range: Range<usize>,
+    width: f32,
+    whitespace: bool,
+    newline: bool,
+    /// The line break opportunity after the cluster.
+    break_after: Option<BreakOpportunity>,
+}
+
+/// A single laid out line of text.
+#[derive(Debug, Clone, PartialEq)]
+struct Line {
+    metrics: LineMetrics,
+    /// The caret stops of the line in byte order: byte index and horizontal
+    /// position of the edge of the grapheme that the text starts from.
+    stops: Vec<(usize, f32)>,
+    /// The range of the fragments of the line.
+    fragments: Range<usize>,
+}
+
+impl Line {
+    /// The horizontal position of the caret before the byte index.
+    fn x_for_index(&self, index: usize) -> f32 {
+        self.stops
+            .iter()
+            .take_while(|(stop, _)| *stop <= index)
+            .last()
+            .map_or(self.metrics.left, |(_, x)| *x)
+    }
+
+    /// The caret stop closest to the horizontal position.
+    fn index_for_x(&self, x: f32) -> usize {
+        self.stops
+            .iter()
+            .min_by(|(_, a), (_, b)| (a - x).abs().total_cmp(&(b - x).abs()))
+            .map_or(self.metrics.range.start, |(index, _)| *index)
+    }
+
+    fn bottom(&self) -> f32 {
+        self.metrics.top + self.metrics.height
+    }
+}
+
+/// TextLayout is the position of every grapheme of a text laid out in lines.
+///
+/// Lines break at the opportunities of the Unicode line breaking algorithm
+/// (UAX #14) where the text would be wider than the maximum width, and
+/// within words wider than it. Newlines always break. The graphemes of every
+/// line are then put in visual order by the Unicode bidirectional algorithm
+/// (UAX #9), so runs of right-to-left text read from right to left.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextLayout {
+    lines: Vec<Line>,
+    fragments: Vec<Fragment>,
+    line_height: f32,
+    size: Size,
+    exceeded_max_lines: bool,
+    rtl: bool,
+}
+
+impl TextLayout {
+    /// Lay out the text with the given style and fonts, breaking lines only
+    /// at newlines.
+    pub fn new(text: &str, style: &TextStyle, fonts: &FontCollection) -> Self {
+        let paragraph = ParagraphStyle::default();
+        TextLayout::with_paragraph(text, style, f32::INFINITY, &paragraph, fonts)
+    }
+
+    /// Lay out the text with the given style and fonts in lines no wider
+    /// than the maximum width, arranged as described by the paragraph style.
+    pub fn with_paragraph(
+        text: &str,
+        style: &TextStyle,
+        max_width: f32,
+        paragraph: &ParagraphStyle,
+        fonts: &FontCollection,
+    ) -> Self {
+        let run = TextRun {
+            range: 0..text.len(),
+            kind: RunKind::Text(style.clone()),
+        };
+        TextLayout::from_runs(text, &[run], max_width, paragraph, fonts)
+    }
+
+    /// Lay out text made of runs, which cover the text in order.
+    ///
+    /// Graphemes are measured with the faces of the fonts they are drawn
+    /// with, see [`FontCollection::advances`].
+    pub(crate) fn from_runs(
+        text: &str,
+        runs: &[TextRun],
+        max_width: f32,
+        paragraph: &ParagraphStyle,
+        fonts: &FontCollection,
+    ) -> Self {
+        let clusters = clusters(text, runs, fonts);
+        let level = match paragraph.direction {
+            TextDirection::Ltr => Level::ltr(),
+            TextDirection::Rtl => Level::rtl(),
+        };
+        let bidi = BidiInfo::new(text, Some(level));
+        let mut breaks = break_lines(&clusters, max_width);
+        let exceeded_max_lines = paragraph
+            .max_lines
+            .is_some_and(|max_lines| breaks.len() > max_lines);
+        if let Some(max_lines) = paragraph.max_lines {
+            breaks.truncate(max_lines.max(1));
+        }
+
+        let default_style = runs
+            .iter()
+            .find_map(|run| match &run.kind {
+                RunKind::Text(style) => Some(style.clone()),
+                RunKind::Placeholder(_) => None,
+            })
+            .unwrap_or_default();
+        let mut builder = LineBuilder {
+            runs,
+            clusters: &clusters,
+            lines: Vec::new(),
+            fragments: Vec::new(),
+            top: 0.0,
+            split_spaces: paragraph.align == TextAlign::Justify,
+            bidi: &bidi,
+            rtl: paragraph.direction == TextDirection::Rtl,
+            max_width,
+            default_style: default_style.clone(),
+            fonts,
+            ascents: runs
+                .iter()
+                .map(|run| match &run.kind {
+                    RunKind::Text(style) => fonts.ascent(style),
+                    RunKind::Placeholder(size) => size.height,
+                })
+                .collect(),
+        };
+        let line_count = breaks.len();
+        for (index, (range, hard_break)) in breaks.into_iter().enumerate() {
+            let last = index + 1 == line_count;
+            let ellipsis =
+                last && exceeded_max_lines && paragraph.overflow == TextOverflow::Ellipsis;
+            builder.add_line(range, hard_break, ellipsis);
+            if hard_break && !last {
+                builder.top += paragraph.paragraph_spacing;
+            }
+        }
+        if builder.lines.is_empty() {
+            builder.add_line(0..0, false, false);
+        }
+
+        let LineBuilder {
+            mut lines,
+            mut fragments,
+            top,
+            ..
+        } = builder;
+        let widest = lines
+            .iter()
+            .map(|line| line.metrics.width)
+            .fold(0.0, f32::max);
+        let available = if max_width.is_finite() {
+            max_width
+        } else {
+            widest
+        };
+        align_lines(&mut lines, &mut fragments, &clusters, available, paragraph);
+        let width = match (paragraph.align, paragraph.direction) {
+            (TextAlign::Start, TextDirection::Ltr) | (TextAlign::End, TextDirection::Rtl) => widest,
+            _ => available.max(widest),
+        };
+        let line_height = lines
+            .first()
+            .map_or(default_style.line_extent(), |line| line.metrics.height);
+        TextLayout {
+            lines,
+            fragments,
+            line_height,
+            size: Size::new(width, top),
+            exceeded_max_lines,
+            rtl: paragraph.direction == TextDirection::Rtl,
+        }
+    }
+
+    /// The size of the laid out text.
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// The height of the first line.
+    pub fn line_height(&self) -> f32 {
+        self.line_height
+    }
+
+    /// The number of lines.
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Returns true if lines were cut off by the maximum number of lines.
+    pub fn did_exceed_max_lines(&self) -> bool {
+        self.exceeded_max_lines
+    }
+
+    /// The byte range of the line, excluding the line break.
+    pub fn line_range(&self, line: usize) -> Range<usize> {
+        self.lines
+            .get(line)
+            .map_or(0..0, |line| line.metrics.range.clone())
+    }
+
+    /// The metrics of every line, from top to bottom.
+    pub fn line_metrics(&self) -> impl Iterator<Item = &LineMetrics> + '_ {
+        self.lines.iter().map(|line| &line.metrics)
+    }
+
+    /// The byte ranges and top-left positions of every line.
+    pub fn lines(&self) -> impl Iterator<Item = (Range<usize>, Offset)> + '_ {
+        self.lines.iter().map(|line| {
+            (
+                line.metrics.range.clone(),
+                Offset::new(line.metrics.left, line.metrics.top),
+            )
+        })
+    }
+
+    /// The fragments of the runs, line by line from left to right.
+    pub(crate) fn fragments(&self) -> &[Fragment] {
+        &self.fragments
+    }
+
+    /// The index of the run under the position, if any.
+    pub(crate) fn run_at(&self, position: Offset) -> Option<usize> {
+        self.fragments
+            .iter()
+            .find(|fragment| fragment.rect.contains(position))
+            .map(|fragment| fragment.run)
+    }
+
+    /// The line containing the byte index.
+    ///
+    /// An index where a line was broken to fit the width belongs to the
+    /// line after the break.
+    pub fn line_for_index(&self, index: usize) -> usize {
+        self.lines
+            .iter()
+            .position(|line| {
+                let range = &line.metrics.range;
+                index < range.end || (index == range.end && line.metrics.hard_break)
+            })
+            .unwrap_or_else(|| self.lines.len().saturating_sub(1))
+    }
+
+    /// The rectangle of the caret placed before the byte index.
+    ///
+    /// In right-to-left text the caret is on the right edge of the grapheme
+    /// at the index.
+    pub fn caret_rect(&self, index: usize) -> Rect {
+        let line_index = self.line_for_index(index);
+        self.lines.get(line_index).map_or_else(
+            || Rect::new(0.0, 0.0, CARET_WIDTH, self.line_height),
+            |line| {
+                let x = line.x_for_index(index);
+                Rect::new(x, line.metrics.top, CARET_WIDTH, line.metrics.height)
+            },
+        )
+    }
+
+    /// The byte index of the caret position closest to the point.
+    pub fn index_for_position(&self, position: Offset) -> usize {
+        let line = self
+            .lines
+            .iter()
+            .position(|line| position.y < line.bottom())
+            .unwrap_or_else(|| self.lines.len().saturating_sub(1));
+        self.index_for_line_x(line, position.x)
+    }
+
+    /// The byte index of the caret position on the line closest to `x`.
+    pub fn index_for_line_x(&self, line: usize, x: f32) -> usize {
+        self.lines.get(line).map_or(0, |line| line.index_for_x(x))
+    }
+
+    /// The rectangles covering the byte range, at least one per line.
+    ///
+    /// Text in both directions may need several rectangles on a line, as a
+    /// range of the text is not contiguous on screen.
+    pub fn selection_rects(&self, range: Range<usize>) -> Vec<Rect> {
+        if range.is_empty() {
+            return Vec::new();
+        }
+        let mut rects = Vec::new();
+        for line in &self.lines {
+            let line_range = &line.metrics.range;
+            if range.start > line_range.end || range.end < line_range.start {
+                continue;
+            }
+            let mut spans = Vec::new();
+            for fragment in &self.fragments[line.fragments.clone()] {
+                let start = range.start.max(fragment.range.start);
+                let end = range.end.min(fragment.range.end);
+                if start >= end {
+                    continue;
+                }
+                let from = line.x_for_index(start);
+                let to = match (end == fragment.range.end, fragment.rtl) {
+                    (true, false) => fragment.rect.right(),
+                    (true, true) => fragment.rect.left(),
+                    (false, _) => line.x_for_index(end),
+                };
+                spans.push((from.min(to), from.max(to)));
+            }
+            // Show that the line break is selected.
+            if range.end > line_range.end {
+                let x = line.x_for_index(line_range.end);
+                let width = line.metrics.height / 4.0;
+                spans.push(if self.rtl {
+                    (x - width, x)
+                } else {
+                    (x, x + width)
+                });
+            }
+            spans.sort_by(|a, b| a.0.total_cmp(&b.0));
+            let mut merged: Vec<(f32, f32)> = Vec::new();
+            for (left, right) in spans {
+                match merged.last_mut() {
+                    Some(last) if left <= last.1 => last.1 = last.1.max(right),
+                    _ => merged.push((left, right)),
+                }
+            }
+            rects.extend(merged.into_iter().map(|(left, right)| {
+                Rect::new(left, line.metrics.top, right - left, line.metrics.height)
+            }));
+        }
+        rects
+    }
+}
+
+/// Split the text into graphemes with their widths and the line break
+/// opportunities after them.
+fn clusters(text: &str, runs: &[TextRun], fonts: &FontCollection) -> Vec<Cluster> {
+    let mut opportunities = linebreaks(text).peekable();
+    let mut clusters = Vec::new();
+    for (run_index, run) in runs.iter().enumerate() {
+        let Some(run_text) = text.get(run.range.clone()) else {
+            continue;
+        };
+        let advances = match &run.kind {
+            RunKind::Text(style) => fonts.advances(run_text, style),
+            RunKind::Placeholder(_) => Vec::new(),
+        };
+        for (index, (offset, grapheme)) in run_text.grapheme_indices(true).enumerate() {
+            let start = run.range.start + offset;
+            let end = start + grapheme.len();
+            let width = match run.kind {
+                RunKind::Text(_) => advances[index],
+                RunKind::Placeholder(size) => size.width,
+            };
+            let first = grapheme.chars().next();
+            let mut break_after = None;
+            while let Some((position, opportunity)) = opportunities.peek() {
+                if *position > end {
+                    break;
+                }
+                if *position == end {
+                    break_after = Some(*opportunity);
+                }
+                opportunities.next();
+            }
+            clusters.push(Cluster {
+                run: run_index,
+                range: start..end,
+                width,
+                whitespace: first.is_some_and(char::is_whitespace),
+                newline: matches!(
+                    first,
+                    Some('\n' | '\r' | '\u{0B}' | '\u{0C}' | '\u{85}' | '\u{2028}' | '\u{2029}')
+                ),
+                break_after,
+            });
+        }
+    }
+    clusters
+}
+
+/// Break the clusters into lines greedily, returning the clusters of every
+/// line and whether it ends with a newline.
+fn break_lines(clusters: &[Cluster], max_width: f32) -> Vec<(Range<usize>, bool)> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    let mut index = 0;
+    // The width of the line including trailing white space.
+    let mut advance = 0.0;
+    // The last cluster of the line after which it may break.
+    let mut last_break = None;
+    while index < clusters.len() {
+        let cluster = &clusters[index];
+        if cluster.newline {
+            lines.push((start..index + 1, true));
+            start = index + 1;
+            index = start;
+            advance = 0.0;
+            last_break = None;
+            continue;
+        }
+        // White space hangs past the end of the line.
+        if !cluster.whitespace && index > start && advance + cluster.width > max_width {
+            // Break at the last opportunity, or within the word if there is
+            // none.
+            let end = last_break.map_or(index, |last_break: usize| last_break + 1);
+            lines.push((start..end, false));
+            start = end;
+            index = start;
+            advance = 0.0;
+            last_break = None;
+            continue;
+        }
+        advance += cluster.width;
+        match cluster.break_after {
+            Some(BreakOpportunity::Mandatory) if index + 1 < clusters.len() => {
+                lines.push((start..index + 1, true));
+                start = index + 1;
+                advance = 0.0;
+                last_break = None;
+            }
+            Some(BreakOpportunity::Allowed) => last_break = Some(index),
+            _ => {}
+        }
+        index += 1;
+    }
+    // Text ending with a newline ends with an empty line.
+    if start < clusters.len() || clusters.last().is_some_and(|cluster| cluster.newline) {
+        lines.push((start..clusters.len(), false));
+    }
+    lines
+}
+
+/// Builds the lines and fragments from the clusters of every line.
+struct LineBuilder<'a> {
+    runs: &'a [TextRun],
+    clusters: &'a [Cluster],
+    lines: Vec<Line>,
+    fragments: Vec<Fragment>,
+    top: f32,
+    /// Whether white space gets fragments of its own, so it can be widened.
+    split_spaces: bool,
+    bidi: &'a BidiInfo<'a>,
+    /// Whether the base direction is right-to-left.
+    rtl: bool,
+    max_width: f32,
+    /// The style of lines without text, from the first text run.
+    default_style: TextStyle,
+    fonts: &'a FontCollection,
+    /// The distance from the top of the line box of every run to its
+    /// baseline.
+    ascents: Vec<f32>,
+}
+
+impl LineBuilder<'_> {
+    fn add_line(&mut self, range: Range<usize>, hard_break: bool, ellipsis: bool) {
+        let all = &self.clusters[range.clone()];
+        // The style of the line and its ellipsis, from the last text on it.
+        let (style_run, style) = all
+            .iter()
+            .rev()
+            .chain(self.clusters[..range.start].iter().rev())
+            .find_map(|cluster| match &self.runs[cluster.run].kind {
+                RunKind::Text(style) => Some((cluster.run, style.clone())),
+                RunKind::Placeholder(_) => None,
+            })
+            .unwrap_or_else(|| (0, self.default_style.clone()));
+        let mut visible: Vec<&Cluster> = all.iter().filter(|cluster| !cluster.newline).collect();
+        let mut ellipsis_width = 0.0;
+        if ellipsis {
+            ellipsis_width = self.fonts.measure(ELLIPSIS, &style);
+            let fits = |visible: &[&Cluster]| {
+                let width: f32 = visible.iter().map(|cluster| cluster.width).sum();
+                width + ellipsis_width <= self.max_width
+            };
+            while !visible.is_empty()
+                && (visible.last().is_some_and(|cluster| cluster.whitespace) || !fits(&visible))
+            {
+                visible.pop();
+            }
+        }
+
+        // Empty lines are as high as the text before them.
+        let style_ascent = self.fonts.ascent(&style);
+        let (mut ascent, mut descent) = (style_ascent, style.line_extent() - style_ascent);
+        if !visible.is_empty() {
+            (ascent, descent) = (0.0, 0.0);
+        }
+        for cluster in &visible {
+            let run_ascent = self.ascents[cluster.run];
+            ascent = ascent.max(run_ascent);
+            if let RunKind::Text(style) = &self.runs[cluster.run].kind {
+                descent = descent.max(style.line_extent() - run_ascent);
+            }
+        }
+
+        let start = visible
+            .first()
+            .copied()
+            .or_else(|| all.first())
+            .map_or_else(
+                || {
+                    range
+                        .start
+                        .checked_sub(1)
+                        .map_or(0, |previous| self.clusters[previous].range.end)
+                },
+                |cluster| cluster.range.start,
+            );
+        let end = visible.last().map_or(start, |cluster| cluster.range.end);
+        // White space at the end of the line hangs past its end edge.
+        let content_len = visible
+            .iter()
+            .rposition(|cluster| !cluster.whitespace)
+            .map_or(0, |last| last + 1);
+        let (content, hanging) = visible.split_at(content_len);
+        let content_width: f32 = content.iter().map(|cluster| cluster.width).sum();
+        let hanging_width: f32 = hanging.iter().map(|cluster| cluster.width).sum();
+        // The ellipsis is at the end edge too.
+        let (content_left, ellipsis_left) = if self.rtl {
+            (ellipsis_width, 0.0)
+        } else {
+            (0.0, content_width)
+        };
+        let mut placed = self.visual_order(content);
+        let mut x = content_left;
+        if self.rtl {
+            placed.splice(0..0, hanging.iter().rev().map(|cluster| (*cluster, true)));
+            x -= hanging_width;
+        } else {
+            placed.extend(hanging.iter().map(|cluster| (*cluster, false)));
+        }
+
+        let baseline = self.top + ascent;
+        let mut stops = Vec::with_capacity(placed.len() + 1);
+        let mut end_x = content_left;
+        let first_fragment = self.fragments.len();
+        let mut previous_whitespace = false;
+        for (cluster, rtl) in placed {
+            let (top, height, placeholder) = match &self.runs[cluster.run].kind {
+                RunKind::Text(style) => (
+                    baseline - self.ascents[cluster.run],
+                    style.line_extent(),
+                    false,
+                ),
+                RunKind::Placeholder(size) => (baseline - size.height, size.height, true),
+            };
+            let split =
+                placeholder || self.split_spaces && (cluster.whitespace || previous_whitespace);
+            let follows = |fragment: &Fragment| {
+                if rtl {
+                    cluster.range.end == fragment.range.start
+                } else {
+                    fragment.range.end == cluster.range.start
+                }
+            };
+            match self.fragments[first_fragment..].last_mut() {
+                // Graphemes of the same text and direction next to each other
+                // on a line make one fragment.
+                Some(fragment)
+                    if fragment.run == cluster.run
+                        && fragment.rtl == rtl
+                        && follows(fragment)
+                        && !split =>
+                {
+                    if rtl {
+                        fragment.range.start = cluster.range.start;
+                    } else {
+                        fragment.range.end = cluster.range.end;
+                    }
+                    fragment.rect.size.width += cluster.width;
+                }
+                _ => self.fragments.push(Fragment {
+                    run: cluster.run,
+                    range: cluster.range.clone(),
+                    rect: Rect::new(x, top, cluster.width, height),
+                    ellipsis: false,
+                    rtl,
+                }),
+            }
+            previous_whitespace = cluster.whitespace;
+            let (leading, trailing) = if rtl {
+                (x + cluster.width, x)
+            } else {
+                (x, x + cluster.width)
+            };
+            stops.push((cluster.range.start, leading));
+            if cluster.range.end == end {
+                end_x = trailing;
+            }
+            x += cluster.width;
+        }
+        stops.push((end, end_x));
+        stops.sort_by_key(|(index, _)| *index);
+        let mut width = content_width;
+        if ellipsis {
+            self.fragments.push(Fragment {
+                run: style_run,
+                range: end..end,
+                rect: Rect::new(
+                    ellipsis_left,
+                    baseline - style_ascent,
+                    ellipsis_width,
+                    style.line_extent(),
+                ),
+                ellipsis: true,
+                rtl: self.rtl,
+            });
+            width += ellipsis_width;
+        }
+
+        self.lines.push(Line {
+            metrics: LineMetrics {
+                range: start..end,
+                top: self.top,
+                height: ascent + descent,
+                ascent,
+                descent,
+                left: 0.0,
+                width,
+                hard_break,
+            },
+            stops,
+            fragments: first_fragment..self.fragments.len(),
+        });
+        self.top += ascent + descent;
+    }
+    /// The clusters of a line without trailing white space from left to
+    /// right, and whether each runs from right to left.
+    fn visual_order<'c>(&self, content: &[&'c Cluster]) -> Vec<(&'c Cluster, bool)> {
+        let logical = || content.iter().map(|cluster| (*cluster, false)).collect();
+        let (Some(first), Some(last)) = (content.first(), content.last()) else {
+            return Vec::new();
+        };
+        if !self.bidi.has_rtl() {
+            return logical();
+        }
+        let line = first.range.start..last.range.end;
+        let Some(paragraph) = self
+            .bidi
+            .paragraphs
+            .iter()
+            .find(|paragraph| paragraph.range.contains(&line.start))
+        else {
+            return logical();
+        };
+        let (levels, runs) = self.bidi.visual_runs(paragraph, line);
+        let mut ordered = Vec::with_capacity(content.len());
+        for run in runs {
+            let rtl = levels[run.start].is_rtl();
+            let from = content.partition_point(|cluster| cluster.range.start < run.start);
+            let to = content.partition_point(|cluster| cluster.range.start < run.end);
+            let clusters = content[from..to].iter().map(|cluster| (*cluster, rtl));
+            if rtl {
+                ordered.extend(clusters.rev());
+            } else {
+                ordered.extend(clusters);
+            }
+        }
+        ordered
+    }
+}
+
+/// Move the lines and their fragments to their horizontal positions.
+fn align_lines(
+    lines: &mut [Line],
+    fragments: &mut [Fragment],
+    clusters: &[Cluster],
+    available: f32,
+    paragraph: &ParagraphStyle,
+) {
+    // The position of the start edge as a fraction of the free space.
+    let start = match paragraph.direction {
+        TextDirection::Ltr => 0.0,
+        TextDirection::Rtl => 1.0,
+    };
+    let count = lines.len();
+    for (index, line) in lines.iter_mut().enumerate() {
+        let free = (available - line.metrics.width).max(0.0);
+        let line_fragments = &mut fragments[line.fragments.clone()];
+        match paragraph.align {
+            TextAlign::Start => shift_line(line, line_fragments, free * start),
+            TextAlign::End => shift_line(line, line_fragments, free * (1.0 - start)),
+            TextAlign::Center => shift_line(line, line_fragments, free / 2.0),
+            // The last line of a paragraph is not stretched.
+            TextAlign::Justify if index + 1 < count && !line.metrics.hard_break => {
+                justify_line(line, line_fragments, clusters, free);
+            }
+            TextAlign::Justify => shift_line(line, line_fragments, free * start),
+        }
+    }
+}
+
+/// Move the line and its fragments to the right.
+fn shift_line(line: &mut Line, fragments: &mut [Fragment], dx: f32) {
+    line.metrics.left += dx;
+    for (_, x) in &mut line.stops {
+        *x += dx;
+    }
+    for fragment in fragments {
+        fragment.rect.origin.x += dx;
+    }
+}
+
+/// Stretch the line by the free space, spread over the white space between
+/// its words.
+fn justify_line(line: &mut Line, fragments: &mut [Fragment], clusters: &[Cluster], free: f32) {
+    let range = &line.metrics.range;
+    let on_line = clusters
+        .iter()
+        .filter(|cluster| cluster.range.start >= range.start && cluster.range.end <= range.end);
+    // Trailing white space is not widened.
+    let content_end = on_line
+        .clone()
+        .filter(|cluster| !cluster.whitespace)
+        .last()
+        .map_or(range.start, |cluster| cluster.range.end);
+    // Sorted by their start, since the clusters are in logical order.
+    let gaps: Vec<Range<usize>> = on_line
+        .filter(|cluster| cluster.whitespace && cluster.range.end <= content_end)
+        .map(|cluster| cluster.range.clone())
+        .collect();
+    if gaps.is_empty() {
+        return;
+    }
+    let is_gap = |range: &Range<usize>| {
+        gaps.binary_search_by_key(&range.start, |gap| gap.start)
+            .is_ok_and(|index| gaps[index] == *range)
+    };
+    let extra = free / gaps.len() as f32;
+    // White space has fragments of its own when justifying.
+    let mut gap_lefts: Vec<f32> = fragments
+        .iter()
+        .filter(|fragment| is_gap(&fragment.range))
+        .map(|fragment| fragment.rect.left())
+        .collect();
+    gap_lefts.sort_by(f32::total_cmp);
+    // The extra space left of a horizontal position.
+    let shift = |x: f32| gap_lefts.partition_point(|left| *left < x) as f32 * extra;
+    for (_, x) in &mut line.stops {
+        *x += shift(*x);
+    }
+    for fragment in fragments {
+        let left = fragment.rect.left();
+        fragment.rect.origin.x += shift(left);
+        if is_gap(&fragment.range) {
+            fragment.rect.size.width += extra;
+        }
+    }
+    line.metrics.width += free;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::font::tests::test_font;
+    use super::*;
+
+    #[test]
+    fn test_lines() {
+        let style = TextStyle::new(10.0).line_height(2.0);
+        let layout = TextLayout::new("ab\ncde", &style, &FontCollection::new());
+        assert_eq!(layout.line_count(), 2);
+        assert_eq!(layout.line_range(1), 3..6);
+        assert_eq!(layout.size(), Size::new(18.0, 40.0));
+        assert_eq!(layout.line_for_index(2), 0);
+        assert_eq!(layout.line_for_index(3), 1);
+    }
+
+    #[test]
+    fn test_caret_and_hit_testing() {
+        let style = TextStyle::new(10.0).line_height(2.0);
+        let layout = TextLayout::new("ab\ncde", &style, &FontCollection::new());
+        assert_eq!(layout.caret_rect(1).origin, Offset::new(6.0, 0.0));
+        assert_eq!(layout.caret_rect(5).origin, Offset::new(12.0, 20.0));
+        assert_eq!(layout.index_for_position(Offset::new(7.0, 25.0)), 4);
+        assert_eq!(layout.index_for_position(Offset::new(100.0, 5.0)), 2);
+        assert_eq!(layout.index_for_position(Offset::new(-5.0, 500.0)), 3);
+    }
+
+    #[test]
+    fn test_selection_rects() {
+        let style = TextStyle::new(10.0).line_height(2.0);
+        let layout = TextLayout::new("ab\ncde", &style, &FontCollection::new());
+        let rects = layout.selection_rects(1..4);
+        assert_eq!(rects.len(), 2);
+        assert_eq!(rects[0], Rect::new(6.0, 0.0, 11.0, 20.0));
+        assert_eq!(rects[1], Rect::new(0.0, 20.0, 6.0, 20.0));
+    }
+
+    /// The text of every line.
+    fn line_texts<'a>(layout: &TextLayout, text: &'a str) -> Vec<&'a str> {
+        layout.lines().map(|(range, _)| &text[range]).collect()
+    }
+
+    #[test]
+    fn test_line_breaking() {
+        // Every grapheme is 6 pixels wide and lines are 20 pixels high.
+        let style = TextStyle::new(10.0).line_height(2.0);
+        let paragraph = ParagraphStyle::default();
+        let text = "one two three";
+        let layout =
+            TextLayout::with_paragraph(text, &style, 50.0, &paragraph, &FontCollection::new());
+        assert_eq!(line_texts(&layout, text), ["one two ", "three"]);
+        // Trailing white space does not count towards the width.
+        assert_eq!(layout.size(), Size::new(42.0, 40.0));
+        // The index at a soft break belongs to the next line.
+        assert_eq!(layout.line_for_index(8), 1);
+        assert_eq!(layout.caret_rect(8).origin, Offset::new(0.0, 20.0));
+
+        // Lines break after hyphens and between ideographs, but not before
+        // closing punctuation.
+        let text = "well-known";
+        let layout =
+            TextLayout::with_paragraph(text, &style, 40.0, &paragraph, &FontCollection::new());
+        assert_eq!(line_texts(&layout, text), ["well-", "known"]);
+        let text = "\u{4E00}\u{4E8C}\u{4E09}\u{3002}";
+        let layout =
+            TextLayout::with_paragraph(text, &style, 25.0, &paragraph, &FontCollection::new());
+        assert_eq!(
+            line_texts(&layout, text),
+            ["\u{4E00}\u{4E8C}", "\u{4E09}\u{3002}"]
+        );
+
+        // Words wider than the paragraph break between graphemes.
+        let text = "abcdefgh";
+        let layout =
+            TextLayout::with_paragraph(text, &style, 20.0, &paragraph, &FontCollection::new());
+        assert_eq!(line_texts(&layout, text), ["abc", "def", "gh"]);
+    }
+
+    #[test]
+    fn test_alignment() {
+        let style = TextStyle::new(10.0).line_height(2.0);
+        let text = "aa bb cc dd";
+        let left = |align| {
+            let paragraph = ParagraphStyle::new(align);
+            let layout =
+                TextLayout::with_paragraph(text, &style, 60.0, &paragraph, &FontCollection::new());
+            layout
+                .line_metrics()
+                .map(|line| line.left)
+                .collect::<Vec<_>>()
+        };
+        // Lines of 48 and 12 pixels in 60.
+        assert_eq!(left(TextAlign::Start), [0.0, 0.0]);
+        assert_eq!(left(TextAlign::End), [12.0, 48.0]);
+        assert_eq!(left(TextAlign::Center), [6.0, 24.0]);
+
+        let paragraph = ParagraphStyle::new(TextAlign::Justify);
+        let layout =
+            TextLayout::with_paragraph(text, &style, 60.0, &paragraph, &FontCollection::new());
+        let lines: Vec<_> = layout.line_metrics().collect();
+        assert_eq!(lines[0].width, 60.0);
+        // The last line is not stretched.
+        assert_eq!(lines[1].width, 12.0);
+        // The 12 free pixels widen the two gaps between the three words.
+        assert_eq!(layout.caret_rect(6).left(), 36.0 + 12.0);
+        let words: Vec<f32> = layout
+            .fragments()
+            .iter()
+            .filter(|fragment| !text[fragment.range.clone()].trim().is_empty())
+            .map(|fragment| fragment.rect.left())
+            .collect();
+        assert_eq!(words, [0.0, 24.0, 48.0, 0.0]);
+    }
+
+    #[test]
+    fn test_max_lines() {
+        let style = TextStyle::new(10.0).line_height(2.0);
+        let text = "one two three four";
+        let paragraph = ParagraphStyle::default().max_lines(2, TextOverflow::Ellipsis);
+        let layout =
+            TextLayout::with_paragraph(text, &style, 50.0, &paragraph, &FontCollection::new());
+        assert!(layout.did_exceed_max_lines());
+        assert_eq!(layout.line_count(), 2);
+        // "three " and the ellipsis do not fit in 50 pixels, so the last
+        // line drops graphemes until they do.
+        assert_eq!(line_texts(&layout, text), ["one two ", "three"]);
+        let ellipsis = layout.fragments().iter().find(|fragment| fragment.ellipsis);
+        assert_eq!(ellipsis.map(|fragment| fragment.rect.left()), Some(30.0));
+
+        let layout =
+            TextLayout::with_paragraph(text, &style, 40.0, &paragraph, &FontCollection::new());
+        assert_eq!(line_texts(&layout, text), ["one ", "two"]);
+
+        let paragraph = ParagraphStyle::default().max_lines(2, TextOverflow::Clip);
+        let layout =
+            TextLayout::with_paragraph(text, &style, 50.0, &paragraph, &FontCollection::new());
+        assert!(layout.fragments().iter().all(|fragment| !fragment.ellipsis));
+        assert_eq!(layout.size().height, 40.0);
+
+        let paragraph = ParagraphStyle::default().max_lines(3, TextOverflow::Ellipsis);
+        let layout =
+            TextLayout::with_paragraph("a\nb", &style, 50.0, &paragraph, &FontCollection::new());
+        assert!(!layout.did_exceed_max_lines());
+    }
+
+    #[test]
+    fn test_line_metrics_and_spacing() {
+        let style = TextStyle::new(10.0).line_height(2.0);
+        let paragraph = ParagraphStyle::default().paragraph_spacing(8.0);
+        let text = "first para\nsecond";
+        let layout =
+            TextLayout::with_paragraph(text, &style, 40.0, &paragraph, &FontCollection::new());
+        let lines: Vec<_> = layout.line_metrics().cloned().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(!lines[0].hard_break);
+        assert!(lines[1].hard_break);
+        // Only the line ending the first paragraph is followed by spacing.
+        assert_eq!(
+            lines.iter().map(|line| line.top).collect::<Vec<_>>(),
+            [0.0, 20.0, 48.0]
+        );
+        assert_eq!(layout.size().height, 68.0);
+        assert_eq!(lines[2].ascent, 13.0);
+        assert_eq!(lines[2].baseline(), 61.0);
+        assert_eq!(layout.index_for_position(Offset::new(0.0, 35.0)), 6);
+        // The spacing belongs to the line below it.
+        assert_eq!(layout.index_for_position(Offset::new(0.0, 45.0)), 11);
+
+        // Lines are as high as their highest text.
+        let runs = [
+            TextRun {
+                range: 0..2,
+                kind: RunKind::Text(TextStyle::new(10.0).line_height(1.0)),
+            },
+            TextRun {
+                range: 2..4,
+                kind: RunKind::Text(TextStyle::new(20.0).line_height(1.0)),
+            },
+        ];
+        let layout = TextLayout::from_runs(
+            "abcd",
+            &runs,
+            f32::INFINITY,
+            &ParagraphStyle::default(),
+            &FontCollection::new(),
+        );
+        let line = layout.line_metrics().next().unwrap();
+        assert_eq!((line.ascent, line.descent), (16.0, 4.0));
+        assert_eq!(layout.fragments()[0].rect, Rect::new(0.0, 8.0, 12.0, 10.0));
+    }
+
+    #[test]
+    fn test_font_metrics() {
+        let mut fonts = FontCollection::new();
+        fonts
+            .register(test_font("Test", 400, false, &['a', 'b', ' ']))
+            .unwrap();
+        let style = TextStyle::new(10.0).line_height(2.0);
+        let paragraph = ParagraphStyle::default();
+        // The glyphs are half an em wide, so "ab " and "ab" do not fit on
+        // one line.
+        let layout = TextLayout::with_paragraph("ab ab", &style, 12.0, &paragraph, &fonts);
+        assert_eq!(layout.line_count(), 2);
+        assert_eq!(layout.size(), Size::new(10.0, 40.0));
+        // The 12 pixels high glyphs get half of the remaining 8 pixels of
+        // the line above them.
+        let line = layout.line_metrics().next().unwrap();
+        assert_eq!((line.ascent, line.descent), (13.0, 7.0));
+        // Characters without a glyph are as wide as the missing glyph.
+        assert_eq!(TextLayout::new("z", &style, &fonts).size().width, 5.0);
+    }
+
+    #[test]
+    fn test_bidi() {
+        let style = TextStyle::new(10.0).line_height(2.0);
+        // Hebrew inside left-to-right text reads from right to left.
+        let text = "abc \u{5D0}\u{5D1}\u{5D2} def";
+        let layout = TextLayout::new(text, &style, &FontCollection::new());
+        let fragments: Vec<_> = layout
+            .fragments()
+            .iter()
+            .map(|fragment| (fragment.range.clone(), fragment.rect.left(), fragment.rtl))
+            .collect();
+        assert_eq!(
+            fragments,
+            [
+                (0..4, 0.0, false),
+                (4..10, 24.0, true),
+                (10..14, 42.0, false)
+            ]
+        );
+        // The caret before a Hebrew letter is on its right edge.
+        assert_eq!(layout.caret_rect(4).left(), 42.0);
+        assert_eq!(layout.caret_rect(8).left(), 30.0);
+        assert_eq!(layout.index_for_position(Offset::new(31.0, 5.0)), 8);
+        // A range across the change of direction is not contiguous.
+        assert_eq!(
+            layout.selection_rects(2..6),
+            [
+                Rect::new(12.0, 0.0, 12.0, 20.0),
+                Rect::new(36.0, 0.0, 6.0, 20.0)
+            ]
+        );
+
+        // Right-to-left text starts at the right edge, and left-to-right
+        // runs inside it keep their order.
+        let text = "\u{5D0}\u{5D1} ab";
+        let paragraph = ParagraphStyle::default().direction(TextDirection::Rtl);
+        let layout =
+            TextLayout::with_paragraph(text, &style, 50.0, &paragraph, &FontCollection::new());
+        assert_eq!(layout.size().width, 50.0);
+        assert_eq!(layout.line_metrics().next().unwrap().left, 20.0);
+        let fragments: Vec<_> = layout
+            .fragments()
+            .iter()
+            .map(|fragment| (fragment.range.clone(), fragment.rect.left()))
+            .collect();
+        assert_eq!(fragments, [(5..7, 20.0), (0..5, 32.0)]);
+        assert_eq!(layout.caret_rect(0).left(), 50.0);
+        assert_eq!(layout.caret_rect(7).left(), 32.0);
+
+        let paragraph = paragraph.max_lines(1, TextOverflow::Ellipsis);
+        let layout = TextLayout::with_paragraph(
+            "\u{5D0}\u{5D1}\u{5D2}",
+            &style,
+            14.0,
+            &paragraph,
+            &FontCollection::new(),
+        );
+        // The ellipsis ends right-to-left text on the left.
+        let ellipsis = layout.fragments().iter().find(|fragment| fragment.ellipsis);
+        assert!(ellipsis
+            .is_some_and(|ellipsis| ellipsis.rect.right() <= layout.fragments()[0].rect.left()));
+    }
+}
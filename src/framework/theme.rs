@@ -0,0 +1,298 @@
+use crate::framework::{Color, TextStyle};
+
+/// Brightness is whether a theme has dark content on light surfaces or light
+/// content on dark surfaces.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Brightness {
+    /// Dark content on light surfaces.
+    #[default]
+    Light,
+    /// Light content on dark surfaces.
+    Dark,
+}
+
+/// ColorScheme is the set of colors widgets are painted with.
+///
+/// The `on_` colors are for content, such as text and icons, drawn on the
+/// color of the same name.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorScheme {
+    /// Whether the scheme is light or dark.
+    pub brightness: Brightness,
+    /// The accent color of filled buttons, active controls and focus rings.
+    pub primary: Color,
+    /// The color of content on the primary color.
+    pub on_primary: Color,
+    /// The background of text fields, menus, tables and pages.
+    pub surface: Color,
+    /// The color of text and icons on surfaces.
+    pub on_surface: Color,
+    /// The color of secondary text on surfaces, such as placeholders and
+    /// column headers.
+    pub on_surface_variant: Color,
+    /// The color of the outlines of text fields and unchecked controls.
+    pub outline: Color,
+    /// The color of dividers and grid lines.
+    pub outline_variant: Color,
+    /// The color of the parts of disabled controls.
+    pub disabled: Color,
+    /// The color of errors.
+    pub error: Color,
+    /// The background of tooltips and other surfaces that stand out.
+    pub inverse_surface: Color,
+    /// The color of content on the inverse surface.
+    pub on_inverse_surface: Color,
+}
+
+impl ColorScheme {
+    /// The color scheme with dark content on light surfaces.
+    pub fn light() -> Self {
+        let black = Color::rgb(0.0, 0.0, 0.0);
+        let white = Color::rgb(1.0, 1.0, 1.0);
+        ColorScheme {
+            brightness: Brightness::Light,
+            primary: Color::from_rgba(33, 150, 243, 255),
+            on_primary: white,
+            surface: white,
+            on_surface: black,
+            on_surface_variant: black.with_alpha(0.6),
+            outline: black.with_alpha(0.38),
+            outline_variant: black.with_alpha(0.12),
+            disabled: black.with_alpha(0.38),
+            error: Color::from_rgba(176, 0, 32, 255),
+            inverse_surface: Color::new(0.38, 0.38, 0.38, 0.9),
+            on_inverse_surface: white,
+        }
+    }
+
+    /// The color scheme with light content on dark surfaces.
+    pub fn dark() -> Self {
+        let white = Color::rgb(1.0, 1.0, 1.0);
+        ColorScheme {
+            brightness: Brightness::Dark,
+            primary: Color::from_rgba(144, 202, 249, 255),
+            on_primary: Color::from_rgba(13, 71, 161, 255),
+            surface: Color::from_rgba(18, 18, 18, 255),
+            on_surface: white,
+            on_surface_variant: white.with_alpha(0.7),
+            outline: white.with_alpha(0.38),
+            outline_variant: white.with_alpha(0.12),
+            disabled: white.with_alpha(0.38),
+            error: Color::from_rgba(207, 102, 121, 255),
+            inverse_surface: Color::new(0.88, 0.88, 0.88, 0.9),
+            on_inverse_surface: Color::rgb(0.0, 0.0, 0.0),
+        }
+    }
+}
+
+impl Default for ColorScheme {
+    fn default() -> Self {
+        ColorScheme::light()
+    }
+}
+
+/// Typography is the scale of text styles, from headlines to captions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Typography {
+    /// The style of page headlines.
+    pub headline: TextStyle,
+    /// The style of titles, e.g. of dialogs and sections.
+    pub title: TextStyle,
+    /// The style of body text and of text entered in fields.
+    pub body: TextStyle,
+    /// The style of the labels of buttons, tabs and menu items.
+    pub label: TextStyle,
+    /// The style of small text, such as tooltips.
+    pub caption: TextStyle,
+}
+
+impl Typography {
+    /// The default scale with text of the given color.
+    pub fn new(color: Color) -> Self {
+        let style = |font_size: f32| TextStyle::new(font_size).color(color);
+        Typography {
+            headline: style(24.0).line_height(1.3),
+            title: style(20.0).line_height(1.3),
+            body: style(14.0),
+            label: style(14.0),
+            caption: style(12.0),
+        }
+    }
+}
+
+impl Default for Typography {
+    fn default() -> Self {
+        Typography::new(ColorScheme::light().on_surface)
+    }
+}
+
+/// Spacing is the scale of gaps and padding in logical pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Spacing {
+    /// The gap between tightly related items, such as an icon and its label.
+    pub extra_small: f32,
+    /// The gap between related items.
+    pub small: f32,
+    /// The default padding of controls and cards.
+    pub medium: f32,
+    /// The gap between groups of items.
+    pub large: f32,
+    /// The gap between sections of a page.
+    pub extra_large: f32,
+}
+
+impl Default for Spacing {
+    fn default() -> Self {
+        Spacing {
+            extra_small: 4.0,
+            small: 8.0,
+            medium: 16.0,
+            large: 24.0,
+            extra_large: 32.0,
+        }
+    }
+}
+
+/// Radii is the scale of corner radii in logical pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Radii {
+    /// The radius of small elements, such as checkboxes and chips.
+    pub small: f32,
+    /// The radius of buttons, text fields and menus.
+    pub medium: f32,
+    /// The radius of cards and dialogs.
+    pub large: f32,
+}
+
+impl Default for Radii {
+    fn default() -> Self {
+        Radii {
+            small: 4.0,
+            medium: 8.0,
+            large: 16.0,
+        }
+    }
+}
+
+/// Elevation is the scale of how far surfaces are raised above the page,
+/// as the depth of their shadow in logical pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Elevation {
+    /// The elevation of cards and raised buttons.
+    pub raised: f32,
+    /// The elevation of menus and tooltips.
+    pub menu: f32,
+    /// The elevation of dialogs.
+    pub dialog: f32,
+    /// The color of shadows.
+    pub shadow: Color,
+}
+
+impl Default for Elevation {
+    fn default() -> Self {
+        Elevation {
+            raised: 1.0,
+            menu: 3.0,
+            dialog: 6.0,
+            shadow: Color::new(0.0, 0.0, 0.0, 0.2),
+        }
+    }
+}
+
+/// Theme is the set of design tokens widgets take their default colors,
+/// text styles and metrics from.
+///
+/// The application theme is set with [`State::set_theme`] and can be
+/// overridden for a subtree with a [`ThemeScope`]. Widgets read the theme of
+/// their subtree with [`State::theme`] while they are built and painted;
+/// colors and styles given to a widget explicitly take precedence.
+///
+/// [`State::set_theme`]: crate::framework::State::set_theme
+/// [`State::theme`]: crate::framework::State::theme
+/// [`ThemeScope`]: crate::framework::ThemeScope
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    /// The colors.
+    pub colors: ColorScheme,
+    /// The text styles.
+    pub typography: Typography,
+    /// The gaps and padding.
+    pub spacing: Spacing,
+    /// The corner radii.
+    pub radii: Radii,
+    /// The elevations of raised surfaces.
+    pub elevation: Elevation,
+}
+
+impl Theme {
+    /// Create a new Theme instance with the default tokens for the given
+    /// brightness.
+    pub fn new(brightness: Brightness) -> Self {
+        let colors = match brightness {
+            Brightness::Light => ColorScheme::light(),
+            Brightness::Dark => ColorScheme::dark(),
+        };
+        Theme::from_colors(colors)
+    }
+
+    /// The default light theme.
+    pub fn light() -> Self {
+        Theme::new(Brightness::Light)
+    }
+
+    /// The default dark theme.
+    pub fn dark() -> Self {
+        Theme::new(Brightness::Dark)
+    }
+
+    /// Create a new Theme instance with the given colors and the default
+    /// typography in the color of text on surfaces.
+    pub fn from_colors(colors: ColorScheme) -> Self {
+        Theme {
+            colors,
+            typography: Typography::new(colors.on_surface),
+            spacing: Spacing::default(),
+            radii: Radii::default(),
+            elevation: Elevation::default(),
+        }
+    }
+
+    /// Whether the theme is light or dark.
+    pub fn brightness(&self) -> Brightness {
+        self.colors.brightness
+    }
+
+    /// Return the theme with the given primary color.
+    pub fn with_primary(mut self, primary: Color) -> Self {
+        self.colors.primary = primary;
+        self
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::light()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_light_and_dark() {
+        let light = Theme::default();
+        assert_eq!(light, Theme::light());
+        assert_eq!(light.brightness(), Brightness::Light);
+        assert_eq!(light.typography.body, TextStyle::default());
+
+        let dark = Theme::dark();
+        assert_eq!(dark.brightness(), Brightness::Dark);
+        assert_eq!(dark.typography.body.color, dark.colors.on_surface);
+        assert!(dark.colors.surface.to_hsl().2 < 0.1);
+        assert_eq!(dark.spacing, light.spacing);
+
+        let red = Color::rgb(1.0, 0.0, 0.0);
+        assert_eq!(dark.with_primary(red).colors.primary, red);
+    }
+}
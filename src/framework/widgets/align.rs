@@ -0,0 +1,213 @@
+use super::single_child::SingleChild;
+use super::widget::Widget;
+use crate::framework::{Alignment, BoxConstraints, Event, Size, State, TextDirection};
+use std::fmt;
+
+/// Align is a widget that positions its child within itself.
+///
+/// The box expands to fill bounded constraints unless a size factor is given,
+/// in which case its size is the child's size multiplied by the factor.
+#[derive(Default)]
+pub struct Align {
+    child: SingleChild,
+    alignment: Alignment,
+    /// The inherited text direction, which resolves directional alignments.
+    text_direction: TextDirection,
+    width_factor: Option<f32>,
+    height_factor: Option<f32>,
+}
+
+impl Align {
+    /// Create a new Align instance with the given alignment.
+    pub fn new(alignment: Alignment) -> Self {
+        Align {
+            alignment,
+            ..Default::default()
+        }
+    }
+
+    /// Set the child of the box.
+    pub fn child(mut self, child: impl Widget + 'static) -> Self {
+        self.child.set(child);
+        self
+    }
+
+    /// Size the box to the child's width multiplied by the factor.
+    pub fn width_factor(mut self, factor: f32) -> Self {
+        self.width_factor = Some(factor);
+        self
+    }
+
+    /// Size the box to the child's height multiplied by the factor.
+    pub fn height_factor(mut self, factor: f32) -> Self {
+        self.height_factor = Some(factor);
+        self
+    }
+}
+
+impl Widget for Align {
+    fn build(&mut self, state: &mut State<'_>) {
+        let text_direction = state.text_direction();
+        if text_direction != self.text_direction && self.alignment.is_directional() {
+            self.child.mark_needs_layout();
+        }
+        self.text_direction = text_direction;
+        self.child.build(state);
+    }
+
+    fn layout(&mut self, constraints: BoxConstraints) -> Size {
+        if let Some(size) = self.child.cached(constraints) {
+            return size;
+        }
+        let shrink_wrap_width = self.width_factor.is_some() || !constraints.has_bounded_width();
+        let shrink_wrap_height = self.height_factor.is_some() || !constraints.has_bounded_height();
+
+        let child_size = self.child.layout(constraints.loosen());
+        let content = child_size.unwrap_or(Size::ZERO);
+        let size = constraints.constrain(Size {
+            width: if shrink_wrap_width {
+                content.width * self.width_factor.unwrap_or(1.0)
+            } else {
+                f32::INFINITY
+            },
+            height: if shrink_wrap_height {
+                content.height * self.height_factor.unwrap_or(1.0)
+            } else {
+                f32::INFINITY
+            },
+        });
+        if let Some(child_size) = child_size {
+            self.child.set_offset(
+                self.alignment
+                    .resolve(self.text_direction)
+                    .align(child_size, size),
+            );
+        }
+        self.child.store(constraints, size)
+    }
+
+    fn paint(&self, state: &mut State<'_>) {
+        self.child.paint(state);
+    }
+
+    fn event(&mut self, event: &Event, state: &mut State<'_>) -> bool {
+        self.child.event(event, state)
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.child.needs_layout()
+    }
+
+    fn mark_needs_layout(&mut self) {
+        self.child.mark_needs_layout();
+    }
+
+    fn is_relayout_boundary(&self) -> bool {
+        self.child.is_relayout_boundary()
+    }
+}
+
+impl fmt::Debug for Align {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Align")
+            .field("alignment", &self.alignment)
+            .field("text_direction", &self.text_direction)
+            .field("width_factor", &self.width_factor)
+            .field("height_factor", &self.height_factor)
+            .field("has_child", &self.child.is_some())
+            .finish()
+    }
+}
+
+/// Center is a widget that centers its child within itself.
+#[derive(Debug, Default)]
+pub struct Center {
+    align: Align,
+}
+
+impl Center {
+    /// Create a new Center instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the child of the box.
+    pub fn child(mut self, child: impl Widget + 'static) -> Self {
+        self.align = self.align.child(child);
+        self
+    }
+
+    /// Size the box to the child's width multiplied by the factor.
+    pub fn width_factor(mut self, factor: f32) -> Self {
+        self.align = self.align.width_factor(factor);
+        self
+    }
+
+    /// Size the box to the child's height multiplied by the factor.
+    pub fn height_factor(mut self, factor: f32) -> Self {
+        self.align = self.align.height_factor(factor);
+        self
+    }
+}
+
+impl Widget for Center {
+    fn build(&mut self, state: &mut State<'_>) {
+        self.align.build(state);
+    }
+
+    fn layout(&mut self, constraints: BoxConstraints) -> Size {
+        self.align.layout(constraints)
+    }
+
+    fn paint(&self, state: &mut State<'_>) {
+        self.align.paint(state);
+    }
+
+    fn event(&mut self, event: &Event, state: &mut State<'_>) -> bool {
+        self.align.event(event, state)
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.align.needs_layout()
+    }
+
+    fn mark_needs_layout(&mut self) {
+        self.align.mark_needs_layout();
+    }
+
+    fn is_relayout_boundary(&self) -> bool {
+        self.align.is_relayout_boundary()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::{Offset, SizedBox};
+
+    #[test]
+    fn test_align_expands() {
+        let mut align = Align::new(Alignment::BottomRight).child(SizedBox::new(20.0, 10.0));
+        let size = align.layout(BoxConstraints::loose(Size::new(100.0, 50.0)));
+        assert_eq!(size, Size::new(100.0, 50.0));
+        assert_eq!(align.child.offset(), Offset::new(80.0, 40.0));
+    }
+
+    #[test]
+    fn test_align_factors() {
+        let mut align = Align::new(Alignment::Center)
+            .width_factor(2.0)
+            .child(SizedBox::new(20.0, 10.0));
+        let size = align.layout(BoxConstraints::new(0.0, 100.0, 0.0, f32::INFINITY));
+        assert_eq!(size, Size::new(40.0, 10.0));
+        assert_eq!(align.child.offset(), Offset::new(10.0, 0.0));
+    }
+
+    #[test]
+    fn test_center() {
+        let mut center = Center::new().child(SizedBox::new(20.0, 10.0));
+        let size = center.layout(BoxConstraints::tight(Size::new(100.0, 50.0)));
+        assert_eq!(size, Size::new(100.0, 50.0));
+        assert_eq!(center.align.child.offset(), Offset::new(40.0, 20.0));
+    }
+}
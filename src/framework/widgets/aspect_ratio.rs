@@ -0,0 +1,151 @@
+use super::single_child::SingleChild;
+use super::widget::Widget;
+use crate::framework::{BoxConstraints, Event, Size, State};
+use std::fmt;
+
+/// AspectRatio is a widget that sizes its child to a specific aspect ratio.
+///
+/// The box tries the largest width its constraints allow and derives the
+/// height from the ratio, then adjusts to satisfy the remaining constraints.
+pub struct AspectRatio {
+    child: SingleChild,
+    aspect_ratio: f32,
+}
+
+impl AspectRatio {
+    /// Create a new AspectRatio instance with the given width-to-height ratio.
+    ///
+    /// The ratio must be finite and greater than zero.
+    pub fn new(aspect_ratio: f32) -> Self {
+        AspectRatio {
+            child: SingleChild::default(),
+            aspect_ratio,
+        }
+    }
+
+    /// Set the child of the box.
+    pub fn child(mut self, child: impl Widget + 'static) -> Self {
+        self.child.set(child);
+        self
+    }
+
+    /// The size with the aspect ratio that best fits the constraints.
+    fn apply_aspect_ratio(&self, constraints: BoxConstraints) -> Size {
+        if constraints.is_tight() {
+            return constraints.smallest();
+        }
+        if !(self.aspect_ratio.is_finite() && self.aspect_ratio > 0.0) {
+            log::error!(
+                "AspectRatio requires a finite positive ratio, got {}",
+                self.aspect_ratio
+            );
+            return constraints.smallest();
+        }
+        if !constraints.has_bounded_width() && !constraints.has_bounded_height() {
+            log::error!("AspectRatio requires bounded width or height constraints");
+            return constraints.smallest();
+        }
+
+        let ratio = self.aspect_ratio;
+        let (mut width, mut height) = if constraints.has_bounded_width() {
+            (constraints.max_width, constraints.max_width / ratio)
+        } else {
+            (constraints.max_height * ratio, constraints.max_height)
+        };
+        if width > constraints.max_width {
+            width = constraints.max_width;
+            height = width / ratio;
+        }
+        if height > constraints.max_height {
+            height = constraints.max_height;
+            width = height * ratio;
+        }
+        if width < constraints.min_width {
+            width = constraints.min_width;
+            height = width / ratio;
+        }
+        if height < constraints.min_height {
+            height = constraints.min_height;
+            width = height * ratio;
+        }
+        constraints.constrain(Size { width, height })
+    }
+}
+
+impl Widget for AspectRatio {
+    fn build(&mut self, state: &mut State<'_>) {
+        self.child.build(state);
+    }
+
+    fn layout(&mut self, constraints: BoxConstraints) -> Size {
+        if let Some(size) = self.child.cached(constraints) {
+            return size;
+        }
+        let size = self.apply_aspect_ratio(constraints);
+        self.child.layout(BoxConstraints::tight(size));
+        self.child.store(constraints, size)
+    }
+
+    fn paint(&self, state: &mut State<'_>) {
+        self.child.paint(state);
+    }
+
+    fn event(&mut self, event: &Event, state: &mut State<'_>) -> bool {
+        self.child.event(event, state)
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.child.needs_layout()
+    }
+
+    fn mark_needs_layout(&mut self) {
+        self.child.mark_needs_layout();
+    }
+
+    fn is_relayout_boundary(&self) -> bool {
+        self.child.is_relayout_boundary()
+    }
+}
+
+impl fmt::Debug for AspectRatio {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AspectRatio")
+            .field("aspect_ratio", &self.aspect_ratio)
+            .field("has_child", &self.child.is_some())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aspect_ratio() {
+        let mut widget = AspectRatio::new(2.0);
+        let size = widget.layout(BoxConstraints::loose(Size::new(100.0, 100.0)));
+        assert_eq!(size, Size::new(100.0, 50.0));
+
+        // Height-limited: the width follows the height.
+        let size = widget.layout(BoxConstraints::loose(Size::new(100.0, 20.0)));
+        assert_eq!(size, Size::new(40.0, 20.0));
+
+        // Unbounded width derives from the bounded height.
+        let size = widget.layout(
+            BoxConstraints::tight_for(None, None).enforce(BoxConstraints::new(
+                0.0,
+                f32::INFINITY,
+                0.0,
+                30.0,
+            )),
+        );
+        assert_eq!(size, Size::new(60.0, 30.0));
+    }
+
+    #[test]
+    fn test_invalid_aspect_ratio() {
+        let mut widget = AspectRatio::new(0.0);
+        let size = widget.layout(BoxConstraints::loose(Size::new(100.0, 100.0)));
+        assert_eq!(size, Size::ZERO);
+    }
+}
@@ -0,0 +1,515 @@
+use super::align::Center;
+use super::container::{Border, Container};
+use super::widget::Widget;
+use super::widget_states::WidgetStates;
+use crate::framework::{
+    BoxConstraints, Color, EdgeInsets, Event, FocusId, Offset, Rect, Size, State, Theme,
+};
+use std::fmt;
+use winit::event::MouseButton;
+use winit::keyboard::{Key, NamedKey};
+
+/// The width of the focus ring in logical pixels.
+const FOCUS_RING_WIDTH: f32 = 2.0;
+
+/// ButtonVariant is the visual emphasis of a button.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ButtonVariant {
+    /// A solid background, for the primary action.
+    #[default]
+    Filled,
+    /// A transparent background with an outline.
+    Outlined,
+    /// No background or outline until hovered.
+    Text,
+}
+
+/// ButtonStyle describes how a button looks in each of its states.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ButtonStyle {
+    /// The background color at rest.
+    pub background: Color,
+    /// The background color while hovered.
+    pub hovered_background: Color,
+    /// The background color while pressed.
+    pub pressed_background: Color,
+    /// The background color while disabled.
+    pub disabled_background: Color,
+    /// The outline at rest, if any.
+    pub border: Option<Border>,
+    /// The outline while disabled, if any.
+    pub disabled_border: Option<Border>,
+    /// The color of the ring painted around the focused button.
+    pub focus_color: Color,
+    /// The space between the outline and the label.
+    pub padding: EdgeInsets,
+    /// The minimum size of the button.
+    pub min_size: Size,
+}
+
+impl ButtonStyle {
+    /// The style of a button of the given variant with the given accent color.
+    pub fn for_variant(variant: ButtonVariant, color: Color) -> Self {
+        ButtonStyle::from_theme(variant, &Theme::default().with_primary(color))
+    }
+
+    /// The style of a button of the given variant in the theme, with the
+    /// primary color as accent and the padding from the spacing scale.
+    pub fn from_theme(variant: ButtonVariant, theme: &Theme) -> Self {
+        let color = theme.colors.primary;
+        let spacing = &theme.spacing;
+        let disabled = theme.colors.on_surface.with_alpha(0.12);
+        let transparent = Color::new(color.r, color.g, color.b, 0.0);
+        let tinted = |alpha: f64| Color::new(color.r, color.g, color.b, alpha);
+        let base = ButtonStyle {
+            background: transparent,
+            hovered_background: tinted(0.08),
+            pressed_background: tinted(0.16),
+            disabled_background: transparent,
+            border: None,
+            disabled_border: None,
+            focus_color: tinted(0.5),
+            padding: EdgeInsets::symmetric(spacing.medium, spacing.small),
+            min_size: Size::new(64.0, 36.0),
+        };
+        match variant {
+            ButtonVariant::Filled => ButtonStyle {
+                background: color,
+                hovered_background: color.lerp(Color::rgb(1.0, 1.0, 1.0), 0.12),
+                pressed_background: color.lerp(Color::rgb(0.0, 0.0, 0.0), 0.12),
+                disabled_background: disabled,
+                ..base
+            },
+            ButtonVariant::Outlined => ButtonStyle {
+                border: Some(Border::new(1.0, color)),
+                disabled_border: Some(Border::new(1.0, disabled)),
+                ..base
+            },
+            ButtonVariant::Text => ButtonStyle {
+                padding: EdgeInsets::symmetric(spacing.small, spacing.small),
+                ..base
+            },
+        }
+    }
+
+    /// The background color for the given states.
+    pub fn background_for(&self, states: WidgetStates) -> Color {
+        if states.contains(WidgetStates::DISABLED) {
+            self.disabled_background
+        } else if states.contains(WidgetStates::PRESSED) {
+            self.pressed_background
+        } else if states.contains(WidgetStates::HOVERED) {
+            self.hovered_background
+        } else {
+            self.background
+        }
+    }
+
+    /// The outline for the given states.
+    pub fn border_for(&self, states: WidgetStates) -> Option<Border> {
+        if states.contains(WidgetStates::DISABLED) {
+            self.disabled_border
+        } else {
+            self.border
+        }
+    }
+}
+
+impl Default for ButtonStyle {
+    fn default() -> Self {
+        ButtonStyle::from_theme(ButtonVariant::Filled, &Theme::default())
+    }
+}
+
+/// Button is a widget that calls a callback when pressed.
+///
+/// It can be pressed with the primary pointer button, or with Enter or Space
+/// while focused. A button without an `on_press` callback is disabled.
+///
+/// Unless a style is set, the style of its variant is derived from the
+/// [`Theme`], so the button follows theme changes.
+pub struct Button {
+    container: Container,
+    style: ButtonStyle,
+    /// The style set with [`Button::style`], instead of the theme's.
+    custom_style: Option<ButtonStyle>,
+    variant: ButtonVariant,
+    /// The accent color, instead of the primary color of the theme.
+    color: Option<Color>,
+    on_press: Option<Box<dyn FnMut()>>,
+    enabled: bool,
+    states: WidgetStates,
+    focus_id: FocusId,
+    size: Size,
+}
+
+impl Button {
+    /// Create a new Button instance with the given label.
+    pub fn new(label: impl Widget + 'static) -> Self {
+        let style = ButtonStyle::default();
+        let mut button = Button {
+            container: Container::default().child(
+                Center::new()
+                    .width_factor(1.0)
+                    .height_factor(1.0)
+                    .child(label),
+            ),
+            style,
+            custom_style: None,
+            variant: ButtonVariant::default(),
+            color: None,
+            on_press: None,
+            enabled: true,
+            states: WidgetStates::empty(),
+            focus_id: FocusId::new(),
+            size: Size::ZERO,
+        };
+        button.apply_style();
+        button
+    }
+
+    /// Set the callback called when the button is pressed.
+    pub fn on_press(mut self, on_press: impl FnMut() + 'static) -> Self {
+        self.on_press = Some(Box::new(on_press));
+        self.update_states(|_| {});
+        self
+    }
+
+    /// Set whether the button accepts input.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self.update_states(|_| {});
+        self
+    }
+
+    /// Use the style of the given variant, filled by default.
+    pub fn variant(mut self, variant: ButtonVariant) -> Self {
+        self.variant = variant;
+        self.restyle(self.themed_style(&Theme::default()));
+        self
+    }
+
+    /// Set the accent color, instead of the primary color of the theme.
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self.restyle(self.themed_style(&Theme::default()));
+        self
+    }
+
+    /// Set the style of the button, instead of the style of its variant in
+    /// the theme.
+    pub fn style(mut self, style: ButtonStyle) -> Self {
+        self.custom_style = Some(style);
+        self.restyle(style);
+        self
+    }
+
+    /// The focus identifier of the button.
+    pub fn focus_id(&self) -> FocusId {
+        self.focus_id
+    }
+
+    /// The current interactive states, excluding focus which is tracked by
+    /// the [`FocusManager`](crate::framework::FocusManager).
+    pub fn states(&self) -> WidgetStates {
+        self.states
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled && self.on_press.is_some()
+    }
+
+    /// The style of the variant and color of the button in the theme.
+    fn themed_style(&self, theme: &Theme) -> ButtonStyle {
+        let theme = match self.color {
+            Some(color) => theme.clone().with_primary(color),
+            None => theme.clone(),
+        };
+        ButtonStyle::from_theme(self.variant, &theme)
+    }
+
+    /// Switch to the style if it differs from the current one.
+    fn restyle(&mut self, style: ButtonStyle) {
+        if style != self.style {
+            self.style = style;
+            self.apply_style();
+            self.container.mark_needs_layout();
+        }
+    }
+
+    /// Apply the style properties that do not depend on the states.
+    fn apply_style(&mut self) {
+        let container = std::mem::take(&mut self.container);
+        self.container = container
+            .padding(self.style.padding)
+            .constraints(BoxConstraints::new(
+                self.style.min_size.width,
+                f32::INFINITY,
+                self.style.min_size.height,
+                f32::INFINITY,
+            ));
+        self.update_states(|_| {});
+    }
+
+    /// Change the states and update the container to match.
+    fn update_states(&mut self, update: impl FnOnce(&mut WidgetStates)) {
+        let mut states = self.states;
+        update(&mut states);
+        states.set(WidgetStates::DISABLED, !self.is_enabled());
+        if states.contains(WidgetStates::DISABLED) {
+            states.remove(WidgetStates::HOVERED | WidgetStates::PRESSED);
+        }
+        self.states = states;
+        self.container.set_color(self.style.background_for(states));
+        self.container.set_border(self.style.border_for(states));
+    }
+
+    fn press(&mut self) {
+        if let Some(on_press) = &mut self.on_press {
+            on_press();
+        }
+    }
+
+    fn contains(&self, position: Offset) -> bool {
+        Rect::from_size(self.size).contains(position)
+    }
+}
+
+impl Widget for Button {
+    fn build(&mut self, state: &mut State<'_>) {
+        let style = match self.custom_style {
+            Some(style) => style,
+            None => self.themed_style(state.theme()),
+        };
+        self.restyle(style);
+        self.container.build(state);
+    }
+
+    fn layout(&mut self, constraints: BoxConstraints) -> Size {
+        self.size = self.container.layout(constraints);
+        self.size
+    }
+
+    fn paint(&self, state: &mut State<'_>) {
+        self.container.paint(state);
+        if self.is_enabled() {
+            state.focus_mut().register(self.focus_id);
+            if state.focus().is_focused(self.focus_id) {
+                let ring = Rect::new(
+                    -FOCUS_RING_WIDTH,
+                    -FOCUS_RING_WIDTH,
+                    self.size.width + FOCUS_RING_WIDTH * 2.0,
+                    self.size.height + FOCUS_RING_WIDTH * 2.0,
+                );
+                state
+                    .canvas_mut()
+                    .stroke_rect(ring, FOCUS_RING_WIDTH, self.style.focus_color);
+            }
+        }
+    }
+
+    fn event(&mut self, event: &Event, state: &mut State<'_>) -> bool {
+        if !self.is_enabled() {
+            return false;
+        }
+        match event {
+            Event::PointerMove { position } => {
+                let hovered = self.contains(*position);
+                self.update_states(|states| states.set(WidgetStates::HOVERED, hovered));
+                false
+            }
+            Event::PointerExit => {
+                self.update_states(|states| {
+                    states.remove(WidgetStates::HOVERED | WidgetStates::PRESSED);
+                });
+                false
+            }
+            Event::PointerDown {
+                position,
+                button: MouseButton::Left,
+            } if self.contains(*position) => {
+                self.update_states(|states| states.insert(WidgetStates::PRESSED));
+                true
+            }
+            Event::PointerUp {
+                position,
+                button: MouseButton::Left,
+            } if self.states.contains(WidgetStates::PRESSED) => {
+                self.update_states(|states| states.remove(WidgetStates::PRESSED));
+                if self.contains(*position) {
+                    self.press();
+                }
+                true
+            }
+            Event::KeyDown {
+                key: Key::Named(NamedKey::Enter | NamedKey::Space),
+                ..
+            } if state.focus().is_focused(self.focus_id) => {
+                self.press();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.container.needs_layout()
+    }
+
+    fn mark_needs_layout(&mut self) {
+        self.container.mark_needs_layout();
+    }
+
+    fn is_relayout_boundary(&self) -> bool {
+        self.container.is_relayout_boundary()
+    }
+}
+
+impl fmt::Debug for Button {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Button")
+            .field("style", &self.style)
+            .field("enabled", &self.is_enabled())
+            .field("states", &self.states)
+            .field("focus_id", &self.focus_id)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::widgets::test_support::click;
+    use crate::framework::SizedBox;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use winit::keyboard::ModifiersState;
+
+    fn button(presses: &Rc<Cell<u32>>) -> Button {
+        let presses = Rc::clone(presses);
+        let mut button =
+            Button::new(SizedBox::new(40.0, 10.0)).on_press(move || presses.set(presses.get() + 1));
+        button.layout(BoxConstraints::loose(Size::new(200.0, 200.0)));
+        button
+    }
+
+    #[test]
+    fn test_layout_uses_min_size() {
+        let presses = Rc::new(Cell::new(0));
+        let button = button(&presses);
+        assert_eq!(button.size, Size::new(72.0, 36.0));
+    }
+
+    #[test]
+    fn test_pointer_press() {
+        let presses = Rc::new(Cell::new(0));
+        let mut button = button(&presses);
+        let mut state = State::default();
+
+        button.event(
+            &Event::PointerMove {
+                position: Offset::new(5.0, 5.0),
+            },
+            &mut state,
+        );
+        assert!(button.states().contains(WidgetStates::HOVERED));
+
+        click(&mut button, &mut state, Offset::new(5.0, 5.0));
+        assert_eq!(presses.get(), 1);
+
+        // Releasing outside the button cancels the press.
+        button.event(
+            &Event::PointerDown {
+                position: Offset::new(5.0, 5.0),
+                button: MouseButton::Left,
+            },
+            &mut state,
+        );
+        assert!(button.states().contains(WidgetStates::PRESSED));
+        button.event(
+            &Event::PointerUp {
+                position: Offset::new(500.0, 5.0),
+                button: MouseButton::Left,
+            },
+            &mut state,
+        );
+        assert_eq!(presses.get(), 1);
+        assert!(!button.states().contains(WidgetStates::PRESSED));
+    }
+
+    #[test]
+    fn test_disabled_button() {
+        let presses = Rc::new(Cell::new(0));
+        let mut button = button(&presses).enabled(false);
+        button.layout(BoxConstraints::loose(Size::new(200.0, 200.0)));
+        let mut state = State::default();
+
+        click(&mut button, &mut state, Offset::new(5.0, 5.0));
+        assert_eq!(presses.get(), 0);
+        assert!(button.states().contains(WidgetStates::DISABLED));
+        assert!(Button::new(SizedBox::shrink())
+            .states()
+            .contains(WidgetStates::DISABLED));
+    }
+
+    #[test]
+    fn test_keyboard_activation() {
+        let presses = Rc::new(Cell::new(0));
+        let mut button = button(&presses);
+        let mut state = State::default();
+        let enter = Event::KeyDown {
+            key: Key::Named(NamedKey::Enter),
+            modifiers: ModifiersState::empty(),
+            text: None,
+        };
+
+        assert!(!button.event(&enter, &mut state));
+        state.focus_mut().request_focus(button.focus_id());
+        assert!(button.event(&enter, &mut state));
+        assert_eq!(presses.get(), 1);
+    }
+
+    #[test]
+    fn test_variant_styles() {
+        let color = Color::rgb(0.0, 0.0, 1.0);
+        let filled = ButtonStyle::for_variant(ButtonVariant::Filled, color);
+        assert_eq!(filled.background_for(WidgetStates::empty()), color);
+        assert_eq!(
+            filled.background_for(WidgetStates::DISABLED | WidgetStates::HOVERED),
+            filled.disabled_background
+        );
+
+        let outlined = ButtonStyle::for_variant(ButtonVariant::Outlined, color);
+        assert_eq!(
+            outlined.border_for(WidgetStates::empty()).map(|b| b.color),
+            Some(color)
+        );
+
+        let text = ButtonStyle::for_variant(ButtonVariant::Text, color);
+        assert!(text.border.is_none());
+        assert_eq!(text.background_for(WidgetStates::empty()).a, 0.0);
+    }
+
+    #[test]
+    fn test_theme_style() {
+        let presses = Rc::new(Cell::new(0));
+        let mut button = button(&presses);
+        let mut state = State::default();
+        state.set_theme(Theme::dark());
+
+        button.build(&mut state);
+        let primary = Theme::dark().colors.primary;
+        assert_eq!(button.style.background_for(WidgetStates::empty()), primary);
+
+        // An explicit color or style takes precedence over the theme.
+        let red = Color::rgb(1.0, 0.0, 0.0);
+        let mut button = button.color(red);
+        button.build(&mut state);
+        assert_eq!(button.style.background_for(WidgetStates::empty()), red);
+
+        let style = ButtonStyle::default();
+        let mut button = button.style(style);
+        button.build(&mut state);
+        assert_eq!(button.style, style);
+    }
+}
@@ -0,0 +1,6 @@
+/// Called with the newly selected value of a widget.
+pub(crate) type ChangeCallback<T> = Box<dyn FnMut(&T)>;
+
+/// Called with the indices of the selected items when the selection changes,
+/// e.g. the selected rows of a table or the path of the selected tree node.
+pub(crate) type SelectionCallback = Box<dyn FnMut(&[usize])>;
@@ -0,0 +1,273 @@
+use super::toggleable::{Response, Toggleable};
+use super::widget::Widget;
+use super::widget_states::WidgetStates;
+use crate::framework::{BoxConstraints, Canvas, Color, Event, FocusId, Offset, Rect, Size, State};
+use std::fmt;
+
+/// The size of the checkbox square in logical pixels.
+const BOX_SIZE: f32 = 18.0;
+
+/// The size of the area around the square that reacts to the pointer.
+const TAP_TARGET_SIZE: f32 = 40.0;
+
+/// The width of the outline of an unchecked checkbox.
+const BORDER_WIDTH: f32 = 2.0;
+
+/// Checkbox is a control that toggles between checked and unchecked.
+///
+/// A tristate checkbox also has a third, indeterminate value, represented by
+/// `None`, which it cycles through after checked. A checkbox without an
+/// `on_changed` callback is disabled.
+///
+/// Colors that are not set are taken from the [`Theme`](crate::framework::Theme).
+pub struct Checkbox {
+    toggleable: Toggleable,
+    value: Option<bool>,
+    tristate: bool,
+    enabled: bool,
+    active_color: Option<Color>,
+    check_color: Option<Color>,
+    on_changed: Option<Box<dyn FnMut(Option<bool>)>>,
+}
+
+impl Checkbox {
+    /// Create a new Checkbox instance, checked or not.
+    pub fn new(checked: bool) -> Self {
+        let mut checkbox = Checkbox {
+            toggleable: Toggleable::default(),
+            value: Some(checked),
+            tristate: false,
+            enabled: true,
+            active_color: None,
+            check_color: None,
+            on_changed: None,
+        };
+        checkbox.update_states();
+        checkbox
+    }
+
+    /// Allow the indeterminate value.
+    pub fn tristate(mut self, tristate: bool) -> Self {
+        self.tristate = tristate;
+        self
+    }
+
+    /// Set the value, where `None` is indeterminate.
+    pub fn value(mut self, value: Option<bool>) -> Self {
+        self.value = value;
+        self.update_states();
+        self
+    }
+
+    /// Set the color of the filled square of a checked checkbox.
+    pub fn active_color(mut self, color: Color) -> Self {
+        self.active_color = Some(color);
+        self
+    }
+
+    /// Set the color of the check mark.
+    pub fn check_color(mut self, color: Color) -> Self {
+        self.check_color = Some(color);
+        self
+    }
+
+    /// Set whether the checkbox accepts input.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self.update_states();
+        self
+    }
+
+    /// Set the callback called with the new value when the checkbox is toggled.
+    pub fn on_changed(mut self, on_changed: impl FnMut(Option<bool>) + 'static) -> Self {
+        self.on_changed = Some(Box::new(on_changed));
+        self.update_states();
+        self
+    }
+
+    /// The current value, where `None` is indeterminate.
+    pub fn checked(&self) -> Option<bool> {
+        self.value
+    }
+
+    /// The focus identifier of the checkbox.
+    pub fn focus_id(&self) -> FocusId {
+        self.toggleable.focus_id()
+    }
+
+    /// The current interactive states.
+    pub fn states(&self) -> WidgetStates {
+        self.toggleable.states()
+    }
+
+    fn update_states(&mut self) {
+        self.toggleable
+            .set_enabled(self.enabled && self.on_changed.is_some());
+        self.toggleable.set_selected(self.value != Some(false));
+    }
+
+    fn toggle(&mut self) {
+        self.value = match self.value {
+            Some(false) => Some(true),
+            Some(true) if self.tristate => None,
+            Some(true) | None => Some(false),
+        };
+        self.update_states();
+        if let Some(on_changed) = &mut self.on_changed {
+            on_changed(self.value);
+        }
+    }
+}
+
+impl Widget for Checkbox {
+    fn build(&mut self, _state: &mut State<'_>) {}
+
+    fn layout(&mut self, constraints: BoxConstraints) -> Size {
+        self.toggleable
+            .layout(constraints, Size::new(TAP_TARGET_SIZE, TAP_TARGET_SIZE))
+    }
+
+    fn paint(&self, state: &mut State<'_>) {
+        let focused = self.toggleable.register_focus(state);
+        let size = self.toggleable.size();
+        let center = Offset::new(size.width / 2.0, size.height / 2.0);
+        let colors = state.theme().colors;
+        let color = if self.toggleable.is_enabled() {
+            self.active_color.unwrap_or(colors.primary)
+        } else {
+            colors.disabled
+        };
+        let check_color = self.check_color.unwrap_or(colors.on_primary);
+        self.toggleable.paint_halo(state, center, color, focused);
+
+        let square = Rect::new(
+            center.x - BOX_SIZE / 2.0,
+            center.y - BOX_SIZE / 2.0,
+            BOX_SIZE,
+            BOX_SIZE,
+        );
+        let canvas = state.canvas_mut();
+        match self.value {
+            Some(false) => {
+                let border = if self.toggleable.is_enabled() {
+                    colors.on_surface_variant
+                } else {
+                    colors.disabled
+                };
+                canvas.stroke_rect(square, BORDER_WIDTH, border);
+            }
+            Some(true) => {
+                canvas.fill_rect(square, color);
+                paint_check_mark(canvas, square.origin, check_color);
+            }
+            None => {
+                canvas.fill_rect(square, color);
+                canvas.fill_rect(
+                    Rect::new(square.left() + 4.0, center.y - 1.0, BOX_SIZE - 8.0, 2.0),
+                    check_color,
+                );
+            }
+        }
+    }
+
+    fn event(&mut self, event: &Event, state: &mut State<'_>) -> bool {
+        let response = self.toggleable.event(event, state);
+        if response == Response::Activated {
+            self.toggle();
+        }
+        response.is_handled()
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.toggleable.needs_layout()
+    }
+
+    fn mark_needs_layout(&mut self) {
+        self.toggleable.mark_needs_layout();
+    }
+
+    fn is_relayout_boundary(&self) -> bool {
+        self.toggleable.is_relayout_boundary()
+    }
+}
+
+/// Paint a check mark inside the checkbox square at the origin.
+///
+/// The two strokes of the mark are drawn as staircases of small squares.
+fn paint_check_mark(canvas: &mut Canvas, origin: Offset, color: Color) {
+    let dot = |canvas: &mut Canvas, x: f32, y: f32| {
+        canvas.fill_rect(
+            Rect::new(origin.x + x - 1.0, origin.y + y - 1.0, 2.5, 2.5),
+            color,
+        );
+    };
+    for step in 0..=3 {
+        let step = step as f32;
+        dot(canvas, 4.0 + step, 9.0 + step);
+    }
+    for step in 1..=7 {
+        let step = step as f32;
+        dot(canvas, 7.0 + step, 12.0 - step);
+    }
+}
+
+impl fmt::Debug for Checkbox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Checkbox")
+            .field("value", &self.value)
+            .field("tristate", &self.tristate)
+            .field("enabled", &self.toggleable.is_enabled())
+            .field("states", &self.toggleable.states())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::widgets::test_support::{click, key};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use winit::keyboard::NamedKey;
+
+    #[test]
+    fn test_tristate_cycle() {
+        let values = Rc::new(RefCell::new(Vec::new()));
+        let mut checkbox = {
+            let values = Rc::clone(&values);
+            Checkbox::new(false)
+                .tristate(true)
+                .on_changed(move |value| values.borrow_mut().push(value))
+        };
+        let mut state = State::default();
+        assert_eq!(
+            checkbox.layout(BoxConstraints::loose(Size::new(100.0, 100.0))),
+            Size::new(40.0, 40.0)
+        );
+
+        click(&mut checkbox, &mut state, Offset::new(20.0, 20.0));
+        click(&mut checkbox, &mut state, Offset::new(20.0, 20.0));
+        click(&mut checkbox, &mut state, Offset::new(20.0, 20.0));
+        assert_eq!(*values.borrow(), [Some(true), None, Some(false)]);
+        assert!(state.focus().is_focused(checkbox.focus_id()));
+
+        assert!(checkbox.event(&key(NamedKey::Space), &mut state));
+        assert_eq!(checkbox.checked(), Some(true));
+        assert!(checkbox.states().contains(WidgetStates::SELECTED));
+    }
+
+    #[test]
+    fn test_disabled_checkbox() {
+        let mut checkbox = Checkbox::new(true);
+        let mut state = State::default();
+        checkbox.layout(BoxConstraints::loose(Size::new(100.0, 100.0)));
+        click(&mut checkbox, &mut state, Offset::new(20.0, 20.0));
+        assert_eq!(checkbox.checked(), Some(true));
+        assert!(checkbox.states().contains(WidgetStates::DISABLED));
+
+        // Disabled controls are skipped by focus traversal.
+        checkbox.paint(&mut state);
+        state.focus_mut().focus_next();
+        assert_eq!(state.focus().focused(), None);
+    }
+}
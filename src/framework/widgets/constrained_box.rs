@@ -0,0 +1,251 @@
+use super::single_child::SingleChild;
+use super::widget::Widget;
+use crate::framework::{
+    overflow_edges, paint_overflow_indicator, Alignment, BoxConstraints, EdgeInsets, Event, Rect,
+    Size, State, TextDirection,
+};
+use std::fmt;
+
+/// ConstrainedBox is a widget that imposes additional constraints on its child.
+#[derive(Default)]
+pub struct ConstrainedBox {
+    child: SingleChild,
+    constraints: BoxConstraints,
+}
+
+impl ConstrainedBox {
+    /// Create a new ConstrainedBox instance with the given additional constraints.
+    pub fn new(constraints: BoxConstraints) -> Self {
+        ConstrainedBox {
+            constraints,
+            ..Default::default()
+        }
+    }
+
+    /// Set the child of the box.
+    pub fn child(mut self, child: impl Widget + 'static) -> Self {
+        self.child.set(child);
+        self
+    }
+}
+
+/// Lay out a child with additional constraints enforced by the incoming ones.
+///
+/// Without a child the box is as small as the combined constraints allow.
+pub(crate) fn layout_constrained(
+    child: &mut SingleChild,
+    additional: BoxConstraints,
+    constraints: BoxConstraints,
+) -> Size {
+    if let Some(size) = child.cached(constraints) {
+        return size;
+    }
+    let enforced = additional.enforce(constraints);
+    let size = child
+        .layout(enforced)
+        .map_or_else(|| enforced.smallest(), |size| enforced.constrain(size));
+    child.store(constraints, size)
+}
+
+impl Widget for ConstrainedBox {
+    fn build(&mut self, state: &mut State<'_>) {
+        self.child.build(state);
+    }
+
+    fn layout(&mut self, constraints: BoxConstraints) -> Size {
+        layout_constrained(&mut self.child, self.constraints, constraints)
+    }
+
+    fn paint(&self, state: &mut State<'_>) {
+        self.child.paint(state);
+    }
+
+    fn event(&mut self, event: &Event, state: &mut State<'_>) -> bool {
+        self.child.event(event, state)
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.child.needs_layout()
+    }
+
+    fn mark_needs_layout(&mut self) {
+        self.child.mark_needs_layout();
+    }
+
+    fn is_relayout_boundary(&self) -> bool {
+        self.child.is_relayout_boundary()
+    }
+}
+
+impl fmt::Debug for ConstrainedBox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConstrainedBox")
+            .field("constraints", &self.constraints)
+            .field("has_child", &self.child.is_some())
+            .finish()
+    }
+}
+
+/// Axis selects the horizontal or vertical dimension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    /// The horizontal dimension.
+    Horizontal,
+    /// The vertical dimension.
+    Vertical,
+}
+
+/// UnconstrainedBox is a widget that lets its child size itself without
+/// the incoming constraints.
+///
+/// The box itself still respects its constraints, so a child larger than the
+/// box overflows and the debug overflow indicator is painted.
+#[derive(Default)]
+pub struct UnconstrainedBox {
+    child: SingleChild,
+    alignment: Alignment,
+    /// The inherited text direction, which resolves directional alignments.
+    text_direction: TextDirection,
+    constrained_axis: Option<Axis>,
+    size: Size,
+    overflow: EdgeInsets,
+}
+
+impl UnconstrainedBox {
+    /// Create a new UnconstrainedBox instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the child of the box.
+    pub fn child(mut self, child: impl Widget + 'static) -> Self {
+        self.child.set(child);
+        self
+    }
+
+    /// Set the alignment of the child within the box.
+    pub fn alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// Keep the incoming constraints along the given axis.
+    pub fn constrained_axis(mut self, axis: Axis) -> Self {
+        self.constrained_axis = Some(axis);
+        self
+    }
+}
+
+impl Widget for UnconstrainedBox {
+    fn build(&mut self, state: &mut State<'_>) {
+        let text_direction = state.text_direction();
+        if text_direction != self.text_direction && self.alignment.is_directional() {
+            self.child.mark_needs_layout();
+        }
+        self.text_direction = text_direction;
+        self.child.build(state);
+    }
+
+    fn layout(&mut self, constraints: BoxConstraints) -> Size {
+        if let Some(size) = self.child.cached(constraints) {
+            return size;
+        }
+        let child_constraints = match self.constrained_axis {
+            Some(Axis::Horizontal) => BoxConstraints {
+                min_width: constraints.min_width,
+                max_width: constraints.max_width,
+                ..BoxConstraints::UNBOUNDED
+            },
+            Some(Axis::Vertical) => BoxConstraints {
+                min_height: constraints.min_height,
+                max_height: constraints.max_height,
+                ..BoxConstraints::UNBOUNDED
+            },
+            None => BoxConstraints::UNBOUNDED,
+        };
+
+        let size = match self.child.layout(child_constraints) {
+            Some(child_size) => {
+                let size = constraints.constrain(child_size);
+                let offset = self
+                    .alignment
+                    .resolve(self.text_direction)
+                    .align(child_size, size);
+                self.child.set_offset(offset);
+                let child_rect = Rect {
+                    origin: offset,
+                    size: child_size,
+                };
+                self.overflow = overflow_edges(child_rect, Rect::from_size(size));
+                size
+            }
+            None => {
+                self.overflow = EdgeInsets::ZERO;
+                constraints.smallest()
+            }
+        };
+        self.size = size;
+        self.child.store(constraints, size)
+    }
+
+    fn paint(&self, state: &mut State<'_>) {
+        self.child.paint(state);
+        paint_overflow_indicator(
+            state.canvas_mut(),
+            Rect::from_size(self.size),
+            self.overflow,
+        );
+    }
+
+    fn event(&mut self, event: &Event, state: &mut State<'_>) -> bool {
+        self.child.event(event, state)
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.child.needs_layout()
+    }
+
+    fn mark_needs_layout(&mut self) {
+        self.child.mark_needs_layout();
+    }
+
+    fn is_relayout_boundary(&self) -> bool {
+        self.child.is_relayout_boundary()
+    }
+}
+
+impl fmt::Debug for UnconstrainedBox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UnconstrainedBox")
+            .field("alignment", &self.alignment)
+            .field("constrained_axis", &self.constrained_axis)
+            .field("has_child", &self.child.is_some())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::SizedBox;
+
+    #[test]
+    fn test_constrained_box() {
+        let loose = BoxConstraints::loose(Size::new(100.0, 100.0));
+        let mut constrained = ConstrainedBox::new(BoxConstraints::new(50.0, 60.0, 0.0, 5.0))
+            .child(SizedBox::new(10.0, 10.0));
+        assert_eq!(constrained.layout(loose), Size::new(50.0, 5.0));
+    }
+
+    #[test]
+    fn test_unconstrained_box() {
+        let mut unconstrained = UnconstrainedBox::new().child(SizedBox::new(200.0, 10.0));
+        let size = unconstrained.layout(BoxConstraints::loose(Size::new(100.0, 100.0)));
+        assert_eq!(size, Size::new(100.0, 10.0));
+
+        let mut state = State::default();
+        unconstrained.paint(&mut state);
+        // The child overflows horizontally, so the indicator is painted.
+        assert!(!state.canvas().commands().is_empty());
+    }
+}
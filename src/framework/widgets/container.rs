@@ -1,34 +1,130 @@
+use super::single_child::SingleChild;
 use super::widget::Widget;
-use crate::framework::{BoxConstraints, Color, Size, State};
+use crate::framework::{
+    overflow_edges, paint_overflow_indicator, Alignment, BoxConstraints, Color, EdgeInsets,
+    EdgeInsetsGeometry, Event, Offset, Rect, Size, State, TextDirection,
+};
 use std::fmt;
 
+/// Border is a solid outline painted along the inside of a box.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Border {
+    /// The width of the border in logical pixels.
+    pub width: f32,
+    /// The color of the border.
+    pub color: Color,
+}
+
+impl Border {
+    /// Create a new Border instance.
+    pub fn new(width: f32, color: Color) -> Self {
+        Border { width, color }
+    }
+}
+
 /// Container is a widget that contains another widget.
+///
+/// It combines common painting, positioning and sizing behavior: an optional
+/// background color, padding around the child, margin around the decoration,
+/// size constraints, and alignment of the child within the available space.
 #[derive(Default)]
 pub struct Container {
-    child: Option<Box<dyn Widget>>,
-    width: f32,
-    height: f32,
-    padding: f32,
+    child: SingleChild,
+    width: Option<f32>,
+    height: Option<f32>,
+    constraints: Option<BoxConstraints>,
+    padding: EdgeInsetsGeometry,
+    margin: EdgeInsetsGeometry,
+    alignment: Option<Alignment>,
+    /// The direction set with [`Container::text_direction`], instead of the
+    /// inherited one.
+    text_direction: Option<TextDirection>,
+    inherited_direction: TextDirection,
     color: Color,
+    border: Option<Border>,
+    decoration_size: Size,
+    child_overflow: EdgeInsets,
 }
 
 impl Container {
     /// Set the child of the container.
     pub fn child(mut self, child: impl Widget + 'static) -> Self {
-        self.child = Some(Box::new(child));
+        self.child.set(child);
         self
     }
 
     /// Set the size of the container.
     pub fn size(mut self, width: f32, height: f32) -> Self {
-        self.width = width;
-        self.height = height;
+        self.width = Some(width);
+        self.height = Some(height);
+        self
+    }
+
+    /// Set the width of the container.
+    pub fn width(mut self, width: f32) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// Set the height of the container.
+    pub fn height(mut self, height: f32) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    /// Set additional constraints on the size of the container, excluding the margin.
+    pub fn constraints(mut self, constraints: BoxConstraints) -> Self {
+        self.constraints = Some(constraints);
+        self
+    }
+
+    /// Set the minimum and maximum width of the container.
+    pub fn width_range(mut self, min_width: f32, max_width: f32) -> Self {
+        let constraints = self.constraints.unwrap_or_default();
+        self.constraints = Some(BoxConstraints {
+            min_width,
+            max_width,
+            ..constraints
+        });
+        self
+    }
+
+    /// Set the minimum and maximum height of the container.
+    pub fn height_range(mut self, min_height: f32, max_height: f32) -> Self {
+        let constraints = self.constraints.unwrap_or_default();
+        self.constraints = Some(BoxConstraints {
+            min_height,
+            max_height,
+            ..constraints
+        });
+        self
+    }
+
+    /// Set the padding of the container, the empty space inside the decoration.
+    pub fn padding(mut self, padding: impl Into<EdgeInsetsGeometry>) -> Self {
+        self.padding = padding.into();
+        self
+    }
+
+    /// Set the margin of the container, the empty space around the decoration.
+    pub fn margin(mut self, margin: impl Into<EdgeInsetsGeometry>) -> Self {
+        self.margin = margin.into();
+        self
+    }
+
+    /// Set the alignment of the child within the container.
+    ///
+    /// An aligned container expands to fill its constraints and positions the
+    /// child inside, while an unaligned container sizes itself to the child.
+    pub fn alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = Some(alignment);
         self
     }
 
-    /// Set the padding of the container.
-    pub fn padding(mut self, padding: f32) -> Self {
-        self.padding = padding;
+    /// Set the text direction used to resolve directional padding, margin
+    /// and alignment, instead of the inherited one.
+    pub fn text_direction(mut self, text_direction: TextDirection) -> Self {
+        self.text_direction = Some(text_direction);
         self
     }
 
@@ -37,43 +133,177 @@ impl Container {
         self.color = color;
         self
     }
+
+    /// Set the border of the container.
+    ///
+    /// The border width is added to the padding so the child is never painted
+    /// over the border.
+    pub fn border(mut self, border: Border) -> Self {
+        self.border = Some(border);
+        self
+    }
+
+    /// Change the background color after construction.
+    pub(crate) fn set_color(&mut self, color: Color) {
+        self.color = color;
+    }
+
+    /// Change the border after construction.
+    pub(crate) fn set_border(&mut self, border: Option<Border>) {
+        let width = |border: Option<Border>| border.map_or(0.0, |border| border.width);
+        if width(self.border) != width(border) {
+            self.child.mark_needs_layout();
+        }
+        self.border = border;
+    }
+
+    /// The direction that resolves directional padding, margin and
+    /// alignment.
+    fn direction(&self) -> TextDirection {
+        self.text_direction.unwrap_or(self.inherited_direction)
+    }
+
+    /// The padding plus the border width on every side.
+    fn resolved_padding(&self) -> EdgeInsets {
+        let padding = self.padding.resolve(self.direction());
+        match self.border {
+            Some(border) => EdgeInsets {
+                left: padding.left + border.width,
+                top: padding.top + border.width,
+                right: padding.right + border.width,
+                bottom: padding.bottom + border.width,
+            },
+            None => padding,
+        }
+    }
+
+    /// The constraints on the decoration: the explicit size combined with the
+    /// additional constraints.
+    fn own_constraints(&self) -> Option<BoxConstraints> {
+        match self.constraints {
+            Some(constraints) => Some(constraints.tighten(self.width, self.height)),
+            None if self.width.is_some() || self.height.is_some() => {
+                Some(BoxConstraints::tight_for(self.width, self.height))
+            }
+            None => None,
+        }
+    }
 }
 
 impl Widget for Container {
-    fn build(&self, state: &mut State<'_>) {
-        if let Some(child) = &self.child {
-            child.build(state);
+    fn build(&mut self, state: &mut State<'_>) {
+        let direction = self.direction();
+        self.inherited_direction = state.text_direction();
+        if self.direction() != direction {
+            self.child.mark_needs_layout();
         }
+        self.child.build(state);
     }
     /// Layout the widget.
     fn layout(&mut self, constraints: BoxConstraints) -> Size {
-        let padding = self.padding * 2.0;
-
-        if let Some(child) = &mut self.child {
-            let child_constraints = BoxConstraints {
-                min_width: constraints.min_width - padding,
-                max_width: constraints.max_width - padding,
-                min_height: constraints.min_height - padding,
-                max_height: constraints.max_height - padding,
-            };
+        if let Some(size) = self.child.cached(constraints) {
+            return size;
+        }
 
-            let child_size = child.layout(child_constraints);
-            Size {
-                width: child_size.width + padding,
-                height: child_size.height + padding,
+        let cache_key = constraints;
+        let constraints = match constraints.debug_validate() {
+            Ok(()) => constraints,
+            Err(err) => {
+                log::error!("Container received invalid constraints: {err}");
+                constraints.normalize()
             }
+        };
+        let margin = self.margin.resolve(self.direction());
+        let padding = self.resolved_padding();
+
+        let outer = constraints.deflate(margin);
+        let inner = self
+            .own_constraints()
+            .map_or(outer, |own| own.enforce(outer));
+        let content = inner.deflate(padding);
+
+        let size = match self.alignment {
+            Some(alignment) => self.child.layout(content.loosen()).map(|child_size| {
+                let content_size = Size {
+                    width: if content.has_bounded_width() {
+                        content.max_width
+                    } else {
+                        child_size.width
+                    },
+                    height: if content.has_bounded_height() {
+                        content.max_height
+                    } else {
+                        child_size.height
+                    },
+                };
+                let content_size = content.constrain(content_size);
+                let offset = alignment
+                    .resolve(self.direction())
+                    .align(child_size, content_size);
+                self.child.set_offset(Offset {
+                    x: padding.left + offset.x,
+                    y: padding.top + offset.y,
+                });
+                inner.constrain(padding.inflate_size(content_size))
+            }),
+            None => self.child.layout(content).map(|child_size| {
+                self.child.set_offset(padding.top_left());
+                inner.constrain(padding.inflate_size(child_size))
+            }),
+        };
+        let size = size.unwrap_or_else(|| inner.smallest());
+
+        self.decoration_size = size;
+        self.child_overflow = if self.child.is_some() {
+            let child_rect = Rect {
+                origin: self.child.offset(),
+                size: self.child.size(),
+            };
+            overflow_edges(child_rect, Rect::from_size(size))
         } else {
-            Size {
-                width: constraints.min_width,
-                height: constraints.min_height,
-            }
-        }
+            EdgeInsets::ZERO
+        };
+        let size = constraints.constrain(margin.inflate_size(size));
+        self.child.store(cache_key, size)
     }
     /// Paint the widget.
     fn paint(&self, state: &mut State<'_>) {
-        if let Some(child) = &self.child {
-            child.paint(state);
+        let margin = self.margin.resolve(self.direction());
+        let canvas = state.canvas_mut();
+        canvas.save();
+        canvas.translate(margin.top_left());
+        canvas.fill_rect(Rect::from_size(self.decoration_size), self.color);
+        if let Some(border) = self.border {
+            canvas.stroke_rect(
+                Rect::from_size(self.decoration_size),
+                border.width,
+                border.color,
+            );
         }
+        self.child.paint(state);
+        paint_overflow_indicator(
+            state.canvas_mut(),
+            Rect::from_size(self.decoration_size),
+            self.child_overflow,
+        );
+        state.canvas_mut().restore();
+    }
+
+    fn event(&mut self, event: &Event, state: &mut State<'_>) -> bool {
+        let margin = self.margin.resolve(self.direction());
+        self.child.event(&event.to_local(margin.top_left()), state)
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.child.needs_layout()
+    }
+
+    fn mark_needs_layout(&mut self) {
+        self.child.mark_needs_layout();
+    }
+
+    fn is_relayout_boundary(&self) -> bool {
+        self.child.is_relayout_boundary()
     }
 }
 
@@ -82,9 +312,223 @@ impl fmt::Debug for Container {
         f.debug_struct("Container")
             .field("width", &self.width)
             .field("height", &self.height)
+            .field("constraints", &self.constraints)
             .field("padding", &self.padding)
+            .field("margin", &self.margin)
+            .field("alignment", &self.alignment)
             .field("color", &self.color)
+            .field("border", &self.border)
             .field("has_child", &self.child.is_some())
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::state::next_version;
+    use crate::framework::{DrawCommand, EdgeInsetsDirectional, LayoutCache, LayoutMarker};
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[derive(Debug)]
+    struct Leaf(Size);
+
+    impl Widget for Leaf {
+        fn build(&mut self, _state: &mut State<'_>) {}
+
+        fn layout(&mut self, constraints: BoxConstraints) -> Size {
+            constraints.constrain(self.0)
+        }
+
+        fn paint(&self, state: &mut State<'_>) {
+            state
+                .canvas_mut()
+                .fill_rect(Rect::from_size(self.0), Color::rgb(0.0, 0.0, 1.0));
+        }
+    }
+
+    /// A leaf that caches its layout and counts how often it is computed.
+    #[derive(Debug)]
+    struct CountingLeaf {
+        size: Size,
+        layouts: Rc<Cell<usize>>,
+        cache: LayoutCache,
+    }
+
+    impl CountingLeaf {
+        fn new(size: Size, layouts: &Rc<Cell<usize>>) -> (Self, LayoutMarker) {
+            let cache = LayoutCache::default();
+            let marker = cache.marker();
+            let leaf = CountingLeaf {
+                size,
+                layouts: Rc::clone(layouts),
+                cache,
+            };
+            (leaf, marker)
+        }
+    }
+
+    impl Widget for CountingLeaf {
+        fn build(&mut self, _state: &mut State<'_>) {}
+
+        fn layout(&mut self, constraints: BoxConstraints) -> Size {
+            if let Some(size) = self.cache.get(constraints) {
+                return size;
+            }
+            self.layouts.set(self.layouts.get() + 1);
+            let size = constraints.constrain(self.size);
+            self.cache.store(constraints, size);
+            size
+        }
+
+        fn paint(&self, _state: &mut State<'_>) {}
+
+        fn needs_layout(&self) -> bool {
+            self.cache.is_dirty()
+        }
+
+        fn mark_needs_layout(&mut self) {
+            self.cache.mark_dirty();
+        }
+
+        fn is_relayout_boundary(&self) -> bool {
+            self.cache.is_relayout_boundary()
+        }
+    }
+
+    fn loose() -> BoxConstraints {
+        BoxConstraints::loose(Size::new(200.0, 100.0))
+    }
+
+    #[test]
+    fn test_padding_and_margin() {
+        let mut container = Container::default()
+            .padding(EdgeInsets::symmetric(10.0, 5.0))
+            .margin(4.0)
+            .color(Color::rgb(1.0, 0.0, 0.0))
+            .child(Leaf(Size::new(20.0, 20.0)));
+
+        let size = container.layout(loose());
+        assert_eq!(size, Size::new(48.0, 38.0));
+
+        let mut state = State::default();
+        container.paint(&mut state);
+        assert_eq!(
+            state.canvas().commands(),
+            &[
+                DrawCommand::FillRect {
+                    rect: Rect::new(4.0, 4.0, 40.0, 30.0),
+                    color: Color::rgb(1.0, 0.0, 0.0),
+                },
+                DrawCommand::FillRect {
+                    rect: Rect::new(14.0, 9.0, 20.0, 20.0),
+                    color: Color::rgb(0.0, 0.0, 1.0),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_padding_never_negative() {
+        let mut container = Container::default()
+            .padding(50.0)
+            .child(Leaf(Size::new(20.0, 20.0)));
+        let size = container.layout(BoxConstraints::tight(Size::new(30.0, 30.0)));
+        assert_eq!(size, Size::new(30.0, 30.0));
+    }
+
+    #[test]
+    fn test_size_and_constraints() {
+        let mut container = Container::default()
+            .size(500.0, 50.0)
+            .child(Leaf(Size::new(20.0, 20.0)));
+        assert_eq!(container.layout(loose()), Size::new(200.0, 50.0));
+
+        let mut container = Container::default()
+            .width_range(60.0, 80.0)
+            .child(Leaf(Size::new(20.0, 20.0)));
+        assert_eq!(container.layout(loose()), Size::new(60.0, 20.0));
+    }
+
+    #[test]
+    fn test_alignment() {
+        let mut container = Container::default()
+            .alignment(Alignment::BottomRight)
+            .child(Leaf(Size::new(20.0, 20.0)));
+        assert_eq!(container.layout(loose()), Size::new(200.0, 100.0));
+        assert_eq!(container.child.offset(), Offset::new(180.0, 80.0));
+    }
+
+    #[test]
+    fn test_directional_padding() {
+        let mut container = Container::default()
+            .padding(EdgeInsetsDirectional::only(10.0, 0.0, 0.0, 0.0))
+            .text_direction(TextDirection::Rtl)
+            .child(Leaf(Size::new(20.0, 20.0)));
+        assert_eq!(container.layout(loose()), Size::new(30.0, 20.0));
+        assert_eq!(container.child.offset(), Offset::ZERO);
+
+        // Without a direction of its own the container follows the
+        // inherited one.
+        let mut container = Container::default()
+            .padding(EdgeInsetsDirectional::only(10.0, 0.0, 0.0, 0.0))
+            .alignment(Alignment::CenterStart)
+            .child(Leaf(Size::new(20.0, 20.0)));
+        let mut state = State::default();
+        container.build(&mut state);
+        container.layout(loose());
+        assert_eq!(container.child.offset(), Offset::new(10.0, 40.0));
+        state.push_inherited(Rc::new(TextDirection::Rtl), next_version(), None);
+        container.build(&mut state);
+        state.pop_inherited();
+        assert!(container.needs_layout());
+        container.layout(loose());
+        assert_eq!(container.child.offset(), Offset::new(170.0, 40.0));
+    }
+
+    #[test]
+    fn test_layout_is_cached() {
+        let layouts = Rc::new(Cell::new(0));
+        let (leaf, marker) = CountingLeaf::new(Size::new(20.0, 20.0), &layouts);
+        let mut container = Container::default().padding(5.0).child(leaf);
+
+        container.layout(loose());
+        container.layout(loose());
+        assert_eq!(layouts.get(), 1);
+        assert!(!container.needs_layout());
+
+        // A dirty leaf with loose constraints makes its ancestors dirty too.
+        marker.mark_dirty();
+        assert!(container.needs_layout());
+        container.layout(loose());
+        assert_eq!(layouts.get(), 2);
+
+        // New constraints invalidate the cache.
+        container.layout(BoxConstraints::loose(Size::new(50.0, 50.0)));
+        assert_eq!(layouts.get(), 3);
+
+        // A child without a cache may change at any time.
+        let mut container = Container::default().child(Leaf(Size::new(20.0, 20.0)));
+        container.layout(loose());
+        assert!(container.needs_layout());
+    }
+
+    #[test]
+    fn test_relayout_boundary() {
+        let layouts = Rc::new(Cell::new(0));
+        let (leaf, marker) = CountingLeaf::new(Size::new(20.0, 20.0), &layouts);
+        let inner = Container::default().size(40.0, 40.0).child(leaf);
+        let mut outer = Container::default().child(inner);
+
+        outer.layout(loose());
+        assert_eq!(layouts.get(), 1);
+
+        // The leaf is laid out with tight constraints, so its parent does not
+        // need layout, but the leaf itself is still laid out again.
+        marker.mark_dirty();
+        assert!(!outer.needs_layout());
+        assert_eq!(outer.layout(loose()), Size::new(40.0, 40.0));
+        assert_eq!(layouts.get(), 2);
+    }
+}
@@ -0,0 +1,986 @@
+use super::callbacks::SelectionCallback;
+use super::widget::Widget;
+use crate::framework::{
+    BoxConstraints, Canvas, Event, FocusId, FontCollection, LayoutCache, Offset, Rect, Size, State,
+    TextDirection, TextStyle, Theme,
+};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::ops::Range;
+use std::rc::Rc;
+use unicode_segmentation::UnicodeSegmentation;
+use winit::event::MouseButton;
+use winit::keyboard::{Key, NamedKey};
+use winit::window::CursorIcon;
+
+/// The default height of the header row.
+const HEADER_HEIGHT: f32 = 40.0;
+
+/// The default height of a data row.
+const ROW_HEIGHT: f32 = 32.0;
+
+/// The space left and right of the text in a cell.
+const CELL_PADDING: f32 = 12.0;
+
+/// How close to the end edge of a header cell the pointer resizes the column.
+const RESIZE_MARGIN: f32 = 4.0;
+
+/// The default width of a column.
+const COLUMN_WIDTH: f32 = 120.0;
+
+/// The default minimum width of a column.
+const MIN_COLUMN_WIDTH: f32 = 40.0;
+
+/// SortDirection is the order of the rows of a sorted column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortDirection {
+    /// Smallest values first.
+    Ascending,
+    /// Largest values first.
+    Descending,
+}
+
+/// SelectionMode is how many rows of a [`DataTable`] can be selected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SelectionMode {
+    /// Rows cannot be selected.
+    None,
+    /// One row at a time.
+    #[default]
+    Single,
+    /// Any number of rows, with Ctrl+click and Shift+click.
+    Multiple,
+}
+
+/// DataColumn describes a column of a [`DataTable`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataColumn {
+    title: String,
+    width: f32,
+    min_width: f32,
+    sortable: bool,
+    numeric: bool,
+}
+
+impl DataColumn {
+    /// Create a new DataColumn instance with the given title.
+    pub fn new(title: impl Into<String>) -> Self {
+        DataColumn {
+            title: title.into(),
+            width: COLUMN_WIDTH,
+            min_width: MIN_COLUMN_WIDTH,
+            sortable: false,
+            numeric: false,
+        }
+    }
+
+    /// Set the initial width of the column.
+    pub fn width(mut self, width: f32) -> Self {
+        self.width = width.max(self.min_width);
+        self
+    }
+
+    /// Set the width the column cannot be resized below.
+    pub fn min_width(mut self, min_width: f32) -> Self {
+        self.min_width = min_width.max(0.0);
+        self.width = self.width.max(self.min_width);
+        self
+    }
+
+    /// Set whether clicking the header sorts by the column.
+    pub fn sortable(mut self, sortable: bool) -> Self {
+        self.sortable = sortable;
+        self
+    }
+
+    /// Set whether the column holds numbers, which are aligned to the right.
+    pub fn numeric(mut self, numeric: bool) -> Self {
+        self.numeric = numeric;
+        self
+    }
+
+    /// The title shown in the header.
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// The current width of the column.
+    pub fn current_width(&self) -> f32 {
+        self.width
+    }
+}
+
+/// Called with the index of a column and the direction it is sorted in.
+type SortCallback = Box<dyn FnMut(usize, SortDirection)>;
+
+/// Shorten the text with an ellipsis so it fits in the width.
+pub(super) fn fit_text(
+    text: &str,
+    width: f32,
+    style: &TextStyle,
+    fonts: &FontCollection,
+) -> String {
+    let advances = fonts.advances(text, style);
+    if advances.iter().sum::<f32>() <= width {
+        return text.to_string();
+    }
+    let available = width - fonts.measure("…", style);
+    let mut used = 0.0;
+    let mut end = 0;
+    for ((index, grapheme), advance) in text.grapheme_indices(true).zip(advances) {
+        used += advance;
+        if used > available {
+            break;
+        }
+        end = index + grapheme.len();
+    }
+    format!("{}…", &text[..end])
+}
+
+/// DataTable shows rows of text in columns below a header row.
+///
+/// Cells are fetched from a callback with the row and column index, only for
+/// the rows scrolled into view, so tables with many rows stay cheap to paint.
+/// The header stays at the top while the rows scroll. The table does not sort
+/// the rows itself: clicking the header of a sortable column reports the new
+/// sort order, and the owner reorders the data the callback reads from.
+///
+/// Columns are resized by dragging the end edge of their header cell. Rows
+/// are selected by clicking them, with Ctrl+click and Shift+click in
+/// [`SelectionMode::Multiple`], or with the arrow keys while the table has
+/// focus. Colors, and styles that are not set, are taken from the [`Theme`].
+///
+/// In right-to-left text the first column is on the right, and the table
+/// scrolls from its right edge.
+pub struct DataTable {
+    columns: Vec<DataColumn>,
+    row_count: usize,
+    cell: Box<dyn Fn(usize, usize) -> String>,
+    style: Option<TextStyle>,
+    header_style: Option<TextStyle>,
+    fonts: Rc<FontCollection>,
+    row_height: f32,
+    header_height: f32,
+    selection_mode: SelectionMode,
+    selected: BTreeSet<usize>,
+    /// The row Shift+click and Shift+arrow extend the selection from.
+    anchor: Option<usize>,
+    /// The row last clicked or moved to, which the arrow keys move from.
+    lead: Option<usize>,
+    sort: Option<(usize, SortDirection)>,
+    on_sort: Option<SortCallback>,
+    on_selection_changed: Option<SelectionCallback>,
+    focus_id: FocusId,
+    size: Size,
+    /// The inherited text direction, which decides the side the columns
+    /// start from.
+    text_direction: TextDirection,
+    scroll: Offset,
+    hovered_row: Option<usize>,
+    hovered_edge: Option<usize>,
+    /// The resized column and the distance from its end edge to the
+    /// pointer, while the edge is dragged.
+    resizing: Option<(usize, f32)>,
+    cache: LayoutCache,
+}
+
+impl DataTable {
+    /// Create a new DataTable instance with the columns, the number of rows
+    /// and the callback returning the text of a cell by row and column.
+    pub fn new(
+        columns: Vec<DataColumn>,
+        row_count: usize,
+        cell: impl Fn(usize, usize) -> String + 'static,
+    ) -> Self {
+        DataTable {
+            columns,
+            row_count,
+            cell: Box::new(cell),
+            style: None,
+            header_style: None,
+            fonts: Rc::default(),
+            row_height: ROW_HEIGHT,
+            header_height: HEADER_HEIGHT,
+            selection_mode: SelectionMode::default(),
+            selected: BTreeSet::new(),
+            anchor: None,
+            lead: None,
+            sort: None,
+            on_sort: None,
+            on_selection_changed: None,
+            focus_id: FocusId::new(),
+            size: Size::ZERO,
+            text_direction: TextDirection::Ltr,
+            scroll: Offset::ZERO,
+            hovered_row: None,
+            hovered_edge: None,
+            resizing: None,
+            cache: LayoutCache::default(),
+        }
+    }
+
+    /// Set the style of the cells.
+    pub fn style(mut self, style: TextStyle) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    /// Set the style of the column titles.
+    pub fn header_style(mut self, style: TextStyle) -> Self {
+        self.header_style = Some(style);
+        self
+    }
+
+    /// Set the height of the data rows.
+    pub fn row_height(mut self, row_height: f32) -> Self {
+        self.row_height = row_height.max(1.0);
+        self
+    }
+
+    /// Set the height of the header row.
+    pub fn header_height(mut self, header_height: f32) -> Self {
+        self.header_height = header_height.max(0.0);
+        self
+    }
+
+    /// Set how many rows can be selected.
+    pub fn selection_mode(mut self, selection_mode: SelectionMode) -> Self {
+        self.selection_mode = selection_mode;
+        self
+    }
+
+    /// Show the sort indicator on a column without reporting it.
+    pub fn sorted_by(mut self, column: usize, direction: SortDirection) -> Self {
+        self.sort = (column < self.columns.len()).then_some((column, direction));
+        self
+    }
+
+    /// Set the callback called with the column and direction to sort by.
+    pub fn on_sort(mut self, on_sort: impl FnMut(usize, SortDirection) + 'static) -> Self {
+        self.on_sort = Some(Box::new(on_sort));
+        self
+    }
+
+    /// Set the callback called with the selected rows when they change.
+    pub fn on_selection_changed(
+        mut self,
+        on_selection_changed: impl FnMut(&[usize]) + 'static,
+    ) -> Self {
+        self.on_selection_changed = Some(Box::new(on_selection_changed));
+        self
+    }
+
+    /// The columns of the table with their current widths.
+    pub fn columns(&self) -> &[DataColumn] {
+        &self.columns
+    }
+
+    /// The number of rows.
+    pub fn row_count(&self) -> usize {
+        self.row_count
+    }
+
+    /// Change the number of rows, e.g. after the data changed, dropping the
+    /// selection of rows that no longer exist.
+    pub fn set_row_count(&mut self, row_count: usize) {
+        self.row_count = row_count;
+        self.cache.mark_dirty();
+        let before = self.selected.len();
+        self.selected.retain(|row| *row < row_count);
+        if self.anchor.is_some_and(|anchor| anchor >= row_count) {
+            self.anchor = None;
+        }
+        if self.lead.is_some_and(|lead| lead >= row_count) {
+            self.lead = None;
+        }
+        self.scroll = self.clamp_scroll(self.scroll);
+        if self.selected.len() != before {
+            self.selection_changed();
+        }
+    }
+
+    /// The column and direction the rows are sorted by, if any.
+    pub fn sort(&self) -> Option<(usize, SortDirection)> {
+        self.sort
+    }
+
+    /// The selected rows in ascending order.
+    pub fn selected_rows(&self) -> Vec<usize> {
+        self.selected.iter().copied().collect()
+    }
+
+    /// The focus identifier of the table.
+    pub fn focus_id(&self) -> FocusId {
+        self.focus_id
+    }
+
+    /// The scroll position of the rows; horizontally, the distance scrolled
+    /// from the start edge.
+    pub fn scroll_offset(&self) -> Offset {
+        self.scroll
+    }
+
+    /// Scroll the least distance that shows the whole row.
+    pub fn scroll_to_row(&mut self, row: usize) {
+        let top = row as f32 * self.row_height;
+        let viewport = self.viewport_height();
+        let y = if top < self.scroll.y {
+            top
+        } else if top + self.row_height > self.scroll.y + viewport {
+            top + self.row_height - viewport
+        } else {
+            self.scroll.y
+        };
+        self.scroll = self.clamp_scroll(Offset::new(self.scroll.x, y));
+    }
+
+    /// The rows that are at least partly scrolled into view.
+    pub fn visible_rows(&self) -> Range<usize> {
+        let first = (self.scroll.y / self.row_height).floor() as usize;
+        let last = ((self.scroll.y + self.viewport_height()) / self.row_height).ceil() as usize;
+        first.min(self.row_count)..last.min(self.row_count)
+    }
+
+    fn viewport_height(&self) -> f32 {
+        (self.size.height - self.header_height).max(0.0)
+    }
+
+    fn content_size(&self) -> Size {
+        Size::new(
+            self.columns.iter().map(|column| column.width).sum(),
+            self.row_count as f32 * self.row_height,
+        )
+    }
+
+    fn clamp_scroll(&self, scroll: Offset) -> Offset {
+        let content = self.content_size();
+        Offset::new(
+            scroll.x.min(content.width - self.size.width).max(0.0),
+            scroll
+                .y
+                .min(content.height - self.viewport_height())
+                .max(0.0),
+        )
+    }
+
+    /// The start edge of the column, in table coordinates.
+    fn column_start(&self, column: usize) -> f32 {
+        let before: f32 = self.columns[..column]
+            .iter()
+            .map(|column| column.width)
+            .sum();
+        match self.text_direction {
+            TextDirection::Ltr => before - self.scroll.x,
+            TextDirection::Rtl => self.size.width - before + self.scroll.x,
+        }
+    }
+
+    /// The end edge of the column, in table coordinates.
+    fn column_end(&self, column: usize) -> f32 {
+        let width = self.columns[column].width;
+        match self.text_direction {
+            TextDirection::Ltr => self.column_start(column) + width,
+            TextDirection::Rtl => self.column_start(column) - width,
+        }
+    }
+
+    /// The left edge of the column, in table coordinates.
+    fn column_left(&self, column: usize) -> f32 {
+        self.column_start(column).min(self.column_end(column))
+    }
+
+    /// The left edge of text of the width in a cell of the column, aligned
+    /// to the start or the end of the cell.
+    fn text_left(&self, column: usize, width: f32, at_end: bool) -> f32 {
+        if at_end == (self.text_direction == TextDirection::Ltr) {
+            self.column_left(column) + self.columns[column].width - CELL_PADDING - width
+        } else {
+            self.column_left(column) + CELL_PADDING
+        }
+    }
+
+    fn column_at(&self, x: f32) -> Option<usize> {
+        (0..self.columns.len()).find(|&column| {
+            let left = self.column_left(column);
+            x >= left && x < left + self.columns[column].width
+        })
+    }
+
+    /// The column whose end edge is under the pointer in the header.
+    fn edge_at(&self, position: Offset) -> Option<usize> {
+        if position.y < 0.0 || position.y >= self.header_height {
+            return None;
+        }
+        (0..self.columns.len())
+            .rev()
+            .find(|&column| (position.x - self.column_end(column)).abs() <= RESIZE_MARGIN)
+    }
+
+    fn row_at(&self, position: Offset) -> Option<usize> {
+        if position.y < self.header_height
+            || position.y >= self.size.height
+            || position.x < 0.0
+            || position.x >= self.size.width
+        {
+            return None;
+        }
+        let row = ((position.y - self.header_height + self.scroll.y) / self.row_height) as usize;
+        (row < self.row_count).then_some(row)
+    }
+
+    fn row_top(&self, row: usize) -> f32 {
+        self.header_height + row as f32 * self.row_height - self.scroll.y
+    }
+
+    fn selection_changed(&mut self) {
+        let rows = self.selected_rows();
+        if let Some(on_selection_changed) = &mut self.on_selection_changed {
+            on_selection_changed(&rows);
+        }
+    }
+
+    /// Select the row, extending or toggling the selection as the modifiers say.
+    fn select(&mut self, row: usize, extend: bool, toggle: bool) {
+        let before = self.selected.clone();
+        match self.selection_mode {
+            SelectionMode::None => return,
+            SelectionMode::Multiple if extend => {
+                let anchor = *self.anchor.get_or_insert(row);
+                self.selected = (anchor.min(row)..=anchor.max(row)).collect();
+            }
+            SelectionMode::Multiple if toggle => {
+                if !self.selected.remove(&row) {
+                    self.selected.insert(row);
+                }
+                self.anchor = Some(row);
+            }
+            _ => {
+                self.selected = BTreeSet::from([row]);
+                self.anchor = Some(row);
+            }
+        }
+        self.lead = Some(row);
+        if self.selected != before {
+            self.selection_changed();
+        }
+    }
+
+    fn sort_by(&mut self, column: usize) {
+        if !self.columns[column].sortable {
+            return;
+        }
+        let direction = match self.sort {
+            Some((sorted, SortDirection::Ascending)) if sorted == column => {
+                SortDirection::Descending
+            }
+            _ => SortDirection::Ascending,
+        };
+        self.sort = Some((column, direction));
+        if let Some(on_sort) = &mut self.on_sort {
+            on_sort(column, direction);
+        }
+    }
+
+    fn key_event(&mut self, key: NamedKey, state: &State<'_>) -> bool {
+        if self.row_count == 0 || self.selection_mode == SelectionMode::None {
+            return false;
+        }
+        let page = ((self.viewport_height() / self.row_height) as usize).max(1);
+        let last = self.row_count - 1;
+        let row = match (key, self.lead) {
+            (NamedKey::ArrowDown, Some(row)) => (row + 1).min(last),
+            (NamedKey::ArrowUp, Some(row)) => row.saturating_sub(1),
+            (NamedKey::PageDown, Some(row)) => (row + page).min(last),
+            (NamedKey::PageUp, Some(row)) => row.saturating_sub(page),
+            (NamedKey::ArrowDown | NamedKey::PageDown | NamedKey::Home, None)
+            | (NamedKey::Home, _) => 0,
+            (NamedKey::ArrowUp | NamedKey::PageUp | NamedKey::End, None) | (NamedKey::End, _) => {
+                last
+            }
+            _ => return false,
+        };
+        self.select(row, state.modifiers().shift_key(), false);
+        self.scroll_to_row(row);
+        true
+    }
+
+    fn paint_rows(&self, canvas: &mut Canvas, theme: &Theme) {
+        let colors = &theme.colors;
+        let style = self
+            .style
+            .clone()
+            .unwrap_or_else(|| theme.typography.body.clone());
+        let viewport = Rect::new(
+            0.0,
+            self.header_height,
+            self.size.width,
+            self.viewport_height(),
+        );
+        let text_top = (self.row_height - style.line_extent()) / 2.0;
+        // Rows cut off by the header or the bottom edge are painted in part.
+        canvas.save();
+        canvas.clip_rect(viewport);
+        for row in self.visible_rows() {
+            let top = self.row_top(row);
+            let background = if self.selected.contains(&row) {
+                Some(colors.primary.with_alpha(0.12))
+            } else if self.hovered_row == Some(row) {
+                Some(colors.on_surface.with_alpha(0.04))
+            } else {
+                None
+            };
+            if let Some(background) = background {
+                canvas.fill_rect(
+                    Rect::new(0.0, top, self.size.width, self.row_height),
+                    background,
+                );
+            }
+            canvas.fill_rect(
+                Rect::new(0.0, top + self.row_height - 1.0, self.size.width, 1.0),
+                colors.on_surface.with_alpha(0.08),
+            );
+            for (index, column) in self.columns.iter().enumerate() {
+                let left = self.column_left(index);
+                if left + column.width <= 0.0 || left >= self.size.width {
+                    continue;
+                }
+                let text = fit_text(
+                    &(self.cell)(row, index),
+                    column.width - CELL_PADDING * 2.0,
+                    &style,
+                    &self.fonts,
+                );
+                // Numbers line up at the end of the cell.
+                let width = self.fonts.measure(&text, &style);
+                let x = self.text_left(index, width, column.numeric);
+                canvas.draw_directed_text(
+                    Offset::new(x, top + text_top),
+                    &text,
+                    &style,
+                    self.text_direction,
+                );
+            }
+        }
+        canvas.restore();
+    }
+
+    fn paint_header(&self, canvas: &mut Canvas, theme: &Theme) {
+        let colors = &theme.colors;
+        let style = self.header_style.clone().unwrap_or_else(|| {
+            theme
+                .typography
+                .label
+                .clone()
+                .color(colors.on_surface_variant)
+        });
+        canvas.save();
+        canvas.clip_rect(Rect::new(0.0, 0.0, self.size.width, self.header_height));
+        canvas.fill_rect(
+            Rect::new(0.0, 0.0, self.size.width, self.header_height),
+            colors.surface,
+        );
+        let text_top = (self.header_height - style.line_extent()) / 2.0;
+        for (index, column) in self.columns.iter().enumerate() {
+            let left = self.column_left(index);
+            if left + column.width <= 0.0 || left >= self.size.width {
+                continue;
+            }
+            let indicator = match self.sort {
+                Some((sorted, SortDirection::Ascending)) if sorted == index => " ▲",
+                Some((sorted, SortDirection::Descending)) if sorted == index => " ▼",
+                _ => "",
+            };
+            let indicator_width = self.fonts.measure(indicator.trim_start(), &style) * 2.0;
+            let title = fit_text(
+                &column.title,
+                column.width - CELL_PADDING * 2.0 - indicator_width,
+                &style,
+                &self.fonts,
+            );
+            let title = format!("{title}{indicator}");
+            let x = self.text_left(index, self.fonts.measure(&title, &style), false);
+            canvas.draw_directed_text(
+                Offset::new(x, text_top),
+                &title,
+                &style,
+                self.text_direction,
+            );
+            let edge = if self.hovered_edge == Some(index)
+                || self.resizing.is_some_and(|(resized, _)| resized == index)
+            {
+                colors.primary
+            } else {
+                colors.outline_variant
+            };
+            let edge_left = match self.text_direction {
+                TextDirection::Ltr => left + column.width - 1.0,
+                TextDirection::Rtl => left,
+            };
+            canvas.fill_rect(
+                Rect::new(edge_left, 8.0, 1.0, self.header_height - 16.0),
+                edge,
+            );
+        }
+        canvas.fill_rect(
+            Rect::new(0.0, self.header_height - 1.0, self.size.width, 1.0),
+            colors.outline_variant,
+        );
+        canvas.restore();
+    }
+}
+
+impl Widget for DataTable {
+    fn build(&mut self, state: &mut State<'_>) {
+        self.text_direction = state.text_direction();
+        self.fonts = Rc::clone(state.fonts());
+    }
+
+    fn layout(&mut self, constraints: BoxConstraints) -> Size {
+        if let Some(size) = self.cache.get(constraints) {
+            return size;
+        }
+        let content = self.content_size();
+        self.size = constraints.constrain(Size::new(
+            content.width,
+            content.height + self.header_height,
+        ));
+        self.scroll = self.clamp_scroll(self.scroll);
+        self.cache.store(constraints, self.size);
+        self.size
+    }
+
+    fn paint(&self, state: &mut State<'_>) {
+        state.focus_mut().register(self.focus_id);
+        let focused = state.focus().is_focused(self.focus_id);
+        let theme = state.theme().clone();
+        let canvas = state.canvas_mut();
+        // Rows are painted first so the header covers rows scrolled under it.
+        self.paint_rows(canvas, &theme);
+        self.paint_header(canvas, &theme);
+        if focused {
+            canvas.stroke_rect(Rect::from_size(self.size), 2.0, theme.colors.primary);
+        }
+        if self.hovered_edge.is_some() || self.resizing.is_some() {
+            state.request_cursor(CursorIcon::ColResize);
+        }
+    }
+
+    fn event(&mut self, event: &Event, state: &mut State<'_>) -> bool {
+        match event {
+            Event::PointerDown {
+                position,
+                button: MouseButton::Left,
+            } => {
+                if let Some(column) = self.edge_at(*position) {
+                    let grab = position.x - self.column_end(column);
+                    self.resizing = Some((column, grab));
+                    return true;
+                }
+                if !Rect::from_size(self.size).contains(*position) {
+                    return false;
+                }
+                state.focus_mut().request_focus(self.focus_id);
+                if position.y < self.header_height {
+                    if let Some(column) = self.column_at(position.x) {
+                        self.sort_by(column);
+                    }
+                } else if let Some(row) = self.row_at(*position) {
+                    let modifiers = state.modifiers();
+                    let toggle = modifiers.control_key() || modifiers.super_key();
+                    self.select(row, modifiers.shift_key(), toggle);
+                }
+                true
+            }
+            Event::PointerMove { position } => {
+                if let Some((column, grab)) = self.resizing {
+                    let end = position.x - grab;
+                    let width = match self.text_direction {
+                        TextDirection::Ltr => end - self.column_start(column),
+                        TextDirection::Rtl => self.column_start(column) - end,
+                    };
+                    let column = &mut self.columns[column];
+                    column.width = width.max(column.min_width);
+                    self.cache.mark_dirty();
+                    self.scroll = self.clamp_scroll(self.scroll);
+                    return true;
+                }
+                self.hovered_edge = self.edge_at(*position);
+                self.hovered_row = self.row_at(*position);
+                false
+            }
+            Event::PointerUp {
+                button: MouseButton::Left,
+                ..
+            } if self.resizing.is_some() => {
+                self.resizing = None;
+                true
+            }
+            Event::PointerExit => {
+                self.hovered_edge = None;
+                self.hovered_row = None;
+                false
+            }
+            Event::Scroll { position, delta } => {
+                if !Rect::from_size(self.size).contains(*position) {
+                    return false;
+                }
+                // Shift turns a vertical wheel into horizontal scrolling.
+                let delta = if state.modifiers().shift_key() && delta.x == 0.0 {
+                    Offset::new(delta.y, 0.0)
+                } else {
+                    *delta
+                };
+                // Scrolling to the right moves towards the start of
+                // right-to-left columns.
+                let delta_x = match self.text_direction {
+                    TextDirection::Ltr => delta.x,
+                    TextDirection::Rtl => -delta.x,
+                };
+                let scroll = self.clamp_scroll(Offset::new(
+                    self.scroll.x + delta_x,
+                    self.scroll.y + delta.y,
+                ));
+                let moved = scroll != self.scroll;
+                self.scroll = scroll;
+                self.hovered_row = self.row_at(*position);
+                moved
+            }
+            Event::KeyDown {
+                key: Key::Named(key),
+                ..
+            } if state.focus().is_focused(self.focus_id) => self.key_event(*key, state),
+            _ => false,
+        }
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.cache.is_dirty()
+    }
+
+    fn mark_needs_layout(&mut self) {
+        self.cache.mark_dirty();
+    }
+
+    fn is_relayout_boundary(&self) -> bool {
+        self.cache.is_relayout_boundary()
+    }
+}
+
+impl fmt::Debug for DataTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DataTable")
+            .field("columns", &self.columns)
+            .field("row_count", &self.row_count)
+            .field("selection_mode", &self.selection_mode)
+            .field("selected", &self.selected)
+            .field("sort", &self.sort)
+            .field("scroll", &self.scroll)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::state::next_version;
+    use crate::framework::widgets::test_support::click;
+    use crate::framework::DrawCommand;
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+    use winit::keyboard::ModifiersState;
+
+    fn table(fetched: &Rc<Cell<usize>>) -> DataTable {
+        let fetched = Rc::clone(fetched);
+        let columns = vec![
+            DataColumn::new("Name").width(200.0).sortable(true),
+            DataColumn::new("Size").width(100.0).numeric(true),
+        ];
+        DataTable::new(columns, 100_000, move |row, column| {
+            fetched.set(fetched.get() + 1);
+            format!("{row}:{column}")
+        })
+    }
+
+    #[test]
+    fn test_virtualized_rows() {
+        let fetched = Rc::new(Cell::new(0));
+        let mut table = table(&fetched);
+        let mut state = State::default();
+        table.layout(BoxConstraints::loose(Size::new(300.0, 360.0)));
+        assert_eq!(table.visible_rows(), 0..10);
+        table.paint(&mut state);
+        assert_eq!(fetched.get(), 20);
+
+        let scroll = Event::Scroll {
+            position: Offset::new(10.0, 100.0),
+            delta: Offset::new(0.0, 16.0),
+        };
+        assert!(table.event(&scroll, &mut state));
+        assert_eq!(table.visible_rows(), 0..11);
+        // Rows cut off by the header or the bottom keep their text, clipped
+        // to the area under the header like the rest of the row.
+        state.canvas_mut().clear();
+        table.paint(&mut state);
+        let clip = |wanted: &str| {
+            state
+                .canvas()
+                .commands()
+                .iter()
+                .find_map(|command| match command {
+                    DrawCommand::Text { text, clip, .. } if text == wanted => *clip,
+                    _ => None,
+                })
+        };
+        let body = Rect::new(0.0, table.header_height, 300.0, table.viewport_height());
+        assert_eq!(clip("0:0"), Some(body));
+        assert_eq!(clip("10:1"), Some(body));
+        assert_eq!(
+            clip("Name"),
+            Some(Rect::new(0.0, 0.0, 300.0, table.header_height))
+        );
+        let scroll = Event::Scroll {
+            position: Offset::new(10.0, 100.0),
+            delta: Offset::new(0.0, 1.0e9),
+        };
+        table.event(&scroll, &mut state);
+        assert_eq!(table.visible_rows(), 99_990..100_000);
+
+        // The header is painted above the rows.
+        state.canvas_mut().clear();
+        table.paint(&mut state);
+        let texts: Vec<_> = state
+            .canvas()
+            .commands()
+            .iter()
+            .filter_map(|command| match command {
+                DrawCommand::Text { text, .. } => Some(text.as_str()),
+                DrawCommand::FillRect { .. } => None,
+            })
+            .collect();
+        assert_eq!(texts.first(), Some(&"99990:0"));
+        assert_eq!(texts.last(), Some(&"Size"));
+    }
+
+    #[test]
+    fn test_sort_and_resize() {
+        let sorts = Rc::new(RefCell::new(Vec::new()));
+        let mut table = {
+            let sorts = Rc::clone(&sorts);
+            table(&Rc::new(Cell::new(0)))
+                .on_sort(move |column, direction| sorts.borrow_mut().push((column, direction)))
+        };
+        let mut state = State::default();
+        table.layout(BoxConstraints::loose(Size::new(300.0, 360.0)));
+
+        click(&mut table, &mut state, Offset::new(50.0, 20.0));
+        click(&mut table, &mut state, Offset::new(50.0, 20.0));
+        // The numeric column is not sortable.
+        click(&mut table, &mut state, Offset::new(250.0, 20.0));
+        assert_eq!(
+            *sorts.borrow(),
+            [
+                (0, SortDirection::Ascending),
+                (0, SortDirection::Descending)
+            ]
+        );
+
+        let button = MouseButton::Left;
+        let position = Offset::new(201.0, 20.0);
+        table.event(&Event::PointerDown { position, button }, &mut state);
+        let position = Offset::new(101.0, 20.0);
+        table.event(&Event::PointerMove { position }, &mut state);
+        assert_eq!(table.columns()[0].current_width(), 100.0);
+        assert!(table.needs_layout());
+        let position = Offset::new(0.0, 20.0);
+        table.event(&Event::PointerMove { position }, &mut state);
+        table.event(&Event::PointerUp { position, button }, &mut state);
+        assert_eq!(table.columns()[0].current_width(), MIN_COLUMN_WIDTH);
+        assert_eq!(
+            *sorts.borrow(),
+            [
+                (0, SortDirection::Ascending),
+                (0, SortDirection::Descending)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_right_to_left() {
+        let sorts = Rc::new(RefCell::new(Vec::new()));
+        let mut table = {
+            let sorts = Rc::clone(&sorts);
+            table(&Rc::new(Cell::new(0)))
+                .on_sort(move |column, direction| sorts.borrow_mut().push((column, direction)))
+        };
+        let mut state = State::default();
+        state.push_inherited(Rc::new(TextDirection::Rtl), next_version(), None);
+        table.build(&mut state);
+        state.pop_inherited();
+        table.layout(BoxConstraints::loose(Size::new(250.0, 360.0)));
+
+        // The first column is on the right and the second is cut off on the
+        // left.
+        assert_eq!(table.column_left(0), 50.0);
+        assert_eq!(table.column_left(1), -50.0);
+        click(&mut table, &mut state, Offset::new(240.0, 20.0));
+        assert_eq!(*sorts.borrow(), [(0, SortDirection::Ascending)]);
+
+        // Scrolling to the left shows the columns at the end.
+        let position = Offset::new(100.0, 100.0);
+        let delta = Offset::new(-30.0, 0.0);
+        table.event(&Event::Scroll { position, delta }, &mut state);
+        assert_eq!(table.scroll_offset(), Offset::new(30.0, 0.0));
+        assert_eq!(table.column_left(1), -20.0);
+
+        // The end edge of a column is on its left.
+        let button = MouseButton::Left;
+        let position = Offset::new(81.0, 20.0);
+        table.event(&Event::PointerDown { position, button }, &mut state);
+        let position = Offset::new(31.0, 20.0);
+        table.event(&Event::PointerMove { position }, &mut state);
+        table.event(&Event::PointerUp { position, button }, &mut state);
+        assert_eq!(table.columns()[0].current_width(), 250.0);
+    }
+
+    #[test]
+    fn test_row_selection() {
+        let selections = Rc::new(RefCell::new(Vec::new()));
+        let mut table = {
+            let selections = Rc::clone(&selections);
+            table(&Rc::new(Cell::new(0)))
+                .selection_mode(SelectionMode::Multiple)
+                .on_selection_changed(move |rows| selections.borrow_mut().push(rows.to_vec()))
+        };
+        let mut state = State::default();
+        table.layout(BoxConstraints::loose(Size::new(300.0, 360.0)));
+        let row = |row: usize| Offset::new(10.0, HEADER_HEIGHT + ROW_HEIGHT * (row as f32 + 0.5));
+
+        click(&mut table, &mut state, row(1));
+        state.set_modifiers(ModifiersState::SHIFT);
+        click(&mut table, &mut state, row(3));
+        state.set_modifiers(ModifiersState::CONTROL);
+        click(&mut table, &mut state, row(2));
+        assert_eq!(table.selected_rows(), [1, 3]);
+
+        state.set_modifiers(ModifiersState::empty());
+        let down = Event::KeyDown {
+            key: Key::Named(NamedKey::ArrowDown),
+            modifiers: ModifiersState::empty(),
+            text: None,
+        };
+        assert!(table.event(&down, &mut state));
+        assert_eq!(
+            *selections.borrow(),
+            [vec![1], vec![1, 2, 3], vec![1, 3], vec![3]]
+        );
+
+        let end = Event::KeyDown {
+            key: Key::Named(NamedKey::End),
+            modifiers: ModifiersState::empty(),
+            text: None,
+        };
+        table.event(&end, &mut state);
+        assert_eq!(table.selected_rows(), [99_999]);
+        assert_eq!(table.visible_rows(), 99_990..100_000);
+    }
+}
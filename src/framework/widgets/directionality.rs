@@ -0,0 +1,140 @@
+use super::widget::Widget;
+use crate::framework::state::next_version;
+use crate::framework::{BoxConstraints, Event, Size, State, TextDirection};
+use std::fmt;
+use std::rc::Rc;
+
+/// Directionality sets the text direction of its child and the widgets below
+/// it.
+///
+/// The direction decides the order of bidirectional text and which side is
+/// the start of directional alignments and insets, the order of the columns
+/// of a [`DataTable`](crate::framework::DataTable) and the tabs of a
+/// [`TabView`](crate::framework::TabView), and the side they scroll from.
+///
+/// A direction that changes at run time, e.g. with the language, can be
+/// given by a [`Provider`](crate::framework::Provider) of `TextDirection`
+/// instead.
+pub struct Directionality {
+    child: Box<dyn Widget>,
+    direction: Rc<TextDirection>,
+    version: u64,
+}
+
+impl Directionality {
+    /// Create a new Directionality instance giving the child the direction.
+    pub fn new(direction: TextDirection, child: impl Widget + 'static) -> Self {
+        Directionality {
+            child: Box::new(child),
+            direction: Rc::new(direction),
+            version: next_version(),
+        }
+    }
+
+    fn push_direction(&self, state: &mut State<'_>) {
+        state.push_inherited(Rc::clone(&self.direction), self.version, None);
+    }
+}
+
+impl Widget for Directionality {
+    fn build(&mut self, state: &mut State<'_>) {
+        self.push_direction(state);
+        self.child.build(state);
+        state.pop_inherited();
+    }
+
+    fn layout(&mut self, constraints: BoxConstraints) -> Size {
+        self.child.layout(constraints)
+    }
+
+    fn paint(&self, state: &mut State<'_>) {
+        self.push_direction(state);
+        self.child.paint(state);
+        state.pop_inherited();
+    }
+
+    fn event(&mut self, event: &Event, state: &mut State<'_>) -> bool {
+        self.push_direction(state);
+        let handled = self.child.event(event, state);
+        state.pop_inherited();
+        handled
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.child.needs_layout()
+    }
+
+    fn mark_needs_layout(&mut self) {
+        self.child.mark_needs_layout();
+    }
+
+    fn is_relayout_boundary(&self) -> bool {
+        self.child.is_relayout_boundary()
+    }
+}
+
+impl fmt::Debug for Directionality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Directionality")
+            .field("direction", &self.direction)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::{Align, Alignment, Color, Container, DrawCommand, Rect};
+    use std::cell::Cell;
+
+    /// Probe records the text direction it was built in.
+    struct Probe(Rc<Cell<Option<TextDirection>>>);
+
+    impl Widget for Probe {
+        fn build(&mut self, state: &mut State<'_>) {
+            self.0.set(Some(state.text_direction()));
+        }
+
+        fn layout(&mut self, constraints: BoxConstraints) -> Size {
+            constraints.smallest()
+        }
+
+        fn paint(&self, _state: &mut State<'_>) {}
+    }
+
+    #[test]
+    fn test_direction_is_inherited() {
+        let built = Rc::new(Cell::new(None));
+        let mut state = State::default();
+        let mut scope = Directionality::new(
+            TextDirection::Rtl,
+            Directionality::new(TextDirection::Ltr, Probe(Rc::clone(&built))),
+        );
+        scope.build(&mut state);
+        // The innermost direction wins.
+        assert_eq!(built.get(), Some(TextDirection::Ltr));
+        assert_eq!(state.text_direction(), TextDirection::Ltr);
+
+        let mut scope = Directionality::new(
+            TextDirection::Rtl,
+            Align::new(Alignment::TopStart).child(
+                Container::default()
+                    .size(20.0, 10.0)
+                    .color(Color::rgb(1.0, 0.0, 0.0)),
+            ),
+        );
+        scope.build(&mut state);
+        scope.layout(BoxConstraints::tight(Size::new(100.0, 50.0)));
+        scope.paint(&mut state);
+        let rects: Vec<Rect> = state
+            .canvas()
+            .commands()
+            .iter()
+            .filter_map(|command| match command {
+                DrawCommand::FillRect { rect, .. } => Some(*rect),
+                DrawCommand::Text { .. } => None,
+            })
+            .collect();
+        assert_eq!(rects, [Rect::new(80.0, 0.0, 20.0, 10.0)]);
+    }
+}
@@ -0,0 +1,335 @@
+use super::callbacks::ChangeCallback;
+use super::menu::{Menu, MenuItem};
+use super::toggleable::{Response, Toggleable};
+use super::widget::Widget;
+use super::widget_states::WidgetStates;
+use crate::framework::{
+    BoxConstraints, Event, FocusId, FontCollection, Offset, OverlayId, Rect, Size, State,
+    TextLayout, TextStyle,
+};
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::rc::Rc;
+use winit::keyboard::{Key, NamedKey};
+
+/// The height of a dropdown in logical pixels.
+const HEIGHT: f32 = 40.0;
+
+/// The space left and right of the label.
+const HORIZONTAL_PADDING: f32 = 12.0;
+
+/// The space reserved for the arrow.
+const ARROW_WIDTH: f32 = 24.0;
+
+/// The selected item and the callback, shared with the items of the open menu.
+struct DropdownSelection<T> {
+    items: Vec<(T, String)>,
+    selected: Cell<Option<usize>>,
+    on_changed: RefCell<Option<ChangeCallback<T>>>,
+}
+
+/// Dropdown is a control that selects one of a list of values from a menu.
+///
+/// Clicking the dropdown, or pressing Enter, Space or the down arrow while it
+/// is focused, opens a [`Menu`] with the labels of the values below it. A
+/// dropdown without an `on_changed` callback is disabled. Its text style and
+/// colors are taken from the [`Theme`](crate::framework::Theme).
+pub struct Dropdown<T> {
+    toggleable: Toggleable,
+    selection: Rc<DropdownSelection<T>>,
+    hint: Option<String>,
+    style: TextStyle,
+    fonts: Rc<FontCollection>,
+    enabled: bool,
+    menu: Option<OverlayId>,
+    /// The bounds in window space in the last paint, to place the menu.
+    bounds: Cell<Rect>,
+}
+
+impl<T: 'static> Dropdown<T> {
+    /// Create a new Dropdown instance with the values and their labels.
+    pub fn new<L: Into<String>>(items: impl IntoIterator<Item = (T, L)>) -> Self {
+        let mut dropdown = Dropdown {
+            toggleable: Toggleable::default(),
+            selection: Rc::new(DropdownSelection {
+                items: items
+                    .into_iter()
+                    .map(|(value, label)| (value, label.into()))
+                    .collect(),
+                selected: Cell::new(None),
+                on_changed: RefCell::new(None),
+            }),
+            hint: None,
+            style: TextStyle::default(),
+            fonts: Rc::default(),
+            enabled: true,
+            menu: None,
+            bounds: Cell::new(Rect::from_size(Size::ZERO)),
+        };
+        dropdown.update_states();
+        dropdown
+    }
+
+    /// Select the value, if it is one of the values.
+    pub fn selected(self, value: &T) -> Self
+    where
+        T: PartialEq,
+    {
+        let index = self
+            .selection
+            .items
+            .iter()
+            .position(|(item, _)| item == value);
+        self.selection.selected.set(index);
+        self
+    }
+
+    /// Set the text shown while no value is selected.
+    pub fn hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+
+    /// Set whether the dropdown accepts input.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self.update_states();
+        self
+    }
+
+    /// Set the callback called with the value chosen from the menu.
+    pub fn on_changed(mut self, on_changed: impl FnMut(&T) + 'static) -> Self {
+        *self.selection.on_changed.borrow_mut() = Some(Box::new(on_changed));
+        self.update_states();
+        self
+    }
+
+    /// The selected value, if any.
+    pub fn value(&self) -> Option<&T> {
+        let index = self.selection.selected.get()?;
+        self.selection.items.get(index).map(|(value, _)| value)
+    }
+
+    /// Returns true if the menu of the dropdown is shown.
+    pub fn is_open(&self, state: &State<'_>) -> bool {
+        self.menu.is_some_and(|menu| state.overlay().contains(menu))
+    }
+
+    /// The focus identifier of the dropdown.
+    pub fn focus_id(&self) -> FocusId {
+        self.toggleable.focus_id()
+    }
+
+    /// The current interactive states.
+    pub fn states(&self) -> WidgetStates {
+        self.toggleable.states()
+    }
+
+    fn update_states(&mut self) {
+        let has_callback = self.selection.on_changed.borrow().is_some();
+        self.toggleable
+            .set_enabled(self.enabled && has_callback && !self.selection.items.is_empty());
+    }
+
+    fn label(&self) -> Option<&str> {
+        match self.selection.selected.get() {
+            Some(index) => self
+                .selection
+                .items
+                .get(index)
+                .map(|(_, label)| label.as_str()),
+            None => self.hint.as_deref(),
+        }
+    }
+
+    fn open(&mut self, state: &mut State<'_>) {
+        if self.is_open(state) {
+            return;
+        }
+        let items = (0..self.selection.items.len())
+            .map(|index| {
+                let selection = Rc::clone(&self.selection);
+                MenuItem::new(self.selection.items[index].1.clone()).on_select(move || {
+                    selection.selected.set(Some(index));
+                    if let Some(on_changed) = selection.on_changed.borrow_mut().as_mut() {
+                        on_changed(&selection.items[index].0);
+                    }
+                })
+            })
+            .collect();
+        let anchor = self.bounds.get();
+        self.menu = Some(Menu::show_for_anchor(
+            items,
+            anchor,
+            self.selection.selected.get(),
+            state,
+        ));
+    }
+}
+
+impl<T: 'static> Widget for Dropdown<T> {
+    fn build(&mut self, state: &mut State<'_>) {
+        let style = state.theme().typography.body.clone();
+        if style != self.style || !Rc::ptr_eq(&self.fonts, state.fonts()) {
+            self.style = style;
+            self.fonts = Rc::clone(state.fonts());
+            self.mark_needs_layout();
+        }
+    }
+
+    fn layout(&mut self, constraints: BoxConstraints) -> Size {
+        let label_width = self
+            .selection
+            .items
+            .iter()
+            .map(|(_, label)| label.as_str())
+            .chain(self.hint.as_deref())
+            .map(|label| {
+                TextLayout::new(label, &self.style, &self.fonts)
+                    .size()
+                    .width
+            })
+            .fold(0.0, f32::max);
+        let width = label_width + HORIZONTAL_PADDING * 2.0 + ARROW_WIDTH;
+        self.toggleable
+            .layout(constraints, Size::new(width, HEIGHT))
+    }
+
+    fn paint(&self, state: &mut State<'_>) {
+        let focused = self.toggleable.register_focus(state);
+        let size = self.toggleable.size();
+        let bounds = Rect::from_size(size);
+        self.bounds.set(state.canvas().to_window(bounds));
+
+        let enabled = self.toggleable.is_enabled();
+        let colors = state.theme().colors;
+        let canvas = state.canvas_mut();
+        if self.toggleable.states().contains(WidgetStates::HOVERED) {
+            canvas.fill_rect(bounds, colors.on_surface.with_alpha(0.04));
+        }
+        if focused {
+            canvas.stroke_rect(bounds, 2.0, colors.primary);
+        } else {
+            canvas.stroke_rect(bounds, 1.0, colors.outline);
+        }
+
+        let text_top = (size.height - self.style.line_extent()) / 2.0;
+        let style = match (enabled, self.selection.selected.get()) {
+            (false, _) => self.style.clone().color(colors.disabled),
+            (true, None) => self.style.clone().color(colors.on_surface_variant),
+            (true, Some(_)) => self.style.clone(),
+        };
+        if let Some(label) = self.label() {
+            canvas.draw_text(Offset::new(HORIZONTAL_PADDING, text_top), label, &style);
+        }
+        canvas.draw_text(
+            Offset::new(
+                size.width - HORIZONTAL_PADDING - ARROW_WIDTH / 2.0,
+                text_top,
+            ),
+            "▾",
+            &style,
+        );
+    }
+
+    fn event(&mut self, event: &Event, state: &mut State<'_>) -> bool {
+        let response = match event {
+            Event::KeyDown {
+                key: Key::Named(NamedKey::ArrowDown),
+                ..
+            } if self.toggleable.is_enabled() && state.focus().is_focused(self.focus_id()) => {
+                Response::Activated
+            }
+            _ => self.toggleable.event(event, state),
+        };
+        if response == Response::Activated {
+            self.open(state);
+        }
+        response.is_handled()
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.toggleable.needs_layout()
+    }
+
+    fn mark_needs_layout(&mut self) {
+        self.toggleable.mark_needs_layout();
+    }
+
+    fn is_relayout_boundary(&self) -> bool {
+        self.toggleable.is_relayout_boundary()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Dropdown<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let items: Vec<_> = self
+            .selection
+            .items
+            .iter()
+            .map(|(value, _)| value)
+            .collect();
+        f.debug_struct("Dropdown")
+            .field("items", &items)
+            .field("selected", &self.selection.selected.get())
+            .field("hint", &self.hint)
+            .field("states", &self.toggleable.states())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::widgets::test_support::{click, key};
+    use crate::framework::Application;
+
+    fn frame(app: &mut Application, state: &mut State<'_>) {
+        app.layout(BoxConstraints::loose(Size::new(400.0, 300.0)));
+        app.paint(state);
+    }
+
+    #[test]
+    fn test_choose_from_menu() {
+        let chosen = Rc::new(RefCell::new(Vec::new()));
+        let dropdown = {
+            let chosen = Rc::clone(&chosen);
+            Dropdown::new([(1, "One"), (2, "Two"), (3, "Three")])
+                .selected(&2)
+                .on_changed(move |value| chosen.borrow_mut().push(*value))
+        };
+        let focus_id = dropdown.focus_id();
+        let mut app = Application::new("test", dropdown);
+        let mut state = State::default();
+        frame(&mut app, &mut state);
+
+        state.focus_mut().request_focus(focus_id);
+        assert!(app.event(&key(NamedKey::Space), &mut state));
+        frame(&mut app, &mut state);
+        assert!(!state.overlay().is_empty());
+
+        // The menu opens with the selected value highlighted.
+        app.event(&key(NamedKey::ArrowDown), &mut state);
+        app.event(&key(NamedKey::Enter), &mut state);
+        assert_eq!(*chosen.borrow(), [3]);
+        assert!(state.overlay().is_empty());
+
+        // The menu opens below the dropdown, where the first row is clicked.
+        click(&mut app, &mut state, Offset::new(10.0, 10.0));
+        frame(&mut app, &mut state);
+        click(&mut app, &mut state, Offset::new(10.0, HEIGHT + 20.0));
+        assert_eq!(*chosen.borrow(), [3, 1]);
+    }
+
+    #[test]
+    fn test_value_and_disabled() {
+        let dropdown = Dropdown::new([("a", "A"), ("b", "B")]).selected(&"b");
+        assert_eq!(dropdown.value(), Some(&"b"));
+        assert_eq!(dropdown.label(), Some("B"));
+        assert!(dropdown.states().contains(WidgetStates::DISABLED));
+
+        let dropdown = Dropdown::new([("a", "A")]).hint("Pick one");
+        assert_eq!(dropdown.value(), None);
+        assert_eq!(dropdown.label(), Some("Pick one"));
+    }
+}
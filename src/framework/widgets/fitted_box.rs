@@ -0,0 +1,242 @@
+use super::single_child::SingleChild;
+use super::widget::Widget;
+use crate::framework::{
+    Alignment, BoxConstraints, Event, Offset, Rect, Size, State, TextDirection,
+};
+use std::fmt;
+
+/// BoxFit is how a box should be inscribed into another box.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BoxFit {
+    /// Fill the target box, distorting the aspect ratio.
+    Fill,
+    /// As large as possible while still containing the source entirely.
+    #[default]
+    Contain,
+    /// As small as possible while still covering the entire target box.
+    Cover,
+    /// Make the source width match the target width.
+    FitWidth,
+    /// Make the source height match the target height.
+    FitHeight,
+    /// Keep the source size.
+    None,
+    /// Like `Contain`, but never scale the source up.
+    ScaleDown,
+}
+
+impl BoxFit {
+    /// The horizontal and vertical scale that fits a source of the given size
+    /// into a target of the given size.
+    pub fn scale(&self, source: Size, target: Size) -> (f32, f32) {
+        if source.width <= 0.0 || source.height <= 0.0 {
+            return (1.0, 1.0);
+        }
+        let scale_x = target.width / source.width;
+        let scale_y = target.height / source.height;
+        let uniform = |scale: f32| (scale, scale);
+        match self {
+            BoxFit::Fill => (scale_x, scale_y),
+            BoxFit::Contain => uniform(scale_x.min(scale_y)),
+            BoxFit::Cover => uniform(scale_x.max(scale_y)),
+            BoxFit::FitWidth => uniform(scale_x),
+            BoxFit::FitHeight => uniform(scale_y),
+            BoxFit::None => uniform(1.0),
+            BoxFit::ScaleDown => uniform(scale_x.min(scale_y).min(1.0)),
+        }
+    }
+}
+
+/// FittedBox is a widget that scales and positions its child within itself
+/// according to a [`BoxFit`].
+///
+/// The child is laid out without constraints; the box then takes the largest
+/// size its constraints allow that preserves the child's aspect ratio.
+///
+/// Fits such as [`BoxFit::Cover`] and [`BoxFit::None`] can scale the child
+/// beyond the box; the part outside the box is clipped.
+#[derive(Default)]
+pub struct FittedBox {
+    child: SingleChild,
+    fit: BoxFit,
+    alignment: Alignment,
+    /// The inherited text direction, which resolves directional alignments.
+    text_direction: TextDirection,
+    scale: (f32, f32),
+    offset: Offset,
+    /// Whether the scaled child extends beyond the box.
+    clipped: bool,
+    size: Size,
+}
+
+impl FittedBox {
+    /// Create a new FittedBox instance with the given fit.
+    pub fn new(fit: BoxFit) -> Self {
+        FittedBox {
+            fit,
+            ..Default::default()
+        }
+    }
+
+    /// Set the child of the box.
+    pub fn child(mut self, child: impl Widget + 'static) -> Self {
+        self.child.set(child);
+        self
+    }
+
+    /// Set the alignment of the scaled child within the box.
+    pub fn alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// The scale applied to the child in the last layout.
+    pub fn child_scale(&self) -> (f32, f32) {
+        self.scale
+    }
+}
+
+impl Widget for FittedBox {
+    fn build(&mut self, state: &mut State<'_>) {
+        let text_direction = state.text_direction();
+        if text_direction != self.text_direction && self.alignment.is_directional() {
+            self.child.mark_needs_layout();
+        }
+        self.text_direction = text_direction;
+        self.child.build(state);
+    }
+
+    fn layout(&mut self, constraints: BoxConstraints) -> Size {
+        if let Some(size) = self.child.cached(constraints) {
+            return size;
+        }
+        let size = match self.child.layout(BoxConstraints::UNBOUNDED) {
+            Some(child_size) if child_size.width > 0.0 && child_size.height > 0.0 => {
+                let size = constraints.constrain_preserving_aspect_ratio(child_size);
+                self.scale = self.fit.scale(child_size, size);
+                let scaled = Size {
+                    width: child_size.width * self.scale.0,
+                    height: child_size.height * self.scale.1,
+                };
+                self.offset = self
+                    .alignment
+                    .resolve(self.text_direction)
+                    .align(scaled, size);
+                self.clipped = scaled.width > size.width || scaled.height > size.height;
+                size
+            }
+            _ => {
+                self.scale = (1.0, 1.0);
+                self.offset = Offset::ZERO;
+                self.clipped = false;
+                constraints.smallest()
+            }
+        };
+        self.size = size;
+        self.child.store(constraints, size)
+    }
+
+    fn paint(&self, state: &mut State<'_>) {
+        let canvas = state.canvas_mut();
+        canvas.save();
+        if self.clipped {
+            canvas.clip_rect(Rect::from_size(self.size));
+        }
+        canvas.translate(self.offset);
+        canvas.scale(self.scale.0, self.scale.1);
+        self.child.paint(state);
+        state.canvas_mut().restore();
+    }
+
+    fn event(&mut self, event: &Event, state: &mut State<'_>) -> bool {
+        let event = event
+            .to_local(self.offset)
+            .to_scaled(self.scale.0, self.scale.1);
+        self.child.event(&event, state)
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.child.needs_layout()
+    }
+
+    fn mark_needs_layout(&mut self) {
+        self.child.mark_needs_layout();
+    }
+
+    fn is_relayout_boundary(&self) -> bool {
+        self.child.is_relayout_boundary()
+    }
+}
+
+impl fmt::Debug for FittedBox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FittedBox")
+            .field("fit", &self.fit)
+            .field("alignment", &self.alignment)
+            .field("has_child", &self.child.is_some())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::{Color, Container, DrawCommand};
+
+    #[test]
+    fn test_fitted_box_scales_child() {
+        let child = Container::default()
+            .size(200.0, 100.0)
+            .color(Color::rgb(1.0, 0.0, 0.0));
+        let mut fitted = FittedBox::new(BoxFit::Contain).child(child);
+        let size = fitted.layout(BoxConstraints::loose(Size::new(100.0, 100.0)));
+        assert_eq!(size, Size::new(100.0, 50.0));
+        assert_eq!(fitted.child_scale(), (0.5, 0.5));
+
+        let mut state = State::default();
+        fitted.paint(&mut state);
+        assert_eq!(
+            state.canvas().commands(),
+            &[DrawCommand::FillRect {
+                rect: Rect::new(0.0, 0.0, 100.0, 50.0),
+                color: Color::rgb(1.0, 0.0, 0.0),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_fitted_box_clips_cover() {
+        let child = Container::default()
+            .size(200.0, 100.0)
+            .color(Color::rgb(1.0, 0.0, 0.0));
+        let mut fitted = FittedBox::new(BoxFit::Cover).child(child);
+        let size = fitted.layout(BoxConstraints::tight(Size::new(100.0, 100.0)));
+        assert_eq!(size, Size::new(100.0, 100.0));
+        assert_eq!(fitted.child_scale(), (1.0, 1.0));
+
+        let mut state = State::default();
+        fitted.paint(&mut state);
+        assert_eq!(
+            state.canvas().commands(),
+            &[DrawCommand::FillRect {
+                rect: Rect::new(0.0, 0.0, 100.0, 100.0),
+                color: Color::rgb(1.0, 0.0, 0.0),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_box_fit_scale() {
+        let source = Size::new(200.0, 100.0);
+        let target = Size::new(100.0, 100.0);
+        assert_eq!(BoxFit::Fill.scale(source, target), (0.5, 1.0));
+        assert_eq!(BoxFit::Contain.scale(source, target), (0.5, 0.5));
+        assert_eq!(BoxFit::Cover.scale(source, target), (1.0, 1.0));
+        assert_eq!(BoxFit::FitHeight.scale(source, target), (1.0, 1.0));
+        assert_eq!(BoxFit::None.scale(source, target), (1.0, 1.0));
+        assert_eq!(
+            BoxFit::ScaleDown.scale(Size::new(10.0, 10.0), target),
+            (1.0, 1.0)
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use super::single_child::SingleChild;
+use super::widget::Widget;
+use crate::framework::{Alignment, BoxConstraints, Event, Size, State, TextDirection};
+use std::fmt;
+
+/// FractionallySizedBox is a widget that sizes its child to a fraction of the
+/// available space.
+///
+/// A factor left as `None` passes the incoming constraints along that
+/// dimension through to the child.
+#[derive(Default)]
+pub struct FractionallySizedBox {
+    child: SingleChild,
+    width_factor: Option<f32>,
+    height_factor: Option<f32>,
+    alignment: Alignment,
+    /// The inherited text direction, which resolves directional alignments.
+    text_direction: TextDirection,
+}
+
+impl FractionallySizedBox {
+    /// Create a new FractionallySizedBox instance with the given factors.
+    pub fn new(width_factor: Option<f32>, height_factor: Option<f32>) -> Self {
+        FractionallySizedBox {
+            width_factor,
+            height_factor,
+            ..Default::default()
+        }
+    }
+
+    /// Set the child of the box.
+    pub fn child(mut self, child: impl Widget + 'static) -> Self {
+        self.child.set(child);
+        self
+    }
+
+    /// Set the alignment of the child within the box.
+    pub fn alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// The constraints for the child given the incoming constraints.
+    fn child_constraints(&self, constraints: BoxConstraints) -> BoxConstraints {
+        let (min_width, max_width) = match self.width_factor {
+            Some(factor) if constraints.has_bounded_width() => {
+                let width = constraints.max_width * factor;
+                (width, width)
+            }
+            _ => (constraints.min_width, constraints.max_width),
+        };
+        let (min_height, max_height) = match self.height_factor {
+            Some(factor) if constraints.has_bounded_height() => {
+                let height = constraints.max_height * factor;
+                (height, height)
+            }
+            _ => (constraints.min_height, constraints.max_height),
+        };
+        BoxConstraints {
+            min_width,
+            max_width,
+            min_height,
+            max_height,
+        }
+    }
+}
+
+impl Widget for FractionallySizedBox {
+    fn build(&mut self, state: &mut State<'_>) {
+        let text_direction = state.text_direction();
+        if text_direction != self.text_direction && self.alignment.is_directional() {
+            self.child.mark_needs_layout();
+        }
+        self.text_direction = text_direction;
+        self.child.build(state);
+    }
+
+    fn layout(&mut self, constraints: BoxConstraints) -> Size {
+        if let Some(size) = self.child.cached(constraints) {
+            return size;
+        }
+        let child_constraints = self.child_constraints(constraints);
+        let size = match self.child.layout(child_constraints) {
+            Some(child_size) => {
+                let size = constraints.constrain(child_size);
+                self.child.set_offset(
+                    self.alignment
+                        .resolve(self.text_direction)
+                        .align(child_size, size),
+                );
+                size
+            }
+            None => constraints.constrain(child_constraints.smallest()),
+        };
+        self.child.store(constraints, size)
+    }
+
+    fn paint(&self, state: &mut State<'_>) {
+        self.child.paint(state);
+    }
+
+    fn event(&mut self, event: &Event, state: &mut State<'_>) -> bool {
+        self.child.event(event, state)
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.child.needs_layout()
+    }
+
+    fn mark_needs_layout(&mut self) {
+        self.child.mark_needs_layout();
+    }
+
+    fn is_relayout_boundary(&self) -> bool {
+        self.child.is_relayout_boundary()
+    }
+}
+
+impl fmt::Debug for FractionallySizedBox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FractionallySizedBox")
+            .field("width_factor", &self.width_factor)
+            .field("height_factor", &self.height_factor)
+            .field("alignment", &self.alignment)
+            .field("text_direction", &self.text_direction)
+            .field("has_child", &self.child.is_some())
+            .finish()
+    }
+}
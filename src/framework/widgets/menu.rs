@@ -0,0 +1,715 @@
+use super::single_child::SingleChild;
+use super::widget::Widget;
+use crate::framework::{
+    BoxConstraints, Event, FontCollection, LayoutCache, Offset, OverlayId, Placement, Rect, Size,
+    State, TextLayout, TextStyle,
+};
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::rc::Rc;
+use winit::event::MouseButton;
+use winit::keyboard::{Key, NamedKey};
+
+/// The height of an item row in logical pixels.
+const ITEM_HEIGHT: f32 = 32.0;
+
+/// The height of a separator row.
+const SEPARATOR_HEIGHT: f32 = 9.0;
+
+/// The space left and right of the item labels.
+const HORIZONTAL_PADDING: f32 = 12.0;
+
+/// The space above the first and below the last item.
+const VERTICAL_PADDING: f32 = 4.0;
+
+/// The space reserved for the arrow of submenu items.
+const ARROW_WIDTH: f32 = 16.0;
+
+/// The minimum width of a menu.
+const MIN_WIDTH: f32 = 112.0;
+
+/// What a [`MenuItem`] does when chosen.
+#[derive(Clone)]
+enum MenuItemKind {
+    Action(Option<Rc<RefCell<dyn FnMut()>>>),
+    Submenu(Vec<MenuItem>),
+    Separator,
+}
+
+/// MenuItem is an entry of a menu.
+///
+/// Items are cheap to clone; clones share the callback.
+#[derive(Clone)]
+pub struct MenuItem {
+    label: String,
+    kind: MenuItemKind,
+    enabled: bool,
+}
+
+impl MenuItem {
+    /// Create a new MenuItem instance with the given label.
+    pub fn new(label: impl Into<String>) -> Self {
+        MenuItem {
+            label: label.into(),
+            kind: MenuItemKind::Action(None),
+            enabled: true,
+        }
+    }
+
+    /// Create a new MenuItem instance that opens a nested menu.
+    pub fn submenu(label: impl Into<String>, items: Vec<MenuItem>) -> Self {
+        MenuItem {
+            label: label.into(),
+            kind: MenuItemKind::Submenu(items),
+            enabled: true,
+        }
+    }
+
+    /// Create a new MenuItem instance separating groups of items.
+    pub fn separator() -> Self {
+        MenuItem {
+            label: String::new(),
+            kind: MenuItemKind::Separator,
+            enabled: false,
+        }
+    }
+
+    /// Set the callback called when the item is chosen.
+    pub fn on_select(mut self, on_select: impl FnMut() + 'static) -> Self {
+        self.kind = MenuItemKind::Action(Some(Rc::new(RefCell::new(on_select))));
+        self
+    }
+
+    /// Set whether the item can be chosen.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled && !matches!(self.kind, MenuItemKind::Separator);
+        self
+    }
+
+    /// The label of the item.
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    fn height(&self) -> f32 {
+        match self.kind {
+            MenuItemKind::Separator => SEPARATOR_HEIGHT,
+            _ => ITEM_HEIGHT,
+        }
+    }
+}
+
+impl fmt::Debug for MenuItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match &self.kind {
+            MenuItemKind::Action(_) => "Action",
+            MenuItemKind::Submenu(_) => "Submenu",
+            MenuItemKind::Separator => "Separator",
+        };
+        f.debug_struct("MenuItem")
+            .field("label", &self.label)
+            .field("kind", &kind)
+            .field("enabled", &self.enabled)
+            .finish()
+    }
+}
+
+/// The overlay entries of an open menu and its open submenus, outermost first.
+type MenuChain = Rc<RefCell<Vec<OverlayId>>>;
+
+/// Menu is a list of items shown in the [`Overlay`](crate::framework::Overlay).
+///
+/// Items are highlighted with the pointer or the arrow keys and chosen by
+/// clicking or with Enter or Space. Submenus open when their item is
+/// hovered or with the right arrow, and close with the left arrow. Escape
+/// closes the innermost menu; choosing an item closes all of them.
+///
+/// Menus take their style from the theme of the application rather than of
+/// the widget that showed them, like every entry of the overlay.
+pub struct Menu {
+    items: Vec<MenuItem>,
+    level: usize,
+    chain: MenuChain,
+    style: TextStyle,
+    fonts: Rc<FontCollection>,
+    min_width: f32,
+    highlighted: Option<usize>,
+    pressed: Option<usize>,
+    open_submenu: Option<usize>,
+    rows: Vec<Rect>,
+    size: Size,
+    origin: Cell<Offset>,
+    cache: LayoutCache,
+}
+
+impl Menu {
+    /// Show a menu with the items next to the anchor, given in window space.
+    pub fn show(
+        items: Vec<MenuItem>,
+        anchor: Rect,
+        placement: Placement,
+        state: &mut State<'_>,
+    ) -> OverlayId {
+        Menu::new(items, 0, &MenuChain::default()).show_in(anchor, placement, state)
+    }
+
+    /// Show a menu below the anchor, at least as wide as the anchor and with
+    /// the given item highlighted, e.g. the current value of a dropdown.
+    pub(crate) fn show_for_anchor(
+        items: Vec<MenuItem>,
+        anchor: Rect,
+        highlighted: Option<usize>,
+        state: &mut State<'_>,
+    ) -> OverlayId {
+        let mut menu = Menu::new(items, 0, &MenuChain::default());
+        menu.highlighted = highlighted;
+        menu.min_width = menu.min_width.max(anchor.size.width);
+        menu.show_in(anchor, Placement::Below, state)
+    }
+
+    fn new(items: Vec<MenuItem>, level: usize, chain: &MenuChain) -> Self {
+        Menu {
+            items,
+            level,
+            chain: Rc::clone(chain),
+            style: TextStyle::default(),
+            fonts: Rc::default(),
+            min_width: MIN_WIDTH,
+            highlighted: None,
+            pressed: None,
+            open_submenu: None,
+            rows: Vec::new(),
+            size: Size::ZERO,
+            origin: Cell::new(Offset::ZERO),
+            cache: LayoutCache::default(),
+        }
+    }
+
+    /// Show the menu in the overlay as the innermost menu of its chain.
+    fn show_in(self, anchor: Rect, placement: Placement, state: &mut State<'_>) -> OverlayId {
+        self.origin.set(anchor.origin);
+        let level = self.level;
+        let chain = Rc::clone(&self.chain);
+        let id = state.overlay_mut().show(self, anchor, placement);
+        let mut chain = chain.borrow_mut();
+        chain.truncate(level);
+        chain.push(id);
+        id
+    }
+
+    /// Close the menus from the given level inwards.
+    fn close_from(&mut self, level: usize, state: &mut State<'_>) {
+        let closed = {
+            let mut chain = self.chain.borrow_mut();
+            let level = level.min(chain.len());
+            chain.split_off(level)
+        };
+        for id in closed {
+            state.overlay_mut().remove(id);
+        }
+        if level <= self.level + 1 {
+            self.open_submenu = None;
+        }
+    }
+
+    fn is_selectable(&self, index: usize) -> bool {
+        self.items.get(index).is_some_and(|item| item.enabled)
+    }
+
+    fn row_at(&self, position: Offset) -> Option<usize> {
+        self.rows.iter().position(|row| row.contains(position))
+    }
+
+    /// Move the highlight to the next selectable item in the direction, wrapping around.
+    fn move_highlight(&mut self, forward: bool) {
+        let len = self.items.len();
+        let mut index = self.highlighted;
+        for _ in 0..len {
+            let next = match (index, forward) {
+                (None, true) => 0,
+                (None, false) => len - 1,
+                (Some(index), true) => (index + 1) % len,
+                (Some(index), false) => (index + len - 1) % len,
+            };
+            if self.is_selectable(next) {
+                self.highlighted = Some(next);
+                return;
+            }
+            index = Some(next);
+        }
+    }
+
+    /// The item whose submenu is open, if it was not closed in the meantime.
+    fn open_submenu(&self) -> Option<usize> {
+        self.open_submenu
+            .filter(|_| self.chain.borrow().len() > self.level + 1)
+    }
+
+    fn show_submenu(&mut self, index: usize, keyboard: bool, state: &mut State<'_>) {
+        if self.open_submenu() == Some(index) {
+            return;
+        }
+        self.close_from(self.level + 1, state);
+        let Some(MenuItemKind::Submenu(items)) = self.items.get(index).map(|item| &item.kind)
+        else {
+            return;
+        };
+        let highlighted = if keyboard {
+            items.iter().position(|item| item.enabled)
+        } else {
+            None
+        };
+        // Before the first layout the row is not known yet.
+        let row = self
+            .rows
+            .get(index)
+            .copied()
+            .unwrap_or_else(|| Rect::from_size(Size::ZERO));
+        let anchor = row.translate(self.origin.get());
+        let mut submenu = Menu::new(items.clone(), self.level + 1, &self.chain);
+        submenu.highlighted = highlighted;
+        submenu.show_in(anchor, Placement::Beside, state);
+        self.open_submenu = Some(index);
+    }
+
+    fn activate(&mut self, index: usize, keyboard: bool, state: &mut State<'_>) {
+        if !self.is_selectable(index) {
+            return;
+        }
+        match &self.items[index].kind {
+            MenuItemKind::Action(on_select) => {
+                let on_select = on_select.clone();
+                self.close_from(0, state);
+                if let Some(on_select) = on_select {
+                    (on_select.borrow_mut())();
+                }
+            }
+            MenuItemKind::Submenu(_) => self.show_submenu(index, keyboard, state),
+            MenuItemKind::Separator => {}
+        }
+    }
+}
+
+impl Widget for Menu {
+    fn build(&mut self, state: &mut State<'_>) {
+        let style = state.theme().typography.label.clone();
+        if style != self.style || !Rc::ptr_eq(&self.fonts, state.fonts()) {
+            self.style = style;
+            self.fonts = Rc::clone(state.fonts());
+            self.cache.mark_dirty();
+        }
+    }
+
+    fn layout(&mut self, constraints: BoxConstraints) -> Size {
+        if let Some(size) = self.cache.get(constraints) {
+            return size;
+        }
+        let label_width = self
+            .items
+            .iter()
+            .map(|item| {
+                TextLayout::new(&item.label, &self.style, &self.fonts)
+                    .size()
+                    .width
+            })
+            .fold(0.0, f32::max);
+        let has_submenus = self
+            .items
+            .iter()
+            .any(|item| matches!(item.kind, MenuItemKind::Submenu(_)));
+        let arrow = if has_submenus { ARROW_WIDTH } else { 0.0 };
+        let width = (label_width + arrow + HORIZONTAL_PADDING * 2.0).max(self.min_width);
+        let mut top = VERTICAL_PADDING;
+        self.rows = self
+            .items
+            .iter()
+            .map(|item| {
+                let row = Rect::new(0.0, top, width, item.height());
+                top += item.height();
+                row
+            })
+            .collect();
+        self.size = constraints.constrain(Size::new(width, top + VERTICAL_PADDING));
+        self.cache.store(constraints, self.size);
+        self.size
+    }
+
+    fn paint(&self, state: &mut State<'_>) {
+        self.origin.set(state.canvas().origin());
+        let colors = state.theme().colors;
+        let canvas = state.canvas_mut();
+        let bounds = Rect::from_size(self.size);
+        canvas.fill_rect(bounds, colors.surface);
+        canvas.stroke_rect(bounds, 1.0, colors.outline_variant);
+        let text_top = (ITEM_HEIGHT - self.style.line_extent()) / 2.0;
+        for (index, (item, row)) in self.items.iter().zip(&self.rows).enumerate() {
+            if matches!(item.kind, MenuItemKind::Separator) {
+                canvas.fill_rect(
+                    Rect::new(0.0, row.top() + row.size.height / 2.0, row.size.width, 1.0),
+                    colors.outline_variant,
+                );
+                continue;
+            }
+            if self.highlighted == Some(index) || self.open_submenu() == Some(index) {
+                canvas.fill_rect(*row, colors.on_surface.with_alpha(0.08));
+            }
+            let style = if item.enabled {
+                self.style.clone()
+            } else {
+                self.style.clone().color(colors.disabled)
+            };
+            canvas.draw_text(
+                Offset::new(HORIZONTAL_PADDING, row.top() + text_top),
+                &item.label,
+                &style,
+            );
+            if matches!(item.kind, MenuItemKind::Submenu(_)) {
+                canvas.draw_text(
+                    Offset::new(
+                        row.right() - HORIZONTAL_PADDING - ARROW_WIDTH / 2.0,
+                        row.top() + text_top,
+                    ),
+                    "▸",
+                    &style,
+                );
+            }
+        }
+    }
+
+    fn event(&mut self, event: &Event, state: &mut State<'_>) -> bool {
+        match event {
+            Event::PointerMove { position } => {
+                let Some(index) = self.row_at(*position) else {
+                    return false;
+                };
+                if !self.is_selectable(index) {
+                    return true;
+                }
+                self.highlighted = Some(index);
+                if matches!(self.items[index].kind, MenuItemKind::Submenu(_)) {
+                    self.show_submenu(index, false, state);
+                } else if self.open_submenu().is_some() {
+                    self.close_from(self.level + 1, state);
+                }
+                true
+            }
+            Event::PointerDown {
+                position,
+                button: MouseButton::Left,
+            } => {
+                self.pressed = self.row_at(*position);
+                true
+            }
+            Event::PointerUp {
+                position,
+                button: MouseButton::Left,
+            } => {
+                let index = self.row_at(*position);
+                if index.is_some() && index == self.pressed.take() {
+                    self.activate(index.unwrap_or_default(), false, state);
+                }
+                true
+            }
+            Event::KeyDown { key, .. } => match key {
+                Key::Named(NamedKey::ArrowDown) => {
+                    self.move_highlight(true);
+                    true
+                }
+                Key::Named(NamedKey::ArrowUp) => {
+                    self.move_highlight(false);
+                    true
+                }
+                Key::Named(NamedKey::Enter | NamedKey::Space) => {
+                    if let Some(index) = self.highlighted {
+                        self.activate(index, true, state);
+                    }
+                    true
+                }
+                Key::Named(NamedKey::ArrowRight) => {
+                    if let Some(index) = self.highlighted {
+                        self.show_submenu(index, true, state);
+                    }
+                    true
+                }
+                Key::Named(NamedKey::ArrowLeft) if self.level > 0 => {
+                    self.close_from(self.level, state);
+                    true
+                }
+                Key::Named(NamedKey::Escape) => {
+                    self.close_from(self.level, state);
+                    true
+                }
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.cache.is_dirty()
+    }
+
+    fn mark_needs_layout(&mut self) {
+        self.cache.mark_dirty();
+    }
+
+    fn is_relayout_boundary(&self) -> bool {
+        self.cache.is_relayout_boundary()
+    }
+}
+
+impl fmt::Debug for Menu {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Menu")
+            .field("items", &self.items)
+            .field("level", &self.level)
+            .field("highlighted", &self.highlighted)
+            .field("open_submenu", &self.open_submenu)
+            .finish_non_exhaustive()
+    }
+}
+
+/// ContextMenu is a widget that shows a menu when its child is right-clicked.
+///
+/// The menu opens at the pointer and is moved to stay inside the window.
+pub struct ContextMenu {
+    child: SingleChild,
+    items: Vec<MenuItem>,
+    /// The bounds in window space in the last paint, to place the menu.
+    bounds: Cell<Rect>,
+}
+
+impl ContextMenu {
+    /// Create a new ContextMenu instance showing the items.
+    pub fn new(items: Vec<MenuItem>) -> Self {
+        ContextMenu {
+            child: SingleChild::default(),
+            items,
+            bounds: Cell::new(Rect::from_size(Size::ZERO)),
+        }
+    }
+
+    /// Set the child widget.
+    pub fn child(mut self, child: impl Widget + 'static) -> Self {
+        self.child.set(child);
+        self
+    }
+
+    /// Map a position relative to the widget to window space.
+    fn to_window(&self, position: Offset) -> Offset {
+        let bounds = self.bounds.get();
+        let size = self.child.size();
+        let scale = |window: f32, local: f32| if local > 0.0 { window / local } else { 1.0 };
+        Offset::new(
+            bounds.left() + position.x * scale(bounds.size.width, size.width),
+            bounds.top() + position.y * scale(bounds.size.height, size.height),
+        )
+    }
+}
+
+impl Widget for ContextMenu {
+    fn build(&mut self, state: &mut State<'_>) {
+        self.child.build(state);
+    }
+
+    fn layout(&mut self, constraints: BoxConstraints) -> Size {
+        if let Some(size) = self.child.cached(constraints) {
+            return size;
+        }
+        let size = self
+            .child
+            .layout(constraints)
+            .unwrap_or_else(|| constraints.smallest());
+        self.child.store(constraints, size)
+    }
+
+    fn paint(&self, state: &mut State<'_>) {
+        let bounds = Rect::from_size(self.child.size());
+        self.bounds.set(state.canvas().to_window(bounds));
+        self.child.paint(state);
+    }
+
+    fn event(&mut self, event: &Event, state: &mut State<'_>) -> bool {
+        match event {
+            Event::PointerDown {
+                position,
+                button: MouseButton::Right,
+            } if Rect::from_size(self.child.size()).contains(*position) => {
+                let anchor = Rect {
+                    origin: self.to_window(*position),
+                    size: Size::ZERO,
+                };
+                Menu::show(self.items.clone(), anchor, Placement::Below, state);
+                true
+            }
+            _ => self.child.event(event, state),
+        }
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.child.needs_layout()
+    }
+
+    fn mark_needs_layout(&mut self) {
+        self.child.mark_needs_layout();
+    }
+
+    fn is_relayout_boundary(&self) -> bool {
+        self.child.is_relayout_boundary()
+    }
+}
+
+impl fmt::Debug for ContextMenu {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ContextMenu")
+            .field("items", &self.items)
+            .field("has_child", &self.child.is_some())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::widgets::test_support::{click_with, key};
+    use crate::framework::{Application, Color, SizedBox};
+
+    fn frame(app: &mut Application, state: &mut State<'_>) {
+        app.layout(BoxConstraints::tight(Size::new(400.0, 300.0)));
+        app.paint(state);
+    }
+
+    fn context_menu(chosen: &Rc<RefCell<Vec<&'static str>>>) -> ContextMenu {
+        let item = |label: &'static str| {
+            let chosen = Rc::clone(chosen);
+            MenuItem::new(label).on_select(move || chosen.borrow_mut().push(label))
+        };
+        ContextMenu::new(vec![
+            item("Cut"),
+            MenuItem::separator(),
+            MenuItem::new("Disabled").enabled(false),
+            MenuItem::submenu("More", vec![item("First"), item("Second")]),
+        ])
+        .child(SizedBox::expand())
+    }
+
+    #[test]
+    fn test_pointer_selection() {
+        let chosen = Rc::new(RefCell::new(Vec::new()));
+        let mut app = Application::new("test", context_menu(&chosen));
+        let mut state = State::default();
+        frame(&mut app, &mut state);
+
+        click_with(
+            &mut app,
+            &mut state,
+            Offset::new(10.0, 10.0),
+            MouseButton::Right,
+        );
+        frame(&mut app, &mut state);
+        assert!(!state.overlay().is_empty());
+
+        // Rows: Cut at 4, separator at 36, Disabled at 45, More at 77.
+        let row = |top: f32| Offset::new(20.0, 10.0 + top + 10.0);
+        click_with(&mut app, &mut state, row(45.0), MouseButton::Left);
+        assert!(chosen.borrow().is_empty());
+        app.event(
+            &Event::PointerMove {
+                position: row(77.0),
+            },
+            &mut state,
+        );
+        frame(&mut app, &mut state);
+
+        // The submenu opens beside the More row.
+        let submenu_item = Offset::new(10.0 + MIN_WIDTH + 20.0, 10.0 + 77.0 + 50.0);
+        click_with(&mut app, &mut state, submenu_item, MouseButton::Left);
+        assert_eq!(*chosen.borrow(), ["Second"]);
+        assert!(state.overlay().is_empty());
+    }
+
+    #[test]
+    fn test_keyboard_navigation() {
+        let chosen = Rc::new(RefCell::new(Vec::new()));
+        let mut app = Application::new("test", context_menu(&chosen));
+        let mut state = State::default();
+        frame(&mut app, &mut state);
+        click_with(
+            &mut app,
+            &mut state,
+            Offset::new(10.0, 10.0),
+            MouseButton::Right,
+        );
+        frame(&mut app, &mut state);
+
+        // Moving up wraps around and skips the disabled item and separator.
+        app.event(&key(NamedKey::ArrowUp), &mut state);
+        app.event(&key(NamedKey::ArrowRight), &mut state);
+        frame(&mut app, &mut state);
+        app.event(&key(NamedKey::ArrowDown), &mut state);
+        app.event(&key(NamedKey::ArrowLeft), &mut state);
+        app.event(&key(NamedKey::ArrowDown), &mut state);
+        app.event(&key(NamedKey::Enter), &mut state);
+        assert_eq!(*chosen.borrow(), ["Cut"]);
+        assert!(state.overlay().is_empty());
+
+        click_with(
+            &mut app,
+            &mut state,
+            Offset::new(10.0, 10.0),
+            MouseButton::Right,
+        );
+        app.event(&key(NamedKey::ArrowUp), &mut state);
+        app.event(&key(NamedKey::Enter), &mut state);
+        frame(&mut app, &mut state);
+        app.event(&key(NamedKey::Enter), &mut state);
+        assert_eq!(*chosen.borrow(), ["Cut", "First"]);
+
+        click_with(
+            &mut app,
+            &mut state,
+            Offset::new(10.0, 10.0),
+            MouseButton::Right,
+        );
+        app.event(&key(NamedKey::Escape), &mut state);
+        assert!(state.overlay().is_empty());
+    }
+
+    #[test]
+    fn test_repositioned_near_edge() {
+        let chosen = Rc::new(RefCell::new(Vec::new()));
+        let mut app = Application::new("test", context_menu(&chosen));
+        let mut state = State::default();
+        frame(&mut app, &mut state);
+        click_with(
+            &mut app,
+            &mut state,
+            Offset::new(390.0, 290.0),
+            MouseButton::Right,
+        );
+        frame(&mut app, &mut state);
+
+        let background = state
+            .canvas()
+            .commands()
+            .iter()
+            .find_map(|command| match command {
+                crate::framework::DrawCommand::FillRect { rect, color }
+                    if *color == Color::rgb(1.0, 1.0, 1.0) =>
+                {
+                    Some(*rect)
+                }
+                _ => None,
+            });
+        let height = VERTICAL_PADDING * 2.0 + ITEM_HEIGHT * 3.0 + SEPARATOR_HEIGHT;
+        assert_eq!(
+            background,
+            Some(Rect::new(
+                400.0 - MIN_WIDTH,
+                290.0 - height,
+                MIN_WIDTH,
+                height
+            ))
+        );
+    }
+}
@@ -1,5 +1,60 @@
+mod align;
+mod aspect_ratio;
+mod button;
+mod callbacks;
+mod checkbox;
+mod constrained_box;
 mod container;
+mod data_table;
+mod directionality;
+mod dropdown;
+mod fitted_box;
+mod fractionally_sized_box;
+mod menu;
+mod navigator;
+mod provider;
+mod radio;
+mod rich_text;
+mod single_child;
+mod sized_box;
+mod slider;
+mod split_pane;
+mod switch;
+mod tab_view;
+#[cfg(test)]
+mod test_support;
+mod text_field;
+mod theme_scope;
+mod toggleable;
+mod tooltip;
+mod tree_view;
 mod widget;
+mod widget_states;
 
-pub use container::Container;
+pub use align::{Align, Center};
+pub use aspect_ratio::AspectRatio;
+pub use button::{Button, ButtonStyle, ButtonVariant};
+pub use checkbox::Checkbox;
+pub use constrained_box::{Axis, ConstrainedBox, UnconstrainedBox};
+pub use container::{Border, Container};
+pub use data_table::{DataColumn, DataTable, SelectionMode, SortDirection};
+pub use directionality::Directionality;
+pub use dropdown::Dropdown;
+pub use fitted_box::{BoxFit, FittedBox};
+pub use fractionally_sized_box::FractionallySizedBox;
+pub use menu::{ContextMenu, Menu, MenuItem};
+pub use navigator::{Navigator, NavigatorHandle, Transition};
+pub use provider::{Consumer, Dependency, Provider, ProviderHandle};
+pub use radio::{Radio, RadioGroup};
+pub use rich_text::{RichText, TextSpan};
+pub use sized_box::SizedBox;
+pub use slider::{RangeSlider, Slider};
+pub use split_pane::SplitPane;
+pub use switch::Switch;
+pub use tab_view::{Tab, TabView};
+pub use text_field::TextField;
+pub use theme_scope::ThemeScope;
+pub use tooltip::Tooltip;
+pub use tree_view::{TreeNode, TreeView};
 pub use widget::Widget;
+pub use widget_states::WidgetStates;
@@ -0,0 +1,593 @@
+use super::widget::Widget;
+use crate::framework::{
+    layout_child, BoxConstraints, Event, LayoutCache, LayoutMarker, NavigationError, Offset, Size,
+    State,
+};
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+use std::time::Duration;
+use winit::keyboard::{Key, NamedKey};
+
+/// The default length of a page transition.
+const TRANSITION_DURATION: Duration = Duration::from_millis(300);
+
+/// Transition is how a page enters and leaves the screen.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Transition {
+    /// The new page replaces the old one at once.
+    None,
+    /// The new page slides in from the right, and slides out to the right
+    /// when popped.
+    #[default]
+    Slide,
+    /// The new page fades in above the old one, and fades out when popped.
+    Fade,
+}
+
+type RouteBuilder = Box<dyn Fn(&dyn Any) -> Option<Box<dyn Widget>>>;
+
+/// A change of the page stack, applied by the navigator before its next
+/// layout or event.
+enum Request {
+    Push(Box<dyn Widget>),
+    Pop(usize),
+    Replace(Box<dyn Widget>),
+}
+
+/// The routes and the pending changes, shared with the handles.
+#[derive(Default)]
+struct NavigatorShared {
+    routes: RefCell<HashMap<String, RouteBuilder>>,
+    requests: RefCell<Vec<Request>>,
+    /// The route names of the pages including the pending changes, `None`
+    /// for pages pushed without a name.
+    history: RefCell<Vec<Option<String>>>,
+    /// Marks the layout of the navigator dirty when a change is requested.
+    layout: LayoutMarker,
+}
+
+/// NavigatorHandle pushes and pops the pages of a [`Navigator`].
+///
+/// Pages keep a clone of the handle, typically in the callbacks of their
+/// buttons. Changes take effect before the next layout of the navigator, but
+/// [`NavigatorHandle::history`] reflects them right away.
+#[derive(Clone)]
+pub struct NavigatorHandle {
+    shared: Rc<NavigatorShared>,
+}
+
+impl NavigatorHandle {
+    /// Push a page on top of the stack.
+    pub fn push(&self, page: impl Widget + 'static) {
+        self.push_page(None, Box::new(page));
+    }
+
+    /// Push the page of a named route, built with the arguments.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NavigationError::UnknownRoute`] if no route has the name, and
+    /// [`NavigationError::InvalidArguments`] if the route expects arguments of
+    /// another type.
+    pub fn push_named<A: 'static>(&self, name: &str, arguments: A) -> Result<(), NavigationError> {
+        let page = self.build(name, &arguments)?;
+        self.push_page(Some(name.to_string()), page);
+        Ok(())
+    }
+
+    /// Replace the top page of the stack.
+    pub fn replace(&self, page: impl Widget + 'static) {
+        self.replace_page(None, Box::new(page));
+    }
+
+    /// Replace the top page of the stack with the page of a named route.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`NavigatorHandle::push_named`].
+    pub fn replace_named<A: 'static>(
+        &self,
+        name: &str,
+        arguments: A,
+    ) -> Result<(), NavigationError> {
+        let page = self.build(name, &arguments)?;
+        self.replace_page(Some(name.to_string()), page);
+        Ok(())
+    }
+
+    /// Pop the top page, returning false if it is the only page.
+    pub fn pop(&self) -> bool {
+        self.pop_pages(1)
+    }
+
+    /// Pop pages until the top page is the named route, returning false and
+    /// leaving the stack alone if no page below the top has the name.
+    pub fn pop_until(&self, name: &str) -> bool {
+        let index = self
+            .shared
+            .history
+            .borrow()
+            .iter()
+            .rposition(|page| page.as_deref() == Some(name));
+        match index {
+            Some(index) => {
+                let count = self.depth() - 1 - index;
+                count > 0 && self.pop_pages(count)
+            }
+            None => false,
+        }
+    }
+
+    /// Returns true if there is a page below the top page.
+    pub fn can_pop(&self) -> bool {
+        self.depth() > 1
+    }
+
+    /// The number of pages on the stack.
+    pub fn depth(&self) -> usize {
+        self.shared.history.borrow().len()
+    }
+
+    /// The route names of the pages from the bottom to the top, `None` for
+    /// pages pushed without a name.
+    pub fn history(&self) -> Vec<Option<String>> {
+        self.shared.history.borrow().clone()
+    }
+
+    fn build(&self, name: &str, arguments: &dyn Any) -> Result<Box<dyn Widget>, NavigationError> {
+        let routes = self.shared.routes.borrow();
+        let builder = routes
+            .get(name)
+            .ok_or_else(|| NavigationError::UnknownRoute(name.to_string()))?;
+        builder(arguments).ok_or_else(|| NavigationError::InvalidArguments(name.to_string()))
+    }
+
+    fn push_page(&self, name: Option<String>, page: Box<dyn Widget>) {
+        self.shared.history.borrow_mut().push(name);
+        self.request(Request::Push(page));
+    }
+
+    fn replace_page(&self, name: Option<String>, page: Box<dyn Widget>) {
+        if let Some(top) = self.shared.history.borrow_mut().last_mut() {
+            *top = name;
+        }
+        self.request(Request::Replace(page));
+    }
+
+    fn pop_pages(&self, count: usize) -> bool {
+        let mut history = self.shared.history.borrow_mut();
+        if count >= history.len() {
+            return false;
+        }
+        let len = history.len() - count;
+        history.truncate(len);
+        self.request(Request::Pop(count));
+        true
+    }
+
+    fn request(&self, request: Request) {
+        self.shared.requests.borrow_mut().push(request);
+        self.shared.layout.mark_dirty();
+    }
+}
+
+impl fmt::Debug for NavigatorHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NavigatorHandle")
+            .field("history", &self.shared.history.borrow())
+            .finish()
+    }
+}
+
+/// A running page transition.
+struct Animation {
+    /// True if the top page enters, false if the exiting page leaves.
+    forward: bool,
+    /// The page popped or replaced, painted until the transition ends.
+    exiting: Option<Box<dyn Widget>>,
+    /// The frame time of the first frame painted, set while painting.
+    start: Cell<Option<Duration>>,
+    /// Set while painting once the transition has ended.
+    finished: Cell<bool>,
+}
+
+/// Navigator is a stack of pages of which the top page is shown.
+///
+/// Pages are pushed and popped through a [`NavigatorHandle`], either as
+/// widgets or by the name of a route registered with [`Navigator::route`],
+/// which builds the page from typed arguments. The browser back key pops the
+/// top page if the page does not handle it.
+///
+/// Pushing and popping animate with the [`Transition`] of the navigator. The
+/// animation is driven by [`State::frame_time`]; the navigator requests frames
+/// with [`State::request_frame`] while [`Navigator::is_animating`] returns
+/// true.
+pub struct Navigator {
+    shared: Rc<NavigatorShared>,
+    pages: Vec<Box<dyn Widget>>,
+    transition: Transition,
+    duration: Duration,
+    animation: Option<Animation>,
+    size: Size,
+    /// Whether pages were pushed since the last build.
+    needs_build: bool,
+    cache: LayoutCache,
+}
+
+impl Navigator {
+    /// Create a new Navigator instance with the given home page.
+    pub fn new(home: impl Widget + 'static) -> Self {
+        let cache = LayoutCache::default();
+        let shared = Rc::new(NavigatorShared {
+            history: RefCell::new(vec![None]),
+            layout: cache.marker(),
+            ..Default::default()
+        });
+        Navigator {
+            shared,
+            pages: vec![Box::new(home)],
+            transition: Transition::default(),
+            duration: TRANSITION_DURATION,
+            animation: None,
+            size: Size::ZERO,
+            needs_build: false,
+            cache,
+        }
+    }
+
+    /// Register a named route whose page is built from arguments of type `A`.
+    pub fn route<A: 'static, W: Widget + 'static>(
+        self,
+        name: impl Into<String>,
+        builder: impl Fn(&A) -> W + 'static,
+    ) -> Self {
+        let builder: RouteBuilder = Box::new(move |arguments| {
+            let arguments = arguments.downcast_ref::<A>()?;
+            Some(Box::new(builder(arguments)))
+        });
+        self.shared.routes.borrow_mut().insert(name.into(), builder);
+        self
+    }
+
+    /// Set the transition between pages.
+    pub fn transition(mut self, transition: Transition) -> Self {
+        self.transition = transition;
+        self
+    }
+
+    /// Set the length of the transition between pages.
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// A handle that pushes and pops the pages of this navigator.
+    pub fn handle(&self) -> NavigatorHandle {
+        NavigatorHandle {
+            shared: Rc::clone(&self.shared),
+        }
+    }
+
+    /// Returns true while a page transition runs.
+    pub fn is_animating(&self) -> bool {
+        self.animation
+            .as_ref()
+            .is_some_and(|animation| !animation.finished.get())
+    }
+
+    /// Apply the pending changes of the handles to the pages.
+    fn apply_requests(&mut self) {
+        if self
+            .animation
+            .as_ref()
+            .is_some_and(|animation| animation.finished.get())
+        {
+            self.animation = None;
+        }
+        let requests = std::mem::take(&mut *self.shared.requests.borrow_mut());
+        for request in requests {
+            let animation = match request {
+                Request::Push(page) => {
+                    self.pages.push(page);
+                    self.needs_build = true;
+                    (true, None)
+                }
+                Request::Pop(count) => {
+                    let exiting = self.pages.pop();
+                    let len = self.pages.len() + 1 - count.max(1);
+                    self.pages.truncate(len);
+                    (false, exiting)
+                }
+                Request::Replace(page) => {
+                    let exiting = self.pages.pop();
+                    self.pages.push(page);
+                    self.needs_build = true;
+                    (true, exiting)
+                }
+            };
+            self.animation = match (self.transition, animation) {
+                (Transition::None, _) => None,
+                (_, (forward, exiting)) => Some(Animation {
+                    forward,
+                    exiting,
+                    start: Cell::new(None),
+                    finished: Cell::new(false),
+                }),
+            };
+        }
+    }
+
+    /// The page below the entering or leaving page, and that page.
+    fn transition_pages<'a>(
+        &'a self,
+        animation: &'a Animation,
+    ) -> (Option<&'a dyn Widget>, Option<&'a dyn Widget>) {
+        let top = self.pages.last().map(AsRef::as_ref);
+        let exiting = animation.exiting.as_deref();
+        if animation.forward {
+            let below = match exiting {
+                Some(exiting) => Some(exiting),
+                None => self
+                    .pages
+                    .len()
+                    .checked_sub(2)
+                    .map(|index| self.pages[index].as_ref()),
+            };
+            (below, top)
+        } else {
+            (top, exiting)
+        }
+    }
+
+    /// How far the moving page has entered, from 0 (off screen) to 1.
+    fn progress(&self, animation: &Animation, frame_time: Duration) -> f32 {
+        let start = animation.start.get().unwrap_or(frame_time);
+        animation.start.set(Some(start));
+        let elapsed = frame_time.saturating_sub(start);
+        let t = if self.duration.is_zero() {
+            1.0
+        } else {
+            (elapsed.as_secs_f32() / self.duration.as_secs_f32()).min(1.0)
+        };
+        animation.finished.set(t >= 1.0);
+        // Ease out, so the page slows down as it arrives.
+        let t = 1.0 - (1.0 - t) * (1.0 - t);
+        if animation.forward {
+            t
+        } else {
+            1.0 - t
+        }
+    }
+
+    fn paint_moving(&self, page: &dyn Widget, progress: f32, state: &mut State<'_>) {
+        let canvas = state.canvas_mut();
+        canvas.save();
+        match self.transition {
+            Transition::Slide => {
+                canvas.translate(Offset::new(self.size.width * (1.0 - progress), 0.0));
+            }
+            Transition::Fade => canvas.apply_opacity(f64::from(progress)),
+            Transition::None => {}
+        }
+        page.paint(state);
+        state.canvas_mut().restore();
+    }
+}
+
+impl Widget for Navigator {
+    fn build(&mut self, state: &mut State<'_>) {
+        // The pages below keep their state and are shown again when popped.
+        for page in &mut self.pages {
+            page.build(state);
+        }
+        self.needs_build = false;
+    }
+
+    fn layout(&mut self, constraints: BoxConstraints) -> Size {
+        self.apply_requests();
+        if let Some(size) = self
+            .cache
+            .get(constraints)
+            .filter(|_| !self.cache.take_dirty_descendant())
+        {
+            return size;
+        }
+        let biggest = constraints.biggest();
+        let page_constraints = if biggest.is_finite() {
+            BoxConstraints::tight(biggest)
+        } else {
+            constraints
+        };
+        let mut size = constraints.smallest();
+        if let Some(page) = self.pages.last_mut() {
+            size = layout_child(page.as_mut(), page_constraints);
+        }
+        // The pages seen during a transition are laid out as well.
+        if let Some(animation) = &mut self.animation {
+            if let Some(exiting) = &mut animation.exiting {
+                layout_child(exiting.as_mut(), page_constraints);
+            } else if animation.forward && self.pages.len() > 1 {
+                let index = self.pages.len() - 2;
+                layout_child(self.pages[index].as_mut(), page_constraints);
+            }
+        }
+        self.size = size;
+        self.cache.store(constraints, size);
+        size
+    }
+
+    fn paint(&self, state: &mut State<'_>) {
+        if self.needs_build {
+            state.request_build();
+        }
+        if let Some(animation) = &self.animation {
+            let progress = self.progress(animation, state.frame_time());
+            if !animation.finished.get() {
+                state.request_frame();
+                let (below, moving) = self.transition_pages(animation);
+                if let Some(below) = below {
+                    below.paint(state);
+                }
+                if let Some(moving) = moving {
+                    self.paint_moving(moving, progress, state);
+                }
+                return;
+            }
+        }
+        if let Some(page) = self.pages.last() {
+            page.paint(state);
+        }
+    }
+
+    fn event(&mut self, event: &Event, state: &mut State<'_>) -> bool {
+        self.apply_requests();
+        if let Some(page) = self.pages.last_mut() {
+            if page.event(event, state) {
+                return true;
+            }
+        }
+        match event {
+            Event::KeyDown {
+                key: Key::Named(NamedKey::BrowserBack),
+                ..
+            } => self.handle().pop(),
+            _ => false,
+        }
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.cache.is_dirty()
+    }
+
+    fn mark_needs_layout(&mut self) {
+        self.cache.mark_dirty();
+    }
+
+    fn is_relayout_boundary(&self) -> bool {
+        self.cache.is_relayout_boundary()
+    }
+}
+
+impl fmt::Debug for Navigator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Navigator")
+            .field("history", &self.shared.history.borrow())
+            .field("transition", &self.transition)
+            .field("duration", &self.duration)
+            .field("is_animating", &self.is_animating())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::{Color, DrawCommand, Rect, SizedBox};
+
+    /// A page that paints a rectangle of its width.
+    struct Page(f32);
+
+    impl Widget for Page {
+        fn build(&mut self, _state: &mut State<'_>) {}
+
+        fn layout(&mut self, constraints: BoxConstraints) -> Size {
+            constraints.biggest()
+        }
+
+        fn paint(&self, state: &mut State<'_>) {
+            state
+                .canvas_mut()
+                .fill_rect(Rect::new(0.0, 0.0, self.0, 10.0), Color::rgb(0.0, 0.0, 0.0));
+        }
+    }
+
+    fn painted(navigator: &mut Navigator, state: &mut State<'_>) -> Vec<(f32, f32, f64)> {
+        navigator.layout(BoxConstraints::tight(Size::new(100.0, 100.0)));
+        state.begin_frame();
+        navigator.paint(state);
+        state
+            .canvas()
+            .commands()
+            .iter()
+            .map(|command| match command {
+                DrawCommand::FillRect { rect, color } => (rect.left(), rect.size.width, color.a),
+                DrawCommand::Text { .. } => unreachable!(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_named_routes() {
+        let mut navigator = Navigator::new(SizedBox::new(10.0, 10.0))
+            .route("/details", |id: &u32| SizedBox::new(*id as f32, 10.0))
+            .transition(Transition::None);
+        let handle = navigator.handle();
+        assert!(!handle.can_pop());
+        assert!(!handle.pop());
+
+        handle.push_named("/details", 5_u32).unwrap();
+        handle.push(SizedBox::new(1.0, 1.0));
+        assert_eq!(handle.history(), [None, Some("/details".to_string()), None]);
+        assert!(matches!(
+            handle.push_named("/missing", ()),
+            Err(NavigationError::UnknownRoute(_))
+        ));
+        assert!(matches!(
+            handle.push_named("/details", "five"),
+            Err(NavigationError::InvalidArguments(_))
+        ));
+
+        navigator.layout(BoxConstraints::loose(Size::new(100.0, 100.0)));
+        assert_eq!(navigator.pages.len(), 3);
+        assert!(!navigator.needs_layout());
+        assert!(handle.pop_until("/details"));
+        assert!(navigator.needs_layout());
+        assert!(!handle.pop_until("/missing"));
+        handle.replace_named("/details", 7_u32).unwrap();
+        assert_eq!(handle.depth(), 2);
+        let size = navigator.layout(BoxConstraints::loose(Size::new(100.0, 100.0)));
+        assert_eq!(navigator.pages.len(), 2);
+        assert_eq!(size, Size::new(100.0, 100.0));
+    }
+
+    #[test]
+    fn test_transitions() {
+        let mut navigator = Navigator::new(Page(20.0));
+        let handle = navigator.handle();
+        let mut state = State::default();
+        assert_eq!(painted(&mut navigator, &mut state), [(0.0, 20.0, 1.0)]);
+
+        // The new page slides in from the right above the home page.
+        handle.push(Page(30.0));
+        state.set_frame_time(Duration::from_secs(1));
+        assert_eq!(
+            painted(&mut navigator, &mut state),
+            [(0.0, 20.0, 1.0), (100.0, 30.0, 1.0)]
+        );
+        assert!(navigator.is_animating());
+        assert!(state.needs_frame());
+        state.set_frame_time(Duration::from_secs(1) + TRANSITION_DURATION / 2);
+        let commands = painted(&mut navigator, &mut state);
+        assert!(commands[1].0 > 0.0 && commands[1].0 < 50.0);
+        state.set_frame_time(Duration::from_secs(2));
+        assert_eq!(painted(&mut navigator, &mut state), [(0.0, 30.0, 1.0)]);
+        assert!(!navigator.is_animating());
+        assert!(!state.needs_frame());
+
+        // A popped page fades out above the page below it.
+        let mut navigator = navigator.transition(Transition::Fade);
+        handle.pop();
+        assert_eq!(
+            painted(&mut navigator, &mut state),
+            [(0.0, 20.0, 1.0), (0.0, 30.0, 1.0)]
+        );
+        state.set_frame_time(Duration::from_secs(2) + TRANSITION_DURATION / 2);
+        let commands = painted(&mut navigator, &mut state);
+        assert!(commands[1].2 > 0.0 && commands[1].2 < 0.5);
+        state.set_frame_time(Duration::from_secs(3));
+        assert_eq!(painted(&mut navigator, &mut state), [(0.0, 20.0, 1.0)]);
+    }
+}
@@ -0,0 +1,413 @@
+use super::single_child::SingleChild;
+use super::widget::Widget;
+use crate::framework::state::next_version;
+use crate::framework::{BoxConstraints, Event, Size, State};
+use std::any::{type_name, Any};
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+/// The value of a provider, shared with its handles.
+struct ProviderShared<T> {
+    value: RefCell<Rc<T>>,
+    version: Cell<u64>,
+    /// Whether the value changed since the provider was last built.
+    changed: Cell<bool>,
+}
+
+/// Provider exposes a value to the widgets below it.
+///
+/// Descendants look the value up by type with [`State::read`], or get a
+/// [`ProviderHandle`] to change it with [`ProviderHandle::of`]. The nearest
+/// provider of a type wins, so a provider can override the value of another
+/// one above it for a subtree.
+///
+/// When the value changes, the tree is built again before the next layout,
+/// and the widgets that track the value with a [`Dependency`], such as a
+/// [`Consumer`], rebuild what they derived from it. Widgets that do not
+/// depend on the value are left alone.
+pub struct Provider<T> {
+    child: Box<dyn Widget>,
+    shared: Rc<ProviderShared<T>>,
+}
+
+impl<T: 'static> Provider<T> {
+    /// Create a new Provider instance exposing the value to the child.
+    pub fn new(value: T, child: impl Widget + 'static) -> Self {
+        Provider {
+            child: Box::new(child),
+            shared: Rc::new(ProviderShared {
+                value: RefCell::new(Rc::new(value)),
+                version: Cell::new(next_version()),
+                changed: Cell::new(false),
+            }),
+        }
+    }
+
+    /// A handle to change the value, e.g. from a callback outside the
+    /// subtree.
+    pub fn handle(&self) -> ProviderHandle<T> {
+        ProviderHandle {
+            shared: Rc::clone(&self.shared),
+        }
+    }
+
+    /// Make the value inherited by the widgets visited until it is popped.
+    fn push(&self, state: &mut State<'_>) {
+        let value = Rc::clone(&self.shared.value.borrow());
+        let source: Rc<dyn Any> = self.shared.clone();
+        state.push_inherited(value, self.shared.version.get(), Some(source));
+    }
+}
+
+impl<T: 'static> Widget for Provider<T> {
+    fn build(&mut self, state: &mut State<'_>) {
+        self.shared.changed.set(false);
+        self.push(state);
+        self.child.build(state);
+        state.pop_inherited();
+    }
+
+    fn layout(&mut self, constraints: BoxConstraints) -> Size {
+        self.child.layout(constraints)
+    }
+
+    fn paint(&self, state: &mut State<'_>) {
+        if self.shared.changed.get() {
+            state.request_build();
+        }
+        self.push(state);
+        self.child.paint(state);
+        state.pop_inherited();
+    }
+
+    fn event(&mut self, event: &Event, state: &mut State<'_>) -> bool {
+        self.push(state);
+        let handled = self.child.event(event, state);
+        state.pop_inherited();
+        handled
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.child.needs_layout()
+    }
+
+    fn mark_needs_layout(&mut self) {
+        self.child.mark_needs_layout();
+    }
+
+    fn is_relayout_boundary(&self) -> bool {
+        self.child.is_relayout_boundary()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Provider<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Provider")
+            .field("value", &self.shared.value.borrow())
+            .finish_non_exhaustive()
+    }
+}
+
+/// ProviderHandle reads and changes the value of a [`Provider`].
+///
+/// Changes take effect when the tree is built again before the next layout,
+/// but [`ProviderHandle::get`] reflects them right away.
+pub struct ProviderHandle<T> {
+    shared: Rc<ProviderShared<T>>,
+}
+
+impl<T: 'static> ProviderHandle<T> {
+    /// The handle of the nearest provider of a value of type `T` above the
+    /// widget being visited, if any.
+    pub fn of(state: &State<'_>) -> Option<Self> {
+        let shared = Rc::clone(state.source::<T>()?).downcast().ok()?;
+        Some(ProviderHandle { shared })
+    }
+
+    /// The current value.
+    pub fn get(&self) -> Rc<T> {
+        Rc::clone(&self.shared.value.borrow())
+    }
+
+    /// Replace the value, rebuilding the widgets that depend on it.
+    pub fn set(&self, value: T) {
+        *self.shared.value.borrow_mut() = Rc::new(value);
+        self.shared.version.set(next_version());
+        self.shared.changed.set(true);
+    }
+
+    /// Change the value in place, rebuilding the widgets that depend on it.
+    ///
+    /// The value is cloned first if a widget still holds the old one.
+    pub fn update(&self, update: impl FnOnce(&mut T))
+    where
+        T: Clone,
+    {
+        update(Rc::make_mut(&mut self.shared.value.borrow_mut()));
+        self.shared.version.set(next_version());
+        self.shared.changed.set(true);
+    }
+}
+
+impl<T> Clone for ProviderHandle<T> {
+    fn clone(&self) -> Self {
+        ProviderHandle {
+            shared: Rc::clone(&self.shared),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ProviderHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProviderHandle")
+            .field("value", &self.shared.value.borrow())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Dependency tracks an inherited value a widget derives something from.
+///
+/// A widget keeps one per value it depends on and checks it while it is
+/// built, redoing its work only if the value changed:
+///
+/// ```ignore
+/// fn build(&mut self, state: &mut State<'_>) {
+///     if self.locale.changed(state) {
+///         self.label = translate(state.read::<Locale>());
+///     }
+/// }
+/// ```
+pub struct Dependency<T> {
+    /// Whether the value has been checked before.
+    checked: bool,
+    /// The version seen by the last check, `None` without a provider.
+    version: Option<u64>,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T: 'static> Dependency<T> {
+    /// Create a new Dependency instance that has not seen the value yet.
+    pub fn new() -> Self {
+        Dependency {
+            checked: false,
+            version: None,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns true if the value inherited by the widget being visited
+    /// changed since the last check, or if this is the first check.
+    ///
+    /// Gaining or losing a provider counts as a change.
+    pub fn changed(&mut self, state: &State<'_>) -> bool {
+        let version = state.version::<T>();
+        let changed = !self.checked || version != self.version;
+        self.checked = true;
+        self.version = version;
+        changed
+    }
+}
+
+impl<T: 'static> Default for Dependency<T> {
+    fn default() -> Self {
+        Dependency::new()
+    }
+}
+
+impl<T> fmt::Debug for Dependency<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Dependency")
+            .field("type", &type_name::<T>())
+            .field("version", &self.version)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Builds the child of a consumer from the value and sets it.
+type ChildBuilder<T> = dyn Fn(&T, &mut SingleChild);
+
+/// Consumer builds its child from the value of the nearest [`Provider`] of
+/// its type.
+///
+/// The child is built again from scratch only when the value changes.
+/// Without a provider above it, the consumer has no child.
+pub struct Consumer<T> {
+    builder: Box<ChildBuilder<T>>,
+    dependency: Dependency<T>,
+    child: SingleChild,
+}
+
+impl<T: 'static> Consumer<T> {
+    /// Create a new Consumer instance building its child with the builder.
+    pub fn new<W: Widget + 'static>(builder: impl Fn(&T) -> W + 'static) -> Self {
+        Consumer {
+            builder: Box::new(move |value, child| child.set(builder(value))),
+            dependency: Dependency::new(),
+            child: SingleChild::default(),
+        }
+    }
+}
+
+impl<T: 'static> Widget for Consumer<T> {
+    fn build(&mut self, state: &mut State<'_>) {
+        if self.dependency.changed(state) {
+            if let Some(value) = state.read::<T>() {
+                (self.builder)(value, &mut self.child);
+            } else {
+                log::error!("Consumer<{}> has no Provider above it", type_name::<T>());
+                self.child = SingleChild::default();
+            }
+        }
+        self.child.build(state);
+    }
+
+    fn layout(&mut self, constraints: BoxConstraints) -> Size {
+        if let Some(size) = self.child.cached(constraints) {
+            return size;
+        }
+        let size = self
+            .child
+            .layout(constraints)
+            .unwrap_or_else(|| constraints.smallest());
+        self.child.store(constraints, size)
+    }
+
+    fn paint(&self, state: &mut State<'_>) {
+        self.child.paint(state);
+    }
+
+    fn event(&mut self, event: &Event, state: &mut State<'_>) -> bool {
+        self.child.event(event, state)
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.child.needs_layout()
+    }
+
+    fn mark_needs_layout(&mut self) {
+        self.child.mark_needs_layout();
+    }
+
+    fn is_relayout_boundary(&self) -> bool {
+        self.child.is_relayout_boundary()
+    }
+}
+
+impl<T> fmt::Debug for Consumer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Consumer")
+            .field("type", &type_name::<T>())
+            .field("has_child", &self.child.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::{SizedBox, Theme, ThemeScope};
+
+    /// A widget whose size is the value it was built from.
+    fn counter_box(builds: &Rc<Cell<u32>>) -> Consumer<u32> {
+        let builds = Rc::clone(builds);
+        Consumer::new(move |count: &u32| {
+            builds.set(builds.get() + 1);
+            SizedBox::new(*count as f32, 10.0)
+        })
+    }
+
+    #[test]
+    fn test_lookup_by_type() {
+        let mut state = State::default();
+        assert_eq!(state.read::<u32>(), None);
+
+        let outer = Provider::new(1u32, SizedBox::shrink());
+        outer.push(&mut state);
+        let inner = Provider::new(2u32, SizedBox::shrink());
+        inner.push(&mut state);
+        let label = Provider::new("label", SizedBox::shrink());
+        label.push(&mut state);
+        // The nearest provider of the type wins.
+        assert_eq!(state.read::<u32>(), Some(&2));
+        assert_eq!(state.read::<&str>(), Some(&"label"));
+
+        ProviderHandle::<u32>::of(&state).unwrap().set(3);
+        assert_eq!(*inner.handle().get(), 3);
+        assert_eq!(*outer.handle().get(), 1);
+
+        state.pop_inherited();
+        state.pop_inherited();
+        assert_eq!(state.read::<u32>(), Some(&1));
+        state.pop_inherited();
+        assert_eq!(state.read::<u32>(), None);
+        assert!(ProviderHandle::<u32>::of(&state).is_none());
+    }
+
+    #[test]
+    fn test_only_dependents_rebuild() {
+        let builds = Rc::new(Cell::new(0));
+        let unrelated = Rc::new(Cell::new(0));
+        let mut provider = Provider::new(
+            20u32,
+            Provider::new(
+                "title",
+                ThemeScope::new(Theme::dark(), counter_box(&builds)),
+            ),
+        );
+        let handle = provider.handle();
+        let mut state = State::default();
+        let constraints = BoxConstraints::loose(Size::new(100.0, 100.0));
+
+        provider.build(&mut state);
+        assert_eq!(builds.get(), 1);
+        assert_eq!(provider.layout(constraints), Size::new(20.0, 10.0));
+
+        // Building again without a change leaves the child alone.
+        provider.build(&mut state);
+        assert_eq!(builds.get(), 1);
+        assert!(!provider.needs_layout());
+
+        handle.update(|count| *count += 10);
+        provider.paint(&mut state);
+        assert!(state.needs_build());
+        provider.build(&mut state);
+        assert_eq!(builds.get(), 2);
+        assert!(provider.needs_layout());
+        assert_eq!(provider.layout(constraints), Size::new(30.0, 10.0));
+
+        let mut other = Provider::new(0u32, counter_box(&unrelated));
+        other.build(&mut state);
+        handle.set(40);
+        provider.build(&mut state);
+        other.build(&mut state);
+        assert_eq!(builds.get(), 3);
+        assert_eq!(unrelated.get(), 1);
+    }
+
+    #[test]
+    fn test_consumer_without_provider() {
+        let builds = Rc::new(Cell::new(0));
+        let mut consumer = counter_box(&builds);
+        let mut state = State::default();
+
+        consumer.build(&mut state);
+        assert_eq!(builds.get(), 0);
+        let constraints = BoxConstraints::loose(Size::new(100.0, 100.0));
+        assert_eq!(consumer.layout(constraints), Size::ZERO);
+    }
+
+    #[test]
+    fn test_theme_dependency() {
+        let mut dependency = Dependency::<Theme>::new();
+        let mut state = State::default();
+        assert!(dependency.changed(&state));
+        assert!(!dependency.changed(&state));
+
+        state.set_theme(Theme::dark());
+        assert!(dependency.changed(&state));
+        state.set_theme(Theme::dark());
+        assert!(!dependency.changed(&state));
+    }
+}
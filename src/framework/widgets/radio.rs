@@ -0,0 +1,250 @@
+use super::callbacks::ChangeCallback;
+use super::toggleable::{Response, Toggleable};
+use super::widget::Widget;
+use super::widget_states::WidgetStates;
+use crate::framework::{BoxConstraints, Color, Event, FocusId, Offset, Size, State};
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+/// The outer radius of the radio circle in logical pixels.
+const OUTER_RADIUS: f32 = 10.0;
+
+/// The radius of the dot of a selected radio button.
+const INNER_RADIUS: f32 = 5.0;
+
+/// The width of the ring of the radio circle.
+const RING_WIDTH: f32 = 2.0;
+
+/// The size of the area around the circle that reacts to the pointer.
+const TAP_TARGET_SIZE: f32 = 40.0;
+
+struct RadioGroupState<T> {
+    selected: Option<T>,
+    on_changed: Option<ChangeCallback<T>>,
+}
+
+/// RadioGroup is the shared selection of a set of [`Radio`] buttons.
+///
+/// Cloning a group returns another handle to the same selection.
+pub struct RadioGroup<T> {
+    state: Rc<RefCell<RadioGroupState<T>>>,
+}
+
+impl<T> Clone for RadioGroup<T> {
+    fn clone(&self) -> Self {
+        RadioGroup {
+            state: Rc::clone(&self.state),
+        }
+    }
+}
+
+impl<T: PartialEq + Clone> RadioGroup<T> {
+    /// Create a new RadioGroup instance with the given value selected.
+    pub fn new(selected: Option<T>) -> Self {
+        RadioGroup {
+            state: Rc::new(RefCell::new(RadioGroupState {
+                selected,
+                on_changed: None,
+            })),
+        }
+    }
+
+    /// Set the callback called with the newly selected value.
+    pub fn on_changed(self, on_changed: impl FnMut(&T) + 'static) -> Self {
+        self.state.borrow_mut().on_changed = Some(Box::new(on_changed));
+        self
+    }
+
+    /// The selected value, if any.
+    pub fn selected(&self) -> Option<T> {
+        self.state.borrow().selected.clone()
+    }
+
+    /// Returns true if the value is selected.
+    pub fn is_selected(&self, value: &T) -> bool {
+        self.state.borrow().selected.as_ref() == Some(value)
+    }
+
+    /// Select the value, calling the callback if the selection changed.
+    pub fn select(&self, value: T) {
+        if self.is_selected(&value) {
+            return;
+        }
+        // Take the callback out so it may use the group.
+        let on_changed = {
+            let mut state = self.state.borrow_mut();
+            state.selected = Some(value.clone());
+            state.on_changed.take()
+        };
+        if let Some(mut on_changed) = on_changed {
+            on_changed(&value);
+            self.state.borrow_mut().on_changed.get_or_insert(on_changed);
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for RadioGroup<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.borrow();
+        f.debug_struct("RadioGroup")
+            .field("selected", &state.selected)
+            .field("has_on_changed", &state.on_changed.is_some())
+            .finish()
+    }
+}
+
+/// Radio is a control that selects its value in a [`RadioGroup`].
+///
+/// Selecting a radio button deselects the other buttons of its group. Colors
+/// that are not set are taken from the [`Theme`](crate::framework::Theme).
+pub struct Radio<T> {
+    toggleable: Toggleable,
+    value: T,
+    group: RadioGroup<T>,
+    active_color: Option<Color>,
+}
+
+impl<T: PartialEq + Clone> Radio<T> {
+    /// Create a new Radio instance selecting the value in the group.
+    pub fn new(value: T, group: &RadioGroup<T>) -> Self {
+        Radio {
+            toggleable: Toggleable::default(),
+            value,
+            group: group.clone(),
+            active_color: None,
+        }
+    }
+
+    /// Set whether the radio button accepts input.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.toggleable.set_enabled(enabled);
+        self
+    }
+
+    /// Set the color of a selected radio button.
+    pub fn active_color(mut self, color: Color) -> Self {
+        self.active_color = Some(color);
+        self
+    }
+
+    /// Returns true if the value of the radio button is selected.
+    pub fn is_selected(&self) -> bool {
+        self.group.is_selected(&self.value)
+    }
+
+    /// The focus identifier of the radio button.
+    pub fn focus_id(&self) -> FocusId {
+        self.toggleable.focus_id()
+    }
+
+    /// The current interactive states.
+    pub fn states(&self) -> WidgetStates {
+        let mut states = self.toggleable.states();
+        states.set(WidgetStates::SELECTED, self.is_selected());
+        states
+    }
+}
+
+impl<T: PartialEq + Clone> Widget for Radio<T> {
+    fn build(&mut self, _state: &mut State<'_>) {}
+
+    fn layout(&mut self, constraints: BoxConstraints) -> Size {
+        self.toggleable
+            .layout(constraints, Size::new(TAP_TARGET_SIZE, TAP_TARGET_SIZE))
+    }
+
+    fn paint(&self, state: &mut State<'_>) {
+        let focused = self.toggleable.register_focus(state);
+        let size = self.toggleable.size();
+        let center = Offset::new(size.width / 2.0, size.height / 2.0);
+        let selected = self.is_selected();
+        let colors = state.theme().colors;
+        let active_color = self.active_color.unwrap_or(colors.primary);
+        let color = match (self.toggleable.is_enabled(), selected) {
+            (false, _) => colors.disabled,
+            (true, true) => active_color,
+            (true, false) => colors.on_surface_variant,
+        };
+        self.toggleable
+            .paint_halo(state, center, active_color, focused);
+
+        // The ring is painted as a filled circle with the inside cut out
+        // by a circle of the background, which is assumed to be white.
+        let canvas = state.canvas_mut();
+        canvas.fill_circle(center, OUTER_RADIUS, color);
+        canvas.fill_circle(center, OUTER_RADIUS - RING_WIDTH, colors.surface);
+        if selected {
+            canvas.fill_circle(center, INNER_RADIUS, color);
+        }
+    }
+
+    fn event(&mut self, event: &Event, state: &mut State<'_>) -> bool {
+        let response = self.toggleable.event(event, state);
+        if response == Response::Activated {
+            self.group.select(self.value.clone());
+        }
+        response.is_handled()
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.toggleable.needs_layout()
+    }
+
+    fn mark_needs_layout(&mut self) {
+        self.toggleable.mark_needs_layout();
+    }
+
+    fn is_relayout_boundary(&self) -> bool {
+        self.toggleable.is_relayout_boundary()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Radio<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Radio")
+            .field("value", &self.value)
+            .field("group", &self.group)
+            .field("states", &self.toggleable.states())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::widgets::test_support::click;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Choice {
+        Small,
+        Large,
+    }
+
+    #[test]
+    fn test_group_selection() {
+        let changes = Rc::new(RefCell::new(Vec::new()));
+        let group = {
+            let changes = Rc::clone(&changes);
+            RadioGroup::new(Some(Choice::Small))
+                .on_changed(move |size| changes.borrow_mut().push(*size))
+        };
+        let constraints = BoxConstraints::loose(Size::new(100.0, 100.0));
+        let mut small = Radio::new(Choice::Small, &group);
+        let mut large = Radio::new(Choice::Large, &group);
+        small.layout(constraints);
+        large.layout(constraints);
+        let mut state = State::default();
+
+        assert!(small.is_selected());
+        click(&mut large, &mut state, Offset::new(20.0, 20.0));
+        assert!(large.is_selected());
+        assert!(!small.is_selected());
+        assert!(large.states().contains(WidgetStates::SELECTED));
+
+        // Selecting the selected value again does not report a change.
+        click(&mut large, &mut state, Offset::new(20.0, 20.0));
+        assert_eq!(*changes.borrow(), [Choice::Large]);
+        assert_eq!(group.selected(), Some(Choice::Large));
+    }
+}
@@ -0,0 +1,657 @@
+use super::widget::Widget;
+use crate::framework::text::{RunKind, TextRun, ELLIPSIS};
+use crate::framework::{
+    layout_child, BoxConstraints, Color, Event, FontCollection, FontStyle, FontWeight, LayoutCache,
+    Offset, ParagraphStyle, Size, State, TextDirection, TextLayout, TextStyle,
+};
+use std::fmt;
+use std::rc::Rc;
+use winit::event::MouseButton;
+use winit::window::CursorIcon;
+
+/// The character standing for an inline widget in the laid out text.
+const OBJECT_REPLACEMENT: char = '\u{FFFC}';
+
+/// The style properties a span sets on top of the style of its paragraph.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct SpanStyle {
+    font_family: Option<Rc<str>>,
+    font_size: Option<f32>,
+    weight: Option<FontWeight>,
+    font_style: Option<FontStyle>,
+    color: Option<Color>,
+    underline: Option<bool>,
+    strikethrough: Option<bool>,
+    letter_spacing: Option<f32>,
+    background: Option<Color>,
+}
+
+impl SpanStyle {
+    /// The style of the span in a paragraph of the given style.
+    fn apply(&self, base: &TextStyle) -> TextStyle {
+        TextStyle {
+            font_family: self
+                .font_family
+                .clone()
+                .or_else(|| base.font_family.clone()),
+            font_size: self.font_size.unwrap_or(base.font_size),
+            weight: self.weight.unwrap_or(base.weight),
+            font_style: self.font_style.unwrap_or(base.font_style),
+            line_height: base.line_height,
+            color: self.color.unwrap_or(base.color),
+            underline: self.underline.unwrap_or(base.underline),
+            strikethrough: self.strikethrough.unwrap_or(base.strikethrough),
+            letter_spacing: self.letter_spacing.unwrap_or(base.letter_spacing),
+            background: self.background.or(base.background),
+        }
+    }
+}
+
+/// TextSpan is a run of text in a [`RichText`].
+///
+/// A span only sets the style properties it is given; the others come from
+/// the style of the paragraph. A span with an `on_tap` callback acts as a
+/// link.
+pub struct TextSpan {
+    text: String,
+    style: SpanStyle,
+    on_tap: Option<Box<dyn FnMut()>>,
+}
+
+impl TextSpan {
+    /// Create a new TextSpan instance with the given text.
+    pub fn new(text: impl Into<String>) -> Self {
+        TextSpan {
+            text: text.into(),
+            style: SpanStyle::default(),
+            on_tap: None,
+        }
+    }
+
+    /// The text of the span.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Set every style property, ignoring the style of the paragraph but for
+    /// the line height.
+    pub fn style(mut self, style: TextStyle) -> Self {
+        self.style = SpanStyle {
+            font_family: style.font_family,
+            font_size: Some(style.font_size),
+            weight: Some(style.weight),
+            font_style: Some(style.font_style),
+            color: Some(style.color),
+            underline: Some(style.underline),
+            strikethrough: Some(style.strikethrough),
+            letter_spacing: Some(style.letter_spacing),
+            background: style.background,
+        };
+        self
+    }
+
+    /// Set the font family.
+    pub fn font_family(mut self, font_family: impl Into<Rc<str>>) -> Self {
+        self.style.font_family = Some(font_family.into());
+        self
+    }
+
+    /// Set the font size in logical pixels.
+    pub fn font_size(mut self, font_size: f32) -> Self {
+        self.style.font_size = Some(font_size);
+        self
+    }
+
+    /// Set the thickness of the glyphs.
+    pub fn weight(mut self, weight: FontWeight) -> Self {
+        self.style.weight = Some(weight);
+        self
+    }
+
+    /// Use the bold weight.
+    pub fn bold(self) -> Self {
+        self.weight(FontWeight::BOLD)
+    }
+
+    /// Use the italic glyphs.
+    pub fn italic(mut self) -> Self {
+        self.style.font_style = Some(FontStyle::Italic);
+        self
+    }
+
+    /// Set the color of the text.
+    pub fn color(mut self, color: Color) -> Self {
+        self.style.color = Some(color);
+        self
+    }
+
+    /// Draw a line below the text.
+    pub fn underline(mut self) -> Self {
+        self.style.underline = Some(true);
+        self
+    }
+
+    /// Draw a line through the text.
+    pub fn strikethrough(mut self) -> Self {
+        self.style.strikethrough = Some(true);
+        self
+    }
+
+    /// Set the extra space after every grapheme.
+    pub fn letter_spacing(mut self, letter_spacing: f32) -> Self {
+        self.style.letter_spacing = Some(letter_spacing);
+        self
+    }
+
+    /// Set the color painted behind the text.
+    pub fn background(mut self, background: Color) -> Self {
+        self.style.background = Some(background);
+        self
+    }
+
+    /// Set the callback called when the span is clicked, making it a link.
+    pub fn on_tap(mut self, on_tap: impl FnMut() + 'static) -> Self {
+        self.on_tap = Some(Box::new(on_tap));
+        self
+    }
+}
+
+impl fmt::Debug for TextSpan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TextSpan")
+            .field("text", &self.text)
+            .field("style", &self.style)
+            .field("is_link", &self.on_tap.is_some())
+            .finish()
+    }
+}
+
+/// A span of a rich text: text, or a widget placed in the text.
+enum Span {
+    Text(TextSpan),
+    Widget {
+        widget: Box<dyn Widget>,
+        size: Size,
+        offset: Offset,
+    },
+}
+
+/// RichText is a paragraph of differently styled spans of text and inline
+/// widgets, such as icons and badges.
+///
+/// Lines break like those of a [`TextLayout`]; inline widgets break like
+/// the object replacement character standing for them in the text, and sit
+/// on the baseline. Unless a style is set, the paragraph uses the body style
+/// of the [`Theme`](crate::framework::Theme).
+///
+/// Spans are numbered in the order they were added, counting inline widgets;
+/// [`RichText::span_at`] returns the span under a position.
+pub struct RichText {
+    spans: Vec<Span>,
+    custom_style: Option<TextStyle>,
+    style: TextStyle,
+    paragraph: ParagraphStyle,
+    text_direction: Option<TextDirection>,
+    fonts: Rc<FontCollection>,
+    /// The text of the spans with an object replacement character for every
+    /// inline widget, as laid out.
+    content: String,
+    text_layout: TextLayout,
+    size: Size,
+    cache: LayoutCache,
+    /// The link under the pointer.
+    hovered: Option<usize>,
+    /// The link the primary button was pressed on.
+    pressed: Option<usize>,
+}
+
+impl RichText {
+    /// Create a new RichText instance without spans.
+    pub fn new() -> Self {
+        let fonts: Rc<FontCollection> = Rc::default();
+        RichText {
+            spans: Vec::new(),
+            custom_style: None,
+            style: TextStyle::default(),
+            paragraph: ParagraphStyle::default(),
+            text_direction: None,
+            content: String::new(),
+            text_layout: TextLayout::new("", &TextStyle::default(), &fonts),
+            fonts,
+            size: Size::ZERO,
+            cache: LayoutCache::default(),
+            hovered: None,
+            pressed: None,
+        }
+    }
+
+    /// Append a span of text.
+    pub fn span(mut self, span: TextSpan) -> Self {
+        self.spans.push(Span::Text(span));
+        self.cache.mark_dirty();
+        self
+    }
+
+    /// Append a widget, which is laid out with the width of the paragraph
+    /// and placed in the text as a single unit.
+    pub fn widget(mut self, widget: impl Widget + 'static) -> Self {
+        self.spans.push(Span::Widget {
+            widget: Box::new(widget),
+            size: Size::ZERO,
+            offset: Offset::ZERO,
+        });
+        self.cache.mark_dirty();
+        self
+    }
+
+    /// Set the style of the paragraph, instead of the body style of the
+    /// theme.
+    pub fn style(mut self, style: TextStyle) -> Self {
+        self.custom_style = Some(style.clone());
+        self.style = style;
+        self.cache.mark_dirty();
+        self
+    }
+
+    /// Set the alignment, maximum number of lines and spacing of the
+    /// paragraph.
+    ///
+    /// The direction of the paragraph style is replaced by the inherited
+    /// text direction when the text is built, unless one is set with
+    /// [`RichText::text_direction`].
+    pub fn paragraph(mut self, paragraph: ParagraphStyle) -> Self {
+        self.paragraph = paragraph;
+        self.cache.mark_dirty();
+        self
+    }
+
+    /// Set the base direction of the text, instead of the inherited one.
+    pub fn text_direction(mut self, text_direction: TextDirection) -> Self {
+        self.text_direction = Some(text_direction);
+        self.paragraph.direction = text_direction;
+        self.cache.mark_dirty();
+        self
+    }
+
+    /// The layout of the text as of the last layout, with an object
+    /// replacement character for every inline widget.
+    pub fn text_layout(&self) -> &TextLayout {
+        &self.text_layout
+    }
+
+    /// The text of the spans, without the inline widgets.
+    pub fn text(&self) -> String {
+        self.spans
+            .iter()
+            .filter_map(|span| match span {
+                Span::Text(span) => Some(span.text.as_str()),
+                Span::Widget { .. } => None,
+            })
+            .collect()
+    }
+
+    /// The index of the span under the position in the last layout, if any.
+    pub fn span_at(&self, position: Offset) -> Option<usize> {
+        self.text_layout.run_at(position)
+    }
+
+    /// The link under the position, if any.
+    fn link_at(&self, position: Offset) -> Option<usize> {
+        self.span_at(position).filter(
+            |index| matches!(&self.spans[*index], Span::Text(span) if span.on_tap.is_some()),
+        )
+    }
+
+    /// Forward the event to the inline widgets until one handles it.
+    fn widget_event(&mut self, event: &Event, state: &mut State<'_>) -> bool {
+        // Pointer moves reach every widget so they notice the pointer leaving.
+        let broadcast = matches!(event, Event::PointerMove { .. } | Event::PointerExit);
+        let mut handled = false;
+        for span in &mut self.spans {
+            if let Span::Widget { widget, offset, .. } = span {
+                handled |= widget.event(&event.to_local(*offset), state);
+                if handled && !broadcast {
+                    break;
+                }
+            }
+        }
+        handled
+    }
+}
+
+impl Default for RichText {
+    fn default() -> Self {
+        RichText::new()
+    }
+}
+
+impl Widget for RichText {
+    fn build(&mut self, state: &mut State<'_>) {
+        let style = self
+            .custom_style
+            .clone()
+            .unwrap_or_else(|| state.theme().typography.body.clone());
+        if style != self.style || !Rc::ptr_eq(&self.fonts, state.fonts()) {
+            self.style = style;
+            self.fonts = Rc::clone(state.fonts());
+            self.cache.mark_dirty();
+        }
+        let direction = self
+            .text_direction
+            .unwrap_or_else(|| state.text_direction());
+        if direction != self.paragraph.direction {
+            self.paragraph.direction = direction;
+            self.cache.mark_dirty();
+        }
+        for span in &mut self.spans {
+            if let Span::Widget { widget, .. } = span {
+                widget.build(state);
+            }
+        }
+    }
+
+    fn layout(&mut self, constraints: BoxConstraints) -> Size {
+        if let Some(size) = self.cache.get(constraints) {
+            if !self.needs_layout() {
+                return size;
+            }
+        }
+        let child_constraints = BoxConstraints::new(0.0, constraints.max_width, 0.0, f32::INFINITY);
+        for span in &mut self.spans {
+            if let Span::Widget { widget, size, .. } = span {
+                *size = layout_child(widget.as_mut(), child_constraints);
+            }
+        }
+        self.content.clear();
+        let mut runs = Vec::with_capacity(self.spans.len());
+        for span in &self.spans {
+            let start = self.content.len();
+            let kind = match span {
+                Span::Text(span) => {
+                    self.content.push_str(&span.text);
+                    RunKind::Text(span.style.apply(&self.style))
+                }
+                Span::Widget { size, .. } => {
+                    self.content.push(OBJECT_REPLACEMENT);
+                    RunKind::Placeholder(*size)
+                }
+            };
+            runs.push(TextRun {
+                range: start..self.content.len(),
+                kind,
+            });
+        }
+        self.text_layout = TextLayout::from_runs(
+            &self.content,
+            &runs,
+            constraints.max_width,
+            &self.paragraph,
+            &self.fonts,
+        );
+        for fragment in self.text_layout.fragments() {
+            if let Span::Widget { offset, .. } = &mut self.spans[fragment.run] {
+                *offset = fragment.rect.origin;
+            }
+        }
+        self.size = constraints.constrain(self.text_layout.size());
+        self.cache.store(constraints, self.size);
+        self.size
+    }
+
+    fn paint(&self, state: &mut State<'_>) {
+        for fragment in self.text_layout.fragments() {
+            match &self.spans[fragment.run] {
+                Span::Text(span) => {
+                    let mut style = span.style.apply(&self.style);
+                    if self.hovered == Some(fragment.run) {
+                        style.underline = true;
+                    }
+                    let text = if fragment.ellipsis {
+                        ELLIPSIS
+                    } else {
+                        &self.content[fragment.range.clone()]
+                    };
+                    state.canvas_mut().draw_directed_text(
+                        fragment.rect.origin,
+                        text,
+                        &style,
+                        fragment.direction(),
+                    );
+                }
+                Span::Widget { .. } if fragment.ellipsis => state.canvas_mut().draw_directed_text(
+                    fragment.rect.origin,
+                    ELLIPSIS,
+                    &self.style,
+                    fragment.direction(),
+                ),
+                Span::Widget { widget, offset, .. } => {
+                    let canvas = state.canvas_mut();
+                    canvas.save();
+                    canvas.translate(*offset);
+                    widget.paint(state);
+                    state.canvas_mut().restore();
+                }
+            }
+        }
+        if self.hovered.is_some() {
+            state.request_cursor(CursorIcon::Pointer);
+        }
+    }
+
+    fn event(&mut self, event: &Event, state: &mut State<'_>) -> bool {
+        if self.widget_event(event, state) {
+            return true;
+        }
+        match event {
+            Event::PointerMove { position } => self.hovered = self.link_at(*position),
+            Event::PointerExit => self.hovered = None,
+            Event::PointerDown {
+                position,
+                button: MouseButton::Left,
+            } => {
+                self.pressed = self.link_at(*position);
+                return self.pressed.is_some();
+            }
+            Event::PointerUp {
+                position,
+                button: MouseButton::Left,
+            } => {
+                let Some(pressed) = self.pressed.take() else {
+                    return false;
+                };
+                if self.link_at(*position) == Some(pressed) {
+                    if let Span::Text(TextSpan {
+                        on_tap: Some(on_tap),
+                        ..
+                    }) = &mut self.spans[pressed]
+                    {
+                        on_tap();
+                    }
+                }
+                return true;
+            }
+            _ => {}
+        }
+        false
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.cache.is_dirty()
+            || self.spans.iter().any(|span| match span {
+                Span::Widget { widget, .. } => {
+                    widget.needs_layout() && !widget.is_relayout_boundary()
+                }
+                Span::Text(_) => false,
+            })
+    }
+
+    fn mark_needs_layout(&mut self) {
+        self.cache.mark_dirty();
+    }
+
+    fn is_relayout_boundary(&self) -> bool {
+        self.cache.is_relayout_boundary()
+    }
+}
+
+impl fmt::Debug for RichText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RichText")
+            .field("text", &self.text())
+            .field("spans", &self.spans.len())
+            .field("size", &self.size)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::state::next_version;
+    use crate::framework::widgets::test_support::click;
+    use crate::framework::{DrawCommand, Rect, SizedBox, TextOverflow};
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_span_styles() {
+        let red = Color::rgb(1.0, 0.0, 0.0);
+        let mut text = RichText::new()
+            .style(TextStyle::new(10.0).line_height(2.0))
+            .span(TextSpan::new("plain "))
+            .span(TextSpan::new("bold").bold().color(red).underline())
+            .span(TextSpan::new(" wide").letter_spacing(4.0));
+        // Six pixels per grapheme, plus the letter spacing of the last span.
+        let size = text.layout(BoxConstraints::loose(Size::new(500.0, 100.0)));
+        assert_eq!(size, Size::new(90.0 + 5.0 * 4.0, 20.0));
+        assert_eq!(text.text(), "plain bold wide");
+
+        let mut state = State::default();
+        text.paint(&mut state);
+        let commands = state.canvas().commands();
+        let bold = commands.iter().find_map(|command| match command {
+            DrawCommand::Text {
+                text,
+                weight,
+                color,
+                ..
+            } if text == "bold" => Some((*weight, *color)),
+            _ => None,
+        });
+        assert_eq!(bold, Some((FontWeight::BOLD, red)));
+        // The underline is a rectangle below the bold span.
+        assert!(commands.contains(&DrawCommand::FillRect {
+            rect: Rect::new(36.0, 14.0, 24.0, 1.0),
+            color: red,
+        }));
+    }
+
+    #[test]
+    fn test_inline_widgets_wrap() {
+        let mut text = RichText::new()
+            .style(TextStyle::new(10.0).line_height(2.0))
+            .span(TextSpan::new("ab "))
+            .widget(SizedBox::new(30.0, 12.0))
+            .span(TextSpan::new(" cd"));
+        let size = text.layout(BoxConstraints::loose(Size::new(40.0, 100.0)));
+        // The widget does not fit after "ab " and moves to the next line,
+        // followed by the space; "cd" does not fit after them.
+        assert_eq!(size, Size::new(30.0, 60.0));
+        assert_eq!(text.span_at(Offset::new(5.0, 25.0)), Some(1));
+        assert_eq!(text.span_at(Offset::new(5.0, 45.0)), Some(2));
+        // The widget sits on the baseline, 13 pixels down the second line.
+        assert!(matches!(
+            text.spans[1],
+            Span::Widget { offset, .. } if offset == Offset::new(0.0, 21.0)
+        ));
+    }
+
+    #[test]
+    fn test_link_tap() {
+        let taps = Rc::new(Cell::new(0));
+        let counter = Rc::clone(&taps);
+        let mut text = RichText::new()
+            .style(TextStyle::new(10.0).line_height(2.0))
+            .span(TextSpan::new("see "))
+            .span(TextSpan::new("docs").on_tap(move || counter.set(counter.get() + 1)));
+        text.layout(BoxConstraints::loose(Size::new(500.0, 100.0)));
+        let mut state = State::default();
+
+        click(&mut text, &mut state, Offset::new(5.0, 5.0));
+        assert_eq!(taps.get(), 0);
+        click(&mut text, &mut state, Offset::new(30.0, 5.0));
+        assert_eq!(taps.get(), 1);
+
+        text.event(
+            &Event::PointerMove {
+                position: Offset::new(30.0, 5.0),
+            },
+            &mut state,
+        );
+        state.begin_cursor_frame();
+        text.paint(&mut state);
+        state.end_cursor_frame();
+        assert_eq!(state.cursor(), CursorIcon::Pointer);
+    }
+
+    #[test]
+    fn test_max_lines_ellipsis() {
+        let mut text = RichText::new()
+            .style(TextStyle::new(10.0).line_height(2.0))
+            .paragraph(ParagraphStyle::default().max_lines(1, TextOverflow::Ellipsis))
+            .span(TextSpan::new("first "))
+            .span(TextSpan::new("second").bold());
+        let size = text.layout(BoxConstraints::loose(Size::new(60.0, 100.0)));
+        assert_eq!(size.height, 20.0);
+        assert!(text.text_layout().did_exceed_max_lines());
+
+        let mut state = State::default();
+        text.paint(&mut state);
+        let drawn: Vec<&str> = state
+            .canvas()
+            .commands()
+            .iter()
+            .filter_map(|command| match command {
+                DrawCommand::Text { text, .. } => Some(text.as_str()),
+                DrawCommand::FillRect { .. } => None,
+            })
+            .collect();
+        assert_eq!(drawn, ["first", "\u{2026}"]);
+    }
+
+    #[test]
+    fn test_right_to_left() {
+        let mut text = RichText::new()
+            .style(TextStyle::new(10.0).line_height(2.0))
+            .span(TextSpan::new("\u{5D0}\u{5D1} ab"));
+        let mut state = State::default();
+        state.push_inherited(Rc::new(TextDirection::Rtl), next_version(), None);
+        text.build(&mut state);
+        state.pop_inherited();
+        text.layout(BoxConstraints::loose(Size::new(100.0, 100.0)));
+
+        text.paint(&mut state);
+        let drawn: Vec<_> = state
+            .canvas()
+            .commands()
+            .iter()
+            .filter_map(|command| match command {
+                DrawCommand::Text {
+                    text,
+                    origin,
+                    direction,
+                    ..
+                } => Some((text.as_str(), origin.x, *direction)),
+                DrawCommand::FillRect { .. } => None,
+            })
+            .collect();
+        // The paragraph ends on the left with the Latin word.
+        assert_eq!(
+            drawn,
+            [
+                ("ab", 70.0, TextDirection::Ltr),
+                ("\u{5D0}\u{5D1} ", 82.0, TextDirection::Rtl)
+            ]
+        );
+    }
+}
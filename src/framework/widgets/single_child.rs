@@ -0,0 +1,116 @@
+use super::widget::Widget;
+use crate::framework::{layout_child, BoxConstraints, Event, LayoutCache, Offset, Size, State};
+
+/// SingleChild holds the optional child of a single-child widget together
+/// with the results of its layout.
+///
+/// It implements the parts every single-child widget shares: the layout cache
+/// of the owning widget, laying out and positioning the child, and painting
+/// the child at its offset.
+#[derive(Default)]
+pub(crate) struct SingleChild {
+    child: Option<Box<dyn Widget>>,
+    constraints: BoxConstraints,
+    size: Size,
+    offset: Offset,
+    cache: LayoutCache,
+}
+
+impl SingleChild {
+    /// Set the child widget.
+    pub(crate) fn set(&mut self, child: impl Widget + 'static) {
+        self.child = Some(Box::new(child));
+        self.cache.mark_dirty();
+    }
+
+    /// Returns true if there is a child widget.
+    pub(crate) fn is_some(&self) -> bool {
+        self.child.is_some()
+    }
+
+    /// Build the child widget.
+    pub(crate) fn build(&mut self, state: &mut State<'_>) {
+        if let Some(child) = &mut self.child {
+            child.build(state);
+        }
+    }
+
+    /// Returns the size of the owning widget if it can skip its layout for
+    /// the given constraints.
+    ///
+    /// Dirty relayout boundaries below are laid out again on the way.
+    pub(crate) fn cached(&mut self, constraints: BoxConstraints) -> Option<Size> {
+        let size = self.cache.get(constraints)?;
+        // Only relayout boundaries below can be dirty and they keep their
+        // size, so visit them without laying out the owning widget again.
+        if self.cache.take_dirty_descendant() {
+            if let Some(child) = &mut self.child {
+                layout_child(child.as_mut(), self.constraints);
+            }
+        }
+        Some(size)
+    }
+
+    /// Store the size of the owning widget for the given constraints.
+    pub(crate) fn store(&mut self, constraints: BoxConstraints, size: Size) -> Size {
+        self.cache.store(constraints, size);
+        size
+    }
+
+    /// Lay out the child, returning its size, or `None` if there is no child.
+    pub(crate) fn layout(&mut self, constraints: BoxConstraints) -> Option<Size> {
+        let child = self.child.as_mut()?;
+        self.constraints = constraints;
+        self.size = layout_child(child.as_mut(), constraints);
+        Some(self.size)
+    }
+
+    /// The size the child reported in the last layout.
+    pub(crate) fn size(&self) -> Size {
+        self.size
+    }
+
+    /// The position of the child relative to the owning widget.
+    pub(crate) fn offset(&self) -> Offset {
+        self.offset
+    }
+
+    /// Set the position of the child relative to the owning widget.
+    pub(crate) fn set_offset(&mut self, offset: Offset) {
+        self.offset = offset;
+    }
+
+    /// Paint the child at its offset.
+    pub(crate) fn paint(&self, state: &mut State<'_>) {
+        if let Some(child) = &self.child {
+            let canvas = state.canvas_mut();
+            canvas.save();
+            canvas.translate(self.offset);
+            child.paint(state);
+            state.canvas_mut().restore();
+        }
+    }
+
+    /// Forward an event to the child, relative to its offset.
+    pub(crate) fn event(&mut self, event: &Event, state: &mut State<'_>) -> bool {
+        match &mut self.child {
+            Some(child) => child.event(&event.to_local(self.offset), state),
+            None => false,
+        }
+    }
+
+    /// Returns true if the owning widget must be laid out again.
+    pub(crate) fn needs_layout(&self) -> bool {
+        self.cache.is_dirty()
+    }
+
+    /// Mark the layout of the owning widget as stale.
+    pub(crate) fn mark_needs_layout(&mut self) {
+        self.cache.mark_dirty();
+    }
+
+    /// Returns true if the owning widget was laid out with tight constraints.
+    pub(crate) fn is_relayout_boundary(&self) -> bool {
+        self.cache.is_relayout_boundary()
+    }
+}
@@ -0,0 +1,119 @@
+use super::constrained_box::layout_constrained;
+use super::single_child::SingleChild;
+use super::widget::Widget;
+use crate::framework::{BoxConstraints, Event, Size, State};
+use std::fmt;
+
+/// SizedBox is a widget with a fixed width and/or height.
+///
+/// A dimension left as `None` is sized by the child, or as small as possible
+/// without a child.
+#[derive(Default)]
+pub struct SizedBox {
+    child: SingleChild,
+    width: Option<f32>,
+    height: Option<f32>,
+}
+
+impl SizedBox {
+    /// Create a new SizedBox instance with the given width and height.
+    pub fn new(width: f32, height: f32) -> Self {
+        SizedBox {
+            width: Some(width),
+            height: Some(height),
+            ..Default::default()
+        }
+    }
+
+    /// Create a box with only a fixed width.
+    pub fn from_width(width: f32) -> Self {
+        SizedBox {
+            width: Some(width),
+            ..Default::default()
+        }
+    }
+
+    /// Create a box with only a fixed height.
+    pub fn from_height(height: f32) -> Self {
+        SizedBox {
+            height: Some(height),
+            ..Default::default()
+        }
+    }
+
+    /// Create a box that becomes as large as its parent allows.
+    pub fn expand() -> Self {
+        SizedBox::new(f32::INFINITY, f32::INFINITY)
+    }
+
+    /// Create a box that becomes as small as its parent allows.
+    pub fn shrink() -> Self {
+        SizedBox::new(0.0, 0.0)
+    }
+
+    /// Set the child of the box.
+    pub fn child(mut self, child: impl Widget + 'static) -> Self {
+        self.child.set(child);
+        self
+    }
+}
+
+impl Widget for SizedBox {
+    fn build(&mut self, state: &mut State<'_>) {
+        self.child.build(state);
+    }
+
+    fn layout(&mut self, constraints: BoxConstraints) -> Size {
+        let additional = BoxConstraints::tight_for(self.width, self.height);
+        layout_constrained(&mut self.child, additional, constraints)
+    }
+
+    fn paint(&self, state: &mut State<'_>) {
+        self.child.paint(state);
+    }
+
+    fn event(&mut self, event: &Event, state: &mut State<'_>) -> bool {
+        self.child.event(event, state)
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.child.needs_layout()
+    }
+
+    fn mark_needs_layout(&mut self) {
+        self.child.mark_needs_layout();
+    }
+
+    fn is_relayout_boundary(&self) -> bool {
+        self.child.is_relayout_boundary()
+    }
+}
+
+impl fmt::Debug for SizedBox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SizedBox")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("has_child", &self.child.is_some())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sized_box() {
+        let loose = BoxConstraints::loose(Size::new(100.0, 100.0));
+        assert_eq!(
+            SizedBox::new(20.0, 30.0).layout(loose),
+            Size::new(20.0, 30.0)
+        );
+        assert_eq!(SizedBox::expand().layout(loose), Size::new(100.0, 100.0));
+        assert_eq!(SizedBox::shrink().layout(loose), Size::ZERO);
+
+        let mut sized = SizedBox::from_width(40.0).child(SizedBox::new(10.0, 10.0));
+        assert_eq!(sized.layout(loose), Size::new(40.0, 10.0));
+    }
+}
@@ -0,0 +1,758 @@
+use super::toggleable::{Toggleable, HALO_RADIUS};
+use super::widget::Widget;
+use super::widget_states::WidgetStates;
+use crate::framework::{
+    BoxConstraints, Color, ColorScheme, Event, FocusId, Offset, Rect, Size, State,
+};
+use std::fmt;
+use winit::event::MouseButton;
+use winit::keyboard::{Key, NamedKey};
+
+/// The width of a slider laid out without a bounded width.
+const DEFAULT_WIDTH: f32 = 200.0;
+
+/// The height of a slider, enough for the halo around the thumb.
+const HEIGHT: f32 = HALO_RADIUS * 2.0;
+
+/// The radius of a thumb.
+const THUMB_RADIUS: f32 = 10.0;
+
+/// The height of the track.
+const TRACK_HEIGHT: f32 = 4.0;
+
+/// The number of steps across the track when moving a continuous slider with
+/// the arrow keys.
+const KEYBOARD_STEPS: f32 = 20.0;
+
+/// SliderTrack maps between slider values and positions along the track.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SliderTrack {
+    min: f32,
+    max: f32,
+    divisions: Option<usize>,
+}
+
+impl SliderTrack {
+    /// Clamp the value to the range and round it to the nearest division.
+    fn snap(&self, value: f32) -> f32 {
+        let value = value.clamp(self.min, self.max);
+        match self.divisions {
+            Some(divisions) if divisions > 0 && self.max > self.min => {
+                let step = (self.max - self.min) / divisions as f32;
+                self.min + ((value - self.min) / step).round() * step
+            }
+            _ => value,
+        }
+    }
+
+    /// The amount an arrow key changes the value by.
+    fn step(&self) -> f32 {
+        match self.divisions {
+            Some(divisions) if divisions > 0 => (self.max - self.min) / divisions as f32,
+            _ => (self.max - self.min) / KEYBOARD_STEPS,
+        }
+    }
+
+    /// The usable part of the track; the thumbs stay fully inside the slider.
+    fn bounds(width: f32) -> (f32, f32) {
+        (HALO_RADIUS, (width - HALO_RADIUS * 2.0).max(0.0))
+    }
+
+    /// The horizontal position of the value.
+    fn x_for_value(&self, value: f32, width: f32) -> f32 {
+        let (start, extent) = SliderTrack::bounds(width);
+        let t = if self.max > self.min {
+            (value - self.min) / (self.max - self.min)
+        } else {
+            0.0
+        };
+        start + extent * t
+    }
+
+    /// The snapped value at the horizontal position.
+    fn value_for_x(&self, x: f32, width: f32) -> f32 {
+        let (start, extent) = SliderTrack::bounds(width);
+        let t = if extent > 0.0 {
+            ((x - start) / extent).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        self.snap(self.min + (self.max - self.min) * t)
+    }
+
+    /// The value after pressing the key, or `None` if the key is not a slider key.
+    fn value_for_key(&self, value: f32, key: &Key) -> Option<f32> {
+        let value = match key {
+            Key::Named(NamedKey::ArrowRight | NamedKey::ArrowUp) => value + self.step(),
+            Key::Named(NamedKey::ArrowLeft | NamedKey::ArrowDown) => value - self.step(),
+            Key::Named(NamedKey::PageUp) => value + self.step() * 5.0,
+            Key::Named(NamedKey::PageDown) => value - self.step() * 5.0,
+            Key::Named(NamedKey::Home) => self.min,
+            Key::Named(NamedKey::End) => self.max,
+            _ => return None,
+        };
+        Some(self.snap(value))
+    }
+
+    /// Paint the track, with the part between the positions highlighted.
+    fn paint(&self, state: &mut State<'_>, width: f32, active: (f32, f32), colors: (Color, Color)) {
+        let (active_color, inactive_color) = colors;
+        let (start, extent) = SliderTrack::bounds(width);
+        let top = HEIGHT / 2.0 - TRACK_HEIGHT / 2.0;
+        let canvas = state.canvas_mut();
+        canvas.fill_rect(Rect::new(start, top, extent, TRACK_HEIGHT), inactive_color);
+        canvas.fill_rect(
+            Rect::new(active.0, top, active.1 - active.0, TRACK_HEIGHT),
+            active_color,
+        );
+        if let Some(divisions) = self.divisions.filter(|divisions| *divisions > 0) {
+            for division in 0..=divisions {
+                let x = start + extent * division as f32 / divisions as f32;
+                let color = if x >= active.0 && x <= active.1 {
+                    inactive_color
+                } else {
+                    active_color
+                };
+                canvas.fill_rect(Rect::new(x - 1.0, HEIGHT / 2.0 - 1.0, 2.0, 2.0), color);
+            }
+        }
+    }
+}
+
+/// The preferred size of a slider within the constraints.
+fn preferred_size(constraints: BoxConstraints) -> Size {
+    let width = if constraints.has_bounded_width() {
+        constraints.max_width
+    } else {
+        DEFAULT_WIDTH
+    };
+    Size::new(width, HEIGHT)
+}
+
+/// The track colors for the enabled state and the active color, which
+/// defaults to the primary color of the scheme.
+fn track_colors(enabled: bool, active: Option<Color>, colors: &ColorScheme) -> (Color, Color) {
+    if enabled {
+        let active = active.unwrap_or(colors.primary);
+        (active, active.with_alpha(0.24))
+    } else {
+        (colors.disabled, colors.disabled.with_alpha(0.12))
+    }
+}
+
+/// Slider is a control that selects a value from a range.
+///
+/// The value is changed by dragging the thumb or clicking the track, or with
+/// the arrow keys, Page Up, Page Down, Home and End while focused. With
+/// divisions the value snaps to evenly spaced steps. A slider without an
+/// `on_changed` callback is disabled. Without an active color the primary
+/// color of the [`Theme`](crate::framework::Theme) is used.
+pub struct Slider {
+    toggleable: Toggleable,
+    track: SliderTrack,
+    value: f32,
+    enabled: bool,
+    active_color: Option<Color>,
+    on_changed: Option<Box<dyn FnMut(f32)>>,
+    on_change_end: Option<Box<dyn FnMut(f32)>>,
+}
+
+impl Slider {
+    /// Create a new Slider instance with the value in the range from 0 to 1.
+    pub fn new(value: f32) -> Self {
+        let mut slider = Slider {
+            toggleable: Toggleable::default(),
+            track: SliderTrack {
+                min: 0.0,
+                max: 1.0,
+                divisions: None,
+            },
+            value,
+            enabled: true,
+            active_color: None,
+            on_changed: None,
+            on_change_end: None,
+        };
+        slider.update_states();
+        slider
+    }
+
+    /// Set the range of values.
+    pub fn range(mut self, min: f32, max: f32) -> Self {
+        self.track.min = min;
+        self.track.max = max.max(min);
+        self.value = self.track.snap(self.value);
+        self
+    }
+
+    /// Snap the value to the given number of evenly spaced steps.
+    pub fn divisions(mut self, divisions: usize) -> Self {
+        self.track.divisions = Some(divisions);
+        self.value = self.track.snap(self.value);
+        self
+    }
+
+    /// Set the color of the thumb and the track below the value.
+    pub fn active_color(mut self, color: Color) -> Self {
+        self.active_color = Some(color);
+        self
+    }
+
+    /// Set whether the slider accepts input.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self.update_states();
+        self
+    }
+
+    /// Set the callback called with the new value whenever it changes.
+    pub fn on_changed(mut self, on_changed: impl FnMut(f32) + 'static) -> Self {
+        self.on_changed = Some(Box::new(on_changed));
+        self.update_states();
+        self
+    }
+
+    /// Set the callback called with the final value when a drag ends.
+    pub fn on_change_end(mut self, on_change_end: impl FnMut(f32) + 'static) -> Self {
+        self.on_change_end = Some(Box::new(on_change_end));
+        self
+    }
+
+    /// The current value.
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    /// The focus identifier of the slider.
+    pub fn focus_id(&self) -> FocusId {
+        self.toggleable.focus_id()
+    }
+
+    /// The current interactive states.
+    pub fn states(&self) -> WidgetStates {
+        self.toggleable.states()
+    }
+
+    fn update_states(&mut self) {
+        self.toggleable
+            .set_enabled(self.enabled && self.on_changed.is_some());
+    }
+
+    fn set_value(&mut self, value: f32) {
+        if value == self.value {
+            return;
+        }
+        self.value = value;
+        if let Some(on_changed) = &mut self.on_changed {
+            on_changed(value);
+        }
+    }
+}
+
+impl Widget for Slider {
+    fn build(&mut self, _state: &mut State<'_>) {}
+
+    fn layout(&mut self, constraints: BoxConstraints) -> Size {
+        self.toggleable
+            .layout(constraints, preferred_size(constraints))
+    }
+
+    fn paint(&self, state: &mut State<'_>) {
+        let focused = self.toggleable.register_focus(state);
+        let width = self.toggleable.size().width;
+        let enabled = self.toggleable.is_enabled();
+        let colors = track_colors(enabled, self.active_color, &state.theme().colors);
+        let (start, _) = SliderTrack::bounds(width);
+        let x = self.track.x_for_value(self.value, width);
+        self.track.paint(state, width, (start, x), colors);
+
+        let thumb = Offset::new(x, HEIGHT / 2.0);
+        self.toggleable.paint_halo(state, thumb, colors.0, focused);
+        state
+            .canvas_mut()
+            .fill_circle(thumb, THUMB_RADIUS, colors.0);
+    }
+
+    fn event(&mut self, event: &Event, state: &mut State<'_>) -> bool {
+        if !self.toggleable.is_enabled() {
+            return false;
+        }
+        let width = self.toggleable.size().width;
+        let dragging = self.toggleable.states().contains(WidgetStates::DRAGGED);
+        match event {
+            Event::PointerDown {
+                position,
+                button: MouseButton::Left,
+            } if self.toggleable.contains(*position) => {
+                state.focus_mut().request_focus(self.focus_id());
+                self.toggleable.set_states(WidgetStates::DRAGGED, true);
+                self.set_value(self.track.value_for_x(position.x, width));
+                true
+            }
+            Event::PointerMove { position } if dragging => {
+                self.set_value(self.track.value_for_x(position.x, width));
+                true
+            }
+            Event::PointerUp {
+                button: MouseButton::Left,
+                ..
+            } if dragging => {
+                self.toggleable.set_states(WidgetStates::DRAGGED, false);
+                if let Some(on_change_end) = &mut self.on_change_end {
+                    on_change_end(self.value);
+                }
+                true
+            }
+            Event::PointerMove { position } => {
+                let hovered = self.toggleable.contains(*position);
+                self.toggleable.set_states(WidgetStates::HOVERED, hovered);
+                false
+            }
+            Event::PointerExit => {
+                self.toggleable.set_states(WidgetStates::HOVERED, false);
+                false
+            }
+            Event::KeyDown { key, .. } if state.focus().is_focused(self.focus_id()) => {
+                match self.track.value_for_key(self.value, key) {
+                    Some(value) => {
+                        self.set_value(value);
+                        true
+                    }
+                    None => false,
+                }
+            }
+            _ => false,
+        }
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.toggleable.needs_layout()
+    }
+
+    fn mark_needs_layout(&mut self) {
+        self.toggleable.mark_needs_layout();
+    }
+
+    fn is_relayout_boundary(&self) -> bool {
+        self.toggleable.is_relayout_boundary()
+    }
+}
+
+impl fmt::Debug for Slider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Slider")
+            .field("value", &self.value)
+            .field("min", &self.track.min)
+            .field("max", &self.track.max)
+            .field("divisions", &self.track.divisions)
+            .field("states", &self.toggleable.states())
+            .finish_non_exhaustive()
+    }
+}
+
+/// The thumbs of a [`RangeSlider`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Thumb {
+    Start,
+    End,
+}
+
+/// RangeSlider is a control that selects a range of values.
+///
+/// It has a thumb for each end of the range, which can be dragged or focused
+/// separately; the thumbs cannot pass each other. A range slider without an
+/// `on_changed` callback is disabled. Without an active color the primary
+/// color of the [`Theme`](crate::framework::Theme) is used.
+pub struct RangeSlider {
+    toggleable: Toggleable,
+    end_focus_id: FocusId,
+    track: SliderTrack,
+    values: (f32, f32),
+    enabled: bool,
+    active_color: Option<Color>,
+    dragged: Option<Thumb>,
+    on_changed: Option<Box<dyn FnMut(f32, f32)>>,
+    on_change_end: Option<Box<dyn FnMut(f32, f32)>>,
+}
+
+impl RangeSlider {
+    /// Create a new RangeSlider instance with the range of values from 0 to 1.
+    pub fn new(start: f32, end: f32) -> Self {
+        let mut slider = RangeSlider {
+            toggleable: Toggleable::default(),
+            end_focus_id: FocusId::new(),
+            track: SliderTrack {
+                min: 0.0,
+                max: 1.0,
+                divisions: None,
+            },
+            values: (start.min(end), start.max(end)),
+            enabled: true,
+            active_color: None,
+            dragged: None,
+            on_changed: None,
+            on_change_end: None,
+        };
+        slider.update_states();
+        slider
+    }
+
+    /// Set the range of values.
+    pub fn range(mut self, min: f32, max: f32) -> Self {
+        self.track.min = min;
+        self.track.max = max.max(min);
+        self.snap_values();
+        self
+    }
+
+    /// Snap the values to the given number of evenly spaced steps.
+    pub fn divisions(mut self, divisions: usize) -> Self {
+        self.track.divisions = Some(divisions);
+        self.snap_values();
+        self
+    }
+
+    /// Set the color of the thumbs and the track between them.
+    pub fn active_color(mut self, color: Color) -> Self {
+        self.active_color = Some(color);
+        self
+    }
+
+    /// Set whether the slider accepts input.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self.update_states();
+        self
+    }
+
+    /// Set the callback called with the new start and end whenever they change.
+    pub fn on_changed(mut self, on_changed: impl FnMut(f32, f32) + 'static) -> Self {
+        self.on_changed = Some(Box::new(on_changed));
+        self.update_states();
+        self
+    }
+
+    /// Set the callback called with the final start and end when a drag ends.
+    pub fn on_change_end(mut self, on_change_end: impl FnMut(f32, f32) + 'static) -> Self {
+        self.on_change_end = Some(Box::new(on_change_end));
+        self
+    }
+
+    /// The current start and end of the range.
+    pub fn values(&self) -> (f32, f32) {
+        self.values
+    }
+
+    /// The focus identifier of the start thumb.
+    pub fn start_focus_id(&self) -> FocusId {
+        self.toggleable.focus_id()
+    }
+
+    /// The focus identifier of the end thumb.
+    pub fn end_focus_id(&self) -> FocusId {
+        self.end_focus_id
+    }
+
+    /// The current interactive states.
+    pub fn states(&self) -> WidgetStates {
+        self.toggleable.states()
+    }
+
+    fn update_states(&mut self) {
+        self.toggleable
+            .set_enabled(self.enabled && self.on_changed.is_some());
+    }
+
+    fn snap_values(&mut self) {
+        self.values = (
+            self.track.snap(self.values.0),
+            self.track.snap(self.values.1),
+        );
+    }
+
+    fn focus_id_of(&self, thumb: Thumb) -> FocusId {
+        match thumb {
+            Thumb::Start => self.start_focus_id(),
+            Thumb::End => self.end_focus_id,
+        }
+    }
+
+    /// The thumb closest to the value, preferring the one that can move
+    /// towards it when both are at the same place.
+    fn closest_thumb(&self, value: f32) -> Thumb {
+        let (start, end) = self.values;
+        if (value - start).abs() < (value - end).abs() || (start == end && value < start) {
+            Thumb::Start
+        } else {
+            Thumb::End
+        }
+    }
+
+    /// Move a thumb to the value, keeping it on its side of the other thumb.
+    fn set_value(&mut self, thumb: Thumb, value: f32) {
+        let (start, end) = self.values;
+        let values = match thumb {
+            Thumb::Start => (value.min(end), end),
+            Thumb::End => (start, value.max(start)),
+        };
+        if values == self.values {
+            return;
+        }
+        self.values = values;
+        if let Some(on_changed) = &mut self.on_changed {
+            on_changed(values.0, values.1);
+        }
+    }
+}
+
+impl Widget for RangeSlider {
+    fn build(&mut self, _state: &mut State<'_>) {}
+
+    fn layout(&mut self, constraints: BoxConstraints) -> Size {
+        self.toggleable
+            .layout(constraints, preferred_size(constraints))
+    }
+
+    fn paint(&self, state: &mut State<'_>) {
+        let start_focused = self.toggleable.register_focus(state);
+        let end_focused = if self.toggleable.is_enabled() {
+            state.focus_mut().register(self.end_focus_id);
+            state.focus().is_focused(self.end_focus_id)
+        } else {
+            false
+        };
+        let width = self.toggleable.size().width;
+        let colors = track_colors(
+            self.toggleable.is_enabled(),
+            self.active_color,
+            &state.theme().colors,
+        );
+        let start = self.track.x_for_value(self.values.0, width);
+        let end = self.track.x_for_value(self.values.1, width);
+        self.track.paint(state, width, (start, end), colors);
+
+        for (thumb, x, focused) in [
+            (Thumb::Start, start, start_focused),
+            (Thumb::End, end, end_focused),
+        ] {
+            let center = Offset::new(x, HEIGHT / 2.0);
+            // Only the dragged or focused thumb shows the halo.
+            if self.dragged == Some(thumb) || focused {
+                self.toggleable.paint_halo(state, center, colors.0, true);
+            }
+            state
+                .canvas_mut()
+                .fill_circle(center, THUMB_RADIUS, colors.0);
+        }
+    }
+
+    fn event(&mut self, event: &Event, state: &mut State<'_>) -> bool {
+        if !self.toggleable.is_enabled() {
+            return false;
+        }
+        let width = self.toggleable.size().width;
+        match event {
+            Event::PointerDown {
+                position,
+                button: MouseButton::Left,
+            } if self.toggleable.contains(*position) => {
+                let value = self.track.value_for_x(position.x, width);
+                let thumb = self.closest_thumb(value);
+                state.focus_mut().request_focus(self.focus_id_of(thumb));
+                self.toggleable.set_states(WidgetStates::DRAGGED, true);
+                self.dragged = Some(thumb);
+                self.set_value(thumb, value);
+                true
+            }
+            Event::PointerMove { position } if self.dragged.is_some() => {
+                if let Some(thumb) = self.dragged {
+                    self.set_value(thumb, self.track.value_for_x(position.x, width));
+                }
+                true
+            }
+            Event::PointerUp {
+                button: MouseButton::Left,
+                ..
+            } if self.dragged.is_some() => {
+                self.dragged = None;
+                self.toggleable.set_states(WidgetStates::DRAGGED, false);
+                if let Some(on_change_end) = &mut self.on_change_end {
+                    on_change_end(self.values.0, self.values.1);
+                }
+                true
+            }
+            Event::PointerMove { position } => {
+                let hovered = self.toggleable.contains(*position);
+                self.toggleable.set_states(WidgetStates::HOVERED, hovered);
+                false
+            }
+            Event::PointerExit => {
+                self.toggleable.set_states(WidgetStates::HOVERED, false);
+                false
+            }
+            Event::KeyDown { key, .. } => {
+                let thumb = if state.focus().is_focused(self.start_focus_id()) {
+                    Thumb::Start
+                } else if state.focus().is_focused(self.end_focus_id) {
+                    Thumb::End
+                } else {
+                    return false;
+                };
+                let value = match thumb {
+                    Thumb::Start => self.values.0,
+                    Thumb::End => self.values.1,
+                };
+                match self.track.value_for_key(value, key) {
+                    Some(value) => {
+                        self.set_value(thumb, value);
+                        true
+                    }
+                    None => false,
+                }
+            }
+            _ => false,
+        }
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.toggleable.needs_layout()
+    }
+
+    fn mark_needs_layout(&mut self) {
+        self.toggleable.mark_needs_layout();
+    }
+
+    fn is_relayout_boundary(&self) -> bool {
+        self.toggleable.is_relayout_boundary()
+    }
+}
+
+impl fmt::Debug for RangeSlider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RangeSlider")
+            .field("values", &self.values)
+            .field("min", &self.track.min)
+            .field("max", &self.track.max)
+            .field("divisions", &self.track.divisions)
+            .field("states", &self.toggleable.states())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::widgets::test_support::key;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn pointer_down(x: f32) -> Event {
+        Event::PointerDown {
+            position: Offset::new(x, 20.0),
+            button: MouseButton::Left,
+        }
+    }
+
+    #[test]
+    fn test_track_mapping() {
+        let track = SliderTrack {
+            min: 0.0,
+            max: 100.0,
+            divisions: Some(4),
+        };
+        assert_eq!(track.snap(30.0), 25.0);
+        assert_eq!(track.snap(130.0), 100.0);
+        assert_eq!(track.x_for_value(50.0, 240.0), 120.0);
+        assert_eq!(track.value_for_x(70.0, 240.0), 25.0);
+        assert_eq!(track.value_for_x(-10.0, 240.0), 0.0);
+        assert_eq!(
+            track.value_for_key(50.0, &Key::Named(NamedKey::ArrowRight)),
+            Some(75.0)
+        );
+        assert_eq!(
+            track.value_for_key(50.0, &Key::Named(NamedKey::End)),
+            Some(100.0)
+        );
+    }
+
+    #[test]
+    fn test_slider_drag_and_keyboard() {
+        let values = Rc::new(RefCell::new(Vec::new()));
+        let ended = Rc::new(RefCell::new(None));
+        let mut slider = {
+            let values = Rc::clone(&values);
+            let ended = Rc::clone(&ended);
+            Slider::new(0.0)
+                .range(0.0, 10.0)
+                .divisions(10)
+                .on_changed(move |value| values.borrow_mut().push(value))
+                .on_change_end(move |value| *ended.borrow_mut() = Some(value))
+        };
+        let mut state = State::default();
+        assert_eq!(
+            slider.layout(BoxConstraints::loose(Size::new(240.0, 100.0))),
+            Size::new(240.0, 40.0)
+        );
+
+        assert!(slider.event(&pointer_down(120.0), &mut state));
+        assert!(slider.states().contains(WidgetStates::DRAGGED));
+        slider.event(
+            &Event::PointerMove {
+                position: Offset::new(141.0, 0.0),
+            },
+            &mut state,
+        );
+        slider.event(
+            &Event::PointerUp {
+                position: Offset::new(141.0, 0.0),
+                button: MouseButton::Left,
+            },
+            &mut state,
+        );
+        assert_eq!(*values.borrow(), [5.0, 6.0]);
+        assert_eq!(*ended.borrow(), Some(6.0));
+
+        assert!(state.focus().is_focused(slider.focus_id()));
+        slider.event(&key(NamedKey::ArrowLeft), &mut state);
+        assert_eq!(slider.value(), 5.0);
+        slider.event(&key(NamedKey::Home), &mut state);
+        assert_eq!(slider.value(), 0.0);
+        assert!(!slider.event(&key(NamedKey::Enter), &mut state));
+    }
+
+    #[test]
+    fn test_range_slider() {
+        let mut slider = RangeSlider::new(8.0, 2.0)
+            .range(0.0, 10.0)
+            .divisions(10)
+            .on_changed(|_, _| {});
+        assert_eq!(slider.values(), (2.0, 8.0));
+        slider.layout(BoxConstraints::loose(Size::new(240.0, 100.0)));
+        let mut state = State::default();
+
+        // The press picks the closest thumb, which cannot pass the other.
+        slider.event(&pointer_down(180.0), &mut state);
+        assert_eq!(slider.values(), (2.0, 8.0));
+        assert!(state.focus().is_focused(slider.end_focus_id()));
+        slider.event(
+            &Event::PointerMove {
+                position: Offset::new(0.0, 0.0),
+            },
+            &mut state,
+        );
+        assert_eq!(slider.values(), (2.0, 2.0));
+        slider.event(
+            &Event::PointerUp {
+                position: Offset::new(0.0, 0.0),
+                button: MouseButton::Left,
+            },
+            &mut state,
+        );
+
+        slider.event(&key(NamedKey::End), &mut state);
+        assert_eq!(slider.values(), (2.0, 10.0));
+        state.focus_mut().request_focus(slider.start_focus_id());
+        slider.event(&key(NamedKey::ArrowRight), &mut state);
+        assert_eq!(slider.values(), (3.0, 10.0));
+    }
+}
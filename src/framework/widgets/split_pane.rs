@@ -0,0 +1,422 @@
+use super::constrained_box::Axis;
+use super::widget::Widget;
+use crate::framework::{
+    layout_child, BoxConstraints, Color, Event, LayoutCache, Offset, Rect, Size, State,
+};
+use std::fmt;
+use winit::event::MouseButton;
+use winit::window::CursorIcon;
+
+/// The default thickness of the divider, which is also its hit area.
+const DIVIDER_THICKNESS: f32 = 6.0;
+
+/// SplitPane places two children next to each other with a draggable divider
+/// between them.
+///
+/// The children share the extent along the axis minus the divider according
+/// to a ratio, which the user changes by dragging the divider. Each child
+/// keeps at least its minimum extent while there is room for both. Split
+/// panes can be nested to divide the space further.
+///
+/// A split pane fills the incoming constraints along its axis, which must
+/// therefore be bounded.
+pub struct SplitPane {
+    axis: Axis,
+    first: Box<dyn Widget>,
+    second: Box<dyn Widget>,
+    ratio: f32,
+    min_first: f32,
+    min_second: f32,
+    divider_thickness: f32,
+    divider_color: Option<Color>,
+    on_changed: Option<Box<dyn FnMut(f32)>>,
+    size: Size,
+    /// The extent of the first child along the axis in the last layout.
+    first_extent: f32,
+    /// The distance from the pointer to the start of the divider while dragging.
+    drag_offset: Option<f32>,
+    hovered: bool,
+    cache: LayoutCache,
+}
+
+impl SplitPane {
+    /// Create a new SplitPane instance dividing the space along the axis.
+    ///
+    /// With [`Axis::Horizontal`] the first child is on the left and the
+    /// second on the right; with [`Axis::Vertical`] the first is on top.
+    pub fn new(axis: Axis, first: impl Widget + 'static, second: impl Widget + 'static) -> Self {
+        SplitPane {
+            axis,
+            first: Box::new(first),
+            second: Box::new(second),
+            ratio: 0.5,
+            min_first: 0.0,
+            min_second: 0.0,
+            divider_thickness: DIVIDER_THICKNESS,
+            divider_color: None,
+            on_changed: None,
+            size: Size::ZERO,
+            first_extent: 0.0,
+            drag_offset: None,
+            hovered: false,
+            cache: LayoutCache::default(),
+        }
+    }
+
+    /// Set the share of the first child, from 0 to 1.
+    pub fn ratio(mut self, ratio: f32) -> Self {
+        self.ratio = ratio.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the minimum extents of the children along the axis.
+    pub fn min_extents(mut self, first: f32, second: f32) -> Self {
+        self.min_first = first.max(0.0);
+        self.min_second = second.max(0.0);
+        self
+    }
+
+    /// Set the thickness of the divider.
+    pub fn divider_thickness(mut self, thickness: f32) -> Self {
+        self.divider_thickness = thickness.max(0.0);
+        self
+    }
+
+    /// Set the color of the divider, instead of the outline variant color of
+    /// the theme.
+    pub fn divider_color(mut self, color: Color) -> Self {
+        self.divider_color = Some(color);
+        self
+    }
+
+    /// Set the callback called with the new ratio while the divider is dragged.
+    pub fn on_changed(mut self, on_changed: impl FnMut(f32) + 'static) -> Self {
+        self.on_changed = Some(Box::new(on_changed));
+        self
+    }
+
+    /// The share of the first child, from 0 to 1.
+    pub fn current_ratio(&self) -> f32 {
+        self.ratio
+    }
+
+    /// Returns true while the divider is dragged.
+    pub fn is_dragging(&self) -> bool {
+        self.drag_offset.is_some()
+    }
+
+    /// The extent along the axis and across it.
+    fn extents(&self, size: Size) -> (f32, f32) {
+        match self.axis {
+            Axis::Horizontal => (size.width, size.height),
+            Axis::Vertical => (size.height, size.width),
+        }
+    }
+
+    fn main(&self, position: Offset) -> f32 {
+        match self.axis {
+            Axis::Horizontal => position.x,
+            Axis::Vertical => position.y,
+        }
+    }
+
+    fn size_for(&self, main: f32, cross: f32) -> Size {
+        match self.axis {
+            Axis::Horizontal => Size::new(main, cross),
+            Axis::Vertical => Size::new(cross, main),
+        }
+    }
+
+    fn offset_for(&self, main: f32) -> Offset {
+        match self.axis {
+            Axis::Horizontal => Offset::new(main, 0.0),
+            Axis::Vertical => Offset::new(0.0, main),
+        }
+    }
+
+    /// The space shared by the children.
+    fn available(&self) -> f32 {
+        (self.extents(self.size).0 - self.divider_thickness).max(0.0)
+    }
+
+    /// The extent of the first child for the ratio, within the minimum extents.
+    fn first_extent_for(&self, available: f32) -> f32 {
+        let max = (available - self.min_second).max(0.0);
+        (self.ratio * available)
+            .min(max)
+            .max(self.min_first.min(available))
+    }
+
+    fn divider(&self) -> Rect {
+        let (_, cross) = self.extents(self.size);
+        Rect {
+            origin: self.offset_for(self.first_extent),
+            size: self.size_for(self.divider_thickness, cross),
+        }
+    }
+
+    fn second_offset(&self) -> Offset {
+        self.offset_for(self.first_extent + self.divider_thickness)
+    }
+
+    fn drag_to(&mut self, position: Offset, drag_offset: f32) {
+        let available = self.available();
+        if available <= 0.0 {
+            return;
+        }
+        self.ratio = ((self.main(position) - drag_offset) / available).clamp(0.0, 1.0);
+        let first_extent = self.first_extent_for(available);
+        // Keep the ratio in line with the minimum extents.
+        self.ratio = first_extent / available;
+        if first_extent == self.first_extent {
+            return;
+        }
+        self.first_extent = first_extent;
+        self.cache.mark_dirty();
+        if let Some(on_changed) = &mut self.on_changed {
+            on_changed(self.ratio);
+        }
+    }
+}
+
+impl Widget for SplitPane {
+    fn build(&mut self, state: &mut State<'_>) {
+        self.first.build(state);
+        self.second.build(state);
+    }
+
+    fn layout(&mut self, constraints: BoxConstraints) -> Size {
+        // Dirty relayout boundaries below are reached by laying out both
+        // children again.
+        if let Some(size) = self
+            .cache
+            .get(constraints)
+            .filter(|_| !self.cache.take_dirty_descendant())
+        {
+            return size;
+        }
+        let (main, cross) = self.extents(constraints.biggest());
+        let (min_main, min_cross) = self.extents(constraints.smallest());
+        let main = if main.is_finite() { main } else { min_main };
+        self.size = self.size_for(main, cross);
+
+        let available = self.available();
+        self.first_extent = self.first_extent_for(available);
+        let second_extent = available - self.first_extent;
+        // The children fill the cross axis when it is bounded.
+        let child_constraints = |extent: f32| match (self.axis, cross.is_finite()) {
+            (Axis::Horizontal, true) => BoxConstraints::tight(Size::new(extent, cross)),
+            (Axis::Horizontal, false) => BoxConstraints::new(extent, extent, min_cross, cross),
+            (Axis::Vertical, true) => BoxConstraints::tight(Size::new(cross, extent)),
+            (Axis::Vertical, false) => BoxConstraints::new(min_cross, cross, extent, extent),
+        };
+        let first_constraints = child_constraints(self.first_extent);
+        let second_constraints = child_constraints(second_extent);
+        let first = layout_child(self.first.as_mut(), first_constraints);
+        let second = layout_child(self.second.as_mut(), second_constraints);
+
+        if !cross.is_finite() {
+            let cross = self.extents(first).1.max(self.extents(second).1);
+            self.size = self.size_for(main, cross);
+        }
+        self.cache.store(constraints, self.size);
+        self.size
+    }
+
+    fn paint(&self, state: &mut State<'_>) {
+        self.first.paint(state);
+        let canvas = state.canvas_mut();
+        canvas.save();
+        canvas.translate(self.second_offset());
+        self.second.paint(state);
+        state.canvas_mut().restore();
+
+        // A thin line in the middle of the divider, thicker while active.
+        let divider = self.divider();
+        let active = self.hovered || self.is_dragging();
+        let line = if active { 2.0 } else { 1.0 };
+        let (main, cross) = self.extents(divider.size);
+        let line_rect = Rect {
+            origin: divider.origin,
+            size: self.size_for(line, cross),
+        }
+        .translate(self.offset_for((main - line) / 2.0));
+        let color = self
+            .divider_color
+            .unwrap_or_else(|| state.theme().colors.outline_variant);
+        state.canvas_mut().fill_rect(line_rect, color);
+        if active {
+            state.request_cursor(match self.axis {
+                Axis::Horizontal => CursorIcon::ColResize,
+                Axis::Vertical => CursorIcon::RowResize,
+            });
+        }
+    }
+
+    fn event(&mut self, event: &Event, state: &mut State<'_>) -> bool {
+        let divider = self.divider();
+        match event {
+            Event::PointerDown {
+                position,
+                button: MouseButton::Left,
+            } if divider.contains(*position) => {
+                self.drag_offset = Some(self.main(*position) - self.first_extent);
+                return true;
+            }
+            Event::PointerMove { position } => {
+                self.hovered = divider.contains(*position);
+                if let Some(drag_offset) = self.drag_offset {
+                    self.drag_to(*position, drag_offset);
+                    return true;
+                }
+            }
+            Event::PointerUp {
+                button: MouseButton::Left,
+                ..
+            } if self.is_dragging() => {
+                self.drag_offset = None;
+                return true;
+            }
+            Event::PointerExit => self.hovered = false,
+            _ => {}
+        }
+
+        match event.position() {
+            // Pointer moves reach both children so they notice the pointer leaving.
+            Some(_) if matches!(event, Event::PointerMove { .. }) => {
+                let first = self.first.event(event, state);
+                let second = self
+                    .second
+                    .event(&event.to_local(self.second_offset()), state);
+                first || second
+            }
+            Some(position) => {
+                if self.main(position) < self.first_extent {
+                    self.first.event(event, state)
+                } else {
+                    self.second
+                        .event(&event.to_local(self.second_offset()), state)
+                }
+            }
+            None => {
+                self.first.event(event, state)
+                    || self
+                        .second
+                        .event(&event.to_local(self.second_offset()), state)
+            }
+        }
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.cache.is_dirty()
+    }
+
+    fn mark_needs_layout(&mut self) {
+        self.cache.mark_dirty();
+    }
+
+    fn is_relayout_boundary(&self) -> bool {
+        self.cache.is_relayout_boundary()
+    }
+}
+
+impl fmt::Debug for SplitPane {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SplitPane")
+            .field("axis", &self.axis)
+            .field("ratio", &self.ratio)
+            .field("min_first", &self.min_first)
+            .field("min_second", &self.min_second)
+            .field("divider_thickness", &self.divider_thickness)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::SizedBox;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn pane(axis: Axis) -> SplitPane {
+        SplitPane::new(axis, SizedBox::expand(), SizedBox::expand())
+            .divider_thickness(10.0)
+            .min_extents(50.0, 100.0)
+    }
+
+    #[test]
+    fn test_layout_with_min_extents() {
+        let constraints = BoxConstraints::tight(Size::new(410.0, 200.0));
+        let mut split = pane(Axis::Horizontal);
+        assert_eq!(split.layout(constraints), Size::new(410.0, 200.0));
+        assert_eq!(split.first_extent, 200.0);
+        assert_eq!(split.second_offset(), Offset::new(210.0, 0.0));
+
+        let mut split = pane(Axis::Horizontal).ratio(0.9);
+        split.layout(constraints);
+        assert_eq!(split.first_extent, 300.0);
+        let mut split = pane(Axis::Vertical).ratio(0.0);
+        split.layout(BoxConstraints::tight(Size::new(100.0, 410.0)));
+        assert_eq!(split.first_extent, 50.0);
+        assert_eq!(split.divider(), Rect::new(0.0, 50.0, 100.0, 10.0));
+    }
+
+    #[test]
+    fn test_drag_divider() {
+        let ratios = Rc::new(RefCell::new(Vec::new()));
+        let mut split = {
+            let ratios = Rc::clone(&ratios);
+            pane(Axis::Horizontal).on_changed(move |ratio| ratios.borrow_mut().push(ratio))
+        };
+        let mut state = State::default();
+        split.layout(BoxConstraints::tight(Size::new(410.0, 200.0)));
+        assert!(!split.needs_layout());
+
+        let button = MouseButton::Left;
+        let at = |x: f32| Offset::new(x, 20.0);
+        split.event(
+            &Event::PointerMove {
+                position: at(205.0),
+            },
+            &mut state,
+        );
+        state.begin_cursor_frame();
+        split.paint(&mut state);
+        state.end_cursor_frame();
+        assert_eq!(state.cursor(), CursorIcon::ColResize);
+
+        assert!(split.event(
+            &Event::PointerDown {
+                position: at(205.0),
+                button
+            },
+            &mut state
+        ));
+        split.event(
+            &Event::PointerMove {
+                position: at(105.0),
+            },
+            &mut state,
+        );
+        assert_eq!(split.first_extent, 100.0);
+        assert!(split.needs_layout());
+        // The second child keeps its minimum extent.
+        split.event(
+            &Event::PointerMove {
+                position: at(400.0),
+            },
+            &mut state,
+        );
+        assert_eq!(split.first_extent, 300.0);
+        split.event(
+            &Event::PointerUp {
+                position: at(400.0),
+                button,
+            },
+            &mut state,
+        );
+        assert!(!split.is_dragging());
+        assert_eq!(*ratios.borrow(), [0.25, 0.75]);
+    }
+}
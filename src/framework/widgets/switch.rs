@@ -0,0 +1,272 @@
+use super::toggleable::{Response, Toggleable};
+use super::widget::Widget;
+use super::widget_states::WidgetStates;
+use crate::framework::{BoxConstraints, Color, Event, FocusId, Offset, Rect, Size, State};
+use std::fmt;
+use std::time::Duration;
+
+/// The size of the track in logical pixels.
+const TRACK_SIZE: Size = Size {
+    width: 36.0,
+    height: 14.0,
+};
+
+/// The radius of the thumb.
+const THUMB_RADIUS: f32 = 10.0;
+
+/// The size of the area that reacts to the pointer.
+const TAP_TARGET_SIZE: Size = Size {
+    width: 56.0,
+    height: 40.0,
+};
+
+/// How long the thumb takes to move to the other side.
+const TOGGLE_DURATION: Duration = Duration::from_millis(150);
+
+/// Switch is a control that turns an option on or off.
+///
+/// The thumb slides to its new position when toggled. The animation is
+/// driven by [`State::frame_time`]; the switch requests frames with
+/// [`State::request_frame`] while [`Switch::is_animating`] returns true. A
+/// switch without an `on_changed` callback is disabled.
+///
+/// Colors that are not set are taken from the [`Theme`](crate::framework::Theme).
+pub struct Switch {
+    toggleable: Toggleable,
+    value: bool,
+    enabled: bool,
+    active_color: Option<Color>,
+    on_changed: Option<Box<dyn FnMut(bool)>>,
+    /// The thumb position, from 0 (off) to 1 (on), when the animation started.
+    animation_from: f32,
+    /// The frame time when the animation started.
+    animation_start: Duration,
+}
+
+impl Switch {
+    /// Create a new Switch instance, on or off.
+    pub fn new(value: bool) -> Self {
+        let mut switch = Switch {
+            toggleable: Toggleable::default(),
+            value,
+            enabled: true,
+            active_color: None,
+            on_changed: None,
+            animation_from: if value { 1.0 } else { 0.0 },
+            animation_start: Duration::ZERO,
+        };
+        switch.update_states();
+        switch
+    }
+
+    /// Set the color of the thumb and track while on.
+    pub fn active_color(mut self, color: Color) -> Self {
+        self.active_color = Some(color);
+        self
+    }
+
+    /// Set whether the switch accepts input.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self.update_states();
+        self
+    }
+
+    /// Set the callback called with the new value when the switch is toggled.
+    pub fn on_changed(mut self, on_changed: impl FnMut(bool) + 'static) -> Self {
+        self.on_changed = Some(Box::new(on_changed));
+        self.update_states();
+        self
+    }
+
+    /// Returns true if the switch is on.
+    pub fn value(&self) -> bool {
+        self.value
+    }
+
+    /// The focus identifier of the switch.
+    pub fn focus_id(&self) -> FocusId {
+        self.toggleable.focus_id()
+    }
+
+    /// The current interactive states.
+    pub fn states(&self) -> WidgetStates {
+        self.toggleable.states()
+    }
+
+    /// Returns true if the thumb is still moving at the given frame time.
+    pub fn is_animating(&self, frame_time: Duration) -> bool {
+        frame_time.saturating_sub(self.animation_start) < TOGGLE_DURATION
+            && self.thumb_position(frame_time) != self.target()
+    }
+
+    fn update_states(&mut self) {
+        self.toggleable
+            .set_enabled(self.enabled && self.on_changed.is_some());
+        self.toggleable.set_selected(self.value);
+    }
+
+    fn target(&self) -> f32 {
+        if self.value {
+            1.0
+        } else {
+            0.0
+        }
+    }
+
+    /// The thumb position, from 0 (off) to 1 (on), at the given frame time.
+    fn thumb_position(&self, frame_time: Duration) -> f32 {
+        let elapsed = frame_time.saturating_sub(self.animation_start);
+        let t = (elapsed.as_secs_f32() / TOGGLE_DURATION.as_secs_f32()).min(1.0);
+        // Ease out, so the thumb slows down as it arrives.
+        let t = 1.0 - (1.0 - t) * (1.0 - t);
+        self.animation_from + (self.target() - self.animation_from) * t
+    }
+
+    fn toggle(&mut self, frame_time: Duration) {
+        self.animation_from = self.thumb_position(frame_time);
+        self.animation_start = frame_time;
+        self.value = !self.value;
+        self.update_states();
+        if let Some(on_changed) = &mut self.on_changed {
+            on_changed(self.value);
+        }
+    }
+}
+
+impl Widget for Switch {
+    fn build(&mut self, _state: &mut State<'_>) {}
+
+    fn layout(&mut self, constraints: BoxConstraints) -> Size {
+        self.toggleable.layout(constraints, TAP_TARGET_SIZE)
+    }
+
+    fn paint(&self, state: &mut State<'_>) {
+        let focused = self.toggleable.register_focus(state);
+        let size = self.toggleable.size();
+        let center = Offset::new(size.width / 2.0, size.height / 2.0);
+        let position = self.thumb_position(state.frame_time());
+        if self.is_animating(state.frame_time()) {
+            state.request_frame();
+        }
+        let travel = TRACK_SIZE.width - TRACK_SIZE.height;
+        let thumb = Offset::new(center.x - travel / 2.0 + travel * position, center.y);
+        let colors = state.theme().colors;
+        // Thumbs are opaque shades of the surface.
+        let shade = |amount: f64| {
+            colors
+                .surface
+                .lerp(colors.on_surface.with_alpha(1.0), amount)
+        };
+        let (track_color, thumb_color) = match (self.toggleable.is_enabled(), self.value) {
+            (false, _) => (colors.disabled, shade(0.26)),
+            (true, true) => {
+                let active = self.active_color.unwrap_or(colors.primary);
+                (active.with_alpha(0.5), active)
+            }
+            (true, false) => (colors.outline, shade(0.02)),
+        };
+        self.toggleable
+            .paint_halo(state, thumb, thumb_color, focused);
+
+        // The track is a rectangle with a half circle at each end.
+        let radius = TRACK_SIZE.height / 2.0;
+        let canvas = state.canvas_mut();
+        canvas.fill_rect(
+            Rect::new(
+                center.x - travel / 2.0,
+                center.y - radius,
+                travel,
+                TRACK_SIZE.height,
+            ),
+            track_color,
+        );
+        canvas.fill_circle(
+            Offset::new(center.x - travel / 2.0, center.y),
+            radius,
+            track_color,
+        );
+        canvas.fill_circle(
+            Offset::new(center.x + travel / 2.0, center.y),
+            radius,
+            track_color,
+        );
+        canvas.fill_circle(thumb, THUMB_RADIUS, thumb_color);
+    }
+
+    fn event(&mut self, event: &Event, state: &mut State<'_>) -> bool {
+        let response = self.toggleable.event(event, state);
+        if response == Response::Activated {
+            self.toggle(state.frame_time());
+        }
+        response.is_handled()
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.toggleable.needs_layout()
+    }
+
+    fn mark_needs_layout(&mut self) {
+        self.toggleable.mark_needs_layout();
+    }
+
+    fn is_relayout_boundary(&self) -> bool {
+        self.toggleable.is_relayout_boundary()
+    }
+}
+
+impl fmt::Debug for Switch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Switch")
+            .field("value", &self.value)
+            .field("enabled", &self.toggleable.is_enabled())
+            .field("states", &self.toggleable.states())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::widgets::test_support::key;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use winit::keyboard::NamedKey;
+
+    #[test]
+    fn test_toggle_animation() {
+        let value = Rc::new(Cell::new(false));
+        let mut switch = {
+            let value = Rc::clone(&value);
+            Switch::new(false).on_changed(move |on| value.set(on))
+        };
+        switch.layout(BoxConstraints::loose(Size::new(100.0, 100.0)));
+        let mut state = State::default();
+        state.focus_mut().request_focus(switch.focus_id());
+        state.set_frame_time(Duration::from_secs(1));
+        assert!(!switch.is_animating(state.frame_time()));
+
+        let enter = key(NamedKey::Enter);
+        assert!(switch.event(&enter, &mut state));
+        assert!(value.get());
+        assert!(switch.states().contains(WidgetStates::SELECTED));
+
+        let start = state.frame_time();
+        assert_eq!(switch.thumb_position(start), 0.0);
+        let halfway = start + TOGGLE_DURATION / 2;
+        assert!(switch.is_animating(halfway));
+        assert!(switch.thumb_position(halfway) > 0.5);
+        assert!(!switch.is_animating(start + TOGGLE_DURATION));
+        assert_eq!(switch.thumb_position(start + TOGGLE_DURATION), 1.0);
+
+        // Toggling mid-way reverses from the current position.
+        state.set_frame_time(halfway);
+        switch.event(&enter, &mut state);
+        assert!(!value.get());
+        let position = switch.thumb_position(halfway);
+        assert!(position > 0.5 && position < 1.0);
+        // Painting the moving thumb asks for the next frame.
+        switch.paint(&mut state);
+        assert!(state.needs_frame());
+    }
+}
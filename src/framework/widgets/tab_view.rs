@@ -0,0 +1,762 @@
+use super::widget::Widget;
+use crate::framework::{
+    layout_child, BoxConstraints, Color, Event, FontCollection, LayoutCache, Offset, Rect, Size,
+    State, TextDirection, TextLayout, TextStyle,
+};
+use std::fmt;
+use std::rc::Rc;
+use winit::event::MouseButton;
+use winit::keyboard::{Key, NamedKey};
+
+/// The height of the tab bar.
+const BAR_HEIGHT: f32 = 40.0;
+
+/// The space left and right of a tab label.
+const TAB_PADDING: f32 = 16.0;
+
+/// The size of the close button of a closable tab.
+const CLOSE_SIZE: f32 = 16.0;
+
+/// The space between a tab label and its close button.
+const CLOSE_GAP: f32 = 8.0;
+
+/// How far a pressed tab moves before it is dragged.
+const DRAG_THRESHOLD: f32 = 4.0;
+
+/// Tab is a page of a [`TabView`] with the label shown in the tab bar.
+///
+/// The content is built the first time the tab is selected and kept while the
+/// tab is open, so it keeps its state when another tab is selected.
+pub struct Tab {
+    label: String,
+    builder: Option<Box<dyn FnOnce() -> Box<dyn Widget>>>,
+    content: Option<Box<dyn Widget>>,
+    closable: bool,
+}
+
+impl Tab {
+    /// Create a new Tab instance with the label and the builder of its content.
+    pub fn new<W: Widget + 'static>(
+        label: impl Into<String>,
+        builder: impl FnOnce() -> W + 'static,
+    ) -> Self {
+        Tab {
+            label: label.into(),
+            builder: Some(Box::new(move || Box::new(builder()))),
+            content: None,
+            closable: false,
+        }
+    }
+
+    /// Set whether the tab shows a close button.
+    pub fn closable(mut self, closable: bool) -> Self {
+        self.closable = closable;
+        self
+    }
+
+    /// The label of the tab.
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Returns true if the content of the tab has been built.
+    pub fn is_built(&self) -> bool {
+        self.content.is_some()
+    }
+
+    /// The content of the tab, built on first use.
+    fn content_mut(&mut self) -> Option<&mut Box<dyn Widget>> {
+        if let Some(builder) = self.builder.take() {
+            self.content = Some(builder());
+        }
+        self.content.as_mut()
+    }
+}
+
+impl fmt::Debug for Tab {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tab")
+            .field("label", &self.label)
+            .field("closable", &self.closable)
+            .field("is_built", &self.is_built())
+            .finish()
+    }
+}
+
+/// The tab pressed by the pointer.
+#[derive(Debug, Clone, Copy)]
+struct Press {
+    index: usize,
+    /// True if the close button of the tab was pressed.
+    on_close: bool,
+    start_x: f32,
+    dragging: bool,
+}
+
+/// TabView shows one of several pages, selected from a bar of tabs above it.
+///
+/// Clicking a tab selects it, and Ctrl+Tab, Ctrl+Shift+Tab, Ctrl+PageDown and
+/// Ctrl+PageUp select the next or previous tab. Closable tabs are closed with
+/// their close button or a middle click, and tabs are reordered by dragging
+/// them along the bar. Colors, and the style unless one is set, are taken
+/// from the [`Theme`](crate::framework::Theme).
+///
+/// The tabs start at the start edge of the bar, the right edge in
+/// right-to-left text.
+pub struct TabView {
+    tabs: Vec<Tab>,
+    selected: usize,
+    style: TextStyle,
+    fonts: Rc<FontCollection>,
+    /// The style set with [`TabView::style`], instead of the theme's.
+    custom_style: Option<TextStyle>,
+    indicator_color: Option<Color>,
+    reorderable: bool,
+    on_selected: Option<Box<dyn FnMut(usize)>>,
+    on_closed: Option<Box<dyn FnMut(usize)>>,
+    on_reordered: Option<Box<dyn FnMut(usize, usize)>>,
+    size: Size,
+    /// The inherited text direction, which decides the side the tabs start
+    /// from.
+    text_direction: TextDirection,
+    /// The bounds of the tabs in the bar.
+    tab_rects: Vec<Rect>,
+    pressed: Option<Press>,
+    hovered: Option<usize>,
+    /// Whether content was created since the last build.
+    needs_build: bool,
+    cache: LayoutCache,
+}
+
+impl Default for TabView {
+    fn default() -> Self {
+        TabView {
+            tabs: Vec::new(),
+            selected: 0,
+            style: TextStyle::default(),
+            fonts: Rc::default(),
+            custom_style: None,
+            indicator_color: None,
+            reorderable: true,
+            needs_build: false,
+            on_selected: None,
+            on_closed: None,
+            on_reordered: None,
+            size: Size::ZERO,
+            text_direction: TextDirection::Ltr,
+            tab_rects: Vec::new(),
+            pressed: None,
+            hovered: None,
+            cache: LayoutCache::default(),
+        }
+    }
+}
+
+impl TabView {
+    /// Create a new TabView instance without tabs.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a tab at the end of the bar.
+    pub fn tab(mut self, tab: Tab) -> Self {
+        self.tabs.push(tab);
+        self.update_tab_rects();
+        self
+    }
+
+    /// Select the tab at the index.
+    pub fn selected(mut self, index: usize) -> Self {
+        self.selected = index.min(self.tabs.len().saturating_sub(1));
+        self
+    }
+
+    /// Set the style of the tab labels, instead of the label style of the theme.
+    pub fn style(mut self, style: TextStyle) -> Self {
+        self.custom_style = Some(style.clone());
+        self.style = style;
+        self.update_tab_rects();
+        self
+    }
+
+    /// Set the color of the line below the selected tab, instead of the
+    /// primary color of the theme.
+    pub fn indicator_color(mut self, color: Color) -> Self {
+        self.indicator_color = Some(color);
+        self
+    }
+
+    /// Set whether tabs can be reordered by dragging them.
+    pub fn reorderable(mut self, reorderable: bool) -> Self {
+        self.reorderable = reorderable;
+        self
+    }
+
+    /// Set the callback called with the index of the newly selected tab.
+    pub fn on_selected(mut self, on_selected: impl FnMut(usize) + 'static) -> Self {
+        self.on_selected = Some(Box::new(on_selected));
+        self
+    }
+
+    /// Set the callback called with the index a tab had before it was closed.
+    pub fn on_closed(mut self, on_closed: impl FnMut(usize) + 'static) -> Self {
+        self.on_closed = Some(Box::new(on_closed));
+        self
+    }
+
+    /// Set the callback called with the old and new index of a moved tab.
+    pub fn on_reordered(mut self, on_reordered: impl FnMut(usize, usize) + 'static) -> Self {
+        self.on_reordered = Some(Box::new(on_reordered));
+        self
+    }
+
+    /// The open tabs in bar order.
+    pub fn tabs(&self) -> &[Tab] {
+        &self.tabs
+    }
+
+    /// The index of the selected tab, or `None` if all tabs are closed.
+    pub fn selected_index(&self) -> Option<usize> {
+        (self.selected < self.tabs.len()).then_some(self.selected)
+    }
+
+    /// Select the tab at the index, if it is not selected already.
+    pub fn select(&mut self, index: usize) {
+        if index >= self.tabs.len() || index == self.selected {
+            return;
+        }
+        self.selected = index;
+        self.cache.mark_dirty();
+        if let Some(on_selected) = &mut self.on_selected {
+            on_selected(index);
+        }
+    }
+
+    /// Close the tab at the index, selecting a neighbor if it was selected.
+    pub fn close(&mut self, index: usize) {
+        if index >= self.tabs.len() {
+            return;
+        }
+        self.tabs.remove(index);
+        self.update_tab_rects();
+        self.cache.mark_dirty();
+        self.hovered = None;
+        if let Some(on_closed) = &mut self.on_closed {
+            on_closed(index);
+        }
+        if index < self.selected {
+            self.selected -= 1;
+        } else if index == self.selected && !self.tabs.is_empty() {
+            self.selected = self.selected.min(self.tabs.len() - 1);
+            if let Some(on_selected) = &mut self.on_selected {
+                on_selected(self.selected);
+            }
+        }
+    }
+
+    /// Move the tab at the index to another index, keeping the selected tab.
+    pub fn move_tab(&mut self, from: usize, to: usize) {
+        if from >= self.tabs.len() || to >= self.tabs.len() || from == to {
+            return;
+        }
+        let tab = self.tabs.remove(from);
+        self.tabs.insert(to, tab);
+        self.update_tab_rects();
+        self.cache.mark_dirty();
+        self.selected = if self.selected == from {
+            to
+        } else if from < self.selected && self.selected <= to {
+            self.selected - 1
+        } else if to <= self.selected && self.selected < from {
+            self.selected + 1
+        } else {
+            self.selected
+        };
+        if let Some(on_reordered) = &mut self.on_reordered {
+            on_reordered(from, to);
+        }
+    }
+
+    /// Place the tabs next to each other in the bar, from its start edge.
+    fn update_tab_rects(&mut self) {
+        let mut x = 0.0;
+        let rtl = self.text_direction == TextDirection::Rtl;
+        let bar_width = self.size.width;
+        self.tab_rects = self
+            .tabs
+            .iter()
+            .map(|tab| {
+                let label = TextLayout::new(&tab.label, &self.style, &self.fonts)
+                    .size()
+                    .width;
+                let close = if tab.closable {
+                    CLOSE_GAP + CLOSE_SIZE
+                } else {
+                    0.0
+                };
+                let width = label + close + TAB_PADDING * 2.0;
+                let left = if rtl { bar_width - x - width } else { x };
+                x += width;
+                Rect::new(left, 0.0, width, BAR_HEIGHT)
+            })
+            .collect();
+    }
+
+    fn close_rect(&self, index: usize) -> Option<Rect> {
+        if !self.tabs.get(index)?.closable {
+            return None;
+        }
+        let rect = self.tab_rects[index];
+        // The close button is at the end of the tab.
+        let left = match self.text_direction {
+            TextDirection::Ltr => rect.right() - TAB_PADDING - CLOSE_SIZE,
+            TextDirection::Rtl => rect.left() + TAB_PADDING,
+        };
+        Some(Rect::new(
+            left,
+            (BAR_HEIGHT - CLOSE_SIZE) / 2.0,
+            CLOSE_SIZE,
+            CLOSE_SIZE,
+        ))
+    }
+
+    fn tab_at(&self, position: Offset) -> Option<usize> {
+        self.tab_rects
+            .iter()
+            .position(|rect| rect.contains(position))
+    }
+
+    fn content_offset() -> Offset {
+        Offset::new(0.0, BAR_HEIGHT)
+    }
+
+    fn bar_event(&mut self, event: &Event) -> bool {
+        match event {
+            Event::PointerDown { position, button } => {
+                let Some(index) = self.tab_at(*position) else {
+                    return position.y < BAR_HEIGHT;
+                };
+                match button {
+                    MouseButton::Left => {
+                        let on_close = self
+                            .close_rect(index)
+                            .is_some_and(|rect| rect.contains(*position));
+                        if !on_close {
+                            self.select(index);
+                        }
+                        self.pressed = Some(Press {
+                            index,
+                            on_close,
+                            start_x: position.x,
+                            dragging: false,
+                        });
+                    }
+                    MouseButton::Middle if self.tabs[index].closable => self.close(index),
+                    _ => {}
+                }
+                true
+            }
+            Event::PointerMove { position } => {
+                self.hovered = self.tab_at(*position);
+                let Some(mut press) = self.pressed else {
+                    return false;
+                };
+                if press.on_close || !self.reorderable {
+                    return true;
+                }
+                press.dragging |= (position.x - press.start_x).abs() > DRAG_THRESHOLD;
+                if press.dragging {
+                    let target = self
+                        .tab_rects
+                        .iter()
+                        .position(|rect| position.x >= rect.left() && position.x < rect.right());
+                    let before_start = match self.text_direction {
+                        TextDirection::Ltr => position.x < 0.0,
+                        TextDirection::Rtl => position.x >= self.size.width,
+                    };
+                    let target =
+                        target.unwrap_or(if before_start { 0 } else { self.tabs.len() - 1 });
+                    if target != press.index {
+                        self.move_tab(press.index, target);
+                        press.index = target;
+                    }
+                }
+                self.pressed = Some(press);
+                true
+            }
+            Event::PointerUp {
+                position,
+                button: MouseButton::Left,
+            } => {
+                let Some(press) = self.pressed.take() else {
+                    return false;
+                };
+                if press.on_close
+                    && self
+                        .close_rect(press.index)
+                        .is_some_and(|rect| rect.contains(*position))
+                {
+                    self.close(press.index);
+                }
+                true
+            }
+            Event::PointerExit => {
+                self.hovered = None;
+                false
+            }
+            _ => false,
+        }
+    }
+
+    fn shortcut(&mut self, event: &Event) -> bool {
+        let Event::KeyDown {
+            key: Key::Named(key),
+            modifiers,
+            ..
+        } = event
+        else {
+            return false;
+        };
+        if !modifiers.control_key() || self.tabs.is_empty() {
+            return false;
+        }
+        let len = self.tabs.len();
+        let next = match key {
+            NamedKey::Tab if modifiers.shift_key() => self.selected + len - 1,
+            NamedKey::PageUp => self.selected + len - 1,
+            NamedKey::Tab | NamedKey::PageDown => self.selected + 1,
+            _ => return false,
+        };
+        self.select(next % len);
+        true
+    }
+}
+
+impl Widget for TabView {
+    fn build(&mut self, state: &mut State<'_>) {
+        let style = self
+            .custom_style
+            .clone()
+            .unwrap_or_else(|| state.theme().typography.label.clone());
+        let text_direction = state.text_direction();
+        if style != self.style
+            || text_direction != self.text_direction
+            || !Rc::ptr_eq(&self.fonts, state.fonts())
+        {
+            self.style = style;
+            self.text_direction = text_direction;
+            self.fonts = Rc::clone(state.fonts());
+            self.update_tab_rects();
+            self.cache.mark_dirty();
+        }
+        if let Some(tab) = self.tabs.get_mut(self.selected) {
+            tab.content_mut();
+        }
+        // Hidden tabs keep their content, which must not miss a change of
+        // what it inherits.
+        for content in self.tabs.iter_mut().filter_map(|tab| tab.content.as_mut()) {
+            content.build(state);
+        }
+        self.needs_build = false;
+    }
+
+    fn layout(&mut self, constraints: BoxConstraints) -> Size {
+        if let Some(size) = self
+            .cache
+            .get(constraints)
+            .filter(|_| !self.cache.take_dirty_descendant())
+        {
+            return size;
+        }
+        let biggest = constraints.biggest();
+        let content_max = Size::new(biggest.width, (biggest.height - BAR_HEIGHT).max(0.0));
+        let content_constraints = if content_max.is_finite() {
+            BoxConstraints::tight(content_max)
+        } else {
+            BoxConstraints::loose(content_max)
+        };
+        let tab = self.tabs.get_mut(self.selected);
+        self.needs_build |= tab.as_ref().is_some_and(|tab| !tab.is_built());
+        let content = match tab.and_then(Tab::content_mut) {
+            Some(content) => layout_child(content.as_mut(), content_constraints),
+            None => Size::ZERO,
+        };
+        let bar_width = self
+            .tab_rects
+            .iter()
+            .map(|rect| rect.size.width)
+            .sum::<f32>();
+        let size = constraints.constrain(Size::new(
+            content.width.max(bar_width),
+            content.height + BAR_HEIGHT,
+        ));
+        if size.width != self.size.width && self.text_direction == TextDirection::Rtl {
+            self.size = size;
+            self.update_tab_rects();
+        }
+        self.size = size;
+        self.cache.store(constraints, size);
+        self.size
+    }
+
+    fn paint(&self, state: &mut State<'_>) {
+        if self.needs_build {
+            state.request_build();
+        }
+        let colors = state.theme().colors;
+        let indicator_color = self.indicator_color.unwrap_or(colors.primary);
+        let canvas = state.canvas_mut();
+        canvas.fill_rect(
+            Rect::new(0.0, BAR_HEIGHT - 1.0, self.size.width, 1.0),
+            colors.outline_variant,
+        );
+        let text_top = (BAR_HEIGHT - self.style.line_extent()) / 2.0;
+        for (index, (tab, rect)) in self.tabs.iter().zip(&self.tab_rects).enumerate() {
+            let selected = index == self.selected;
+            if self.hovered == Some(index) {
+                canvas.fill_rect(*rect, colors.on_surface.with_alpha(0.04));
+            }
+            let style = if selected {
+                self.style.clone().color(indicator_color)
+            } else {
+                self.style.clone().color(colors.on_surface_variant)
+            };
+            let left = match self.text_direction {
+                TextDirection::Ltr => rect.left() + TAB_PADDING,
+                TextDirection::Rtl => {
+                    rect.right() - TAB_PADDING - self.fonts.measure(&tab.label, &style)
+                }
+            };
+            canvas.draw_directed_text(
+                Offset::new(left, text_top),
+                &tab.label,
+                &style,
+                self.text_direction,
+            );
+            if let Some(close) = self.close_rect(index) {
+                let close_style = style.line_height(1.0);
+                canvas.draw_text(
+                    Offset::new(
+                        close.left() + (CLOSE_SIZE - self.fonts.measure("×", &close_style)) / 2.0,
+                        close.top() + (CLOSE_SIZE - close_style.line_extent()) / 2.0,
+                    ),
+                    "×",
+                    &close_style,
+                );
+            }
+            if selected {
+                canvas.fill_rect(
+                    Rect::new(rect.left(), BAR_HEIGHT - 2.0, rect.size.width, 2.0),
+                    indicator_color,
+                );
+            }
+        }
+
+        let content = self
+            .tabs
+            .get(self.selected)
+            .and_then(|tab| tab.content.as_ref());
+        if let Some(content) = content {
+            let canvas = state.canvas_mut();
+            canvas.save();
+            canvas.translate(Self::content_offset());
+            content.paint(state);
+            state.canvas_mut().restore();
+        }
+    }
+
+    fn event(&mut self, event: &Event, state: &mut State<'_>) -> bool {
+        let in_bar = event
+            .position()
+            .is_some_and(|position| position.y < BAR_HEIGHT);
+        // The pressed tab keeps the pointer until it is released.
+        if in_bar || self.pressed.is_some() {
+            if self.bar_event(event) {
+                return true;
+            }
+        } else if matches!(event, Event::PointerMove { .. } | Event::PointerExit) {
+            self.hovered = None;
+        }
+        let handled = match self
+            .tabs
+            .get_mut(self.selected)
+            .and_then(|tab| tab.content.as_mut())
+        {
+            Some(content) => content.event(&event.to_local(Self::content_offset()), state),
+            None => false,
+        };
+        handled || self.shortcut(event)
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.cache.is_dirty()
+    }
+
+    fn mark_needs_layout(&mut self) {
+        self.cache.mark_dirty();
+    }
+
+    fn is_relayout_boundary(&self) -> bool {
+        self.cache.is_relayout_boundary()
+    }
+}
+
+impl fmt::Debug for TabView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TabView")
+            .field("tabs", &self.tabs)
+            .field("selected", &self.selected)
+            .field("reorderable", &self.reorderable)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::state::next_version;
+    use crate::framework::widgets::test_support::click;
+    use crate::framework::SizedBox;
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+    use winit::keyboard::ModifiersState;
+
+    fn tabs(built: &Rc<Cell<usize>>) -> TabView {
+        let tab = |label: &str| {
+            let built = Rc::clone(built);
+            Tab::new(label, move || {
+                built.set(built.get() + 1);
+                SizedBox::expand()
+            })
+            .closable(true)
+        };
+        TabView::new()
+            .tab(tab("One"))
+            .tab(tab("Two"))
+            .tab(tab("Three"))
+    }
+
+    #[test]
+    fn test_lazy_content_and_selection() {
+        let built = Rc::new(Cell::new(0));
+        let mut view = tabs(&built);
+        let mut state = State::default();
+        let constraints = BoxConstraints::tight(Size::new(400.0, 300.0));
+        assert_eq!(view.layout(constraints), Size::new(400.0, 300.0));
+        assert_eq!(built.get(), 1);
+        assert!(view.tabs()[0].is_built() && !view.tabs()[1].is_built());
+        assert!(!view.needs_layout());
+
+        let second = view.tab_rects[1];
+        click(
+            &mut view,
+            &mut state,
+            Offset::new(second.left() + 4.0, 20.0),
+        );
+        assert_eq!(view.selected_index(), Some(1));
+        assert!(view.needs_layout());
+        view.layout(constraints);
+        assert_eq!(built.get(), 2);
+
+        let ctrl_tab = Event::KeyDown {
+            key: Key::Named(NamedKey::Tab),
+            modifiers: ModifiersState::CONTROL,
+            text: None,
+        };
+        assert!(view.event(&ctrl_tab, &mut state));
+        assert_eq!(view.selected_index(), Some(2));
+        view.layout(constraints);
+        assert_eq!(built.get(), 3);
+
+        // Content is built once and kept.
+        view.event(&ctrl_tab, &mut state);
+        assert_eq!(view.selected_index(), Some(0));
+        view.layout(constraints);
+        assert_eq!(built.get(), 3);
+    }
+
+    #[test]
+    fn test_close_and_reorder() {
+        let closed = Rc::new(RefCell::new(Vec::new()));
+        let moved = Rc::new(RefCell::new(Vec::new()));
+        let mut view = {
+            let closed = Rc::clone(&closed);
+            let moved = Rc::clone(&moved);
+            tabs(&Rc::new(Cell::new(0)))
+                .selected(2)
+                .on_closed(move |index| closed.borrow_mut().push(index))
+                .on_reordered(move |from, to| moved.borrow_mut().push((from, to)))
+        };
+        let mut state = State::default();
+        view.layout(BoxConstraints::tight(Size::new(400.0, 300.0)));
+
+        let close = view.close_rect(0).unwrap();
+        click(
+            &mut view,
+            &mut state,
+            Offset::new(close.left() + 2.0, close.top() + 2.0),
+        );
+        assert_eq!(*closed.borrow(), [0]);
+        assert_eq!(view.selected_index(), Some(1));
+        assert_eq!(view.tabs()[1].label(), "Three");
+
+        // Dragging "Two" past "Three" selects and moves it.
+        let first = view.tab_rects[0];
+        let last = view.tab_rects[1];
+        let button = MouseButton::Left;
+        let start = Offset::new(first.left() + 4.0, 20.0);
+        view.event(
+            &Event::PointerDown {
+                position: start,
+                button,
+            },
+            &mut state,
+        );
+        let end = Offset::new(last.right() - 4.0, 20.0);
+        view.event(&Event::PointerMove { position: end }, &mut state);
+        view.event(
+            &Event::PointerUp {
+                position: end,
+                button,
+            },
+            &mut state,
+        );
+        assert_eq!(*moved.borrow(), [(0, 1)]);
+        let labels: Vec<_> = view.tabs().iter().map(Tab::label).collect();
+        assert_eq!(labels, ["Three", "Two"]);
+        assert_eq!(view.selected_index(), Some(1));
+    }
+
+    #[test]
+    fn test_right_to_left() {
+        let built = Rc::new(Cell::new(0));
+        let mut view = tabs(&built);
+        let mut state = State::default();
+        state.push_inherited(Rc::new(TextDirection::Rtl), next_version(), None);
+        view.build(&mut state);
+        state.pop_inherited();
+        view.layout(BoxConstraints::tight(Size::new(400.0, 300.0)));
+
+        // The tabs run from the right edge, with their close buttons on the
+        // left.
+        let (first, second) = (view.tab_rects[0], view.tab_rects[1]);
+        assert_eq!(first.right(), 400.0);
+        assert_eq!(second.right(), first.left());
+        let close = view.close_rect(0).unwrap();
+        assert_eq!(close.left(), first.left() + TAB_PADDING);
+
+        click(
+            &mut view,
+            &mut state,
+            Offset::new(second.right() - 4.0, 20.0),
+        );
+        assert_eq!(view.selected_index(), Some(1));
+        click(
+            &mut view,
+            &mut state,
+            Offset::new(close.left() + 2.0, close.top() + 2.0),
+        );
+        assert_eq!(view.tabs().len(), 2);
+        assert_eq!(view.tab_rects[0].right(), 400.0);
+    }
+}
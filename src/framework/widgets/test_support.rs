@@ -0,0 +1,43 @@
+use super::widget::Widget;
+use crate::framework::{Event, Offset, State};
+use winit::event::MouseButton;
+use winit::keyboard::{Key, ModifiersState, NamedKey};
+
+/// Press and release the primary pointer button at the position.
+pub(super) fn click(widget: &mut impl Widget, state: &mut State<'_>, position: Offset) {
+    click_with(widget, state, position, MouseButton::Left);
+}
+
+/// Press and release a pointer button at the position.
+pub(super) fn click_with(
+    widget: &mut impl Widget,
+    state: &mut State<'_>,
+    position: Offset,
+    button: MouseButton,
+) {
+    widget.event(&Event::PointerDown { position, button }, state);
+    widget.event(&Event::PointerUp { position, button }, state);
+}
+
+/// A press of a named key without modifiers.
+pub(super) fn key(key: NamedKey) -> Event {
+    key_with(Key::Named(key), ModifiersState::empty())
+}
+
+/// A press of a key with the modifiers held, which types no text.
+pub(super) fn key_with(key: Key, modifiers: ModifiersState) -> Event {
+    Event::KeyDown {
+        key,
+        modifiers,
+        text: None,
+    }
+}
+
+/// A key press that types the text.
+pub(super) fn typed(text: &str) -> Event {
+    Event::KeyDown {
+        key: Key::Character(text.into()),
+        modifiers: ModifiersState::empty(),
+        text: Some(text.to_string()),
+    }
+}
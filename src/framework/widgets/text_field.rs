@@ -0,0 +1,1036 @@
+use super::widget::Widget;
+use crate::framework::text::CARET_WIDTH;
+use crate::framework::{
+    BoxConstraints, EdgeInsets, Event, FocusId, FontCollection, InputFilter, LayoutCache, Offset,
+    ParagraphStyle, Rect, Size, State, TextDirection, TextEditor, TextLayout, TextSelection,
+    TextStyle,
+};
+use std::fmt;
+use std::rc::Rc;
+use std::time::Duration;
+use unicode_segmentation::UnicodeSegmentation;
+use winit::event::{Ime, MouseButton};
+use winit::keyboard::{Key, ModifiersState, NamedKey};
+
+/// The width of a text field laid out without a bounded width.
+const DEFAULT_WIDTH: f32 = 200.0;
+
+/// The character shown instead of each grapheme of obscured text.
+const OBSCURING_CHARACTER: char = '•';
+
+/// How long the caret stays visible or hidden while blinking.
+const CARET_BLINK_INTERVAL: Duration = Duration::from_millis(530);
+
+/// The longest time between two clicks that select a word.
+const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(500);
+
+/// Called with the text of the field when it changes or is submitted.
+type TextCallback = Box<dyn FnMut(&str)>;
+
+/// Composition is text being composed with an input method.
+///
+/// It is shown in place of the selection until it is committed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Composition {
+    /// The preedit text.
+    text: String,
+    /// The byte range of the preedit text the input method highlights, which
+    /// is also where the caret is shown.
+    cursor: Option<(usize, usize)>,
+}
+
+/// TextField is an editable single-line or multi-line text input.
+///
+/// It supports selection with the pointer and keyboard, word and line
+/// navigation, undo and redo, the clipboard, and composing text with an input
+/// method. The field must have focus to receive key events; it requests focus
+/// when clicked. Its colors, and its text style unless one is set, are taken
+/// from the [`Theme`](crate::framework::Theme).
+pub struct TextField {
+    editor: TextEditor,
+    style: TextStyle,
+    /// The style set with [`TextField::style`], instead of the theme's.
+    custom_style: Option<TextStyle>,
+    placeholder: Option<String>,
+    padding: EdgeInsets,
+    /// The direction set with [`TextField::text_direction`], instead of the
+    /// inherited one.
+    custom_direction: Option<TextDirection>,
+    direction: TextDirection,
+    obscure_text: bool,
+    multiline: bool,
+    max_lines: Option<usize>,
+    on_changed: Option<TextCallback>,
+    on_submitted: Option<TextCallback>,
+    focus_id: FocusId,
+    fonts: Rc<FontCollection>,
+    text_layout: TextLayout,
+    size: Size,
+    scroll: Offset,
+    cache: LayoutCache,
+    dragging: bool,
+    last_click: Option<Duration>,
+    caret_reset: Duration,
+    composition: Option<Composition>,
+}
+
+impl Default for TextField {
+    fn default() -> Self {
+        TextField::new()
+    }
+}
+
+impl TextField {
+    /// Create a new empty single-line TextField instance.
+    pub fn new() -> Self {
+        let style = TextStyle::default();
+        let fonts: Rc<FontCollection> = Rc::default();
+        TextField {
+            editor: TextEditor::default(),
+            text_layout: TextLayout::new("", &style, &fonts),
+            fonts,
+            style,
+            custom_style: None,
+            placeholder: None,
+            padding: EdgeInsets::symmetric(8.0, 6.0),
+            custom_direction: None,
+            direction: TextDirection::Ltr,
+            obscure_text: false,
+            multiline: false,
+            max_lines: None,
+            on_changed: None,
+            on_submitted: None,
+            focus_id: FocusId::new(),
+            size: Size::ZERO,
+            scroll: Offset::ZERO,
+            cache: LayoutCache::default(),
+            dragging: false,
+            last_click: None,
+            caret_reset: Duration::ZERO,
+            composition: None,
+        }
+    }
+
+    /// Set the initial text.
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        self.set_text(text);
+        self
+    }
+
+    /// Set the text shown while the field is empty.
+    pub fn placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.placeholder = Some(placeholder.into());
+        self
+    }
+
+    /// Set the style of the text, instead of the body style of the theme.
+    pub fn style(mut self, style: TextStyle) -> Self {
+        self.custom_style = Some(style.clone());
+        self.style = style;
+        self.update_text_layout();
+        self
+    }
+
+    /// Set the base direction of the text, instead of the inherited one.
+    ///
+    /// Right-to-left text starts at the right edge of the field, and the
+    /// left and right arrow keys move the caret forward and back.
+    pub fn text_direction(mut self, text_direction: TextDirection) -> Self {
+        self.custom_direction = Some(text_direction);
+        self.direction = text_direction;
+        self.update_text_layout();
+        self
+    }
+
+    /// Show a bullet for each character, e.g. for passwords.
+    ///
+    /// Obscured text cannot be copied or cut, nor entered with an input method.
+    pub fn obscure_text(mut self, obscure_text: bool) -> Self {
+        self.obscure_text = obscure_text;
+        self.update_text_layout();
+        self
+    }
+
+    /// Limit the text to the given number of characters.
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.editor.set_max_length(Some(max_length));
+        self
+    }
+
+    /// Add a filter every entered character must pass.
+    pub fn input_filter(mut self, filter: InputFilter) -> Self {
+        self.editor.add_filter(filter);
+        self
+    }
+
+    /// Allow line breaks, entered with Enter.
+    pub fn multiline(mut self, multiline: bool) -> Self {
+        self.multiline = multiline;
+        self
+    }
+
+    /// Limit the height of a multi-line field to the given number of lines.
+    ///
+    /// Longer text scrolls to keep the caret visible.
+    pub fn max_lines(mut self, max_lines: usize) -> Self {
+        self.max_lines = Some(max_lines.max(1));
+        self
+    }
+
+    /// Set the callback called with the new text whenever it changes.
+    pub fn on_changed(mut self, on_changed: impl FnMut(&str) + 'static) -> Self {
+        self.on_changed = Some(Box::new(on_changed));
+        self
+    }
+
+    /// Set the callback called with the text when Enter is pressed in a
+    /// single-line field.
+    pub fn on_submitted(mut self, on_submitted: impl FnMut(&str) + 'static) -> Self {
+        self.on_submitted = Some(Box::new(on_submitted));
+        self
+    }
+
+    /// The current text.
+    pub fn value(&self) -> &str {
+        self.editor.text()
+    }
+
+    /// Replace the text, clearing the undo history.
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        self.editor.set_text(text);
+        self.update_text_layout();
+    }
+
+    /// The current selection.
+    pub fn selection(&self) -> TextSelection {
+        self.editor.selection()
+    }
+
+    /// The focus id of the text field.
+    pub fn focus_id(&self) -> FocusId {
+        self.focus_id
+    }
+
+    /// The text as displayed, with obscured characters replaced and the
+    /// composition in place of the selection.
+    fn display_text(&self) -> String {
+        let text = self.editor.text();
+        if self.obscure_text {
+            text.graphemes(true).map(|_| OBSCURING_CHARACTER).collect()
+        } else if let Some(composition) = &self.composition {
+            let range = self.editor.selection().range();
+            [&text[..range.start], &composition.text, &text[range.end..]].concat()
+        } else {
+            text.to_string()
+        }
+    }
+
+    /// Map a byte index in the text to one in the displayed text.
+    fn display_index(&self, index: usize) -> usize {
+        if self.obscure_text {
+            self.editor.text()[..index].graphemes(true).count() * OBSCURING_CHARACTER.len_utf8()
+        } else if let Some(composition) = &self.composition {
+            let range = self.editor.selection().range();
+            if index <= range.start {
+                index
+            } else if index >= range.end {
+                index - range.len() + composition.text.len()
+            } else {
+                range.start
+            }
+        } else {
+            index
+        }
+    }
+
+    /// Map a byte index in the displayed text to one in the text.
+    fn text_index(&self, index: usize) -> usize {
+        if self.obscure_text {
+            let text = self.editor.text();
+            text.grapheme_indices(true)
+                .nth(index / OBSCURING_CHARACTER.len_utf8())
+                .map_or(text.len(), |(start, _)| start)
+        } else if let Some(composition) = &self.composition {
+            let range = self.editor.selection().range();
+            if index <= range.start {
+                index
+            } else if index >= range.start + composition.text.len() {
+                index - composition.text.len() + range.len()
+            } else {
+                range.start
+            }
+        } else {
+            index
+        }
+    }
+
+    /// The byte index in the displayed text the caret is shown before.
+    fn caret_index(&self) -> usize {
+        match &self.composition {
+            Some(composition) => {
+                let cursor = composition
+                    .cursor
+                    .map_or(composition.text.len(), |(_, end)| end);
+                self.editor.selection().start() + cursor
+            }
+            None => self.display_index(self.editor.selection().extent),
+        }
+    }
+
+    fn update_text_layout(&mut self) {
+        let paragraph = ParagraphStyle::default().direction(self.direction);
+        self.text_layout = TextLayout::with_paragraph(
+            &self.display_text(),
+            &self.style,
+            f32::INFINITY,
+            &paragraph,
+            &self.fonts,
+        );
+        self.cache.mark_dirty();
+    }
+
+    fn set_composition(&mut self, composition: Option<Composition>) {
+        if self.composition != composition {
+            self.composition = composition;
+            self.update_text_layout();
+        }
+    }
+
+    /// The area inside the padding, in which the text is shown.
+    fn viewport(&self) -> Size {
+        self.padding.deflate_size(self.size)
+    }
+
+    /// The offset of the text within the viewport before scrolling:
+    /// right-to-left text narrower than the viewport ends at its right edge.
+    fn text_left(&self) -> f32 {
+        match self.direction {
+            TextDirection::Ltr => 0.0,
+            TextDirection::Rtl => {
+                let content = self.text_layout.size().width + CARET_WIDTH;
+                (self.viewport().width - content).max(0.0)
+            }
+        }
+    }
+
+    /// The number of lines the field is tall.
+    fn visible_lines(&self) -> usize {
+        if !self.multiline {
+            return 1;
+        }
+        let lines = self.text_layout.line_count();
+        self.max_lines
+            .map_or(lines, |max_lines| lines.min(max_lines))
+    }
+
+    /// Scroll so the caret is inside the viewport.
+    fn scroll_to_caret(&mut self) {
+        let viewport = self.viewport();
+        let caret = self.text_layout.caret_rect(self.caret_index());
+        if caret.right() - self.scroll.x > viewport.width {
+            self.scroll.x = caret.right() - viewport.width;
+        }
+        if caret.left() < self.scroll.x {
+            self.scroll.x = caret.left();
+        }
+        if caret.bottom() - self.scroll.y > viewport.height {
+            self.scroll.y = caret.bottom() - viewport.height;
+        }
+        if caret.top() < self.scroll.y {
+            self.scroll.y = caret.top();
+        }
+        let content = self.text_layout.size();
+        let max_x = (content.width + CARET_WIDTH - viewport.width).max(0.0);
+        let max_y = (content.height - viewport.height).max(0.0);
+        self.scroll.x = self.scroll.x.clamp(0.0, max_x);
+        self.scroll.y = self.scroll.y.clamp(0.0, max_y);
+    }
+
+    /// The text index closest to a position relative to the field.
+    fn index_at(&self, position: Offset) -> usize {
+        let position = Offset::new(
+            position.x - self.padding.left - self.text_left() + self.scroll.x,
+            position.y - self.padding.top + self.scroll.y,
+        );
+        self.text_index(self.text_layout.index_for_position(position))
+    }
+
+    fn contains(&self, position: Offset) -> bool {
+        Rect::from_size(self.size).contains(position)
+    }
+
+    /// Apply an editing operation, notifying listeners if the text changed.
+    fn edit(&mut self, state: &State<'_>, operation: impl FnOnce(&mut TextEditor) -> bool) {
+        if operation(&mut self.editor) {
+            self.update_text_layout();
+            if let Some(on_changed) = &mut self.on_changed {
+                on_changed(self.editor.text());
+            }
+        }
+        self.scroll_to_caret();
+        self.caret_reset = state.frame_time();
+    }
+
+    /// Remove the control characters that cannot be entered into the field.
+    fn sanitize(&self, text: &str) -> String {
+        text.chars()
+            .filter(|c| !c.is_control() || (self.multiline && *c == '\n'))
+            .collect()
+    }
+
+    /// Move the caret to the line above or below, keeping its horizontal position.
+    fn move_vertically(&mut self, down: bool, extend: bool) {
+        let caret = self.display_index(self.editor.selection().extent);
+        let line = self.text_layout.line_for_index(caret);
+        let target = match (down, line) {
+            (false, 0) => 0,
+            (false, line) => {
+                let x = self.text_layout.caret_rect(caret).left();
+                self.text_layout.index_for_line_x(line - 1, x)
+            }
+            (true, line) if line + 1 >= self.text_layout.line_count() => usize::MAX,
+            (true, line) => {
+                let x = self.text_layout.caret_rect(caret).left();
+                self.text_layout.index_for_line_x(line + 1, x)
+            }
+        };
+        let target = if target == usize::MAX {
+            self.editor.text().len()
+        } else {
+            self.text_index(target)
+        };
+        self.editor.move_to(target, extend);
+    }
+
+    fn copy(&self, state: &mut State<'_>) {
+        if !self.obscure_text && !self.editor.selection().is_collapsed() {
+            state.clipboard_mut().set_text(self.editor.selected_text());
+        }
+    }
+
+    fn pointer_down(&mut self, position: Offset, state: &mut State<'_>) {
+        state.focus_mut().request_focus(self.focus_id);
+        let index = self.index_at(position);
+        let now = state.frame_time();
+        let double_click = self
+            .last_click
+            .is_some_and(|last| now.saturating_sub(last) <= DOUBLE_CLICK_TIME);
+        if double_click && !self.obscure_text {
+            let word = self.editor.word_at(index);
+            self.editor
+                .set_selection(TextSelection::new(word.start, word.end));
+            self.last_click = None;
+        } else {
+            self.editor.move_to(index, false);
+            self.last_click = Some(now);
+            self.dragging = true;
+        }
+        self.edit(state, |_| false);
+    }
+
+    fn key_down(
+        &mut self,
+        key: &Key,
+        modifiers: ModifiersState,
+        text: Option<&str>,
+        state: &mut State<'_>,
+    ) -> bool {
+        let shortcut = modifiers.control_key() || modifiers.super_key();
+        let word = modifiers.control_key() || modifiers.alt_key();
+        let extend = modifiers.shift_key();
+        let rtl = self.direction == TextDirection::Rtl;
+        match key {
+            Key::Named(NamedKey::ArrowLeft | NamedKey::ArrowRight) => {
+                // The arrow pointing to the end of the text moves forward.
+                let forward = (*key == Key::Named(NamedKey::ArrowRight)) != rtl;
+                self.edit(state, |editor| {
+                    if forward {
+                        editor.move_right(word, extend);
+                    } else {
+                        editor.move_left(word, extend);
+                    }
+                    false
+                });
+            }
+            Key::Named(NamedKey::ArrowUp) => {
+                self.move_vertically(false, extend);
+                self.edit(state, |_| false);
+            }
+            Key::Named(NamedKey::ArrowDown) => {
+                self.move_vertically(true, extend);
+                self.edit(state, |_| false);
+            }
+            Key::Named(NamedKey::Home) => self.edit(state, |editor| {
+                if shortcut {
+                    editor.move_document_start(extend);
+                } else {
+                    editor.move_line_start(extend);
+                }
+                false
+            }),
+            Key::Named(NamedKey::End) => self.edit(state, |editor| {
+                if shortcut {
+                    editor.move_document_end(extend);
+                } else {
+                    editor.move_line_end(extend);
+                }
+                false
+            }),
+            Key::Named(NamedKey::Backspace) => {
+                self.edit(state, |editor| editor.delete_backward(word));
+            }
+            Key::Named(NamedKey::Delete) => {
+                self.edit(state, |editor| editor.delete_forward(word));
+            }
+            Key::Named(NamedKey::Enter) if self.multiline => {
+                self.edit(state, |editor| editor.insert("\n"));
+            }
+            Key::Named(NamedKey::Enter) => {
+                if let Some(on_submitted) = &mut self.on_submitted {
+                    on_submitted(self.editor.text());
+                }
+            }
+            Key::Named(NamedKey::Tab | NamedKey::Escape) => return false,
+            Key::Character(character) if shortcut => match character.to_lowercase().as_str() {
+                "a" => self.edit(state, |editor| {
+                    editor.select_all();
+                    false
+                }),
+                "c" => self.copy(state),
+                "x" if !self.obscure_text => {
+                    self.copy(state);
+                    self.edit(state, TextEditor::delete_selection);
+                }
+                "v" => {
+                    if let Some(text) = state.clipboard_mut().get_text() {
+                        let text = self.sanitize(&text);
+                        self.edit(state, |editor| editor.paste(&text));
+                    }
+                }
+                "z" if extend => self.edit(state, TextEditor::redo),
+                "z" => self.edit(state, TextEditor::undo),
+                "y" => self.edit(state, TextEditor::redo),
+                _ => return false,
+            },
+            _ => match text {
+                Some(text) if !shortcut => {
+                    let text = self.sanitize(text);
+                    self.edit(state, |editor| editor.insert(&text));
+                }
+                _ => return false,
+            },
+        }
+        true
+    }
+}
+
+impl Widget for TextField {
+    fn build(&mut self, state: &mut State<'_>) {
+        let style = self
+            .custom_style
+            .clone()
+            .unwrap_or_else(|| state.theme().typography.body.clone());
+        let direction = self
+            .custom_direction
+            .unwrap_or_else(|| state.text_direction());
+        if style != self.style
+            || direction != self.direction
+            || !Rc::ptr_eq(&self.fonts, state.fonts())
+        {
+            self.style = style;
+            self.direction = direction;
+            self.fonts = Rc::clone(state.fonts());
+            self.update_text_layout();
+        }
+    }
+
+    fn layout(&mut self, constraints: BoxConstraints) -> Size {
+        if let Some(size) = self.cache.get(constraints) {
+            return size;
+        }
+        let content = self.text_layout.size();
+        let width = if constraints.has_bounded_width() {
+            constraints.max_width
+        } else {
+            (content.width + CARET_WIDTH + self.padding.horizontal()).max(DEFAULT_WIDTH)
+        };
+        let height =
+            self.visible_lines() as f32 * self.text_layout.line_height() + self.padding.vertical();
+        self.size = constraints.constrain(Size::new(width, height));
+        self.scroll_to_caret();
+        self.cache.store(constraints, self.size);
+        self.size
+    }
+
+    fn paint(&self, state: &mut State<'_>) {
+        state.focus_mut().register(self.focus_id);
+        let focused = state.focus().is_focused(self.focus_id);
+        let bounds = Rect::from_size(self.size);
+        let colors = state.theme().colors;
+        let canvas = state.canvas_mut();
+        canvas.fill_rect(bounds, colors.surface);
+        if focused {
+            canvas.stroke_rect(bounds, 2.0, colors.primary);
+        } else {
+            canvas.stroke_rect(bounds, 1.0, colors.outline);
+        }
+
+        let viewport = self.viewport();
+        let text_left = self.text_left();
+        let visible = Rect::new(
+            self.scroll.x - text_left,
+            self.scroll.y,
+            viewport.width,
+            viewport.height,
+        );
+        canvas.save();
+        canvas.translate(Offset::new(
+            self.padding.left + text_left - self.scroll.x,
+            self.padding.top - self.scroll.y,
+        ));
+        canvas.clip_rect(visible);
+
+        let selection = self.editor.selection();
+        match &self.composition {
+            // The composition replaces the selection and is underlined, with
+            // the part the input method highlights underlined thicker.
+            Some(composition) => {
+                let start = selection.start();
+                let mut underlines = vec![(start..start + composition.text.len(), 1.0)];
+                if let Some((from, to)) = composition.cursor.filter(|(from, to)| from != to) {
+                    underlines.push((start + from..start + to, 2.0));
+                }
+                for (range, thickness) in underlines {
+                    for rect in self.text_layout.selection_rects(range) {
+                        let underline = Rect::new(
+                            rect.left(),
+                            rect.bottom() - thickness,
+                            rect.size.width,
+                            thickness,
+                        );
+                        canvas.fill_rect(underline, self.style.color);
+                    }
+                }
+            }
+            None => {
+                let range =
+                    self.display_index(selection.start())..self.display_index(selection.end());
+                for rect in self.text_layout.selection_rects(range) {
+                    canvas.fill_rect(rect, colors.primary.with_alpha(0.3));
+                }
+            }
+        }
+
+        if self.editor.text().is_empty() && self.composition.is_none() {
+            if let Some(placeholder) = &self.placeholder {
+                let style = self.style.clone().color(colors.on_surface_variant);
+                // The placeholder starts where the caret is.
+                let x = match self.direction {
+                    TextDirection::Ltr => 0.0,
+                    TextDirection::Rtl => -self.fonts.measure(placeholder, &style),
+                };
+                canvas.draw_directed_text(Offset::new(x, 0.0), placeholder, &style, self.direction);
+            }
+        } else {
+            let text = self.display_text();
+            for fragment in self.text_layout.fragments() {
+                let rect = fragment.rect;
+                if rect.bottom() > visible.top() && rect.top() < visible.bottom() {
+                    canvas.draw_directed_text(
+                        rect.origin,
+                        &text[fragment.range.clone()],
+                        &self.style,
+                        fragment.direction(),
+                    );
+                }
+            }
+        }
+
+        // The caret is visible for the first interval after every edit or
+        // movement, then blinks.
+        let since_reset = state.frame_time().saturating_sub(self.caret_reset);
+        let blink_phase = since_reset.as_millis() / CARET_BLINK_INTERVAL.as_millis();
+        let caret = self.text_layout.caret_rect(self.caret_index());
+        if focused && blink_phase % 2 == 0 {
+            state.canvas_mut().fill_rect(caret, self.style.color);
+        }
+        if focused {
+            // Keep painting while focused so the caret blinks.
+            state.request_frame();
+        }
+        if focused && !self.obscure_text {
+            let cursor_area = state.canvas().to_window(caret);
+            state.request_ime(cursor_area);
+        }
+        state.canvas_mut().restore();
+    }
+
+    fn event(&mut self, event: &Event, state: &mut State<'_>) -> bool {
+        match event {
+            Event::PointerDown {
+                position,
+                button: MouseButton::Left,
+            } => {
+                if self.contains(*position) {
+                    self.pointer_down(*position, state);
+                    return true;
+                }
+                if state.focus().is_focused(self.focus_id) {
+                    state.focus_mut().unfocus();
+                    self.set_composition(None);
+                }
+                false
+            }
+            Event::PointerMove { position } if self.dragging => {
+                let index = self.index_at(*position);
+                self.editor.move_to(index, true);
+                self.edit(state, |_| false);
+                true
+            }
+            Event::PointerUp {
+                button: MouseButton::Left,
+                ..
+            } if self.dragging => {
+                self.dragging = false;
+                true
+            }
+            Event::Ime(ime) if state.focus().is_focused(self.focus_id) && !self.obscure_text => {
+                match ime {
+                    Ime::Preedit(text, cursor) => {
+                        let composition = (!text.is_empty()).then(|| Composition {
+                            text: text.clone(),
+                            cursor: *cursor,
+                        });
+                        self.set_composition(composition);
+                        self.edit(state, |_| false);
+                    }
+                    Ime::Commit(text) => {
+                        self.set_composition(None);
+                        let text = self.sanitize(text);
+                        self.edit(state, |editor| editor.insert(&text));
+                    }
+                    Ime::Disabled => self.set_composition(None),
+                    Ime::Enabled => {}
+                }
+                true
+            }
+            Event::KeyDown {
+                key,
+                modifiers,
+                text,
+            } if state.focus().is_focused(self.focus_id) => {
+                self.key_down(key, *modifiers, text.as_deref(), state)
+            }
+            _ => false,
+        }
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.cache.is_dirty()
+    }
+
+    fn mark_needs_layout(&mut self) {
+        self.cache.mark_dirty();
+    }
+
+    fn is_relayout_boundary(&self) -> bool {
+        self.cache.is_relayout_boundary()
+    }
+}
+
+impl fmt::Debug for TextField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TextField")
+            .field("editor", &self.editor)
+            .field("style", &self.style)
+            .field("placeholder", &self.placeholder)
+            .field("obscure_text", &self.obscure_text)
+            .field("multiline", &self.multiline)
+            .field("max_lines", &self.max_lines)
+            .field("focus_id", &self.focus_id)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::widgets::test_support::{click, key, key_with, typed};
+    use crate::framework::DrawCommand;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn shortcut(character: &str) -> Event {
+        key_with(Key::Character(character.into()), ModifiersState::CONTROL)
+    }
+
+    fn focused_field(field: TextField, state: &mut State<'_>) -> TextField {
+        let mut field = field;
+        field.layout(BoxConstraints::loose(Size::new(100.0, 100.0)));
+        state.focus_mut().request_focus(field.focus_id());
+        field
+    }
+
+    #[test]
+    fn test_typing_and_callbacks() {
+        let changes = Rc::new(RefCell::new(Vec::new()));
+        let submitted = Rc::new(RefCell::new(None));
+        let mut state = State::default();
+        let mut field = {
+            let changes = Rc::clone(&changes);
+            let submitted = Rc::clone(&submitted);
+            focused_field(
+                TextField::new()
+                    .on_changed(move |text| changes.borrow_mut().push(text.to_string()))
+                    .on_submitted(move |text| *submitted.borrow_mut() = Some(text.to_string())),
+                &mut state,
+            )
+        };
+
+        assert!(field.event(&typed("h"), &mut state));
+        assert!(field.event(&typed("i"), &mut state));
+        field.event(&key(NamedKey::Backspace), &mut state);
+        field.event(&key(NamedKey::Enter), &mut state);
+        assert_eq!(field.value(), "h");
+        assert_eq!(*changes.borrow(), ["h", "hi", "h"]);
+        assert_eq!(submitted.borrow().as_deref(), Some("h"));
+
+        // Unfocused fields ignore the keyboard.
+        state.focus_mut().unfocus();
+        assert!(!field.event(&typed("x"), &mut state));
+    }
+
+    #[test]
+    fn test_clipboard_and_undo() {
+        let mut state = State::default();
+        let mut field = focused_field(TextField::new().text("copy me"), &mut state);
+
+        field.event(&shortcut("a"), &mut state);
+        field.event(&shortcut("x"), &mut state);
+        assert_eq!(field.value(), "");
+        field.event(&shortcut("v"), &mut state);
+        field.event(&shortcut("v"), &mut state);
+        assert_eq!(field.value(), "copy mecopy me");
+        field.event(&shortcut("z"), &mut state);
+        assert_eq!(field.value(), "copy me");
+        field.event(&shortcut("y"), &mut state);
+        assert_eq!(field.value(), "copy mecopy me");
+    }
+
+    #[test]
+    fn test_obscured_text() {
+        let mut state = State::default();
+        let mut field = focused_field(
+            TextField::new().text("secret").obscure_text(true),
+            &mut state,
+        );
+
+        field.event(&shortcut("a"), &mut state);
+        field.event(&shortcut("c"), &mut state);
+        field.event(&shortcut("x"), &mut state);
+        assert_eq!(state.clipboard_mut().get_text(), None);
+        assert_eq!(field.value(), "secret");
+
+        field.paint(&mut state);
+        let texts: Vec<_> = state
+            .canvas()
+            .commands()
+            .iter()
+            .filter_map(|command| match command {
+                DrawCommand::Text { text, .. } => Some(text.clone()),
+                DrawCommand::FillRect { .. } => None,
+            })
+            .collect();
+        assert_eq!(texts, ["••••••"]);
+    }
+
+    #[test]
+    fn test_scrolled_text_is_clipped() {
+        let mut state = State::default();
+        let mut field = focused_field(
+            TextField::new().text("a line much wider than the field"),
+            &mut state,
+        );
+        field.event(&key(NamedKey::End), &mut state);
+        field.layout(BoxConstraints::tight(Size::new(60.0, 30.0)));
+        assert!(field.scroll.x > 0.0);
+
+        field.paint(&mut state);
+        let viewport = Rect {
+            origin: Offset::new(field.padding.left, field.padding.top),
+            size: field.viewport(),
+        };
+        let clips: Vec<_> = state
+            .canvas()
+            .commands()
+            .iter()
+            .filter_map(|command| match command {
+                DrawCommand::Text { origin, clip, .. } => Some((origin.x, *clip)),
+                DrawCommand::FillRect { .. } => None,
+            })
+            .collect();
+        assert_eq!(clips.len(), 1);
+        // The start of the text is scrolled out to the left of the viewport.
+        assert!(clips[0].0 < viewport.left());
+        assert_eq!(clips[0].1, Some(viewport));
+    }
+
+    #[test]
+    fn test_pointer_selection() {
+        let mut state = State::default();
+        let mut field = focused_field(TextField::new().text("one two"), &mut state);
+        state.focus_mut().unfocus();
+        let advance = TextStyle::default().advance("a");
+        let at = |index: f32| Offset::new(8.0 + advance * index, 10.0);
+
+        field.event(
+            &Event::PointerDown {
+                position: at(1.0),
+                button: MouseButton::Left,
+            },
+            &mut state,
+        );
+        assert!(state.focus().is_focused(field.focus_id()));
+        field.event(&Event::PointerMove { position: at(5.0) }, &mut state);
+        field.event(
+            &Event::PointerUp {
+                position: at(5.0),
+                button: MouseButton::Left,
+            },
+            &mut state,
+        );
+        assert_eq!(field.selection(), TextSelection::new(1, 5));
+
+        // A second click right after the first selects the word.
+        state.set_frame_time(Duration::from_secs(1));
+        field.event(
+            &Event::PointerDown {
+                position: at(5.0),
+                button: MouseButton::Left,
+            },
+            &mut state,
+        );
+        assert_eq!(field.selection(), TextSelection::collapsed(5));
+        field.event(
+            &Event::PointerDown {
+                position: at(5.0),
+                button: MouseButton::Left,
+            },
+            &mut state,
+        );
+        assert_eq!(field.selection(), TextSelection::new(4, 7));
+    }
+
+    #[test]
+    fn test_right_to_left() {
+        let mut state = State::default();
+        let text = "\u{5D0}\u{5D1}\u{5D2}";
+        let field = TextField::new()
+            .text(text)
+            .text_direction(TextDirection::Rtl);
+        let mut field = focused_field(field, &mut state);
+        // The text ends at the right edge of the 84 pixel wide viewport,
+        // leaving room for the caret.
+        let right = 8.0 + 84.0 - CARET_WIDTH;
+        let advance = TextStyle::default().advance("\u{5D0}");
+        assert_eq!(
+            field.text_layout.caret_rect(0).left() + field.text_left(),
+            right - 8.0
+        );
+
+        let at = |x: f32| Offset::new(x, 10.0);
+        click(&mut field, &mut state, at(right - 1.0));
+        assert_eq!(field.selection(), TextSelection::collapsed(0));
+        click(&mut field, &mut state, at(right - advance * 3.0));
+        assert_eq!(field.selection(), TextSelection::collapsed(text.len()));
+
+        // The left arrow moves towards the end of the text.
+        click(&mut field, &mut state, at(right - advance));
+        let left = key(NamedKey::ArrowLeft);
+        field.event(&left, &mut state);
+        assert_eq!(field.selection(), TextSelection::collapsed(4));
+    }
+
+    #[test]
+    fn test_multiline_layout() {
+        let mut state = State::default();
+        let mut field = focused_field(TextField::new().multiline(true).max_lines(2), &mut state);
+        let line_height = TextStyle::default().line_extent();
+        let constraints = BoxConstraints::loose(Size::new(100.0, 100.0));
+        assert_eq!(field.layout(constraints).height, line_height + 12.0);
+
+        for text in ["a", "b", "c"] {
+            field.event(&typed(text), &mut state);
+            field.event(&key(NamedKey::Enter), &mut state);
+        }
+        assert_eq!(field.value(), "a\nb\nc\n");
+        assert!(field.needs_layout());
+        assert_eq!(field.layout(constraints).height, line_height * 2.0 + 12.0);
+
+        field.event(
+            &key_with(Key::Named(NamedKey::ArrowUp), ModifiersState::SHIFT),
+            &mut state,
+        );
+        assert_eq!(field.selection(), TextSelection::new(6, 4));
+        assert_eq!(field.scroll.y, line_height * 2.0);
+    }
+
+    #[test]
+    fn test_single_line_rejects_line_breaks() {
+        let mut state = State::default();
+        let mut field = focused_field(TextField::new().max_length(4), &mut state);
+        state.clipboard_mut().set_text("a\nb\tcdef");
+        field.event(&shortcut("v"), &mut state);
+        assert_eq!(field.value(), "abcd");
+    }
+
+    #[test]
+    fn test_ime_composition() {
+        let mut state = State::default();
+        let mut field = focused_field(TextField::new().text("ab"), &mut state);
+        field.event(&key(NamedKey::ArrowLeft), &mut state);
+
+        let preedit = "にほん";
+        assert!(field.event(
+            &Event::Ime(Ime::Preedit(preedit.to_string(), Some((3, 6)))),
+            &mut state
+        ));
+        assert_eq!(field.value(), "ab");
+        assert_eq!(field.display_text(), "aにほんb");
+        assert_eq!(field.caret_index(), 1 + 6);
+        assert_eq!(field.display_index(2), 1 + preedit.len() + 1);
+        assert_eq!(field.text_index(1 + preedit.len() + 1), 2);
+
+        field.event(&Event::Ime(Ime::Commit("日本".to_string())), &mut state);
+        assert_eq!(field.value(), "a日本b");
+        assert_eq!(field.display_text(), "a日本b");
+        assert_eq!(
+            field.selection(),
+            TextSelection::collapsed(1 + "日本".len())
+        );
+    }
+
+    #[test]
+    fn test_ime_cursor_area() {
+        let mut state = State::default();
+        let field = focused_field(TextField::new().text("ab"), &mut state);
+        let paint = |state: &mut State<'_>| {
+            state.begin_ime_frame();
+            state.canvas_mut().translate(Offset::new(10.0, 20.0));
+            field.paint(state);
+            state.canvas_mut().clear();
+            state.end_ime_frame();
+        };
+
+        paint(&mut state);
+        let caret = field.text_layout.caret_rect(2);
+        assert_eq!(
+            state.ime_cursor_area(),
+            Some(caret.translate(Offset::new(18.0, 26.0)))
+        );
+
+        state.focus_mut().unfocus();
+        paint(&mut state);
+        assert_eq!(state.ime_cursor_area(), None);
+    }
+}
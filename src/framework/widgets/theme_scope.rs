@@ -0,0 +1,168 @@
+use super::widget::Widget;
+use crate::framework::state::next_version;
+use crate::framework::{BoxConstraints, Event, Size, State, Theme};
+use std::fmt;
+use std::rc::Rc;
+
+/// ThemeScope overrides the theme for its child and the widgets below it.
+///
+/// The theme is either given outright, e.g. a dark theme for a sidebar, or
+/// derived from the inherited theme, e.g. to change only the primary color.
+/// A derived theme follows changes of the inherited theme when the tree is
+/// built again.
+pub struct ThemeScope {
+    child: Box<dyn Widget>,
+    modify: Box<dyn Fn(&Theme) -> Theme>,
+    /// The theme of the subtree as of the last build and its version.
+    theme: Option<(Rc<Theme>, u64)>,
+}
+
+impl ThemeScope {
+    /// Create a new ThemeScope instance giving the child the theme.
+    pub fn new(theme: Theme, child: impl Widget + 'static) -> Self {
+        ThemeScope::modify(move |_| theme.clone(), child)
+    }
+
+    /// Create a new ThemeScope instance giving the child the theme derived
+    /// from the inherited one.
+    pub fn modify(
+        modify: impl Fn(&Theme) -> Theme + 'static,
+        child: impl Widget + 'static,
+    ) -> Self {
+        ThemeScope {
+            child: Box::new(child),
+            modify: Box::new(modify),
+            theme: None,
+        }
+    }
+
+    /// Make the theme of the subtree inherited, derived again if the scope
+    /// was not built yet.
+    fn push_theme(&self, state: &mut State<'_>) {
+        let (theme, version) = self
+            .theme
+            .clone()
+            .unwrap_or_else(|| (Rc::new((self.modify)(state.theme())), next_version()));
+        state.push_inherited(theme, version, None);
+    }
+}
+
+impl Widget for ThemeScope {
+    fn build(&mut self, state: &mut State<'_>) {
+        let theme = (self.modify)(state.theme());
+        if self
+            .theme
+            .as_ref()
+            .map_or(true, |(built, _)| **built != theme)
+        {
+            self.theme = Some((Rc::new(theme), next_version()));
+        }
+        self.push_theme(state);
+        self.child.build(state);
+        state.pop_inherited();
+    }
+
+    fn layout(&mut self, constraints: BoxConstraints) -> Size {
+        self.child.layout(constraints)
+    }
+
+    fn paint(&self, state: &mut State<'_>) {
+        self.push_theme(state);
+        self.child.paint(state);
+        state.pop_inherited();
+    }
+
+    fn event(&mut self, event: &Event, state: &mut State<'_>) -> bool {
+        self.push_theme(state);
+        let handled = self.child.event(event, state);
+        state.pop_inherited();
+        handled
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.child.needs_layout()
+    }
+
+    fn mark_needs_layout(&mut self) {
+        self.child.mark_needs_layout();
+    }
+
+    fn is_relayout_boundary(&self) -> bool {
+        self.child.is_relayout_boundary()
+    }
+}
+
+impl fmt::Debug for ThemeScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ThemeScope")
+            .field("theme", &self.theme.as_ref().map(|(theme, _)| theme))
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::{Brightness, Color};
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    /// Probe records the brightness of the theme it was built and painted in.
+    struct Probe(Rc<Cell<(Option<Brightness>, Option<Brightness>)>>);
+
+    impl Widget for Probe {
+        fn build(&mut self, state: &mut State<'_>) {
+            self.0
+                .set((Some(state.theme().brightness()), self.0.get().1));
+        }
+
+        fn layout(&mut self, constraints: BoxConstraints) -> Size {
+            constraints.smallest()
+        }
+
+        fn paint(&self, state: &mut State<'_>) {
+            self.0
+                .set((self.0.get().0, Some(state.theme().brightness())));
+        }
+    }
+
+    #[test]
+    fn test_scope_overrides_theme() {
+        let seen = Rc::new(Cell::new((None, None)));
+        let mut scope = ThemeScope::new(Theme::dark(), Probe(seen.clone()));
+        let mut state = State::default();
+
+        scope.build(&mut state);
+        scope.paint(&mut state);
+        assert_eq!(seen.get(), (Some(Brightness::Dark), Some(Brightness::Dark)));
+        // The scope does not leak out of its subtree.
+        assert_eq!(state.theme().brightness(), Brightness::Light);
+    }
+
+    #[test]
+    fn test_modify_follows_inherited_theme() {
+        let red = Color::rgb(1.0, 0.0, 0.0);
+        let seen = Rc::new(Cell::new((None, None)));
+        let mut scope = ThemeScope::modify(
+            move |theme| theme.clone().with_primary(red),
+            Probe(seen.clone()),
+        );
+        let mut state = State::default();
+        assert!(state.needs_build());
+
+        scope.build(&mut state);
+        state.finish_build();
+        assert_eq!(
+            scope.theme.as_ref().map(|(theme, _)| theme.brightness()),
+            Some(Brightness::Light)
+        );
+
+        state.set_theme(Theme::dark());
+        assert!(state.needs_build());
+        scope.build(&mut state);
+        let theme = &scope.theme.as_ref().unwrap().0;
+        assert_eq!(theme.brightness(), Brightness::Dark);
+        assert_eq!(theme.colors.primary, red);
+        assert_eq!(seen.get().0, Some(Brightness::Dark));
+    }
+}
@@ -0,0 +1,200 @@
+use super::widget_states::WidgetStates;
+use crate::framework::{
+    BoxConstraints, Color, Event, FocusId, LayoutCache, Offset, Rect, Size, State,
+};
+use winit::event::MouseButton;
+use winit::keyboard::{Key, NamedKey};
+
+/// The radius of the circle shown behind a hovered or focused control.
+pub(crate) const HALO_RADIUS: f32 = 20.0;
+
+/// Response is how a [`Toggleable`] reacted to an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Response {
+    /// The event was not for the control.
+    Ignored,
+    /// The event was consumed without activating the control.
+    Handled,
+    /// The control was clicked or activated with the keyboard.
+    Activated,
+}
+
+impl Response {
+    /// Returns true if the event was consumed.
+    pub(crate) fn is_handled(self) -> bool {
+        self != Response::Ignored
+    }
+}
+
+/// Toggleable implements the parts every fixed-size toggle control shares:
+/// interactive states, focus, activation by pointer or keyboard, and layout.
+///
+/// Like a button, a control is activated by pressing and releasing the
+/// primary pointer button over it, or with Enter or Space while focused.
+#[derive(Debug)]
+pub(crate) struct Toggleable {
+    states: WidgetStates,
+    focus_id: FocusId,
+    size: Size,
+    cache: LayoutCache,
+}
+
+impl Default for Toggleable {
+    fn default() -> Self {
+        Toggleable {
+            states: WidgetStates::empty(),
+            focus_id: FocusId::new(),
+            size: Size::ZERO,
+            cache: LayoutCache::default(),
+        }
+    }
+}
+
+impl Toggleable {
+    /// The current interactive states.
+    pub(crate) fn states(&self) -> WidgetStates {
+        self.states
+    }
+
+    /// The focus identifier of the control.
+    pub(crate) fn focus_id(&self) -> FocusId {
+        self.focus_id
+    }
+
+    /// The size of the control in the last layout.
+    pub(crate) fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Returns true if the control accepts input.
+    pub(crate) fn is_enabled(&self) -> bool {
+        !self.states.contains(WidgetStates::DISABLED)
+    }
+
+    /// Set whether the control accepts input.
+    pub(crate) fn set_enabled(&mut self, enabled: bool) {
+        self.states.set(WidgetStates::DISABLED, !enabled);
+        if !enabled {
+            self.states
+                .remove(WidgetStates::HOVERED | WidgetStates::PRESSED | WidgetStates::DRAGGED);
+        }
+    }
+
+    /// Set whether the control is selected or checked.
+    pub(crate) fn set_selected(&mut self, selected: bool) {
+        self.states.set(WidgetStates::SELECTED, selected);
+    }
+
+    /// Add or remove interactive states.
+    pub(crate) fn set_states(&mut self, states: WidgetStates, value: bool) {
+        self.states.set(states, value);
+    }
+
+    /// Lay out the control at its preferred size, as far as the constraints allow.
+    pub(crate) fn layout(&mut self, constraints: BoxConstraints, preferred: Size) -> Size {
+        if let Some(size) = self.cache.get(constraints) {
+            return size;
+        }
+        self.size = constraints.constrain(preferred);
+        self.cache.store(constraints, self.size);
+        self.size
+    }
+
+    /// Returns true if the control must be laid out again.
+    pub(crate) fn needs_layout(&self) -> bool {
+        self.cache.is_dirty()
+    }
+
+    /// Mark the layout of the control as stale.
+    pub(crate) fn mark_needs_layout(&mut self) {
+        self.cache.mark_dirty();
+    }
+
+    /// Returns true if the control was laid out with tight constraints.
+    pub(crate) fn is_relayout_boundary(&self) -> bool {
+        self.cache.is_relayout_boundary()
+    }
+
+    /// Register the control for focus traversal, returning true if it has focus.
+    pub(crate) fn register_focus(&self, state: &mut State<'_>) -> bool {
+        if !self.is_enabled() {
+            return false;
+        }
+        state.focus_mut().register(self.focus_id);
+        state.focus().is_focused(self.focus_id)
+    }
+
+    /// Paint the translucent circle shown behind the control while it is
+    /// hovered, pressed, dragged or focused.
+    pub(crate) fn paint_halo(
+        &self,
+        state: &mut State<'_>,
+        center: Offset,
+        color: Color,
+        focused: bool,
+    ) {
+        let alpha = if focused
+            || self.states.contains(WidgetStates::PRESSED)
+            || self.states.contains(WidgetStates::DRAGGED)
+        {
+            0.12
+        } else if self.states.contains(WidgetStates::HOVERED) {
+            0.08
+        } else {
+            return;
+        };
+        state.canvas_mut().fill_circle(
+            center,
+            HALO_RADIUS,
+            Color::new(color.r, color.g, color.b, alpha),
+        );
+    }
+
+    /// Returns true if the position is inside the control.
+    pub(crate) fn contains(&self, position: Offset) -> bool {
+        Rect::from_size(self.size).contains(position)
+    }
+
+    /// Update the states for the event and report whether it activated the control.
+    pub(crate) fn event(&mut self, event: &Event, state: &mut State<'_>) -> Response {
+        if !self.is_enabled() {
+            return Response::Ignored;
+        }
+        match event {
+            Event::PointerMove { position } => {
+                let hovered = self.contains(*position);
+                self.states.set(WidgetStates::HOVERED, hovered);
+                Response::Ignored
+            }
+            Event::PointerExit => {
+                self.states
+                    .remove(WidgetStates::HOVERED | WidgetStates::PRESSED);
+                Response::Ignored
+            }
+            Event::PointerDown {
+                position,
+                button: MouseButton::Left,
+            } if self.contains(*position) => {
+                self.states.insert(WidgetStates::PRESSED);
+                Response::Handled
+            }
+            Event::PointerUp {
+                position,
+                button: MouseButton::Left,
+            } if self.states.contains(WidgetStates::PRESSED) => {
+                self.states.remove(WidgetStates::PRESSED);
+                if self.contains(*position) {
+                    state.focus_mut().request_focus(self.focus_id);
+                    Response::Activated
+                } else {
+                    Response::Handled
+                }
+            }
+            Event::KeyDown {
+                key: Key::Named(NamedKey::Enter | NamedKey::Space),
+                ..
+            } if state.focus().is_focused(self.focus_id) => Response::Activated,
+            _ => Response::Ignored,
+        }
+    }
+}
@@ -0,0 +1,399 @@
+use super::single_child::SingleChild;
+use super::widget::Widget;
+use crate::framework::{
+    BoxConstraints, Color, Event, FontCollection, LayoutCache, Offset, OverlayId, Placement, Rect,
+    Size, State, TextLayout, TextStyle,
+};
+use std::cell::Cell;
+use std::fmt;
+use std::rc::Rc;
+use std::time::Duration;
+use winit::keyboard::{Key, NamedKey};
+
+/// How long the pointer rests on the child, or the child keeps focus, before
+/// the tooltip appears.
+const WAIT_DURATION: Duration = Duration::from_millis(500);
+
+/// The space between the child and the tooltip.
+const GAP: f32 = 8.0;
+
+/// The space around the message inside the tooltip.
+const PADDING: Offset = Offset { x: 8.0, y: 4.0 };
+
+/// The message shown in the overlay.
+struct TooltipLabel {
+    message: String,
+    style: TextStyle,
+    background: Color,
+    fonts: Rc<FontCollection>,
+    size: Size,
+    cache: LayoutCache,
+}
+
+impl Widget for TooltipLabel {
+    fn build(&mut self, state: &mut State<'_>) {
+        if !Rc::ptr_eq(&self.fonts, state.fonts()) {
+            self.fonts = Rc::clone(state.fonts());
+            self.cache.mark_dirty();
+        }
+    }
+
+    fn layout(&mut self, constraints: BoxConstraints) -> Size {
+        if let Some(size) = self.cache.get(constraints) {
+            return size;
+        }
+        let text = TextLayout::new(&self.message, &self.style, &self.fonts).size();
+        self.size = constraints.constrain(Size::new(
+            text.width + PADDING.x * 2.0,
+            text.height + PADDING.y * 2.0,
+        ));
+        self.cache.store(constraints, self.size);
+        self.size
+    }
+
+    fn paint(&self, state: &mut State<'_>) {
+        let canvas = state.canvas_mut();
+        canvas.fill_rect(Rect::from_size(self.size), self.background);
+        let layout = TextLayout::new(&self.message, &self.style, &self.fonts);
+        for (range, offset) in layout.lines() {
+            canvas.draw_text(
+                Offset::new(PADDING.x + offset.x, PADDING.y + offset.y),
+                &self.message[range],
+                &self.style,
+            );
+        }
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.cache.is_dirty()
+    }
+
+    fn is_relayout_boundary(&self) -> bool {
+        self.cache.is_relayout_boundary()
+    }
+}
+
+/// Tooltip shows a short message about its child.
+///
+/// The message appears below the child, or above it near the bottom of the
+/// window, once the pointer rested on the child or a widget inside it kept
+/// keyboard focus for the wait duration. It is shown in the [`Overlay`] so
+/// parents of the child do not clip it, and disappears when the pointer
+/// leaves, focus moves elsewhere, the child is clicked or Escape is pressed.
+///
+/// The delay is measured with [`State::frame_time`]; the tooltip requests
+/// frames with [`State::request_frame`] until the delay has passed. The style
+/// and background that are not set are taken from the [`Theme`].
+///
+/// [`Overlay`]: crate::framework::Overlay
+/// [`Theme`]: crate::framework::Theme
+pub struct Tooltip {
+    child: SingleChild,
+    message: String,
+    style: Option<TextStyle>,
+    background: Option<Color>,
+    wait_duration: Duration,
+    /// The frame time when the pointer entered the child.
+    hovered_since: Option<Duration>,
+    /// The frame time when a widget inside the child was first seen focused.
+    focused_since: Cell<Option<Duration>>,
+    /// Set by a click or Escape until the pointer leaves and focus moves on.
+    dismissed: Cell<bool>,
+    shown: Cell<Option<OverlayId>>,
+}
+
+impl Tooltip {
+    /// Create a new Tooltip instance with the given message.
+    pub fn new(message: impl Into<String>) -> Self {
+        Tooltip {
+            child: SingleChild::default(),
+            message: message.into(),
+            style: None,
+            background: None,
+            wait_duration: WAIT_DURATION,
+            hovered_since: None,
+            focused_since: Cell::new(None),
+            dismissed: Cell::new(false),
+            shown: Cell::new(None),
+        }
+    }
+
+    /// Set the child the tooltip describes.
+    pub fn child(mut self, child: impl Widget + 'static) -> Self {
+        self.child.set(child);
+        self
+    }
+
+    /// Set the style of the message.
+    pub fn style(mut self, style: TextStyle) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    /// Set the background color of the tooltip.
+    pub fn background(mut self, background: Color) -> Self {
+        self.background = Some(background);
+        self
+    }
+
+    /// Set how long the pointer rests on the child before the tooltip appears.
+    pub fn wait_duration(mut self, wait_duration: Duration) -> Self {
+        self.wait_duration = wait_duration;
+        self
+    }
+
+    /// The message of the tooltip.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns true if the tooltip is shown.
+    pub fn is_shown(&self, state: &State<'_>) -> bool {
+        self.shown
+            .get()
+            .is_some_and(|id| state.overlay().contains(id))
+    }
+
+    fn hide(&self, state: &mut State<'_>) {
+        if let Some(id) = self.shown.take() {
+            state.overlay_mut().remove(id);
+        }
+    }
+}
+
+impl Widget for Tooltip {
+    fn build(&mut self, state: &mut State<'_>) {
+        self.child.build(state);
+    }
+
+    fn layout(&mut self, constraints: BoxConstraints) -> Size {
+        if let Some(size) = self.child.cached(constraints) {
+            return size;
+        }
+        let size = self
+            .child
+            .layout(constraints)
+            .unwrap_or_else(|| constraints.smallest());
+        self.child.store(constraints, size)
+    }
+
+    fn paint(&self, state: &mut State<'_>) {
+        let first = state.focus().order().len();
+        self.child.paint(state);
+        let focus = state.focus();
+        let focused = focus
+            .focused()
+            .is_some_and(|id| focus.order()[first..].contains(&id));
+
+        let now = state.frame_time();
+        if !focused {
+            self.focused_since.set(None);
+        } else if self.focused_since.get().is_none() {
+            self.focused_since.set(Some(now));
+        }
+        let since = match (self.hovered_since, self.focused_since.get()) {
+            (Some(hovered), Some(focused)) => Some(hovered.min(focused)),
+            (hovered, focused) => hovered.or(focused),
+        };
+        if since.is_none() {
+            self.dismissed.set(false);
+        }
+        let waiting = !self.dismissed.get() && since.is_some();
+        let visible = waiting && since.is_some_and(|since| now >= since + self.wait_duration);
+        if waiting && !visible {
+            state.request_frame();
+        }
+
+        if !visible {
+            self.hide(state);
+        } else if !self.is_shown(state) {
+            let bounds = state.canvas().to_window(Rect::from_size(self.child.size()));
+            // Widen the anchor so the tooltip keeps its distance on either side.
+            let anchor = Rect::new(
+                bounds.left(),
+                bounds.top() - GAP,
+                bounds.size.width,
+                bounds.size.height + GAP * 2.0,
+            );
+            let theme = state.theme();
+            let label = TooltipLabel {
+                message: self.message.clone(),
+                style: self.style.clone().unwrap_or_else(|| {
+                    theme
+                        .typography
+                        .caption
+                        .clone()
+                        .color(theme.colors.on_inverse_surface)
+                }),
+                background: self.background.unwrap_or(theme.colors.inverse_surface),
+                fonts: Rc::clone(state.fonts()),
+                size: Size::ZERO,
+                cache: LayoutCache::default(),
+            };
+            let id = state
+                .overlay_mut()
+                .show_passive(label, anchor, Placement::BelowCenter);
+            self.shown.set(Some(id));
+        }
+    }
+
+    fn event(&mut self, event: &Event, state: &mut State<'_>) -> bool {
+        match event {
+            Event::PointerMove { position } => {
+                let inside = Rect::from_size(self.child.size()).contains(*position);
+                match (inside, self.hovered_since) {
+                    (true, None) => self.hovered_since = Some(state.frame_time()),
+                    (false, Some(_)) => self.hovered_since = None,
+                    _ => {}
+                }
+            }
+            Event::PointerExit => self.hovered_since = None,
+            Event::PointerDown { position, .. }
+                if Rect::from_size(self.child.size()).contains(*position) =>
+            {
+                self.dismissed.set(true);
+                self.hide(state);
+            }
+            Event::KeyDown {
+                key: Key::Named(NamedKey::Escape),
+                ..
+            } if self.is_shown(state) => {
+                self.dismissed.set(true);
+                self.hide(state);
+                return true;
+            }
+            _ => {}
+        }
+        self.child.event(event, state)
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.child.needs_layout()
+    }
+
+    fn mark_needs_layout(&mut self) {
+        self.child.mark_needs_layout();
+    }
+
+    fn is_relayout_boundary(&self) -> bool {
+        self.child.is_relayout_boundary()
+    }
+}
+
+impl fmt::Debug for Tooltip {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tooltip")
+            .field("message", &self.message)
+            .field("wait_duration", &self.wait_duration)
+            .field("has_child", &self.child.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::{Application, Button, Center, DrawCommand, SizedBox};
+    use std::rc::Rc;
+    use winit::event::MouseButton;
+    use winit::keyboard::ModifiersState;
+
+    fn frame(app: &mut Application, state: &mut State<'_>, time: Duration) {
+        state.set_frame_time(time);
+        app.layout(BoxConstraints::loose(Size::new(200.0, 100.0)));
+        app.paint(state);
+    }
+
+    #[test]
+    fn test_hover_delay() {
+        let pressed = Rc::new(Cell::new(false));
+        let button = {
+            let pressed = Rc::clone(&pressed);
+            Button::new(SizedBox::new(40.0, 20.0)).on_press(move || pressed.set(true))
+        };
+        let mut app = Application::new("test", Tooltip::new("Save the file").child(button));
+        let mut state = State::default();
+        let ms = Duration::from_millis;
+        frame(&mut app, &mut state, ms(0));
+
+        app.event(
+            &Event::PointerMove {
+                position: Offset::new(5.0, 5.0),
+            },
+            &mut state,
+        );
+        frame(&mut app, &mut state, ms(400));
+        assert!(state.overlay().is_empty());
+        // The tooltip keeps frames coming until the delay has passed.
+        assert!(state.needs_frame());
+        frame(&mut app, &mut state, ms(600));
+        assert!(!state.overlay().is_empty());
+        assert!(!state.needs_frame());
+
+        // The tooltip ignores input, so the click reaches the button and
+        // dismisses the tooltip.
+        let position = Offset::new(5.0, 5.0);
+        let button = MouseButton::Left;
+        app.event(&Event::PointerDown { position, button }, &mut state);
+        app.event(&Event::PointerUp { position, button }, &mut state);
+        assert!(pressed.get());
+        frame(&mut app, &mut state, ms(2000));
+        assert!(state.overlay().is_empty());
+
+        // Leaving resets the dismissal.
+        app.event(&Event::PointerExit, &mut state);
+        frame(&mut app, &mut state, ms(2100));
+        app.event(
+            &Event::PointerMove {
+                position: Offset::new(5.0, 5.0),
+            },
+            &mut state,
+        );
+        frame(&mut app, &mut state, ms(2700));
+        assert!(!state.overlay().is_empty());
+    }
+
+    #[test]
+    fn test_keyboard_focus_and_position() {
+        let mut button = Button::new(SizedBox::new(40.0, 20.0)).on_press(|| {});
+        let focus_id = button.focus_id();
+        let height = button
+            .layout(BoxConstraints::loose(Size::new(200.0, 100.0)))
+            .height;
+        let tooltip = Tooltip::new("Save").child(button);
+        let mut app = Application::new("test", Center::new().child(tooltip));
+        let mut state = State::default();
+        frame(&mut app, &mut state, Duration::ZERO);
+        state.focus_mut().request_focus(focus_id);
+        frame(&mut app, &mut state, Duration::from_millis(100));
+        frame(&mut app, &mut state, Duration::from_millis(700));
+        assert!(!state.overlay().is_empty());
+
+        // The tooltip is centered below the child.
+        let rects: Vec<_> = state
+            .canvas()
+            .commands()
+            .iter()
+            .filter_map(|command| match command {
+                DrawCommand::FillRect { rect, color }
+                    if *color == Color::new(0.38, 0.38, 0.38, 0.9) =>
+                {
+                    Some(*rect)
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(rects.len(), 1);
+        let label = rects[0];
+        assert_eq!(label.left() + label.size.width / 2.0, 100.0);
+        assert_eq!(label.top(), (100.0 + height) / 2.0 + GAP);
+
+        let escape = Event::KeyDown {
+            key: Key::Named(NamedKey::Escape),
+            modifiers: ModifiersState::empty(),
+            text: None,
+        };
+        assert!(app.event(&escape, &mut state));
+        assert!(state.overlay().is_empty());
+    }
+}
@@ -0,0 +1,991 @@
+use super::callbacks::SelectionCallback;
+use super::data_table::fit_text;
+use super::widget::Widget;
+use crate::framework::{
+    BoxConstraints, Canvas, ColorScheme, Event, FocusId, FontCollection, LayoutCache, Offset, Rect,
+    Size, State, TextStyle,
+};
+use std::cell::Cell;
+use std::fmt;
+use std::ops::Range;
+use std::rc::Rc;
+use std::time::Duration;
+use winit::event::MouseButton;
+use winit::keyboard::{Key, ModifiersState, NamedKey};
+
+/// The default height of a row.
+const ROW_HEIGHT: f32 = 28.0;
+
+/// The default indentation of each level of the tree.
+const INDENT: f32 = 20.0;
+
+/// The space left of the rows.
+const PADDING: f32 = 8.0;
+
+/// The width of the expand and collapse arrow, which is also its hit area.
+const DISCLOSURE_WIDTH: f32 = 20.0;
+
+/// The default duration of the expand and collapse animation.
+const ANIMATION_DURATION: Duration = Duration::from_millis(200);
+
+/// How long after the last key press typed characters extend the search.
+const TYPE_AHEAD_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Called with the path of a node and whether it was expanded or collapsed.
+type ExpandCallback = Box<dyn FnMut(&[usize], bool)>;
+
+/// TreeNode is a node of a [`TreeView`] with a label and child nodes.
+///
+/// The children are either given up front or loaded by a callback the first
+/// time the node is expanded.
+pub struct TreeNode {
+    label: String,
+    children: Vec<TreeNode>,
+    loader: Option<Box<dyn FnOnce() -> Vec<TreeNode>>>,
+    expanded: bool,
+}
+
+impl TreeNode {
+    /// Create a new TreeNode instance with the given label.
+    pub fn new(label: impl Into<String>) -> Self {
+        TreeNode {
+            label: label.into(),
+            children: Vec::new(),
+            loader: None,
+            expanded: false,
+        }
+    }
+
+    /// Add a child node.
+    pub fn child(mut self, child: TreeNode) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /// Add child nodes.
+    pub fn children(mut self, children: impl IntoIterator<Item = TreeNode>) -> Self {
+        self.children.extend(children);
+        self
+    }
+
+    /// Set the callback loading the children when the node is first expanded.
+    ///
+    /// The node shows an expand arrow until the children are loaded, even if
+    /// the callback returns no children.
+    pub fn lazy(mut self, loader: impl FnOnce() -> Vec<TreeNode> + 'static) -> Self {
+        self.loader = Some(Box::new(loader));
+        self
+    }
+
+    /// Set whether the node starts expanded.
+    pub fn expanded(mut self, expanded: bool) -> Self {
+        self.expanded = expanded;
+        self
+    }
+
+    /// The label of the node.
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// The child nodes loaded so far.
+    pub fn child_nodes(&self) -> &[TreeNode] {
+        &self.children
+    }
+
+    /// Returns true if the node has children or may load some.
+    pub fn has_children(&self) -> bool {
+        !self.children.is_empty() || self.loader.is_some()
+    }
+
+    /// Returns true if the children of the node are loaded.
+    pub fn is_loaded(&self) -> bool {
+        self.loader.is_none()
+    }
+
+    /// Returns true if the node is expanded.
+    pub fn is_expanded(&self) -> bool {
+        self.expanded
+    }
+
+    fn load(&mut self) {
+        if let Some(loader) = self.loader.take() {
+            self.children = loader();
+        }
+    }
+}
+
+impl fmt::Debug for TreeNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TreeNode")
+            .field("label", &self.label)
+            .field("children", &self.children)
+            .field("expanded", &self.expanded)
+            .field("loaded", &self.is_loaded())
+            .finish()
+    }
+}
+
+/// A visible node, by the indices leading to it from the roots.
+#[derive(Debug, Clone, PartialEq)]
+struct Row {
+    path: Vec<usize>,
+    depth: usize,
+}
+
+/// Append the rows of the nodes and their expanded descendants, loading the
+/// children of expanded nodes that were not loaded yet.
+fn flatten(nodes: &mut [TreeNode], path: &mut Vec<usize>, rows: &mut Vec<Row>) {
+    for (index, node) in nodes.iter_mut().enumerate() {
+        path.push(index);
+        rows.push(Row {
+            path: path.clone(),
+            depth: path.len() - 1,
+        });
+        if node.expanded {
+            node.load();
+            flatten(&mut node.children, path, rows);
+        }
+        path.pop();
+    }
+}
+
+fn node_at<'a>(nodes: &'a [TreeNode], path: &[usize]) -> Option<&'a TreeNode> {
+    let (first, rest) = path.split_first()?;
+    let node = nodes.get(*first)?;
+    if rest.is_empty() {
+        Some(node)
+    } else {
+        node_at(&node.children, rest)
+    }
+}
+
+fn node_at_mut<'a>(nodes: &'a mut [TreeNode], path: &[usize]) -> Option<&'a mut TreeNode> {
+    let (first, rest) = path.split_first()?;
+    let node = nodes.get_mut(*first)?;
+    if rest.is_empty() {
+        Some(node)
+    } else {
+        node_at_mut(&mut node.children, rest)
+    }
+}
+
+/// Returns true if the key press typed text rather than a shortcut.
+fn is_typed(modifiers: ModifiersState, text: &str) -> bool {
+    let shortcut = modifiers.control_key() || modifiers.alt_key() || modifiers.super_key();
+    !shortcut && !text.chars().any(char::is_control)
+}
+
+/// The rows of a subtree sliding in or out below their parent.
+struct Animation {
+    /// True if the subtree is expanding, false if it is collapsing.
+    expanding: bool,
+    /// The index of the first row of the subtree, which is the index of the
+    /// row following the subtree once it collapsed.
+    at: usize,
+    /// The number of rows in the subtree.
+    count: usize,
+    /// The rows of a collapsing subtree, which are no longer in the tree.
+    removed: Vec<Row>,
+    /// The frame time of the first frame painted, set while painting.
+    start: Cell<Option<Duration>>,
+    /// Set while painting once the animation has ended.
+    finished: Cell<bool>,
+}
+
+/// The canvas and the colors the rows are painted with.
+struct RowPaint<'a> {
+    canvas: &'a mut Canvas,
+    colors: &'a ColorScheme,
+}
+
+/// TreeView shows a tree of labelled nodes whose children can be expanded
+/// and collapsed, as in file explorers and outline panels.
+///
+/// Only the rows scrolled into view are painted, so trees with many visible
+/// nodes stay cheap to paint. Expanding a node loads its children on the
+/// first expand if they are [lazy](TreeNode::lazy), and slides them in below
+/// the node; collapsing slides them back out.
+///
+/// One node is selected at a time, by clicking it or with the keyboard while
+/// the tree has focus: the up and down arrows, Home, End, Page Up and Page
+/// Down move the selection, the right arrow expands a node or moves to its
+/// first child, the left arrow collapses a node or moves to its parent, and
+/// Enter toggles the node. Typing selects the next node whose label starts
+/// with the typed characters.
+///
+/// The animation is driven by [`State::frame_time`]; the tree requests frames
+/// with [`State::request_frame`] while [`TreeView::is_animating`] returns
+/// true. Colors, and the style unless one is set, are taken from the
+/// [`Theme`](crate::framework::Theme).
+pub struct TreeView {
+    roots: Vec<TreeNode>,
+    rows: Vec<Row>,
+    style: TextStyle,
+    fonts: Rc<FontCollection>,
+    /// The style set with [`TreeView::style`], instead of the theme's.
+    custom_style: Option<TextStyle>,
+    row_height: f32,
+    indent: f32,
+    duration: Duration,
+    selected: Option<Vec<usize>>,
+    on_selected: Option<SelectionCallback>,
+    on_expanded: Option<ExpandCallback>,
+    focus_id: FocusId,
+    size: Size,
+    scroll: f32,
+    hovered: Option<usize>,
+    type_ahead: String,
+    /// The frame time of the last key press extending the type-ahead search.
+    typed_at: Option<Duration>,
+    animation: Option<Animation>,
+    cache: LayoutCache,
+}
+
+impl TreeView {
+    /// Create a new TreeView instance with the given root nodes.
+    pub fn new(roots: Vec<TreeNode>) -> Self {
+        let mut tree = TreeView {
+            roots,
+            rows: Vec::new(),
+            style: TextStyle::default(),
+            fonts: Rc::default(),
+            custom_style: None,
+            row_height: ROW_HEIGHT,
+            indent: INDENT,
+            duration: ANIMATION_DURATION,
+            selected: None,
+            on_selected: None,
+            on_expanded: None,
+            focus_id: FocusId::new(),
+            size: Size::ZERO,
+            scroll: 0.0,
+            hovered: None,
+            type_ahead: String::new(),
+            typed_at: None,
+            animation: None,
+            cache: LayoutCache::default(),
+        };
+        tree.update_rows();
+        tree
+    }
+
+    /// Set the style of the labels, instead of the body style of the theme.
+    pub fn style(mut self, style: TextStyle) -> Self {
+        self.custom_style = Some(style.clone());
+        self.style = style;
+        self
+    }
+
+    /// Set the height of a row.
+    pub fn row_height(mut self, row_height: f32) -> Self {
+        self.row_height = row_height.max(1.0);
+        self
+    }
+
+    /// Set the indentation of each level of the tree.
+    pub fn indent(mut self, indent: f32) -> Self {
+        self.indent = indent.max(0.0);
+        self
+    }
+
+    /// Set the duration of the expand and collapse animation.
+    pub fn animation_duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Set the callback called with the path of the newly selected node.
+    pub fn on_selected(mut self, on_selected: impl FnMut(&[usize]) + 'static) -> Self {
+        self.on_selected = Some(Box::new(on_selected));
+        self
+    }
+
+    /// Set the callback called with the path of a node and whether it was
+    /// expanded or collapsed.
+    pub fn on_expanded(mut self, on_expanded: impl FnMut(&[usize], bool) + 'static) -> Self {
+        self.on_expanded = Some(Box::new(on_expanded));
+        self
+    }
+
+    /// The root nodes of the tree.
+    pub fn roots(&self) -> &[TreeNode] {
+        &self.roots
+    }
+
+    /// The node at the path of child indices from the roots.
+    pub fn node(&self, path: &[usize]) -> Option<&TreeNode> {
+        node_at(&self.roots, path)
+    }
+
+    /// The path of the selected node, if any.
+    pub fn selected(&self) -> Option<&[usize]> {
+        self.selected.as_deref()
+    }
+
+    /// The focus identifier of the tree.
+    pub fn focus_id(&self) -> FocusId {
+        self.focus_id
+    }
+
+    /// The number of rows, i.e. the nodes whose ancestors are all expanded.
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// The rows that are at least partly scrolled into view.
+    pub fn visible_rows(&self) -> Range<usize> {
+        let first = (self.scroll / self.row_height).floor() as usize;
+        let last = ((self.scroll + self.size.height) / self.row_height).ceil() as usize;
+        first.min(self.rows.len())..last.min(self.rows.len())
+    }
+
+    /// Returns true while nodes slide in or out.
+    pub fn is_animating(&self) -> bool {
+        self.animation
+            .as_ref()
+            .is_some_and(|animation| !animation.finished.get())
+    }
+
+    /// Expand the node, loading its children if needed.
+    pub fn expand(&mut self, path: &[usize]) {
+        self.set_expanded(path, true);
+    }
+
+    /// Collapse the node.
+    pub fn collapse(&mut self, path: &[usize]) {
+        self.set_expanded(path, false);
+    }
+
+    /// Expand the node if it is collapsed and collapse it otherwise.
+    pub fn toggle(&mut self, path: &[usize]) {
+        if let Some(node) = self.node(path) {
+            self.set_expanded(path, !node.expanded);
+        }
+    }
+
+    /// Expand the ancestors of the node, select it and scroll it into view.
+    ///
+    /// Returns false if there is no node at the path.
+    pub fn select(&mut self, path: &[usize]) -> bool {
+        if self.node(path).is_none() {
+            return false;
+        }
+        for end in 1..path.len() {
+            self.expand(&path[..end]);
+        }
+        match self.row_of(path) {
+            Some(row) => {
+                self.select_row(row);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Scroll the least distance that shows the whole row.
+    pub fn scroll_to_row(&mut self, row: usize) {
+        let top = row as f32 * self.row_height;
+        if top < self.scroll {
+            self.scroll = top;
+        } else if top + self.row_height > self.scroll + self.size.height {
+            self.scroll = top + self.row_height - self.size.height;
+        }
+        self.scroll = self.clamp_scroll(self.scroll);
+    }
+
+    fn update_rows(&mut self) {
+        self.rows.clear();
+        flatten(&mut self.roots, &mut Vec::new(), &mut self.rows);
+    }
+
+    fn row_of(&self, path: &[usize]) -> Option<usize> {
+        self.rows.iter().position(|row| row.path == path)
+    }
+
+    fn selected_row(&self) -> Option<usize> {
+        self.selected.as_deref().and_then(|path| self.row_of(path))
+    }
+
+    fn clamp_scroll(&self, scroll: f32) -> f32 {
+        let content = self.rows.len() as f32 * self.row_height;
+        scroll.min(content - self.size.height).max(0.0)
+    }
+
+    fn row_at(&self, position: Offset) -> Option<usize> {
+        if !Rect::from_size(self.size).contains(position) {
+            return None;
+        }
+        let row = ((position.y + self.scroll) / self.row_height) as usize;
+        (row < self.rows.len()).then_some(row)
+    }
+
+    /// The left edge of the expand arrow of the row.
+    fn disclosure_left(&self, row: &Row) -> f32 {
+        PADDING + row.depth as f32 * self.indent
+    }
+
+    fn set_expanded(&mut self, path: &[usize], expanded: bool) {
+        let Some(node) = node_at_mut(&mut self.roots, path) else {
+            return;
+        };
+        if node.expanded == expanded || (expanded && !node.has_children()) {
+            return;
+        }
+        node.expanded = expanded;
+        let row = self.row_of(path);
+        let previous = std::mem::take(&mut self.rows);
+        self.update_rows();
+        self.cache.mark_dirty();
+
+        // Rows are only animated if the node itself is visible.
+        self.animation = row
+            .filter(|_| !self.duration.is_zero())
+            .map(|row| {
+                let at = row + 1;
+                let (count, removed) = if expanded {
+                    (self.rows.len() - previous.len(), Vec::new())
+                } else {
+                    let count = previous.len() - self.rows.len();
+                    (count, previous[at..at + count].to_vec())
+                };
+                Animation {
+                    expanding: expanded,
+                    at,
+                    count,
+                    removed,
+                    start: Cell::new(None),
+                    finished: Cell::new(false),
+                }
+            })
+            .filter(|animation| animation.count > 0);
+        self.scroll = self.clamp_scroll(self.scroll);
+        self.hovered = None;
+
+        if let Some(on_expanded) = &mut self.on_expanded {
+            on_expanded(path, expanded);
+        }
+        // A node hidden by the collapse passes the selection to the collapsed node.
+        if !expanded
+            && self
+                .selected
+                .as_ref()
+                .is_some_and(|selected| selected.len() > path.len() && selected.starts_with(path))
+        {
+            if let Some(row) = self.row_of(path) {
+                self.select_row(row);
+            } else {
+                self.selected = None;
+            }
+        }
+    }
+
+    fn select_row(&mut self, row: usize) {
+        self.scroll_to_row(row);
+        let path = &self.rows[row].path;
+        if self.selected.as_ref() == Some(path) {
+            return;
+        }
+        self.selected = Some(path.clone());
+        if let Some(on_selected) = &mut self.on_selected {
+            on_selected(&self.rows[row].path);
+        }
+    }
+
+    /// Select the next row whose label starts with the typed text.
+    fn type_ahead(&mut self, text: &str, now: Duration) {
+        let continued = self
+            .typed_at
+            .is_some_and(|typed_at| now.saturating_sub(typed_at) < TYPE_AHEAD_TIMEOUT);
+        if !continued {
+            self.type_ahead.clear();
+        }
+        self.type_ahead.push_str(&text.to_lowercase());
+        self.typed_at = Some(now);
+
+        // A longer search may still match the selected row; a new one moves on.
+        let start = match self.selected_row() {
+            Some(row) if continued => row,
+            Some(row) => row + 1,
+            None => 0,
+        };
+        let len = self.rows.len();
+        let found = (0..len).map(|offset| (start + offset) % len).find(|&row| {
+            node_at(&self.roots, &self.rows[row].path)
+                .is_some_and(|node| node.label.to_lowercase().starts_with(&self.type_ahead))
+        });
+        if let Some(row) = found {
+            self.select_row(row);
+        }
+    }
+
+    fn key_event(&mut self, key: NamedKey) -> bool {
+        if self.rows.is_empty() {
+            return false;
+        }
+        let last = self.rows.len() - 1;
+        let page = ((self.size.height / self.row_height) as usize).max(1);
+        let current = self.selected_row();
+        let row = match (key, current) {
+            (NamedKey::ArrowDown, Some(row)) => (row + 1).min(last),
+            (NamedKey::ArrowUp, Some(row)) => row.saturating_sub(1),
+            (NamedKey::PageDown, Some(row)) => (row + page).min(last),
+            (NamedKey::PageUp, Some(row)) => row.saturating_sub(page),
+            (NamedKey::ArrowRight, Some(row)) => {
+                let path = self.rows[row].path.clone();
+                match self.node(&path) {
+                    Some(node) if !node.expanded && node.has_children() => {
+                        self.expand(&path);
+                        return true;
+                    }
+                    Some(node) if node.expanded && !node.children.is_empty() => row + 1,
+                    _ => return false,
+                }
+            }
+            (NamedKey::ArrowLeft, Some(row)) => {
+                let path = self.rows[row].path.clone();
+                if self.node(&path).is_some_and(|node| node.expanded) {
+                    self.collapse(&path);
+                    return true;
+                }
+                match self.row_of(&path[..path.len() - 1]) {
+                    Some(parent) => parent,
+                    None => return false,
+                }
+            }
+            (NamedKey::Enter, Some(row)) => {
+                let path = self.rows[row].path.clone();
+                self.toggle(&path);
+                return true;
+            }
+            (NamedKey::ArrowDown | NamedKey::PageDown | NamedKey::Home, None)
+            | (NamedKey::Home, _) => 0,
+            (NamedKey::ArrowUp | NamedKey::PageUp | NamedKey::End, None) | (NamedKey::End, _) => {
+                last
+            }
+            _ => return false,
+        };
+        self.select_row(row);
+        true
+    }
+
+    /// How much of the animated subtree is shown, from 0 to 1.
+    fn reveal(&self, animation: &Animation, frame_time: Duration) -> f32 {
+        let start = animation.start.get().unwrap_or(frame_time);
+        animation.start.set(Some(start));
+        let elapsed = frame_time.saturating_sub(start);
+        let t = if self.duration.is_zero() {
+            1.0
+        } else {
+            (elapsed.as_secs_f32() / self.duration.as_secs_f32()).min(1.0)
+        };
+        animation.finished.set(t >= 1.0);
+        let t = 1.0 - (1.0 - t) * (1.0 - t);
+        if animation.expanding {
+            t
+        } else {
+            1.0 - t
+        }
+    }
+
+    fn paint_row(&self, paint: &mut RowPaint<'_>, row: &Row, top: f32, hovered: bool) {
+        let colors = paint.colors;
+        let Some(node) = self.node(&row.path) else {
+            return;
+        };
+        let background = if self.selected.as_ref() == Some(&row.path) {
+            Some(colors.primary.with_alpha(0.12))
+        } else if hovered {
+            Some(colors.on_surface.with_alpha(0.04))
+        } else {
+            None
+        };
+        if let Some(background) = background {
+            let bottom = (top + self.row_height).min(self.size.height);
+            let top = top.max(0.0);
+            paint.canvas.fill_rect(
+                Rect::new(0.0, top, self.size.width, (bottom - top).max(0.0)),
+                background,
+            );
+        }
+        // Text is left out of rows cut off by the top or the bottom.
+        if top < 0.0 || top + self.row_height > self.size.height {
+            return;
+        }
+        let text_top = top + (self.row_height - self.style.line_extent()) / 2.0;
+        let left = self.disclosure_left(row);
+        if node.has_children() {
+            let arrow = if node.expanded { "▾" } else { "▸" };
+            paint
+                .canvas
+                .draw_text(Offset::new(left, text_top), arrow, &self.style);
+        }
+        let left = left + DISCLOSURE_WIDTH;
+        let label = fit_text(
+            &node.label,
+            self.size.width - left - PADDING,
+            &self.style,
+            &self.fonts,
+        );
+        paint
+            .canvas
+            .draw_text(Offset::new(left, text_top), &label, &self.style);
+    }
+
+    /// Paint the rows of the animated subtree that are revealed below their
+    /// parent, faded by the share that is shown.
+    fn paint_subtree(&self, paint: &mut RowPaint<'_>, animation: &Animation, reveal: f32) {
+        let rows = if animation.expanding {
+            &self.rows[animation.at..animation.at + animation.count]
+        } else {
+            &animation.removed
+        };
+        let parent_bottom = animation.at as f32 * self.row_height - self.scroll;
+        let hidden = (1.0 - reveal) * animation.count as f32 * self.row_height;
+        let first = ((parent_bottom.max(-self.row_height) - parent_bottom + hidden)
+            / self.row_height)
+            .ceil() as usize;
+        let last = ((self.size.height - parent_bottom + hidden) / self.row_height).ceil() as usize;
+        paint.canvas.save();
+        paint.canvas.apply_opacity(f64::from(reveal));
+        for (index, row) in rows.iter().enumerate().take(last).skip(first) {
+            let top = parent_bottom + index as f32 * self.row_height - hidden;
+            if top >= parent_bottom {
+                self.paint_row(paint, row, top, false);
+            }
+        }
+        paint.canvas.restore();
+    }
+}
+
+impl Widget for TreeView {
+    fn build(&mut self, state: &mut State<'_>) {
+        let style = self
+            .custom_style
+            .clone()
+            .unwrap_or_else(|| state.theme().typography.body.clone());
+        if style != self.style || !Rc::ptr_eq(&self.fonts, state.fonts()) {
+            self.style = style;
+            self.fonts = Rc::clone(state.fonts());
+            self.cache.mark_dirty();
+        }
+    }
+
+    fn layout(&mut self, constraints: BoxConstraints) -> Size {
+        if let Some(size) = self.cache.get(constraints) {
+            return size;
+        }
+        if self
+            .animation
+            .as_ref()
+            .is_some_and(|animation| animation.finished.get())
+        {
+            self.animation = None;
+        }
+        let width = if constraints.max_width.is_finite() {
+            constraints.max_width
+        } else {
+            // Only an unbounded width measures every row.
+            self.rows
+                .iter()
+                .filter_map(|row| Some((row, self.node(&row.path)?)))
+                .map(|(row, node)| {
+                    let label = self.fonts.measure(&node.label, &self.style);
+                    self.disclosure_left(row) + DISCLOSURE_WIDTH + label + PADDING
+                })
+                .fold(0.0, f32::max)
+        };
+        self.size =
+            constraints.constrain(Size::new(width, self.rows.len() as f32 * self.row_height));
+        self.scroll = self.clamp_scroll(self.scroll);
+        self.cache.store(constraints, self.size);
+        self.size
+    }
+
+    fn paint(&self, state: &mut State<'_>) {
+        state.focus_mut().register(self.focus_id);
+        let focused = state.focus().is_focused(self.focus_id);
+        let now = state.frame_time();
+        let animation = self
+            .animation
+            .as_ref()
+            .filter(|animation| !animation.finished.get())
+            .map(|animation| (animation, self.reveal(animation, now)));
+        if animation.is_some_and(|(animation, _)| !animation.finished.get()) {
+            state.request_frame();
+        }
+        let colors = state.theme().colors;
+        let mut paint = RowPaint {
+            canvas: state.canvas_mut(),
+            colors: &colors,
+        };
+
+        let visible = self.visible_rows();
+        // Rows below a collapsing subtree move in from below the visible rows.
+        let end = match animation {
+            Some((animation, _)) => (visible.end + animation.count).min(self.rows.len()),
+            None => visible.end,
+        };
+        for index in visible.start..end {
+            let mut top = index as f32 * self.row_height - self.scroll;
+            if let Some((animation, reveal)) = animation {
+                let extent = animation.count as f32 * self.row_height;
+                if !animation.expanding && index >= animation.at {
+                    top += reveal * extent;
+                } else if animation.expanding
+                    && (animation.at..animation.at + animation.count).contains(&index)
+                {
+                    continue;
+                } else if animation.expanding && index >= animation.at + animation.count {
+                    top -= (1.0 - reveal) * extent;
+                }
+            }
+            if top + self.row_height <= 0.0 || top >= self.size.height {
+                continue;
+            }
+            let hovered = self.hovered == Some(index);
+            self.paint_row(&mut paint, &self.rows[index], top, hovered);
+        }
+        if let Some((animation, reveal)) = animation {
+            self.paint_subtree(&mut paint, animation, reveal);
+        }
+        if focused {
+            paint
+                .canvas
+                .stroke_rect(Rect::from_size(self.size), 2.0, colors.primary);
+        }
+    }
+
+    fn event(&mut self, event: &Event, state: &mut State<'_>) -> bool {
+        match event {
+            Event::PointerDown {
+                position,
+                button: MouseButton::Left,
+            } => {
+                if !Rect::from_size(self.size).contains(*position) {
+                    return false;
+                }
+                state.focus_mut().request_focus(self.focus_id);
+                let Some(row) = self.row_at(*position) else {
+                    return true;
+                };
+                let left = self.disclosure_left(&self.rows[row]);
+                let path = self.rows[row].path.clone();
+                let on_arrow = (left..left + DISCLOSURE_WIDTH).contains(&position.x);
+                if on_arrow && self.node(&path).is_some_and(TreeNode::has_children) {
+                    self.toggle(&path);
+                } else {
+                    self.select_row(row);
+                }
+                true
+            }
+            Event::PointerMove { position } => {
+                self.hovered = self.row_at(*position);
+                false
+            }
+            Event::PointerExit => {
+                self.hovered = None;
+                false
+            }
+            Event::Scroll { position, delta } => {
+                if !Rect::from_size(self.size).contains(*position) {
+                    return false;
+                }
+                let scroll = self.clamp_scroll(self.scroll + delta.y);
+                let moved = scroll != self.scroll;
+                self.scroll = scroll;
+                self.hovered = self.row_at(*position);
+                moved
+            }
+            Event::KeyDown {
+                key,
+                modifiers,
+                text,
+            } if state.focus().is_focused(self.focus_id) => match (key, text) {
+                (Key::Named(key), _) if *key != NamedKey::Space => self.key_event(*key),
+                (_, Some(text)) if is_typed(*modifiers, text) => {
+                    self.type_ahead(text, state.frame_time());
+                    true
+                }
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    fn needs_layout(&self) -> bool {
+        self.cache.is_dirty()
+    }
+
+    fn mark_needs_layout(&mut self) {
+        self.cache.mark_dirty();
+    }
+
+    fn is_relayout_boundary(&self) -> bool {
+        self.cache.is_relayout_boundary()
+    }
+}
+
+impl fmt::Debug for TreeView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TreeView")
+            .field("roots", &self.roots)
+            .field("row_count", &self.rows.len())
+            .field("selected", &self.selected)
+            .field("scroll", &self.scroll)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::widgets::test_support::{key, typed};
+    use crate::framework::DrawCommand;
+    use std::rc::Rc;
+
+    fn texts(state: &State<'_>) -> Vec<(String, f32)> {
+        state
+            .canvas()
+            .commands()
+            .iter()
+            .filter_map(|command| match command {
+                DrawCommand::Text { text, origin, .. } => Some((text.clone(), origin.y)),
+                DrawCommand::FillRect { .. } => None,
+            })
+            .collect()
+    }
+
+    fn frame(tree: &mut TreeView, state: &mut State<'_>, time: Duration) {
+        state.set_frame_time(time);
+        tree.layout(BoxConstraints::tight(Size::new(200.0, 280.0)));
+        state.canvas_mut().clear();
+        tree.paint(state);
+    }
+
+    #[test]
+    fn test_lazy_children_and_virtualization() {
+        let loads = Rc::new(Cell::new(0));
+        let root = {
+            let loads = Rc::clone(&loads);
+            TreeNode::new("Root").lazy(move || {
+                loads.set(loads.get() + 1);
+                (0..100_000)
+                    .map(|index| TreeNode::new(format!("Item {index}")))
+                    .collect()
+            })
+        };
+        let mut tree = TreeView::new(vec![root]);
+        let mut state = State::default();
+        frame(&mut tree, &mut state, Duration::ZERO);
+        assert_eq!(loads.get(), 0);
+        assert!(tree.node(&[0]).is_some_and(TreeNode::has_children));
+
+        tree.expand(&[0]);
+        assert_eq!(loads.get(), 1);
+        assert_eq!(tree.row_count(), 100_001);
+        frame(&mut tree, &mut state, Duration::from_secs(1));
+        frame(&mut tree, &mut state, Duration::from_secs(2));
+        assert!(!tree.is_animating());
+        // Ten rows fit, each with a label, and the root also has an arrow.
+        assert_eq!(texts(&state).len(), 11);
+
+        tree.collapse(&[0]);
+        tree.expand(&[0]);
+        assert_eq!(loads.get(), 1);
+        let scroll = Event::Scroll {
+            position: Offset::new(10.0, 10.0),
+            delta: Offset::new(0.0, 1.0e9),
+        };
+        assert!(tree.event(&scroll, &mut state));
+        assert_eq!(tree.visible_rows(), 99_991..100_001);
+    }
+
+    #[test]
+    fn test_expand_animation() {
+        let children = (0..4).map(|index| TreeNode::new(format!("Child {index}")));
+        let roots = vec![
+            TreeNode::new("Parent").children(children),
+            TreeNode::new("Sibling"),
+        ];
+        let mut tree = TreeView::new(roots).animation_duration(Duration::from_millis(100));
+        let mut state = State::default();
+        frame(&mut tree, &mut state, Duration::ZERO);
+        assert!(!tree.needs_layout());
+        tree.expand(&[0]);
+        assert!(tree.is_animating());
+        assert!(tree.needs_layout());
+
+        // The sibling starts right below the parent and the children are hidden.
+        let sibling = |state: &State<'_>| {
+            texts(state)
+                .into_iter()
+                .find(|(text, _)| text == "Sibling")
+                .map(|(_, y)| y)
+                .unwrap()
+        };
+        frame(&mut tree, &mut state, Duration::from_millis(1000));
+        assert!(texts(&state)
+            .iter()
+            .all(|(text, _)| !text.starts_with("Child")));
+        let start = sibling(&state);
+        frame(&mut tree, &mut state, Duration::from_millis(1050));
+        let middle = sibling(&state);
+        assert!(middle > start && middle < start + 4.0 * ROW_HEIGHT);
+        frame(&mut tree, &mut state, Duration::from_millis(1100));
+        assert_eq!(sibling(&state), start + 4.0 * ROW_HEIGHT);
+        assert_eq!(
+            texts(&state)
+                .iter()
+                .filter(|(text, _)| text.starts_with("Child"))
+                .count(),
+            4
+        );
+        assert!(!tree.is_animating());
+    }
+
+    #[test]
+    fn test_keyboard_navigation() {
+        let roots = vec![
+            TreeNode::new("src").children([TreeNode::new("lib.rs"), TreeNode::new("main.rs")]),
+            TreeNode::new("tests").child(TreeNode::new("tree.rs")),
+            TreeNode::new("Cargo.toml"),
+        ];
+        let mut tree = TreeView::new(roots).animation_duration(Duration::ZERO);
+        let mut state = State::default();
+        frame(&mut tree, &mut state, Duration::ZERO);
+        state.focus_mut().request_focus(tree.focus_id());
+
+        assert!(tree.event(&key(NamedKey::ArrowDown), &mut state));
+        assert_eq!(tree.selected(), Some(&[0][..]));
+        tree.event(&key(NamedKey::ArrowRight), &mut state);
+        assert!(tree.node(&[0]).is_some_and(TreeNode::is_expanded));
+        tree.event(&key(NamedKey::ArrowRight), &mut state);
+        tree.event(&key(NamedKey::ArrowDown), &mut state);
+        assert_eq!(tree.selected(), Some(&[0, 1][..]));
+        tree.event(&key(NamedKey::ArrowLeft), &mut state);
+        assert_eq!(tree.selected(), Some(&[0][..]));
+        tree.event(&key(NamedKey::ArrowLeft), &mut state);
+        assert_eq!(tree.row_count(), 3);
+        tree.event(&key(NamedKey::End), &mut state);
+        assert_eq!(tree.selected(), Some(&[2][..]));
+
+        // Typing searches from the next row and wraps around.
+        assert!(tree.event(&typed("t"), &mut state));
+        assert_eq!(tree.selected(), Some(&[1][..]));
+        tree.event(&typed("e"), &mut state);
+        assert_eq!(tree.selected(), Some(&[1][..]));
+        state.set_frame_time(Duration::from_secs(5));
+        tree.event(&typed("c"), &mut state);
+        assert_eq!(tree.selected(), Some(&[2][..]));
+
+        // Collapsing an ancestor moves the selection to it.
+        assert!(tree.select(&[1, 0]));
+        tree.event(&key(NamedKey::Home), &mut state);
+        tree.event(&key(NamedKey::ArrowDown), &mut state);
+        tree.event(&key(NamedKey::ArrowDown), &mut state);
+        assert_eq!(tree.selected(), Some(&[1, 0][..]));
+        tree.collapse(&[1]);
+        assert_eq!(tree.selected(), Some(&[1][..]));
+    }
+}
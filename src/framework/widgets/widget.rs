@@ -1,13 +1,49 @@
-use crate::framework::{BoxConstraints, Size, State};
+use crate::framework::{BoxConstraints, Event, Size, State};
 
 /// Widget is the base trait for all widgets.
 pub trait Widget {
     /// Build the widget.
-    fn build(&self, state: &mut State<'_>);
+    fn build(&mut self, state: &mut State<'_>);
 
     /// Layout the widget.
     fn layout(&mut self, constraints: BoxConstraints) -> Size;
 
     /// Paint the widget.
     fn paint(&self, state: &mut State<'_>);
+
+    /// Handle an input event, returning true if the widget consumed it.
+    ///
+    /// Pointer positions are relative to the widget's top-left corner.
+    fn event(&mut self, _event: &Event, _state: &mut State<'_>) -> bool {
+        false
+    }
+
+    /// Returns true if this widget, or a descendant whose size affects it,
+    /// must be laid out again.
+    ///
+    /// Widgets that do not cache their layout always need layout.
+    fn needs_layout(&self) -> bool {
+        true
+    }
+
+    /// Mark the layout of this widget as stale.
+    ///
+    /// Widgets that cache their layout mark their [`LayoutCache`], which marks
+    /// the ancestors whose layout depends on this widget right away.
+    ///
+    /// [`LayoutCache`]: crate::framework::LayoutCache
+    fn mark_needs_layout(&mut self) {}
+
+    /// Returns true if the size of this widget cannot change when it is laid
+    /// out again, so changes inside it do not affect its parent.
+    fn is_relayout_boundary(&self) -> bool {
+        false
+    }
+
+    /// The name of the widget used in debug output, the type name by default.
+    fn debug_name(&self) -> &'static str {
+        let name = std::any::type_name::<Self>();
+        let path = name.split('<').next().unwrap_or(name);
+        path.rsplit("::").next().unwrap_or(path)
+    }
 }
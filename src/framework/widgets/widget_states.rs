@@ -0,0 +1,60 @@
+use std::ops::BitOr;
+
+/// WidgetStates is a set of interactive states a widget can be in.
+///
+/// Widgets use it to pick their visual appearance, e.g. a lighter background
+/// while hovered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct WidgetStates(u8);
+
+impl WidgetStates {
+    /// The pointer is over the widget.
+    pub const HOVERED: WidgetStates = WidgetStates(1);
+    /// The widget is being pressed.
+    pub const PRESSED: WidgetStates = WidgetStates(1 << 1);
+    /// The widget has keyboard focus.
+    pub const FOCUSED: WidgetStates = WidgetStates(1 << 2);
+    /// The widget does not accept input.
+    pub const DISABLED: WidgetStates = WidgetStates(1 << 3);
+    /// The widget is selected or checked.
+    pub const SELECTED: WidgetStates = WidgetStates(1 << 4);
+    /// The widget is being dragged.
+    pub const DRAGGED: WidgetStates = WidgetStates(1 << 5);
+
+    /// The empty set of states.
+    pub fn empty() -> Self {
+        WidgetStates(0)
+    }
+
+    /// Returns true if all of the given states are set.
+    pub fn contains(&self, states: WidgetStates) -> bool {
+        self.0 & states.0 == states.0
+    }
+
+    /// Add the given states.
+    pub fn insert(&mut self, states: WidgetStates) {
+        self.0 |= states.0;
+    }
+
+    /// Remove the given states.
+    pub fn remove(&mut self, states: WidgetStates) {
+        self.0 &= !states.0;
+    }
+
+    /// Add or remove the given states.
+    pub fn set(&mut self, states: WidgetStates, value: bool) {
+        if value {
+            self.insert(states);
+        } else {
+            self.remove(states);
+        }
+    }
+}
+
+impl BitOr for WidgetStates {
+    type Output = WidgetStates;
+
+    fn bitor(self, rhs: WidgetStates) -> WidgetStates {
+        WidgetStates(self.0 | rhs.0)
+    }
+}